@@ -1,9 +1,12 @@
 use {
     crate::{
+        accounts_file::tiered_storage_offset,
         accounts_hash::AccountHash,
         append_vec::AppendVecStoredAccountMeta,
         storable_accounts::StorableAccounts,
-        tiered_storage::{hot::HotAccountMeta, readable::TieredReadableAccount},
+        tiered_storage::{
+            cold::ColdStoredAccount, hot::HotAccountMeta, readable::TieredReadableAccount,
+        },
     },
     solana_sdk::{account::ReadableAccount, hash::Hash, pubkey::Pubkey, stake_history::Epoch},
     std::{borrow::Borrow, marker::PhantomData},
@@ -115,6 +118,7 @@ impl<
 pub enum StoredAccountMeta<'storage> {
     AppendVec(AppendVecStoredAccountMeta<'storage>),
     Hot(TieredReadableAccount<'storage, HotAccountMeta>),
+    Cold(ColdStoredAccount<'storage>),
 }
 
 impl<'storage> StoredAccountMeta<'storage> {
@@ -122,13 +126,15 @@ impl<'storage> StoredAccountMeta<'storage> {
         match self {
             Self::AppendVec(av) => av.pubkey(),
             Self::Hot(hot) => hot.address(),
+            Self::Cold(cold) => cold.address(),
         }
     }
 
-    pub fn hash(&self) -> &'storage AccountHash {
+    pub fn hash(&self) -> &AccountHash {
         match self {
             Self::AppendVec(av) => av.hash(),
             Self::Hot(hot) => hot.hash().unwrap_or(&DEFAULT_ACCOUNT_HASH),
+            Self::Cold(cold) => cold.hash().unwrap_or(&DEFAULT_ACCOUNT_HASH),
         }
     }
 
@@ -136,20 +142,23 @@ impl<'storage> StoredAccountMeta<'storage> {
         match self {
             Self::AppendVec(av) => av.stored_size(),
             Self::Hot(_) => unimplemented!(),
+            Self::Cold(cold) => cold.stored_size(),
         }
     }
 
     pub fn offset(&self) -> usize {
         match self {
             Self::AppendVec(av) => av.offset(),
-            Self::Hot(hot) => hot.index(),
+            Self::Hot(hot) => tiered_storage_offset(hot.index()),
+            Self::Cold(cold) => tiered_storage_offset(cold.index()),
         }
     }
 
-    pub fn data(&self) -> &'storage [u8] {
+    pub fn data(&self) -> &[u8] {
         match self {
             Self::AppendVec(av) => av.data(),
             Self::Hot(hot) => hot.data(),
+            Self::Cold(cold) => cold.data(),
         }
     }
 
@@ -157,41 +166,42 @@ impl<'storage> StoredAccountMeta<'storage> {
         match self {
             Self::AppendVec(av) => av.data_len(),
             Self::Hot(hot) => hot.data().len() as u64,
+            Self::Cold(cold) => cold.data().len() as u64,
         }
     }
 
     pub fn write_version(&self) -> StoredMetaWriteVersion {
         match self {
             Self::AppendVec(av) => av.write_version(),
-            // Hot account does not support this API as it does not
+            // Tiered accounts do not support this API as they do not
             // use a write version.
-            Self::Hot(_) => StoredMetaWriteVersion::default(),
+            Self::Hot(_) | Self::Cold(_) => StoredMetaWriteVersion::default(),
         }
     }
 
     pub fn meta(&self) -> &StoredMeta {
         match self {
             Self::AppendVec(av) => av.meta(),
-            // Hot account does not support this API as it does not
+            // Tiered accounts do not support this API as they do not
             // use the same in-memory layout as StoredMeta.
-            Self::Hot(_) => unreachable!(),
+            Self::Hot(_) | Self::Cold(_) => unreachable!(),
         }
     }
 
     pub fn set_meta(&mut self, meta: &'storage StoredMeta) {
         match self {
             Self::AppendVec(av) => av.set_meta(meta),
-            // Hot account does not support this API as it does not
+            // Tiered accounts do not support this API as they do not
             // use the same in-memory layout as StoredMeta.
-            Self::Hot(_) => unreachable!(),
+            Self::Hot(_) | Self::Cold(_) => unreachable!(),
         }
     }
 
     pub(crate) fn sanitize(&self) -> bool {
         match self {
             Self::AppendVec(av) => av.sanitize(),
-            // Hot account currently doesn't have the concept of sanitization.
-            Self::Hot(_) => unimplemented!(),
+            // Tiered accounts currently don't have the concept of sanitization.
+            Self::Hot(_) | Self::Cold(_) => unimplemented!(),
         }
     }
 }
@@ -201,30 +211,35 @@ impl<'storage> ReadableAccount for StoredAccountMeta<'storage> {
         match self {
             Self::AppendVec(av) => av.lamports(),
             Self::Hot(hot) => hot.lamports(),
+            Self::Cold(cold) => cold.lamports(),
         }
     }
     fn data(&self) -> &[u8] {
         match self {
             Self::AppendVec(av) => av.data(),
             Self::Hot(hot) => hot.data(),
+            Self::Cold(cold) => cold.data(),
         }
     }
     fn owner(&self) -> &Pubkey {
         match self {
             Self::AppendVec(av) => av.owner(),
             Self::Hot(hot) => hot.owner(),
+            Self::Cold(cold) => cold.owner(),
         }
     }
     fn executable(&self) -> bool {
         match self {
             Self::AppendVec(av) => av.executable(),
             Self::Hot(hot) => hot.executable(),
+            Self::Cold(cold) => cold.executable(),
         }
     }
     fn rent_epoch(&self) -> Epoch {
         match self {
             Self::AppendVec(av) => av.rent_epoch(),
             Self::Hot(hot) => hot.rent_epoch(),
+            Self::Cold(cold) => cold.rent_epoch(),
         }
     }
 }
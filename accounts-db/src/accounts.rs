@@ -40,7 +40,7 @@ use {
             hash_map::{self},
             BinaryHeap, HashMap, HashSet,
         },
-        ops::RangeBounds,
+        ops::{Bound, RangeBounds},
         path::PathBuf,
         sync::{
             atomic::{AtomicBool, AtomicUsize, Ordering},
@@ -522,6 +522,73 @@ impl Accounts {
         Self::maybe_abort_scan(result, &config)
     }
 
+    /// Loads, in pubkey order, the first `limit` accounts after `after` which pass `filter`,
+    /// through the secondary index of `index_key` if the key is indexed
+    #[allow(clippy::too_many_arguments)]
+    pub fn load_page_with_filter<F: Fn(&AccountSharedData) -> bool>(
+        &self,
+        ancestors: &Ancestors,
+        bank_id: BankId,
+        index_key: Option<&IndexKey>,
+        after: Option<&Pubkey>,
+        limit: usize,
+        filter: F,
+        config: &ScanConfig,
+        byte_limit_for_scan: Option<usize>,
+    ) -> ScanResult<Vec<TransactionAccount>> {
+        if limit == 0 {
+            return Ok(vec![]);
+        }
+        let sum = AtomicUsize::default();
+        // The scan is aborted once the page is full
+        let config = ScanConfig {
+            collect_all_unsorted: false,
+            ..config.recreate_with_abort()
+        };
+        let range = (
+            after.map_or(Bound::Unbounded, |after| Bound::Excluded(*after)),
+            Bound::Unbounded,
+        );
+        let mut collector = Vec::new();
+        let result = self
+            .accounts_db
+            .ordered_range_scan_accounts(
+                ancestors,
+                bank_id,
+                index_key.copied(),
+                range,
+                |some_account_tuple| {
+                    Self::load_while_filtering(&mut collector, some_account_tuple, |account| {
+                        let use_account = filter(account);
+                        if use_account
+                            && Self::accumulate_and_check_scan_result_size(
+                                &sum,
+                                account,
+                                &byte_limit_for_scan,
+                            )
+                        {
+                            config.abort();
+                        }
+                        use_account
+                    });
+                    if collector.len() >= limit {
+                        config.abort();
+                    }
+                },
+                &config,
+            )
+            .map(|_| collector);
+        let exceeded_byte_limit = byte_limit_for_scan
+            .map_or(false, |byte_limit| sum.load(Ordering::Relaxed) > byte_limit);
+        if exceeded_byte_limit {
+            ScanResult::Err(ScanError::Aborted(
+                "The accumulated scan results exceeded the limit".to_string(),
+            ))
+        } else {
+            result
+        }
+    }
+
     pub fn account_indexes_include_key(&self, key: &Pubkey) -> bool {
         self.accounts_db.account_indexes.include_key(key)
     }
@@ -937,6 +1004,7 @@ mod tests {
                 status,
                 log_messages: None,
                 inner_instructions: None,
+                executed_instructions: None,
                 durable_nonce_fee: nonce.map(DurableNonceFee::from),
                 return_data: None,
                 executed_units: 0,
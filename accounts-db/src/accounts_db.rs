@@ -66,6 +66,7 @@ use {
         rent_collector::RentCollector,
         sorted_storages::SortedStorages,
         storable_accounts::StorableAccounts,
+        tiered_storage::TieredStorage,
         verify_accounts_hash_in_background::VerifyAccountsHashInBackground,
     },
     blake3::traits::digest::Digest,
@@ -477,6 +478,7 @@ pub const ACCOUNTS_DB_CONFIG_FOR_TESTING: AccountsDbConfig = AccountsDbConfig {
     skip_initial_hash_calc: false,
     exhaustively_verify_refcounts: false,
     create_ancient_storage: CreateAncientStorage::Pack,
+    ancient_cold_storage: false,
     test_partitioned_epoch_rewards: TestPartitionedEpochRewards::CompareResults,
     test_skip_rewrites_but_include_in_bank_hash: false,
 };
@@ -489,6 +491,7 @@ pub const ACCOUNTS_DB_CONFIG_FOR_BENCHMARKS: AccountsDbConfig = AccountsDbConfig
     skip_initial_hash_calc: false,
     exhaustively_verify_refcounts: false,
     create_ancient_storage: CreateAncientStorage::Pack,
+    ancient_cold_storage: false,
     test_partitioned_epoch_rewards: TestPartitionedEpochRewards::None,
     test_skip_rewrites_but_include_in_bank_hash: false,
 };
@@ -534,6 +537,9 @@ pub struct AccountsDbConfig {
     pub exhaustively_verify_refcounts: bool,
     /// how to create ancient storages
     pub create_ancient_storage: CreateAncientStorage,
+    /// true to rewrite ancient slots into compressed cold tiered storages instead of appending them
+    /// to ancient append vecs, when ancient storages are created with `CreateAncientStorage::Append`
+    pub ancient_cold_storage: bool,
     pub test_partitioned_epoch_rewards: TestPartitionedEpochRewards,
 }
 
@@ -1023,6 +1029,16 @@ impl AccountStorageEntry {
         }
     }
 
+    /// Creates a storage entry backed by a new cold tiered storage, which is
+    /// written by the first append to it.
+    pub fn new_cold(path: &Path, slot: Slot, id: AppendVecId) -> Self {
+        let tail = AccountsFile::file_name(slot, id);
+        let path = Path::new(path).join(tail);
+        let accounts = AccountsFile::TieredStorage(TieredStorage::new_writable(path));
+
+        Self::new_existing(slot, id, accounts, 0)
+    }
+
     pub fn new_existing(
         slot: Slot,
         id: AppendVecId,
@@ -1412,6 +1428,9 @@ pub struct AccountsDb {
     /// from AccountsDbConfig
     create_ancient_storage: CreateAncientStorage,
 
+    /// from AccountsDbConfig
+    ancient_cold_storage: bool,
+
     /// true if this client should skip rewrites but still include those rewrites in the bank hash as if rewrites had occurred.
     pub test_skip_rewrites_but_include_in_bank_hash: bool,
 
@@ -2459,6 +2478,7 @@ impl AccountsDb {
 
         AccountsDb {
             create_ancient_storage: CreateAncientStorage::Pack,
+            ancient_cold_storage: false,
             verify_accounts_hash_in_bg: VerifyAccountsHashInBackground::default(),
             active_stats: ActiveStats::default(),
             skip_initial_hash_calc: false,
@@ -2583,6 +2603,11 @@ impl AccountsDb {
             .map(|config| config.create_ancient_storage)
             .unwrap_or(CreateAncientStorage::Append);
 
+        let ancient_cold_storage = accounts_db_config
+            .as_ref()
+            .map(|config| config.ancient_cold_storage)
+            .unwrap_or_default();
+
         let test_partitioned_epoch_rewards = accounts_db_config
             .as_ref()
             .map(|config| config.test_partitioned_epoch_rewards)
@@ -2606,6 +2631,7 @@ impl AccountsDb {
             shrink_ratio,
             accounts_update_notifier,
             create_ancient_storage,
+            ancient_cold_storage,
             write_cache_limit_bytes: accounts_db_config
                 .as_ref()
                 .and_then(|x| x.write_cache_limit_bytes),
@@ -4521,6 +4547,17 @@ impl AccountsDb {
 
         let len = sorted_slots.len();
         for slot in sorted_slots {
+            if self.ancient_cold_storage {
+                if self.write_ancient_slot_to_cold_storage(slot) && guard.is_none() {
+                    guard = Some(self.active_stats.activate(ActiveStatItem::SquashAncient));
+                    info!(
+                        "ancient_append_vec: combine_ancient_slots first cold slot: {}, num_roots: {}",
+                        slot, len
+                    );
+                }
+                continue;
+            }
+
             let Some(old_storage) = self.get_storage_to_move_to_ancient_append_vec(
                 slot,
                 &mut current_ancient,
@@ -4923,12 +4960,16 @@ impl AccountsDb {
     where
         F: FnMut(Option<(&Pubkey, AccountSharedData, Slot)>),
     {
-        let key = match &index_key {
-            IndexKey::ProgramId(key) => key,
-            IndexKey::SplTokenMint(key) => key,
-            IndexKey::SplTokenOwner(key) => key,
+        let include_key = match &index_key {
+            IndexKey::ProgramId(key)
+            | IndexKey::SplTokenMint(key)
+            | IndexKey::SplTokenOwner(key) => self.account_indexes.include_key(key),
+            // The custom indexes ignore the included and excluded keys
+            IndexKey::Memcmp(memcmp_index, _) => {
+                self.account_indexes.memcmp_indexes.contains(memcmp_index)
+            }
         };
-        if !self.account_indexes.include_key(key) {
+        if !include_key {
             // the requested key was not indexed in the secondary index, so do a normal scan
             let used_index = false;
             self.scan_accounts(ancestors, bank_id, scan_func, config)?;
@@ -4952,6 +4993,46 @@ impl AccountsDb {
         Ok(used_index)
     }
 
+    /// Scans the accounts in `range` in pubkey order, through the secondary index of
+    /// `index_key` if the key is indexed, otherwise through all the accounts
+    pub fn ordered_range_scan_accounts<F, R>(
+        &self,
+        ancestors: &Ancestors,
+        bank_id: BankId,
+        index_key: Option<IndexKey>,
+        range: R,
+        mut scan_func: F,
+        config: &ScanConfig,
+    ) -> ScanResult<()>
+    where
+        F: FnMut(Option<(&Pubkey, AccountSharedData, Slot)>),
+        R: RangeBounds<Pubkey> + std::fmt::Debug,
+    {
+        let index_key = index_key.filter(|index_key| match index_key {
+            IndexKey::ProgramId(key)
+            | IndexKey::SplTokenMint(key)
+            | IndexKey::SplTokenOwner(key) => self.account_indexes.include_key(key),
+            IndexKey::Memcmp(memcmp_index, _) => {
+                self.account_indexes.memcmp_indexes.contains(memcmp_index)
+            }
+        });
+        self.accounts_index.ordered_range_scan_accounts(
+            ancestors,
+            bank_id,
+            index_key,
+            range,
+            |pubkey, (account_info, slot)| {
+                let account_slot = self
+                    .get_account_accessor(slot, pubkey, &account_info.storage_location())
+                    .get_loaded_account()
+                    .map(|loaded_account| (pubkey, loaded_account.take_account(), slot));
+                scan_func(account_slot)
+            },
+            config,
+        )?;
+        Ok(())
+    }
+
     /// Scan a specific slot through all the account storage
     pub fn scan_account_storage<R, B>(
         &self,
@@ -5709,6 +5790,28 @@ impl AccountsDb {
         store
     }
 
+    /// Creates a store for `slot` backed by a new cold tiered storage, without inserting it.
+    pub(crate) fn create_cold_store(&self, slot: Slot) -> Arc<AccountStorageEntry> {
+        self.stats
+            .create_store_count
+            .fetch_add(1, Ordering::Relaxed);
+        let path_index = thread_rng().gen_range(0..self.paths.len());
+        let store = Arc::new(AccountStorageEntry::new_cold(
+            Path::new(&self.paths[path_index]),
+            slot,
+            self.next_id(),
+        ));
+
+        debug!(
+            "creating cold store: {} slot: {} path: {:?}",
+            store.append_vec_id(),
+            slot,
+            store.accounts.get_path()
+        );
+
+        store
+    }
+
     fn create_and_insert_store(
         &self,
         slot: Slot,
@@ -17190,6 +17293,73 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_combine_ancient_slots_cold_storage() {
+        solana_logger::setup();
+        for alive in [false, true] {
+            let (mut db, slot1) = create_db_with_storages_and_index(alive, 2, None);
+            db.ancient_cold_storage = true;
+            let storage = db.get_storage_for_slot(slot1).unwrap();
+            let created_accounts = get_all_accounts_from_storages(std::iter::once(&storage));
+            drop(storage);
+
+            db.combine_ancient_slots(vec![slot1], CAN_RANDOMLY_SHRINK_FALSE);
+            let cold_storage = db.get_storage_for_slot(slot1).unwrap();
+            // a slot without alive accounts is left alone
+            assert_eq!(
+                alive,
+                matches!(cold_storage.accounts, AccountsFile::TieredStorage(_))
+            );
+            if !alive {
+                continue;
+            }
+            assert!(is_ancient(&cold_storage.accounts));
+
+            // read the accounts back through the accounts file
+            assert_eq!(
+                get_all_accounts_from_storages(std::iter::once(&cold_storage)),
+                created_accounts
+            );
+            let (stored_account, _) = cold_storage.accounts.get_account(0).unwrap();
+            assert_eq!(
+                cold_storage
+                    .accounts
+                    .account_matches_owners(0, &[*stored_account.owner()]),
+                Ok(0)
+            );
+
+            // and through the accounts index
+            let ancestors = Ancestors::from(vec![slot1]);
+            for (pubkey, account) in &created_accounts {
+                let (loaded_account, slot) =
+                    db.load_without_fixed_root(&ancestors, pubkey).unwrap();
+                assert_eq!(slot, slot1);
+                assert!(accounts_equal(&loaded_account, account));
+            }
+
+            // the cold storage is not rewritten while its accounts are alive
+            db.combine_ancient_slots(vec![slot1], CAN_RANDOMLY_SHRINK_FALSE);
+            assert_eq!(
+                db.get_storage_for_slot(slot1).unwrap().append_vec_id(),
+                cold_storage.append_vec_id()
+            );
+
+            // but it is shrunk into a new cold storage once enough accounts are dead
+            cold_storage.remove_account(stored_account.stored_size(), false);
+            db.combine_ancient_slots(vec![slot1], CAN_RANDOMLY_SHRINK_FALSE);
+            let shrunk_storage = db.get_storage_for_slot(slot1).unwrap();
+            assert_ne!(shrunk_storage.append_vec_id(), cold_storage.append_vec_id());
+            assert!(matches!(
+                shrunk_storage.accounts,
+                AccountsFile::TieredStorage(_)
+            ));
+            assert_eq!(
+                get_all_accounts_from_storages(std::iter::once(&shrunk_storage)),
+                created_accounts
+            );
+        }
+    }
+
     fn get_all_accounts_from_storages<'a>(
         storages: impl Iterator<Item = &'a Arc<AccountStorageEntry>>,
     ) -> Vec<(Pubkey, AccountSharedData)> {
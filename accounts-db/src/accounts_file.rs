@@ -6,7 +6,9 @@ use {
         accounts_hash::AccountHash,
         append_vec::{AppendVec, AppendVecError},
         storable_accounts::StorableAccounts,
-        tiered_storage::error::TieredStorageError,
+        tiered_storage::{
+            cold::COLD_FORMAT, error::TieredStorageError, index::IndexOffset, TieredStorage,
+        },
     },
     solana_sdk::{account::ReadableAccount, clock::Slot, pubkey::Pubkey},
     std::{
@@ -27,6 +29,16 @@ macro_rules! u64_align {
     };
 }
 
+/// Returns the offset the accounts index refers to the tiered storage account
+/// at the specified index by.
+///
+/// Tiered storages address their accounts by index, which is scaled to an
+/// aligned offset as `AccountInfo` only keeps offsets divided by
+/// `ALIGN_BOUNDARY_OFFSET`.
+pub(crate) fn tiered_storage_offset(index: usize) -> usize {
+    index * ALIGN_BOUNDARY_OFFSET
+}
+
 #[derive(Error, Debug)]
 /// An enum for AccountsFile related errors.
 pub enum AccountsFileError {
@@ -55,6 +67,10 @@ pub type Result<T> = std::result::Result<T, AccountsFileError>;
 /// under different formats.
 pub enum AccountsFile {
     AppendVec(AppendVec),
+    /// A tiered storage, which is written once.  Ancient slots are rewritten
+    /// into cold tiered storages when `AccountsDbConfig::ancient_cold_storage`
+    /// is set.
+    TieredStorage(TieredStorage),
 }
 
 impl AccountsFile {
@@ -62,7 +78,16 @@ impl AccountsFile {
     ///
     /// The second element of the returned tuple is the number of accounts in the
     /// accounts file.
+    ///
+    /// Tiered storage files are recognized by their footer, and are always read
+    /// in full regardless of `current_len`.
     pub fn new_from_file(path: impl AsRef<Path>, current_len: usize) -> Result<(Self, usize)> {
+        if TieredStorage::is_tiered_storage_file(&path) {
+            let ts = TieredStorage::new_readonly(path.as_ref())?;
+            let num_accounts = ts.reader().map_or(0, |reader| reader.num_accounts());
+            return Ok((Self::TieredStorage(ts), num_accounts));
+        }
+
         let (av, num_accounts) = AppendVec::new_from_file(path, current_len)?;
         Ok((Self::AppendVec(av), num_accounts))
     }
@@ -70,42 +95,52 @@ impl AccountsFile {
     pub fn flush(&self) -> Result<()> {
         match self {
             Self::AppendVec(av) => av.flush(),
+            // Tiered storages are persisted when their accounts are written.
+            Self::TieredStorage(_) => Ok(()),
         }
     }
 
     pub fn reset(&self) {
         match self {
             Self::AppendVec(av) => av.reset(),
+            Self::TieredStorage(_) => {}
         }
     }
 
     pub fn remaining_bytes(&self) -> u64 {
         match self {
             Self::AppendVec(av) => av.remaining_bytes(),
+            Self::TieredStorage(_) => 0,
         }
     }
 
     pub fn len(&self) -> usize {
         match self {
             Self::AppendVec(av) => av.len(),
+            Self::TieredStorage(ts) => ts.file_size().unwrap_or_default() as usize,
         }
     }
 
     pub fn is_empty(&self) -> bool {
         match self {
             Self::AppendVec(av) => av.is_empty(),
+            Self::TieredStorage(ts) => ts
+                .reader()
+                .map_or(true, |reader| reader.num_accounts() == 0),
         }
     }
 
     pub fn capacity(&self) -> u64 {
         match self {
             Self::AppendVec(av) => av.capacity(),
+            Self::TieredStorage(ts) => ts.file_size().unwrap_or_default(),
         }
     }
 
     pub fn is_recyclable(&self) -> bool {
         match self {
             Self::AppendVec(_) => true,
+            Self::TieredStorage(_) => false,
         }
     }
 
@@ -119,6 +154,11 @@ impl AccountsFile {
     pub fn get_account(&self, index: usize) -> Option<(StoredAccountMeta<'_>, usize)> {
         match self {
             Self::AppendVec(av) => av.get_account(index),
+            Self::TieredStorage(ts) => {
+                let index_offset = IndexOffset(u32::try_from(index / ALIGN_BOUNDARY_OFFSET).ok()?);
+                let account = ts.reader()?.get_account(index_offset).ok()??;
+                Some((account, index.saturating_add(ALIGN_BOUNDARY_OFFSET)))
+            }
         }
     }
 
@@ -129,6 +169,19 @@ impl AccountsFile {
     ) -> std::result::Result<usize, MatchAccountOwnerError> {
         match self {
             Self::AppendVec(av) => av.account_matches_owners(offset, owners),
+            Self::TieredStorage(_) => {
+                let (account, _) = self
+                    .get_account(offset)
+                    .ok_or(MatchAccountOwnerError::UnableToLoad)?;
+                if account.lamports() == 0 {
+                    Err(MatchAccountOwnerError::NoMatch)
+                } else {
+                    owners
+                        .iter()
+                        .position(|entry| account.owner() == entry)
+                        .ok_or(MatchAccountOwnerError::NoMatch)
+                }
+            }
         }
     }
 
@@ -136,6 +189,7 @@ impl AccountsFile {
     pub fn get_path(&self) -> PathBuf {
         match self {
             Self::AppendVec(av) => av.get_path(),
+            Self::TieredStorage(ts) => ts.path().to_path_buf(),
         }
     }
 
@@ -148,6 +202,11 @@ impl AccountsFile {
     pub fn accounts(&self, offset: usize) -> Vec<StoredAccountMeta> {
         match self {
             Self::AppendVec(av) => av.accounts(offset),
+            Self::TieredStorage(_) => AccountsFileIter {
+                file_entry: self,
+                offset,
+            }
+            .collect(),
         }
     }
 
@@ -158,6 +217,8 @@ impl AccountsFile {
     /// So, return.len() is 1 + (number of accounts written)
     /// After each account is appended, the internal `current_len` is updated
     /// and will be available to other threads.
+    ///
+    /// A tiered storage is written by its first call only, in the cold format.
     pub fn append_accounts<
         'a,
         'b,
@@ -171,6 +232,18 @@ impl AccountsFile {
     ) -> Option<Vec<StoredAccountInfo>> {
         match self {
             Self::AppendVec(av) => av.append_accounts(accounts, skip),
+            Self::TieredStorage(ts) => ts
+                .write_accounts(accounts, skip, &COLD_FORMAT)
+                .map(|stored_infos| {
+                    stored_infos
+                        .into_iter()
+                        .map(|stored_info| StoredAccountInfo {
+                            offset: tiered_storage_offset(stored_info.offset),
+                            size: stored_info.size,
+                        })
+                        .collect()
+                })
+                .ok(),
         }
     }
 }
@@ -209,6 +282,7 @@ pub mod tests {
         pub(crate) fn set_current_len_for_tests(&self, len: usize) {
             match self {
                 Self::AppendVec(av) => av.set_current_len_for_tests(len),
+                Self::TieredStorage(_) => {}
             }
         }
     }
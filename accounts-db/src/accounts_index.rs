@@ -12,6 +12,7 @@ use {
         rolling_bit_field::RollingBitField,
         secondary_index::*,
     },
+    dashmap::DashMap,
     log::*,
     ouroboros::self_referencing,
     rand::{thread_rng, Rng},
@@ -24,10 +25,11 @@ use {
     solana_sdk::{
         account::ReadableAccount,
         clock::{BankId, Slot},
-        pubkey::Pubkey,
+        pubkey::{Pubkey, PUBKEY_BYTES},
     },
     std::{
-        collections::{btree_map::BTreeMap, HashSet},
+        cmp::Reverse,
+        collections::{btree_map::BTreeMap, BinaryHeap, HashSet},
         fmt::Debug,
         ops::{
             Bound,
@@ -35,6 +37,7 @@ use {
             Range, RangeBounds,
         },
         path::PathBuf,
+        str::FromStr,
         sync::{
             atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
             Arc, Mutex, OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard,
@@ -158,7 +161,8 @@ pub enum ScanError {
 
 enum ScanTypes<R: RangeBounds<Pubkey>> {
     Unindexed(Option<R>),
-    Indexed(IndexKey),
+    /// The range restricts the scan to the indexed keys within it, scanned in order
+    Indexed(IndexKey, Option<R>),
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -166,6 +170,8 @@ pub enum IndexKey {
     ProgramId(Pubkey),
     SplTokenMint(Pubkey),
     SplTokenOwner(Pubkey),
+    /// The key of `MemcmpIndex::index_key()`
+    Memcmp(MemcmpIndex, Pubkey),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -175,6 +181,64 @@ pub enum AccountIndex {
     SplTokenOwner,
 }
 
+/// A custom secondary index on the `length` bytes at `offset` in the data of the accounts
+/// owned by `program_id`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MemcmpIndex {
+    pub program_id: Pubkey,
+    pub offset: usize,
+    pub length: usize,
+}
+
+impl MemcmpIndex {
+    /// Returns the index key of the indexed bytes of `data`, or None if `data` is too short
+    pub fn index_key(&self, data: &[u8]) -> Option<Pubkey> {
+        let end = self.offset.checked_add(self.length)?;
+        self.index_key_from_bytes(data.get(self.offset..end)?)
+    }
+
+    /// Returns the index key of the indexed bytes, zero-padded to a `Pubkey`, or None if
+    /// `bytes` does not have the indexed length
+    pub fn index_key_from_bytes(&self, bytes: &[u8]) -> Option<Pubkey> {
+        (bytes.len() == self.length).then(|| {
+            let mut key = [0; PUBKEY_BYTES];
+            key[..bytes.len()].copy_from_slice(bytes);
+            Pubkey::new_from_array(key)
+        })
+    }
+}
+
+impl FromStr for MemcmpIndex {
+    type Err = String;
+
+    /// Parses `PROGRAM_ID:OFFSET:LENGTH`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':');
+        let (Some(program_id), Some(offset), Some(length), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(format!("expected PROGRAM_ID:OFFSET:LENGTH, got {s}"));
+        };
+        let program_id = program_id
+            .parse()
+            .map_err(|err| format!("invalid program id {program_id}: {err}"))?;
+        let offset = offset
+            .parse()
+            .map_err(|err| format!("invalid offset {offset}: {err}"))?;
+        let length = length
+            .parse()
+            .map_err(|err| format!("invalid length {length}: {err}"))?;
+        if !(1..=PUBKEY_BYTES).contains(&length) {
+            return Err(format!("length must be between 1 and {PUBKEY_BYTES}"));
+        }
+        Ok(Self {
+            program_id,
+            offset,
+            length,
+        })
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct AccountSecondaryIndexesIncludeExclude {
     pub exclude: bool,
@@ -214,11 +278,13 @@ pub struct AccountsIndexConfig {
 pub struct AccountSecondaryIndexes {
     pub keys: Option<AccountSecondaryIndexesIncludeExclude>,
     pub indexes: HashSet<AccountIndex>,
+    /// The custom indexes, which ignore `keys`
+    pub memcmp_indexes: HashSet<MemcmpIndex>,
 }
 
 impl AccountSecondaryIndexes {
     pub fn is_empty(&self) -> bool {
-        self.indexes.is_empty()
+        self.indexes.is_empty() && self.memcmp_indexes.is_empty()
     }
     pub fn contains(&self, index: &AccountIndex) -> bool {
         self.indexes.contains(index)
@@ -682,6 +748,8 @@ pub struct AccountsIndex<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> {
     program_id_index: SecondaryIndex<DashMapSecondaryIndexEntry>,
     spl_token_mint_index: SecondaryIndex<DashMapSecondaryIndexEntry>,
     spl_token_owner_index: SecondaryIndex<RwLockSecondaryIndexEntry>,
+    /// The custom secondary indexes, created when their first account is indexed
+    memcmp_indexes: DashMap<MemcmpIndex, SecondaryIndex<DashMapSecondaryIndexEntry>>,
     pub roots_tracker: RwLock<RootsTracker>,
     ongoing_scan_roots: RwLock<BTreeMap<Slot, u64>>,
     // Each scan has some latest slot `S` that is the tip of the fork the scan
@@ -736,6 +804,7 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
             spl_token_owner_index: SecondaryIndex::<RwLockSecondaryIndexEntry>::new(
                 "spl_token_owner_index_stats",
             ),
+            memcmp_indexes: DashMap::default(),
             roots_tracker: RwLock::<RootsTracker>::default(),
             ongoing_scan_roots: RwLock::<BTreeMap<Slot, u64>>::default(),
             removed_bank_ids: Mutex::<HashSet<BankId>>::default(),
@@ -958,36 +1027,53 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
                 // Pass "" not to log metrics, so RPC doesn't get spammy
                 self.do_scan_accounts(metric_name, ancestors, func, range, Some(max_root), config);
             }
-            ScanTypes::Indexed(IndexKey::ProgramId(program_id)) => {
+            ScanTypes::Indexed(IndexKey::ProgramId(program_id), range) => {
                 self.do_scan_secondary_index(
                     ancestors,
                     func,
                     &self.program_id_index,
                     &program_id,
+                    range.as_ref(),
                     Some(max_root),
                     config,
                 );
             }
-            ScanTypes::Indexed(IndexKey::SplTokenMint(mint_key)) => {
+            ScanTypes::Indexed(IndexKey::SplTokenMint(mint_key), range) => {
                 self.do_scan_secondary_index(
                     ancestors,
                     func,
                     &self.spl_token_mint_index,
                     &mint_key,
+                    range.as_ref(),
                     Some(max_root),
                     config,
                 );
             }
-            ScanTypes::Indexed(IndexKey::SplTokenOwner(owner_key)) => {
+            ScanTypes::Indexed(IndexKey::SplTokenOwner(owner_key), range) => {
                 self.do_scan_secondary_index(
                     ancestors,
                     func,
                     &self.spl_token_owner_index,
                     &owner_key,
+                    range.as_ref(),
                     Some(max_root),
                     config,
                 );
             }
+            ScanTypes::Indexed(IndexKey::Memcmp(memcmp_index, key), range) => {
+                // No account has been indexed yet if the index does not exist
+                if let Some(index) = self.memcmp_indexes.get(&memcmp_index) {
+                    self.do_scan_secondary_index(
+                        ancestors,
+                        func,
+                        index.value(),
+                        &key,
+                        range.as_ref(),
+                        Some(max_root),
+                        config,
+                    );
+                }
+            }
         }
 
         {
@@ -1097,6 +1183,7 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
 
     fn do_scan_secondary_index<
         F,
+        R,
         SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send,
     >(
         &self,
@@ -1104,12 +1191,29 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
         mut func: F,
         index: &SecondaryIndex<SecondaryIndexEntryType>,
         index_key: &Pubkey,
+        range: Option<&R>,
         max_root: Option<Slot>,
         config: &ScanConfig,
     ) where
         F: FnMut(&Pubkey, (&T, Slot)),
+        R: RangeBounds<Pubkey>,
     {
-        for pubkey in index.get(index_key) {
+        let pubkeys: Box<dyn Iterator<Item = Pubkey>> = match range {
+            Some(range) => {
+                // The keys are ordered lazily, as a paged scan is aborted once it
+                // has visited a page of them.
+                let mut pubkeys: BinaryHeap<_> = index
+                    .get_in_range(index_key, range)
+                    .into_iter()
+                    .map(Reverse)
+                    .collect();
+                Box::new(std::iter::from_fn(move || {
+                    pubkeys.pop().map(|Reverse(pubkey)| pubkey)
+                }))
+            }
+            None => Box::new(index.get(index_key).into_iter()),
+        };
+        for pubkey in pubkeys {
             // Maybe these reads from the AccountsIndex can be batched every time it
             // grabs the read lock as well...
             if let AccountIndexGetResult::Found(list_r, index) =
@@ -1246,11 +1350,34 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
             ancestors,
             scan_bank_id,
             func,
-            ScanTypes::<Range<Pubkey>>::Indexed(index_key),
+            ScanTypes::<Range<Pubkey>>::Indexed(index_key, None),
             config,
         )
     }
 
+    /// Scans the accounts in `range`, in pubkey order, through the secondary index of
+    /// `index_key` if there is one. The scan is unordered if `config.collect_all_unsorted`.
+    pub(crate) fn ordered_range_scan_accounts<F, R>(
+        &self,
+        ancestors: &Ancestors,
+        scan_bank_id: BankId,
+        index_key: Option<IndexKey>,
+        range: R,
+        func: F,
+        config: &ScanConfig,
+    ) -> Result<(), ScanError>
+    where
+        F: FnMut(&Pubkey, (&T, Slot)),
+        R: RangeBounds<Pubkey> + std::fmt::Debug,
+    {
+        let scan_type = match index_key {
+            Some(index_key) => ScanTypes::Indexed(index_key, Some(range)),
+            None => ScanTypes::Unindexed(Some(range)),
+        };
+        // Pass "" not to log metrics, so RPC doesn't get spammy
+        self.do_checked_scan_accounts("", ancestors, scan_bank_id, func, scan_type, config)
+    }
+
     pub fn get_rooted_entries(
         &self,
         slice: SlotSlice<T>,
@@ -1531,6 +1658,12 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
             info!("secondary index: {:?}", AccountIndex::SplTokenOwner);
             self.spl_token_owner_index.log_contents();
         }
+        for entry in self.memcmp_indexes.iter() {
+            if !entry.value().index.is_empty() {
+                info!("secondary index: {:?}", entry.key());
+                entry.value().log_contents();
+            }
+        }
     }
 
     pub(crate) fn update_secondary_indexes(
@@ -1579,6 +1712,23 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
             account_data,
             account_indexes,
         );
+
+        for memcmp_index in &account_indexes.memcmp_indexes {
+            if memcmp_index.program_id != *account_owner {
+                continue;
+            }
+            if let Some(key) = memcmp_index.index_key(account_data) {
+                self.memcmp_indexes
+                    .get(memcmp_index)
+                    .unwrap_or_else(|| {
+                        self.memcmp_indexes
+                            .entry(*memcmp_index)
+                            .or_insert_with(|| SecondaryIndex::new("memcmp_index_stats"))
+                            .downgrade()
+                    })
+                    .insert(&key, pubkey);
+            }
+        }
     }
 
     pub(crate) fn get_bin(&self, pubkey: &Pubkey) -> AccountMaps<T, U> {
@@ -1809,6 +1959,10 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
         if account_indexes.contains(&AccountIndex::SplTokenMint) {
             self.spl_token_mint_index.remove_by_inner_key(inner_key);
         }
+
+        for index in self.memcmp_indexes.iter() {
+            index.remove_by_inner_key(inner_key);
+        }
     }
 
     fn purge_older_root_entries(
@@ -2069,6 +2223,7 @@ pub mod tests {
         AccountSecondaryIndexes {
             indexes: account_indexes,
             keys: None,
+            memcmp_indexes: HashSet::new(),
         }
     }
 
@@ -2078,6 +2233,7 @@ pub mod tests {
         AccountSecondaryIndexes {
             indexes: account_indexes,
             keys: None,
+            memcmp_indexes: HashSet::new(),
         }
     }
 
@@ -3004,6 +3160,79 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_ordered_range_scan_accounts_indexed() {
+        let index = AccountsIndex::<bool, bool>::default_for_tests();
+        let program_id = Pubkey::new_unique();
+        let secondary_indexes = AccountSecondaryIndexes {
+            indexes: HashSet::from([AccountIndex::ProgramId]),
+            ..AccountSecondaryIndexes::default()
+        };
+        let mut pubkeys: Vec<_> = std::iter::repeat_with(solana_sdk::pubkey::new_rand)
+            .take(10)
+            .collect();
+        for pubkey in &pubkeys {
+            index.upsert(
+                0,
+                0,
+                pubkey,
+                &AccountSharedData::create(1, vec![], program_id, false, 0),
+                &secondary_indexes,
+                true,
+                &mut vec![],
+                UPSERT_POPULATE_RECLAIMS,
+            );
+        }
+        // An account of another program is not scanned
+        index.upsert(
+            0,
+            0,
+            &solana_sdk::pubkey::new_rand(),
+            &AccountSharedData::create(1, vec![], Pubkey::new_unique(), false, 0),
+            &secondary_indexes,
+            true,
+            &mut vec![],
+            UPSERT_POPULATE_RECLAIMS,
+        );
+        index.add_root(0);
+        pubkeys.sort_unstable();
+
+        // Page through the accounts of the program, 3 at a time
+        let mut after = None;
+        let mut pages = vec![];
+        loop {
+            let config = ScanConfig::default().recreate_with_abort();
+            let mut page = vec![];
+            index
+                .ordered_range_scan_accounts(
+                    &Ancestors::default(),
+                    0,
+                    Some(IndexKey::ProgramId(program_id)),
+                    (after.map_or(Unbounded, Excluded), Unbounded),
+                    |pubkey, _index| {
+                        page.push(*pubkey);
+                        if page.len() == 3 {
+                            config.abort();
+                        }
+                    },
+                    &config,
+                )
+                .unwrap();
+            let Some(last) = page.last() else {
+                break;
+            };
+            after = Some(*last);
+            pages.push(page);
+        }
+        assert_eq!(
+            pages,
+            pubkeys
+                .chunks(3)
+                .map(<[Pubkey]>::to_vec)
+                .collect::<Vec<_>>()
+        );
+    }
+
     fn run_test_scan_accounts(num_pubkeys: usize) {
         let (index, _) = setup_accounts_index_keys(num_pubkeys);
         let ancestors = Ancestors::default();
@@ -3682,6 +3911,79 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_memcmp_secondary_index() {
+        let program_id = Pubkey::new_unique();
+        let memcmp_index = format!("{program_id}:8:4").parse::<MemcmpIndex>().unwrap();
+        assert_eq!(
+            memcmp_index,
+            MemcmpIndex {
+                program_id,
+                offset: 8,
+                length: 4,
+            }
+        );
+        for invalid in [
+            "8:4".to_string(),
+            format!("{program_id}:8"),
+            format!("{program_id}:8:0"),
+            format!("{program_id}:8:33"),
+            format!("{program_id}:-1:4"),
+        ] {
+            assert!(invalid.parse::<MemcmpIndex>().is_err(), "{invalid}");
+        }
+
+        let secondary_indexes = AccountSecondaryIndexes {
+            memcmp_indexes: HashSet::from([memcmp_index]),
+            ..AccountSecondaryIndexes::default()
+        };
+        assert!(!secondary_indexes.is_empty());
+        let index = AccountsIndex::<bool, bool>::default_for_tests();
+        let account_key = Pubkey::new_unique();
+        let account_data = vec![0, 0, 0, 0, 0, 0, 0, 0, 1, 2, 3, 4];
+        let mut index_key = [0; PUBKEY_BYTES];
+        index_key[..4].copy_from_slice(&[1, 2, 3, 4]);
+        let index_key = Pubkey::from(index_key);
+        assert_eq!(memcmp_index.index_key(&account_data), Some(index_key));
+
+        // Wrong program id, or too short account data
+        index.update_secondary_indexes(
+            &account_key,
+            &AccountSharedData::create(0, account_data.clone(), Pubkey::default(), false, 0),
+            &secondary_indexes,
+        );
+        index.update_secondary_indexes(
+            &account_key,
+            &AccountSharedData::create(0, account_data[..11].to_vec(), program_id, false, 0),
+            &secondary_indexes,
+        );
+        assert!(index.memcmp_indexes.is_empty());
+
+        // Just right
+        index.upsert(
+            0,
+            0,
+            &account_key,
+            &AccountSharedData::create(0, account_data, program_id, false, 0),
+            &secondary_indexes,
+            true,
+            &mut vec![],
+            UPSERT_POPULATE_RECLAIMS,
+        );
+        check_secondary_index_mapping_correct(
+            index.memcmp_indexes.get(&memcmp_index).unwrap().value(),
+            &[index_key],
+            &account_key,
+        );
+
+        // Everything should be deleted
+        index.slot_list_mut(&account_key, |slot_list| slot_list.clear());
+        let _ = index.handle_dead_keys(&[&account_key], &secondary_indexes);
+        let secondary_index = index.memcmp_indexes.get(&memcmp_index).unwrap();
+        assert!(secondary_index.index.is_empty());
+        assert!(secondary_index.reverse_index.is_empty());
+    }
+
     #[test]
     fn test_rwlock_secondary_index() {
         let (key_start, key_end, secondary_indexes) = create_rwlock_secondary_index_state();
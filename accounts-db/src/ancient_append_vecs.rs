@@ -275,6 +275,86 @@ impl AccountsDb {
         self.shrink_ancient_stats.report();
     }
 
+    /// Rewrite the alive accounts of the storage at 'slot' into a new cold tiered storage, which
+    /// replaces it.
+    /// Accounts in ancient slots are rarely accessed, so keeping them compressed saves disk space.
+    /// A cold storage is only rewritten once enough of its accounts are dead, just like an
+    /// ancient append vec is only shrunk then.
+    /// Returns false if there is no storage at 'slot', it is a cold storage with few dead
+    /// accounts or it has no alive accounts.
+    pub(crate) fn write_ancient_slot_to_cold_storage(&self, slot: Slot) -> bool {
+        let Some(old_storage) = self.storage.get_slot_storage_entry(slot) else {
+            return false;
+        };
+        if matches!(old_storage.accounts, AccountsFile::TieredStorage(_)) {
+            self.shrink_ancient_stats
+                .ancient_scanned
+                .fetch_add(1, Ordering::Relaxed);
+            // the file is compressed, so its size cannot be compared to the alive bytes.
+            // Use the ratio of alive accounts instead.
+            let stored_count = old_storage.approx_stored_count();
+            let alive_ratio = if stored_count > 0 {
+                old_storage.count() * 100 / stored_count
+            } else {
+                100
+            };
+            if alive_ratio >= 90 {
+                return false;
+            }
+            log::info!(
+                "ancient_append_vec: shrinking cold storage: {}, alive_ratio: {}",
+                slot,
+                alive_ratio
+            );
+            self.shrink_ancient_stats
+                .ancient_append_vecs_shrunk
+                .fetch_add(1, Ordering::Relaxed);
+        }
+
+        let unique_accounts = self.get_unique_accounts_from_storage_for_shrink(
+            &old_storage,
+            &self.shrink_ancient_stats.shrink_stats,
+        );
+        let shrink_collect = self.shrink_collect::<AliveAccounts<'_>>(
+            &old_storage,
+            &unique_accounts,
+            &self.shrink_ancient_stats.shrink_stats,
+        );
+        if shrink_collect.total_starting_accounts == 0 || shrink_collect.alive_total_bytes == 0 {
+            return false; // skipping slot with no useful accounts to write
+        }
+
+        let mut stats_sub = ShrinkStatsSub::default();
+        let (shrink_in_progress, create_and_insert_store_elapsed_us) = measure_us!(self
+            .storage
+            .shrinking_in_progress(slot, self.create_cold_store(slot)));
+        stats_sub.create_and_insert_store_elapsed_us = create_and_insert_store_elapsed_us;
+
+        // a cold storage is written once, so all the alive accounts are stored in one call
+        let (store_accounts_timing, rewrite_elapsed_us) = measure_us!(self.store_accounts_frozen(
+            (slot, &shrink_collect.alive_accounts.alive_accounts()[..]),
+            None::<Vec<AccountHash>>,
+            shrink_in_progress.new_storage(),
+            None,
+            StoreReclaims::Ignore,
+        ));
+        stats_sub.store_accounts_timing = store_accounts_timing;
+        stats_sub.rewrite_elapsed_us = rewrite_elapsed_us;
+
+        self.remove_old_stores_shrink(
+            &shrink_collect,
+            &self.shrink_ancient_stats.shrink_stats,
+            Some(shrink_in_progress),
+            false,
+        );
+
+        // we should not try to shrink this slot anymore. All shrinking for this slot is now handled by ancient append vec code.
+        self.shrink_candidate_slots.lock().unwrap().remove(&slot);
+
+        Self::update_shrink_stats(&self.shrink_ancient_stats.shrink_stats, stats_sub, true);
+        true
+    }
+
     /// return false if `many_refs_newest` accounts cannot be moved into `target_slots_sorted`.
     /// The slot # would be violated.
     /// accounts in `many_refs_newest` must be moved a slot >= each account's current slot.
@@ -931,10 +1011,12 @@ pub fn get_ancient_append_vec_capacity() -> u64 {
     MAXIMUM_APPEND_VEC_FILE_SIZE / 10 - 2048
 }
 
-/// is this a max-size append vec designed to be used as an ancient append vec?
+/// is this a max-size append vec designed to be used as an ancient append vec, or a cold tiered
+/// storage an ancient slot was rewritten into?
 pub fn is_ancient(storage: &AccountsFile) -> bool {
     match storage {
         AccountsFile::AppendVec(storage) => storage.capacity() >= get_ancient_append_vec_capacity(),
+        AccountsFile::TieredStorage(_) => true,
     }
 }
 
@@ -209,6 +209,9 @@ pub struct AppendVec {
 
     /// The number of bytes available for storing items.
     file_size: u64,
+
+    /// True if the file should automatically be deleted when this AppendVec is dropped.
+    remove_on_drop: bool,
 }
 
 lazy_static! {
@@ -218,6 +221,9 @@ lazy_static! {
 impl Drop for AppendVec {
     fn drop(&mut self) {
         APPEND_VEC_MMAPPED_FILES_OPEN.fetch_sub(1, Ordering::Relaxed);
+        if !self.remove_on_drop {
+            return;
+        }
         if let Err(_err) = remove_file(&self.path) {
             // promote this to panic soon.
             // disabled due to many false positive warnings while running tests.
@@ -281,6 +287,7 @@ impl AppendVec {
             append_lock: Mutex::new(()),
             current_len: AtomicUsize::new(initial_len),
             file_size: size as u64,
+            remove_on_drop: true,
         }
     }
 
@@ -310,6 +317,11 @@ impl AppendVec {
         Ok(())
     }
 
+    /// Keeps the underlying file when this AppendVec is dropped.
+    pub fn set_no_remove_on_drop(&mut self) {
+        self.remove_on_drop = false;
+    }
+
     pub fn reset(&self) {
         // This mutex forces append to be single threaded, but concurrent with reads
         // See UNSAFE usage in `append_ptr`
@@ -381,6 +393,7 @@ impl AppendVec {
             append_lock: Mutex::new(()),
             current_len: AtomicUsize::new(current_len),
             file_size,
+            remove_on_drop: true,
         })
     }
 
@@ -680,7 +693,7 @@ pub mod tests {
             match self {
                 Self::AppendVec(av) => av.ref_executable_byte(),
                 // Tests currently only cover AppendVec.
-                Self::Hot(_) => unreachable!(),
+                Self::Hot(_) | Self::Cold(_) => unreachable!(),
             }
         }
     }
@@ -938,6 +951,23 @@ pub mod tests {
         assert_matches!(result, Err(ref message) if message.to_string().contains("too small file size 0 for AppendVec"));
     }
 
+    #[test]
+    fn test_append_vec_remove_on_drop() {
+        let file = get_append_vec_path("test_append_vec_remove_on_drop");
+        let path = &file.path;
+
+        {
+            let _av = AppendVec::new(path, true, 1024);
+        }
+        assert!(!path.exists());
+
+        {
+            let mut av = AppendVec::new(path, true, 1024);
+            av.set_no_remove_on_drop();
+        }
+        assert!(path.exists());
+    }
+
     #[test]
     fn test_append_vec_sanitize_len_and_size_too_small() {
         const LEN: usize = 0;
@@ -5,6 +5,7 @@ use {
     std::{
         collections::HashSet,
         fmt::Debug,
+        ops::RangeBounds,
         sync::{
             atomic::{AtomicU64, Ordering},
             RwLock,
@@ -24,6 +25,8 @@ pub trait SecondaryIndexEntry: Debug {
     fn remove_inner_key(&self, key: &Pubkey) -> bool;
     fn is_empty(&self) -> bool;
     fn keys(&self) -> Vec<Pubkey>;
+    // Returns, unordered, the keys within `range`.
+    fn keys_in_range<R: RangeBounds<Pubkey>>(&self, range: &R) -> Vec<Pubkey>;
     fn len(&self) -> usize;
 }
 
@@ -62,6 +65,14 @@ impl SecondaryIndexEntry for DashMapSecondaryIndexEntry {
             .collect()
     }
 
+    fn keys_in_range<R: RangeBounds<Pubkey>>(&self, range: &R) -> Vec<Pubkey> {
+        self.account_keys
+            .iter()
+            .map(|entry_ref| *entry_ref.key())
+            .filter(|key| range.contains(key))
+            .collect()
+    }
+
     fn len(&self) -> usize {
         self.account_keys.len()
     }
@@ -94,6 +105,16 @@ impl SecondaryIndexEntry for RwLockSecondaryIndexEntry {
         self.account_keys.read().unwrap().iter().cloned().collect()
     }
 
+    fn keys_in_range<R: RangeBounds<Pubkey>>(&self, range: &R) -> Vec<Pubkey> {
+        self.account_keys
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|key| range.contains(key))
+            .cloned()
+            .collect()
+    }
+
     fn len(&self) -> usize {
         self.account_keys.read().unwrap().len()
     }
@@ -223,6 +244,14 @@ impl<SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send>
         }
     }
 
+    pub fn get_in_range<R: RangeBounds<Pubkey>>(&self, key: &Pubkey, range: &R) -> Vec<Pubkey> {
+        if let Some(inner_keys_map) = self.index.get(key) {
+            inner_keys_map.keys_in_range(range)
+        } else {
+            vec![]
+        }
+    }
+
     /// log top 20 (owner, # accounts) in descending order of # accounts
     pub fn log_contents(&self) {
         let mut entries = self
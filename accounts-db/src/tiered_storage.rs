@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 
 pub mod byte_block;
+pub mod cold;
 pub mod error;
 pub mod file;
 pub mod footer;
@@ -19,7 +20,7 @@ use {
         storable_accounts::StorableAccounts,
     },
     error::TieredStorageError,
-    footer::{AccountBlockFormat, AccountMetaFormat, OwnersBlockFormat},
+    footer::{AccountBlockFormat, AccountMetaFormat, OwnersBlockFormat, TieredStorageFooter},
     index::IndexBlockFormat,
     readable::TieredStorageReader,
     solana_sdk::account::ReadableAccount,
@@ -82,6 +83,12 @@ impl TieredStorage {
         })
     }
 
+    /// Returns true if the file at the specified path is a tiered storage
+    /// accounts file, judging by the magic number at its end.
+    pub fn is_tiered_storage_file(path: impl AsRef<Path>) -> bool {
+        path.as_ref().is_file() && TieredStorageFooter::new_from_path(path).is_ok()
+    }
+
     /// Returns the path to this TieredStorage.
     pub fn path(&self) -> &Path {
         self.path.as_path()
@@ -153,11 +160,12 @@ mod tests {
     use {
         super::*,
         crate::account_storage::meta::{StoredMeta, StoredMetaWriteVersion},
-        footer::{TieredStorageFooter, TieredStorageMagicNumber},
+        footer::TieredStorageMagicNumber,
         hot::HOT_FORMAT,
+        index::IndexOffset,
         solana_accounts_db::rent_collector::RENT_EXEMPT_RENT_EPOCH,
         solana_sdk::{
-            account::{Account, AccountSharedData},
+            account::{accounts_equal, Account, AccountSharedData},
             clock::Slot,
             hash::Hash,
             pubkey::Pubkey,
@@ -196,7 +204,7 @@ mod tests {
                 Err(TieredStorageError::AttemptToUpdateReadOnly(_)),
                 Err(TieredStorageError::AttemptToUpdateReadOnly(_)),
             ) => {}
-            (Err(TieredStorageError::Unsupported()), Err(TieredStorageError::Unsupported())) => {}
+            (Ok(_), Ok(_)) => {}
             // we don't expect error type mis-match or other error types here
             _ => {
                 panic!("actual: {result:?}, expected: {expected_result:?}");
@@ -225,10 +233,7 @@ mod tests {
             assert_eq!(tiered_storage.path(), tiered_storage_path);
             assert_eq!(tiered_storage.file_size().unwrap(), 0);
 
-            // Expect the result to be TieredStorageError::Unsupported as the feature
-            // is not yet fully supported, but we can still check its partial results
-            // in the test.
-            write_zero_accounts(&tiered_storage, Err(TieredStorageError::Unsupported()));
+            write_zero_accounts(&tiered_storage, Ok(vec![]));
         }
 
         let tiered_storage_readonly = TieredStorage::new_readonly(&tiered_storage_path).unwrap();
@@ -253,10 +258,7 @@ mod tests {
         let tiered_storage_path = temp_dir.path().join("test_write_accounts_twice");
 
         let tiered_storage = TieredStorage::new_writable(&tiered_storage_path);
-        // Expect the result to be TieredStorageError::Unsupported as the feature
-        // is not yet fully supported, but we can still check its partial results
-        // in the test.
-        write_zero_accounts(&tiered_storage, Err(TieredStorageError::Unsupported()));
+        write_zero_accounts(&tiered_storage, Ok(vec![]));
         // Expect AttemptToUpdateReadOnly error as write_accounts can only
         // be invoked once.
         write_zero_accounts(
@@ -274,7 +276,7 @@ mod tests {
         let tiered_storage_path = temp_dir.path().join("test_remove_on_drop");
         {
             let tiered_storage = TieredStorage::new_writable(&tiered_storage_path);
-            write_zero_accounts(&tiered_storage, Err(TieredStorageError::Unsupported()));
+            write_zero_accounts(&tiered_storage, Ok(vec![]));
         }
         // expect the file does not exists as it has been removed on drop
         assert!(!tiered_storage_path.try_exists().unwrap());
@@ -282,7 +284,7 @@ mod tests {
         {
             let tiered_storage =
                 ManuallyDrop::new(TieredStorage::new_writable(&tiered_storage_path));
-            write_zero_accounts(&tiered_storage, Err(TieredStorageError::Unsupported()));
+            write_zero_accounts(&tiered_storage, Ok(vec![]));
         }
         // expect the file exists as we have ManuallyDrop this time.
         assert!(tiered_storage_path.try_exists().unwrap());
@@ -355,43 +357,66 @@ mod tests {
         let storable_accounts =
             StorableAccountsWithHashesAndWriteVersions::new_with_hashes_and_write_versions(
                 &account_data,
-                hashes,
+                hashes.clone(),
                 write_versions,
             );
 
         let temp_dir = tempdir().unwrap();
         let tiered_storage_path = temp_dir.path().join(path_suffix);
         let tiered_storage = TieredStorage::new_writable(tiered_storage_path);
-        _ = tiered_storage.write_accounts(&storable_accounts, 0, &format);
+        let stored_infos = tiered_storage
+            .write_accounts(&storable_accounts, 0, &format)
+            .unwrap();
+        assert_eq!(stored_infos.len(), accounts.len());
 
-        verify_hot_storage(&tiered_storage, &accounts, format);
+        verify_hot_storage(&tiered_storage, &accounts, &hashes, format);
     }
 
     /// Verify the generated tiered storage in the test.
     fn verify_hot_storage(
         tiered_storage: &TieredStorage,
         expected_accounts: &[(StoredMeta, AccountSharedData)],
+        expected_hashes: &[AccountHash],
         expected_format: TieredStorageFormat,
     ) {
         let reader = tiered_storage.reader().unwrap();
         assert_eq!(reader.num_accounts(), expected_accounts.len());
 
         let footer = reader.footer();
-        let expected_footer = TieredStorageFooter {
-            account_meta_format: expected_format.account_meta_format,
-            owners_block_format: expected_format.owners_block_format,
-            index_block_format: expected_format.index_block_format,
-            account_block_format: expected_format.account_block_format,
-            account_entry_count: expected_accounts.len() as u32,
-            // Hash is not yet implemented, so we bypass the check
-            hash: footer.hash,
-            ..TieredStorageFooter::default()
-        };
-
-        // TODO(yhchiang): verify account meta and data once the reader side
-        // is implemented in a separate PR.
+        assert_eq!(
+            footer.account_meta_format,
+            expected_format.account_meta_format
+        );
+        assert_eq!(
+            footer.owners_block_format,
+            expected_format.owners_block_format
+        );
+        assert_eq!(
+            footer.index_block_format,
+            expected_format.index_block_format
+        );
+        assert_eq!(
+            footer.account_block_format,
+            expected_format.account_block_format
+        );
+        assert_eq!(footer.account_entry_count, expected_accounts.len() as u32);
 
-        assert_eq!(*footer, expected_footer);
+        let TieredStorageReader::Hot(hot_reader) = reader else {
+            panic!("expect a hot storage reader");
+        };
+        for (i, (stored_meta, account)) in expected_accounts.iter().enumerate() {
+            let stored_account = hot_reader
+                .get_account(IndexOffset(i as u32))
+                .unwrap()
+                .unwrap();
+            assert_eq!(stored_account.pubkey(), &stored_meta.pubkey);
+            assert_eq!(stored_account.hash(), &expected_hashes[i]);
+            assert!(accounts_equal(&stored_account, account));
+        }
+        assert!(hot_reader
+            .get_account(IndexOffset(expected_accounts.len() as u32))
+            .unwrap()
+            .is_none());
     }
 
     #[test]
@@ -9,11 +9,15 @@ use {
     },
 };
 
+/// The compression level used by the zstd byte-block encoder.
+const ZSTD_COMPRESSION_LEVEL: i32 = 3;
+
 /// The encoder for the byte-block.
 #[derive(Debug)]
 pub enum ByteBlockEncoder {
     Raw(Cursor<Vec<u8>>),
     Lz4(lz4::Encoder<Vec<u8>>),
+    Zstd(zstd::stream::write::Encoder<'static, Vec<u8>>),
 }
 
 /// The byte block writer.
@@ -43,6 +47,9 @@ impl ByteBlockWriter {
                         .build(Vec::new())
                         .unwrap(),
                 ),
+                AccountBlockFormat::Zstd => ByteBlockEncoder::Zstd(
+                    zstd::stream::write::Encoder::new(Vec::new(), ZSTD_COMPRESSION_LEVEL).unwrap(),
+                ),
             },
             len: 0,
         }
@@ -90,6 +97,7 @@ impl ByteBlockWriter {
         match &mut self.encoder {
             ByteBlockEncoder::Raw(cursor) => cursor.write_all(buf)?,
             ByteBlockEncoder::Lz4(lz4_encoder) => lz4_encoder.write_all(buf)?,
+            ByteBlockEncoder::Zstd(zstd_encoder) => zstd_encoder.write_all(buf)?,
         };
         self.len += buf.len();
         Ok(())
@@ -105,6 +113,7 @@ impl ByteBlockWriter {
                 result?;
                 Ok(compressed_block)
             }
+            ByteBlockEncoder::Zstd(zstd_encoder) => zstd_encoder.finish(),
         }
     }
 }
@@ -142,6 +151,7 @@ impl ByteBlockReader {
                 decoder.read_to_end(&mut output)?;
                 Ok(output)
             }
+            AccountBlockFormat::Zstd => zstd::stream::decode_all(input),
             AccountBlockFormat::AlignedRaw => panic!("the input buffer is already decoded"),
         }
     }
@@ -200,6 +210,11 @@ mod tests {
         write_single(AccountBlockFormat::Lz4);
     }
 
+    #[test]
+    fn test_write_single_zstd_format() {
+        write_single(AccountBlockFormat::Zstd);
+    }
+
     #[derive(Debug, PartialEq)]
     struct TestMetaStruct {
         lamports: u64,
@@ -302,6 +317,11 @@ mod tests {
         write_multiple(AccountBlockFormat::Lz4);
     }
 
+    #[test]
+    fn test_write_multiple_zstd_format() {
+        write_multiple(AccountBlockFormat::Zstd);
+    }
+
     fn write_optional_fields(format: AccountBlockFormat) {
         let mut test_epoch = 5432312;
 
@@ -373,4 +393,9 @@ mod tests {
     fn test_write_optional_fields_lz4_format() {
         write_optional_fields(AccountBlockFormat::Lz4);
     }
+
+    #[test]
+    fn test_write_optional_fields_zstd_format() {
+        write_optional_fields(AccountBlockFormat::Zstd);
+    }
 }
@@ -0,0 +1,997 @@
+//! The account meta and related structs for cold accounts.
+//!
+//! Unlike hot accounts, multiple cold accounts share one account block, and
+//! each account block is encoded using the AccountBlockFormat of its accounts
+//! file.  This trades read latency for a smaller storage footprint, which
+//! suits accounts that are rarely accessed.
+//!
+//! A cold accounts file consists of the following blocks:
+//!
+//! * account blocks, each persisted as its encoded length (u64) followed by
+//!   the encoded bytes and padded to an 8-byte boundary
+//! * index block, whose entries are sorted by account address
+//! * owners block
+//! * footer
+
+use {
+    crate::{
+        account_storage::meta::{
+            StorableAccountsWithHashesAndWriteVersions, StoredAccountInfo, StoredAccountMeta,
+        },
+        accounts_hash::AccountHash,
+        rent_collector::RENT_EXEMPT_RENT_EPOCH,
+        storable_accounts::StorableAccounts,
+        tiered_storage::{
+            byte_block::{self, ByteBlockReader, ByteBlockWriter},
+            file::TieredStorageFile,
+            footer::{
+                AccountBlockFormat, AccountMetaFormat, OwnersBlockFormat, TieredStorageFooter,
+            },
+            index::{AccountIndexWriterEntry, AccountOffset, IndexBlockFormat, IndexOffset},
+            meta::{AccountMetaFlags, AccountMetaOptionalFields, TieredAccountMeta},
+            mmap_utils::{get_slice, get_type},
+            owners::{OwnerOffset, OwnersBlock, OwnersTable},
+            readable::TieredReadableAccount,
+            TieredStorageError, TieredStorageFormat, TieredStorageResult,
+        },
+    },
+    memmap2::{Mmap, MmapOptions},
+    solana_sdk::{account::ReadableAccount, pubkey::Pubkey, stake_history::Epoch},
+    std::{
+        borrow::Borrow,
+        collections::VecDeque,
+        fs::OpenOptions,
+        io::{Error as IoError, ErrorKind as IoErrorKind},
+        path::Path,
+        sync::{Arc, Mutex},
+    },
+};
+
+pub const COLD_FORMAT: TieredStorageFormat = TieredStorageFormat {
+    meta_entry_size: std::mem::size_of::<ColdAccountMeta>(),
+    account_meta_format: AccountMetaFormat::Cold,
+    owners_block_format: OwnersBlockFormat::LocalIndex,
+    index_block_format: IndexBlockFormat::AddressAndBlockAndIntraBlockOffset,
+    account_block_format: AccountBlockFormat::Lz4,
+};
+
+/// The size of a cold account block before compression.
+///
+/// Accounts are appended to the current account block until the block
+/// would exceed this size.  An account that is bigger than this size gets
+/// its own account block.
+pub const COLD_ACCOUNT_BLOCK_SIZE: u64 = 32 * 1024;
+
+/// The number of decoded account blocks kept by a ColdStorageReader, so that
+/// reading the accounts of an account block one after another only decodes
+/// the block once.
+const COLD_DECODED_ACCOUNT_BLOCKS: usize = 4;
+
+/// The alignment of account entries inside a decoded account block, and of
+/// account blocks inside a cold accounts file.
+const COLD_ALIGNMENT: usize = 8;
+
+/// Returns the number of padding bytes required to align the specified
+/// number of bytes to COLD_ALIGNMENT.
+fn padding_bytes(len: usize) -> usize {
+    (COLD_ALIGNMENT - len % COLD_ALIGNMENT) % COLD_ALIGNMENT
+}
+
+/// The storage and in-memory representation of the metadata entry for a
+/// cold account.
+///
+/// A cold account entry inside its decoded account block consists of the
+/// following elements:
+///
+/// * ColdAccountMeta
+/// * [u8] account data
+/// * 0-7 bytes padding
+/// * optional fields
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[repr(C)]
+pub struct ColdAccountMeta {
+    /// The balance of this account.
+    lamports: u64,
+    /// The size of the account data in bytes.
+    account_data_size: u64,
+    /// The index to the owner of a cold account inside an AccountsFile.
+    owner_offset: u32,
+    /// Stores boolean flags and existence of each optional field.
+    flags: AccountMetaFlags,
+}
+
+impl ColdAccountMeta {
+    /// Returns the size of the account entry associated with the current
+    /// meta, including the meta itself.
+    pub(crate) fn stored_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.account_data_size as usize
+            + self.account_data_padding() as usize
+            + AccountMetaOptionalFields::size_from_flags(&self.flags)
+    }
+}
+
+impl TieredAccountMeta for ColdAccountMeta {
+    /// Construct a ColdAccountMeta instance.
+    fn new() -> Self {
+        ColdAccountMeta {
+            lamports: 0,
+            account_data_size: 0,
+            owner_offset: 0,
+            flags: AccountMetaFlags::new(),
+        }
+    }
+
+    /// A builder function that initializes lamports.
+    fn with_lamports(mut self, lamports: u64) -> Self {
+        self.lamports = lamports;
+        self
+    }
+
+    /// A builder function that initializes the number of padding bytes
+    /// for the account data associated with the current meta.
+    fn with_account_data_padding(self, _padding: u8) -> Self {
+        // Cold meta does not store its padding as it derives the padding
+        // from its account data size.
+        self
+    }
+
+    /// A builder function that initializes the owner's index.
+    fn with_owner_offset(mut self, owner_offset: OwnerOffset) -> Self {
+        self.owner_offset = owner_offset.0;
+        self
+    }
+
+    /// A builder function that initializes the account data size.
+    fn with_account_data_size(mut self, account_data_size: u64) -> Self {
+        self.account_data_size = account_data_size;
+        self
+    }
+
+    /// A builder function that initializes the AccountMetaFlags of the current
+    /// meta.
+    fn with_flags(mut self, flags: &AccountMetaFlags) -> Self {
+        self.flags = *flags;
+        self
+    }
+
+    /// Returns the balance of the lamports associated with the account.
+    fn lamports(&self) -> u64 {
+        self.lamports
+    }
+
+    /// Returns the number of padding bytes for the associated account data
+    fn account_data_padding(&self) -> u8 {
+        padding_bytes(self.account_data_size as usize) as u8
+    }
+
+    /// Returns the index to the accounts' owner in the current AccountsFile.
+    fn owner_offset(&self) -> OwnerOffset {
+        OwnerOffset(self.owner_offset)
+    }
+
+    /// Returns the AccountMetaFlags of the current meta.
+    fn flags(&self) -> &AccountMetaFlags {
+        &self.flags
+    }
+
+    /// Always returns true as multiple ColdAccountMeta entries share the
+    /// same account block.
+    fn supports_shared_account_block() -> bool {
+        true
+    }
+
+    /// Returns the epoch that this account will next owe rent by parsing
+    /// the specified account block.  None will be returned if this account
+    /// does not persist this optional field.
+    fn rent_epoch(&self, account_block: &[u8]) -> Option<Epoch> {
+        self.flags()
+            .has_rent_epoch()
+            .then(|| {
+                let offset = self.optional_fields_offset(account_block)
+                    + AccountMetaOptionalFields::rent_epoch_offset(self.flags());
+                byte_block::read_type::<Epoch>(account_block, offset).copied()
+            })
+            .flatten()
+    }
+
+    /// Returns the account hash by parsing the specified account block.  None
+    /// will be returned if this account does not persist this optional field.
+    fn account_hash<'a>(&self, account_block: &'a [u8]) -> Option<&'a AccountHash> {
+        self.flags()
+            .has_account_hash()
+            .then(|| {
+                let offset = self.optional_fields_offset(account_block)
+                    + AccountMetaOptionalFields::account_hash_offset(self.flags());
+                byte_block::read_type::<AccountHash>(account_block, offset)
+            })
+            .flatten()
+    }
+
+    /// Returns the offset of the optional fields based on the specified account
+    /// block.
+    ///
+    /// As the account block is shared with other accounts, the offset is
+    /// derived from the account data size instead of the block length.
+    fn optional_fields_offset(&self, _account_block: &[u8]) -> usize {
+        self.account_data_size as usize + self.account_data_padding() as usize
+    }
+
+    /// Returns the length of the data associated to this account based on the
+    /// specified account block.
+    fn account_data_size(&self, _account_block: &[u8]) -> usize {
+        self.account_data_size as usize
+    }
+
+    /// Returns the data associated to this account based on the specified
+    /// account block.
+    fn account_data<'a>(&self, account_block: &'a [u8]) -> &'a [u8] {
+        &account_block[..self.account_data_size(account_block)]
+    }
+}
+
+/// A cold account that owns its entry copied out of its decoded account
+/// block, so that the account block doesn't have to outlive the account.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ColdStoredAccount<'accounts_file> {
+    /// The address of the account
+    address: &'accounts_file Pubkey,
+    /// The address of the account owner
+    owner: &'accounts_file Pubkey,
+    /// The index for accessing the account inside its AccountsFile
+    index: usize,
+    meta: ColdAccountMeta,
+    /// The account data, its padding and the optional fields of the account
+    entry: Box<[u8]>,
+}
+
+impl<'accounts_file> ColdStoredAccount<'accounts_file> {
+    /// Returns the address of this account.
+    pub fn address(&self) -> &'accounts_file Pubkey {
+        self.address
+    }
+
+    /// Returns the hash of this account.
+    pub fn hash(&self) -> Option<&AccountHash> {
+        self.meta.account_hash(&self.entry)
+    }
+
+    /// Returns the index to this account in its AccountsFile.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the data associated to this account.
+    pub fn data(&self) -> &[u8] {
+        self.meta.account_data(&self.entry)
+    }
+
+    /// Returns the size of the account entry, including its meta.
+    pub fn stored_size(&self) -> usize {
+        self.meta.stored_size()
+    }
+}
+
+impl<'accounts_file> ReadableAccount for ColdStoredAccount<'accounts_file> {
+    /// Returns the balance of the lamports of this account.
+    fn lamports(&self) -> u64 {
+        self.meta.lamports()
+    }
+
+    /// Returns the address of the owner of this account.
+    fn owner(&self) -> &Pubkey {
+        self.owner
+    }
+
+    /// Returns true if the data associated to this account is executable.
+    fn executable(&self) -> bool {
+        self.meta.flags().executable()
+    }
+
+    /// Returns the epoch that this account will next owe rent.  Epoch::MAX
+    /// will be returned if the account is rent-exempt.
+    fn rent_epoch(&self) -> Epoch {
+        self.meta.rent_epoch(&self.entry).unwrap_or(Epoch::MAX)
+    }
+
+    /// Returns the data associated to this account.
+    fn data(&self) -> &[u8] {
+        self.data()
+    }
+}
+
+/// Persists the encoded account block of the specified writer, prefixed by
+/// its encoded length and padded to COLD_ALIGNMENT, and returns the total
+/// number of bytes written.
+fn write_account_block(
+    file: &TieredStorageFile,
+    block_writer: ByteBlockWriter,
+) -> TieredStorageResult<usize> {
+    let encoded_block = block_writer.finish()?;
+    let mut bytes_written = file.write_type(&(encoded_block.len() as u64))?;
+    bytes_written += file.write_bytes(&encoded_block)?;
+    bytes_written +=
+        file.write_bytes(&[0u8; COLD_ALIGNMENT][..padding_bytes(encoded_block.len())])?;
+
+    Ok(bytes_written)
+}
+
+/// Persists the specified accounts into the specified file using the cold
+/// format, and returns the StoredAccountInfo of each persisted account in
+/// the order of the input accounts.
+///
+/// Accounts are sorted by address in the index block so that lookups by
+/// address can binary search it.  The offset of each StoredAccountInfo is
+/// the IndexOffset of its account.
+pub fn write_cold_accounts<
+    'a,
+    'b,
+    T: ReadableAccount + Sync,
+    U: StorableAccounts<'a, T>,
+    V: Borrow<AccountHash>,
+>(
+    file: &TieredStorageFile,
+    format: &TieredStorageFormat,
+    accounts: &StorableAccountsWithHashesAndWriteVersions<'a, 'b, T, U, V>,
+    skip: usize,
+) -> TieredStorageResult<Vec<StoredAccountInfo>> {
+    // The sort is stable, so if the same address is written more than once,
+    // its latest version comes last in the index block.
+    let mut sorted_indexes: Vec<_> = (skip..accounts.len()).collect();
+    sorted_indexes.sort_by(|a, b| {
+        accounts
+            .accounts
+            .pubkey(*a)
+            .cmp(accounts.accounts.pubkey(*b))
+    });
+
+    let mut stored_infos = vec![StoredAccountInfo { offset: 0, size: 0 }; sorted_indexes.len()];
+    let mut index_entries = Vec::with_capacity(sorted_indexes.len());
+    let mut owners_table = OwnersTable::default();
+
+    let mut cursor = 0;
+    let mut block_offset = 0;
+    let mut block_writer = ByteBlockWriter::new(format.account_block_format);
+
+    for (index, i) in sorted_indexes.into_iter().enumerate() {
+        let (account, address, account_hash, _write_version) = accounts.get(i);
+        let (lamports, owner, data, executable, rent_epoch) = account
+            .map(|account| {
+                (
+                    account.lamports(),
+                    *account.owner(),
+                    account.data(),
+                    account.executable(),
+                    account.rent_epoch(),
+                )
+            })
+            .unwrap_or((0, Pubkey::default(), &[], false, RENT_EXEMPT_RENT_EPOCH));
+
+        let optional_fields = AccountMetaOptionalFields {
+            rent_epoch: (rent_epoch != RENT_EXEMPT_RENT_EPOCH).then_some(rent_epoch),
+            account_hash: Some(*account_hash),
+        };
+        let mut flags = AccountMetaFlags::new_from(&optional_fields);
+        flags.set_executable(executable);
+        let meta = ColdAccountMeta::new()
+            .with_lamports(lamports)
+            .with_owner_offset(owners_table.insert(&owner))
+            .with_account_data_size(data.len() as u64)
+            .with_flags(&flags);
+        let stored_size = meta.stored_size();
+
+        if block_writer.raw_len() > 0
+            && (block_writer.raw_len() + stored_size) as u64 > COLD_ACCOUNT_BLOCK_SIZE
+        {
+            let full_block_writer = std::mem::replace(
+                &mut block_writer,
+                ByteBlockWriter::new(format.account_block_format),
+            );
+            cursor += write_account_block(file, full_block_writer)?;
+            block_offset = cursor;
+        }
+
+        let intra_block_offset = block_writer.raw_len();
+        index_entries.push(AccountIndexWriterEntry {
+            address,
+            block_offset: block_offset.try_into().map_err(|_| {
+                TieredStorageError::OffsetOutOfBounds(block_offset, u32::MAX as usize)
+            })?,
+            intra_block_offset: intra_block_offset.try_into().map_err(|_| {
+                TieredStorageError::OffsetOutOfBounds(intra_block_offset, u32::MAX as usize)
+            })?,
+        });
+        block_writer.write_type(&meta)?;
+        block_writer.write(data)?;
+        block_writer.write(&[0u8; COLD_ALIGNMENT][..meta.account_data_padding() as usize])?;
+        block_writer.write_optional_fields(&optional_fields)?;
+
+        stored_infos[i - skip] = StoredAccountInfo {
+            offset: index,
+            size: stored_size,
+        };
+    }
+
+    if block_writer.raw_len() > 0 {
+        cursor += write_account_block(file, block_writer)?;
+    }
+
+    let index_block_offset = cursor;
+    cursor += format
+        .index_block_format
+        .write_index_block(file, &index_entries)?;
+
+    let owners_block_offset = cursor;
+    OwnersBlock::write_owners_block(file, owners_table.owners())?;
+
+    let footer = TieredStorageFooter {
+        account_meta_format: format.account_meta_format,
+        owners_block_format: format.owners_block_format,
+        index_block_format: format.index_block_format,
+        account_block_format: format.account_block_format,
+        account_entry_count: index_entries
+            .len()
+            .try_into()
+            .expect("num accounts <= u32::MAX"),
+        account_meta_entry_size: format.meta_entry_size as u32,
+        account_block_size: COLD_ACCOUNT_BLOCK_SIZE,
+        owner_count: owners_table.owners().len() as u32,
+        owner_entry_size: std::mem::size_of::<Pubkey>() as u32,
+        index_block_offset: index_block_offset as u64,
+        owners_block_offset: owners_block_offset as u64,
+        min_account_address: index_entries
+            .first()
+            .map(|entry| *entry.address)
+            .unwrap_or_default(),
+        max_account_address: index_entries
+            .last()
+            .map(|entry| *entry.address)
+            .unwrap_or_default(),
+        ..TieredStorageFooter::default()
+    };
+    footer.write_footer_block(file)?;
+
+    Ok(stored_infos)
+}
+
+/// The reader to a cold accounts file.
+#[derive(Debug)]
+pub struct ColdStorageReader {
+    mmap: Mmap,
+    footer: TieredStorageFooter,
+    /// The most recently decoded account blocks and their offsets, the most
+    /// recent first.
+    decoded_account_blocks: Mutex<VecDeque<(u32, Arc<Vec<u8>>)>>,
+}
+
+impl ColdStorageReader {
+    /// Constructs a ColdStorageReader from the specified path.
+    pub fn new_from_path(path: impl AsRef<Path>) -> TieredStorageResult<Self> {
+        let file = OpenOptions::new().read(true).open(path)?;
+        let mmap = unsafe { MmapOptions::new().map(&file)? };
+        // Here we are cloning the footer as accessing any data in a
+        // TieredStorage instance requires accessing its Footer.
+        let footer = TieredStorageFooter::new_from_mmap(&mmap)?.clone();
+
+        Ok(Self {
+            mmap,
+            footer,
+            decoded_account_blocks: Mutex::new(VecDeque::with_capacity(
+                COLD_DECODED_ACCOUNT_BLOCKS,
+            )),
+        })
+    }
+
+    /// Returns the footer of the underlying tiered-storage accounts file.
+    pub fn footer(&self) -> &TieredStorageFooter {
+        &self.footer
+    }
+
+    /// Returns the number of accounts inside the underlying tiered-storage
+    /// accounts file.
+    pub fn num_accounts(&self) -> usize {
+        self.footer.account_entry_count as usize
+    }
+
+    /// Returns the index of the account with the specified address, or None
+    /// if the underlying accounts file does not contain such an account.
+    ///
+    /// If the address was written more than once, the index of its latest
+    /// version is returned.
+    pub fn find_account(&self, address: &Pubkey) -> TieredStorageResult<Option<IndexOffset>> {
+        if self.footer.account_entry_count == 0
+            || *address < self.footer.min_account_address
+            || *address > self.footer.max_account_address
+        {
+            return Ok(None);
+        }
+
+        // Find the first index whose address is greater than the specified
+        // address.  The entry before it is the latest version of the address
+        // if the address exists.
+        let mut low = 0;
+        let mut high = self.footer.account_entry_count;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.get_account_address(IndexOffset(mid))? <= address {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        if low > 0 && self.get_account_address(IndexOffset(low - 1))? == address {
+            Ok(Some(IndexOffset(low - 1)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Invokes the specified callback with the account at the specified index
+    /// and returns its result, or returns None if the index is out of range.
+    ///
+    /// As the account block containing the account has to be decoded first,
+    /// the account is only accessible inside the callback.
+    pub fn get_account<R>(
+        &self,
+        index_offset: IndexOffset,
+        callback: impl FnOnce(TieredReadableAccount<ColdAccountMeta>) -> R,
+    ) -> TieredStorageResult<Option<R>> {
+        if index_offset.0 >= self.footer.account_entry_count {
+            return Ok(None);
+        }
+
+        let account_offset = self.get_account_offset(index_offset)?;
+        let account_block = self.get_decoded_account_block(account_offset)?;
+
+        Ok(Some(callback(
+            self.get_readable_account(index_offset, &account_block)?,
+        )))
+    }
+
+    /// Returns the account at the specified index, or None if the index is
+    /// out of range.
+    ///
+    /// Unlike `get_account`, the entry of the account is copied out of its
+    /// decoded account block, so the account can be accessed for as long as
+    /// the reader lives without the reader keeping the decoded block.
+    pub fn get_stored_account_meta(
+        &self,
+        index_offset: IndexOffset,
+    ) -> TieredStorageResult<Option<StoredAccountMeta<'_>>> {
+        if index_offset.0 >= self.footer.account_entry_count {
+            return Ok(None);
+        }
+
+        let account_offset = self.get_account_offset(index_offset)?;
+        let account_block = self.get_decoded_account_block(account_offset)?;
+        let account = self.get_readable_account(index_offset, &account_block)?;
+        let meta = *account.meta;
+
+        Ok(Some(StoredAccountMeta::Cold(ColdStoredAccount {
+            address: self.get_account_address(index_offset)?,
+            owner: self.get_owner_address(meta.owner_offset())?,
+            index: index_offset.0 as usize,
+            meta,
+            entry: account.account_block.into(),
+        })))
+    }
+
+    /// Returns the account at the specified index inside the specified
+    /// decoded account block.
+    fn get_readable_account<'a>(
+        &'a self,
+        index_offset: IndexOffset,
+        account_block: &'a [u8],
+    ) -> TieredStorageResult<TieredReadableAccount<'a, ColdAccountMeta>> {
+        let intra_block_offset = self.get_intra_block_offset(index_offset)? as usize;
+        let meta = byte_block::read_type::<ColdAccountMeta>(account_block, intra_block_offset)
+            .ok_or_else(|| {
+                IoError::new(
+                    IoErrorKind::InvalidData,
+                    "cold account meta exceeds its account block",
+                )
+            })?;
+        let entry_end = intra_block_offset + meta.stored_size();
+        if entry_end > account_block.len() {
+            return Err(IoError::new(
+                IoErrorKind::InvalidData,
+                "cold account entry exceeds its account block",
+            )
+            .into());
+        }
+
+        Ok(TieredReadableAccount {
+            meta,
+            address: self.get_account_address(index_offset)?,
+            owner: self.get_owner_address(meta.owner_offset())?,
+            index: index_offset.0 as usize,
+            account_block: &account_block
+                [intra_block_offset + std::mem::size_of::<ColdAccountMeta>()..entry_end],
+        })
+    }
+
+    /// Returns the decoded account block located at the specified offset,
+    /// which is only decoded again if it isn't among the most recently
+    /// decoded account blocks.
+    fn get_decoded_account_block(
+        &self,
+        account_offset: AccountOffset,
+    ) -> TieredStorageResult<Arc<Vec<u8>>> {
+        {
+            let mut decoded_account_blocks = self.decoded_account_blocks.lock().unwrap();
+            if let Some(position) = decoded_account_blocks
+                .iter()
+                .position(|(block_offset, _)| *block_offset == account_offset.block)
+            {
+                let entry = decoded_account_blocks.remove(position).unwrap();
+                let account_block = entry.1.clone();
+                decoded_account_blocks.push_front(entry);
+                return Ok(account_block);
+            }
+        }
+
+        // Decode without holding the lock, so that readers of other account
+        // blocks aren't blocked
+        let account_block = Arc::new(self.get_account_block(account_offset)?);
+        let mut decoded_account_blocks = self.decoded_account_blocks.lock().unwrap();
+        decoded_account_blocks.truncate(COLD_DECODED_ACCOUNT_BLOCKS - 1);
+        decoded_account_blocks.push_front((account_offset.block, account_block.clone()));
+        Ok(account_block)
+    }
+
+    /// Returns the decoded account block located at the specified offset.
+    fn get_account_block(&self, account_offset: AccountOffset) -> TieredStorageResult<Vec<u8>> {
+        let (encoded_len, offset) = get_type::<u64>(&self.mmap, account_offset.block as usize)?;
+        let (encoded_block, _) = get_slice(&self.mmap, offset, *encoded_len as usize)?;
+
+        match self.footer.account_block_format {
+            AccountBlockFormat::AlignedRaw => Ok(encoded_block.to_vec()),
+            encoding => Ok(ByteBlockReader::decode(encoding, encoded_block)?),
+        }
+    }
+
+    /// Returns the offset to the account block of the account given the
+    /// specified index.
+    fn get_account_offset(&self, index_offset: IndexOffset) -> TieredStorageResult<AccountOffset> {
+        self.footer
+            .index_block_format
+            .get_account_offset(&self.mmap, &self.footer, index_offset)
+    }
+
+    /// Returns the offset of the account inside its account block given the
+    /// specified index.
+    fn get_intra_block_offset(&self, index_offset: IndexOffset) -> TieredStorageResult<u32> {
+        self.footer.index_block_format.get_intra_block_offset(
+            &self.mmap,
+            &self.footer,
+            index_offset,
+        )
+    }
+
+    /// Returns the address of the account associated with the specified index.
+    fn get_account_address(&self, index: IndexOffset) -> TieredStorageResult<&Pubkey> {
+        self.footer
+            .index_block_format
+            .get_account_address(&self.mmap, &self.footer, index)
+    }
+
+    /// Returns the address of the account owner given the specified
+    /// owner_offset.
+    fn get_owner_address(&self, owner_offset: OwnerOffset) -> TieredStorageResult<&Pubkey> {
+        OwnersBlock::get_owner_address(&self.mmap, &self.footer, owner_offset)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use {
+        super::*,
+        crate::{
+            account_storage::meta::StoredMetaWriteVersion,
+            tiered_storage::{readable::TieredStorageReader, TieredStorage},
+        },
+        memoffset::offset_of,
+        solana_sdk::{
+            account::{accounts_equal, Account, AccountSharedData},
+            clock::Slot,
+            hash::Hash,
+        },
+        std::collections::{HashMap, HashSet},
+        tempfile::TempDir,
+    };
+
+    #[test]
+    fn test_cold_account_meta_layout() {
+        assert_eq!(offset_of!(ColdAccountMeta, lamports), 0x00);
+        assert_eq!(offset_of!(ColdAccountMeta, account_data_size), 0x08);
+        assert_eq!(offset_of!(ColdAccountMeta, owner_offset), 0x10);
+        assert_eq!(offset_of!(ColdAccountMeta, flags), 0x14);
+        assert_eq!(std::mem::size_of::<ColdAccountMeta>(), 24);
+    }
+
+    #[test]
+    fn test_cold_account_meta() {
+        const TEST_LAMPORTS: u64 = 2314232137;
+        const TEST_DATA_SIZE: u64 = 83;
+        const TEST_OWNER_OFFSET: OwnerOffset = OwnerOffset(0xffff_1234);
+
+        let optional_fields = AccountMetaOptionalFields {
+            rent_epoch: Some(7),
+            account_hash: Some(AccountHash(Hash::new_unique())),
+        };
+
+        let mut flags = AccountMetaFlags::new_from(&optional_fields);
+        flags.set_executable(true);
+        let meta = ColdAccountMeta::new()
+            .with_lamports(TEST_LAMPORTS)
+            .with_account_data_size(TEST_DATA_SIZE)
+            // ignored, as the padding is derived from the data size
+            .with_account_data_padding(0)
+            .with_owner_offset(TEST_OWNER_OFFSET)
+            .with_flags(&flags);
+
+        assert_eq!(meta.lamports(), TEST_LAMPORTS);
+        assert_eq!(meta.account_data_padding(), 5);
+        assert_eq!(meta.owner_offset(), TEST_OWNER_OFFSET);
+        assert_eq!(*meta.flags(), flags);
+        assert!(meta.flags().executable());
+        assert_eq!(
+            meta.stored_size(),
+            std::mem::size_of::<ColdAccountMeta>() + 88 + optional_fields.size()
+        );
+    }
+
+    #[test]
+    fn test_cold_account_meta_full() {
+        let account_data = [11u8; 83];
+        let padding = [0u8; 5];
+
+        let optional_fields = AccountMetaOptionalFields {
+            rent_epoch: Some(7),
+            account_hash: Some(AccountHash(Hash::new_unique())),
+        };
+
+        let flags = AccountMetaFlags::new_from(&optional_fields);
+        let expected_meta = ColdAccountMeta::new()
+            .with_lamports(2314232137)
+            .with_account_data_size(account_data.len() as u64)
+            .with_owner_offset(OwnerOffset(0x1fef_1234))
+            .with_flags(&flags);
+
+        let mut writer = ByteBlockWriter::new(AccountBlockFormat::AlignedRaw);
+        writer.write_type(&expected_meta).unwrap();
+        writer.write_type(&account_data).unwrap();
+        writer.write_type(&padding).unwrap();
+        writer.write_optional_fields(&optional_fields).unwrap();
+        // Another account sharing the same account block must not affect
+        // parsing the previous one.
+        writer.write_type(&expected_meta).unwrap();
+        let buffer = writer.finish().unwrap();
+
+        let meta = byte_block::read_type::<ColdAccountMeta>(&buffer, 0).unwrap();
+        assert_eq!(expected_meta, *meta);
+
+        let account_block = &buffer[std::mem::size_of::<ColdAccountMeta>()..];
+        assert_eq!(
+            meta.optional_fields_offset(account_block),
+            account_data.len() + padding.len()
+        );
+        assert_eq!(account_data.len(), meta.account_data_size(account_block));
+        assert_eq!(account_data, meta.account_data(account_block));
+        assert_eq!(meta.rent_epoch(account_block), optional_fields.rent_epoch);
+        assert_eq!(
+            *(meta.account_hash(account_block).unwrap()),
+            optional_fields.account_hash.unwrap()
+        );
+    }
+
+    /// Create a test account based on the specified seed, whose owner is one
+    /// of the specified owners.
+    fn create_test_account(seed: u64, owners: &[Pubkey]) -> AccountSharedData {
+        AccountSharedData::from(Account {
+            lamports: seed + 1,
+            data: vec![seed as u8; seed as usize],
+            owner: owners[seed as usize % owners.len()],
+            executable: seed % 2 > 0,
+            rent_epoch: if seed % 3 > 0 {
+                seed
+            } else {
+                RENT_EXEMPT_RENT_EPOCH
+            },
+        })
+    }
+
+    /// Writes the specified accounts into a new cold accounts file using the
+    /// specified format, and verifies every account can be found by its
+    /// address afterwards.  Returns the number of account blocks.
+    fn write_and_verify_accounts(
+        path_suffix: &str,
+        accounts: &[(Pubkey, AccountSharedData)],
+        format: &TieredStorageFormat,
+    ) -> usize {
+        let account_refs: Vec<_> = accounts
+            .iter()
+            .map(|(address, account)| (address, account))
+            .collect();
+        // Slot information is not used here
+        let account_data = (Slot::MAX, &account_refs[..]);
+        let hashes: Vec<_> = std::iter::repeat_with(|| AccountHash(Hash::new_unique()))
+            .take(accounts.len())
+            .collect();
+        let write_versions = vec![StoredMetaWriteVersion::default(); accounts.len()];
+        let storable_accounts =
+            StorableAccountsWithHashesAndWriteVersions::new_with_hashes_and_write_versions(
+                &account_data,
+                hashes.clone(),
+                write_versions,
+            );
+
+        let temp_dir = TempDir::new().unwrap();
+        let tiered_storage = TieredStorage::new_writable(temp_dir.path().join(path_suffix));
+        let stored_infos = tiered_storage
+            .write_accounts(&storable_accounts, 0, format)
+            .unwrap();
+        assert_eq!(stored_infos.len(), accounts.len());
+
+        let TieredStorageReader::Cold(reader) = tiered_storage.reader().unwrap() else {
+            panic!("expected a cold storage reader");
+        };
+        assert_eq!(reader.num_accounts(), accounts.len());
+        assert_eq!(reader.footer().account_meta_format, AccountMetaFormat::Cold);
+        assert_eq!(
+            reader.footer().account_block_format,
+            format.account_block_format
+        );
+
+        // Only the latest version of each address is expected to be found.
+        let mut latest: HashMap<_, _> = HashMap::new();
+        for (i, (address, _)) in accounts.iter().enumerate() {
+            latest.insert(address, i);
+        }
+
+        for (i, ((address, account), stored_info)) in
+            accounts.iter().zip(stored_infos.iter()).enumerate()
+        {
+            let index_offset = reader.find_account(address).unwrap().unwrap();
+            if latest[address] != i {
+                assert_ne!(index_offset.0 as usize, stored_info.offset);
+                continue;
+            }
+            assert_eq!(index_offset.0 as usize, stored_info.offset);
+
+            reader
+                .get_account(index_offset, |stored_account| {
+                    assert_eq!(stored_account.address(), address);
+                    assert_eq!(stored_account.hash(), Some(&hashes[i]));
+                    assert_eq!(stored_account.lamports(), account.lamports());
+                    assert_eq!(stored_account.owner(), account.owner());
+                    assert_eq!(stored_account.executable(), account.executable());
+                    assert_eq!(stored_account.rent_epoch(), account.rent_epoch());
+                    assert_eq!(stored_account.data(), account.data());
+                })
+                .unwrap()
+                .unwrap();
+
+            let stored_account = reader
+                .get_stored_account_meta(index_offset)
+                .unwrap()
+                .unwrap();
+            assert_eq!(stored_account.pubkey(), address);
+            assert_eq!(stored_account.hash(), &hashes[i]);
+            assert!(accounts_equal(&stored_account, account));
+        }
+
+        // Only the most recently decoded account blocks are kept
+        assert!(reader.decoded_account_blocks.lock().unwrap().len() <= COLD_DECODED_ACCOUNT_BLOCKS);
+
+        assert_eq!(reader.find_account(&Pubkey::new_unique()).unwrap(), None);
+        assert_eq!(
+            reader
+                .get_account(IndexOffset(accounts.len() as u32), |_| ())
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            reader
+                .get_stored_account_meta(IndexOffset(accounts.len() as u32))
+                .unwrap(),
+            None
+        );
+
+        (0..reader.num_accounts())
+            .map(|i| {
+                reader
+                    .get_account_offset(IndexOffset(i as u32))
+                    .unwrap()
+                    .block
+            })
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    fn create_test_accounts(account_data_sizes: &[u64]) -> Vec<(Pubkey, AccountSharedData)> {
+        let owners: Vec<_> = std::iter::repeat_with(Pubkey::new_unique).take(3).collect();
+        account_data_sizes
+            .iter()
+            .map(|size| (Pubkey::new_unique(), create_test_account(*size, &owners)))
+            .collect()
+    }
+
+    #[test]
+    fn test_write_cold_accounts_zero_accounts() {
+        for account_block_format in [
+            AccountBlockFormat::AlignedRaw,
+            AccountBlockFormat::Lz4,
+            AccountBlockFormat::Zstd,
+        ] {
+            let format = TieredStorageFormat {
+                account_block_format,
+                ..COLD_FORMAT
+            };
+            let num_blocks =
+                write_and_verify_accounts("test_write_cold_accounts_zero_accounts", &[], &format);
+            assert_eq!(num_blocks, 0);
+        }
+    }
+
+    #[test]
+    fn test_write_cold_accounts_small_accounts() {
+        let accounts = create_test_accounts(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        for account_block_format in [
+            AccountBlockFormat::AlignedRaw,
+            AccountBlockFormat::Lz4,
+            AccountBlockFormat::Zstd,
+        ] {
+            let format = TieredStorageFormat {
+                account_block_format,
+                ..COLD_FORMAT
+            };
+            let num_blocks = write_and_verify_accounts(
+                "test_write_cold_accounts_small_accounts",
+                &accounts,
+                &format,
+            );
+            // all accounts fit into one account block
+            assert_eq!(num_blocks, 1);
+        }
+    }
+
+    #[test]
+    fn test_write_cold_accounts_mixed_size() {
+        let account_data_sizes: Vec<_> = (0..500)
+            .chain([COLD_ACCOUNT_BLOCK_SIZE * 3, 0, 1000, 2000, 3000, 4000])
+            .collect();
+        let accounts = create_test_accounts(&account_data_sizes);
+        for account_block_format in [
+            AccountBlockFormat::AlignedRaw,
+            AccountBlockFormat::Lz4,
+            AccountBlockFormat::Zstd,
+        ] {
+            let format = TieredStorageFormat {
+                account_block_format,
+                ..COLD_FORMAT
+            };
+            let num_blocks = write_and_verify_accounts(
+                "test_write_cold_accounts_mixed_size",
+                &accounts,
+                &format,
+            );
+            // the accounts exceed the size of one account block
+            assert!(num_blocks > 1);
+        }
+    }
+
+    #[test]
+    fn test_write_cold_accounts_duplicate_address() {
+        let mut accounts = create_test_accounts(&[10, 20, 30, 40]);
+        let (address, _) = accounts[1].clone();
+        accounts.push((address, create_test_account(50, &[Pubkey::new_unique()])));
+
+        write_and_verify_accounts(
+            "test_write_cold_accounts_duplicate_address",
+            &accounts,
+            &COLD_FORMAT,
+        );
+    }
+}
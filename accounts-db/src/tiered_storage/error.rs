@@ -16,4 +16,7 @@ pub enum TieredStorageError {
 
     #[error("Unsupported: the feature is not yet supported")]
     Unsupported(),
+
+    #[error("OffsetOutOfBounds: offset {0} is larger than the supported size {1}")]
+    OffsetOutOfBounds(usize, usize),
 }
@@ -47,8 +47,7 @@ impl Default for TieredStorageMagicNumber {
 pub enum AccountMetaFormat {
     #[default]
     Hot = 0,
-    // Temporarily comment out to avoid unimplemented!() block
-    // Cold = 1,
+    Cold = 1,
 }
 
 #[repr(u16)]
@@ -67,6 +66,7 @@ pub enum AccountBlockFormat {
     #[default]
     AlignedRaw = 0,
     Lz4 = 1,
+    Zstd = 2,
 }
 
 #[repr(u16)]
@@ -3,23 +3,30 @@
 
 use {
     crate::{
+        account_storage::meta::{
+            StorableAccountsWithHashesAndWriteVersions, StoredAccountInfo, StoredAccountMeta,
+        },
         accounts_hash::AccountHash,
+        rent_collector::RENT_EXEMPT_RENT_EPOCH,
+        storable_accounts::StorableAccounts,
         tiered_storage::{
-            byte_block,
+            byte_block::{self, ByteBlockWriter},
+            file::TieredStorageFile,
             footer::{
                 AccountBlockFormat, AccountMetaFormat, OwnersBlockFormat, TieredStorageFooter,
             },
-            index::{AccountOffset, IndexBlockFormat, IndexOffset},
+            index::{AccountIndexWriterEntry, AccountOffset, IndexBlockFormat, IndexOffset},
             meta::{AccountMetaFlags, AccountMetaOptionalFields, TieredAccountMeta},
-            mmap_utils::get_type,
-            owners::{OwnerOffset, OwnersBlock},
-            TieredStorageFormat, TieredStorageResult,
+            mmap_utils::{get_slice, get_type},
+            owners::{OwnerOffset, OwnersBlock, OwnersTable},
+            readable::TieredReadableAccount,
+            TieredStorageError, TieredStorageFormat, TieredStorageResult,
         },
     },
     memmap2::{Mmap, MmapOptions},
     modular_bitfield::prelude::*,
-    solana_sdk::{pubkey::Pubkey, stake_history::Epoch},
-    std::{fs::OpenOptions, option::Option, path::Path},
+    solana_sdk::{account::ReadableAccount, pubkey::Pubkey, stake_history::Epoch},
+    std::{borrow::Borrow, fs::OpenOptions, option::Option, path::Path},
 };
 
 pub const HOT_FORMAT: TieredStorageFormat = TieredStorageFormat {
@@ -33,6 +40,9 @@ pub const HOT_FORMAT: TieredStorageFormat = TieredStorageFormat {
 /// The maximum number of padding bytes used in a hot account entry.
 const MAX_HOT_PADDING: u8 = 7;
 
+/// The alignment of hot account entries inside a hot accounts file.
+const HOT_ACCOUNT_ALIGNMENT: usize = 8;
+
 /// The maximum allowed value for the owner index of a hot account.
 const MAX_HOT_OWNER_OFFSET: OwnerOffset = OwnerOffset((1 << 29) - 1);
 
@@ -223,6 +233,48 @@ impl HotStorageReader {
         self.footer.account_entry_count as usize
     }
 
+    /// Returns the account located at the specified index, or None if the
+    /// index is out of range.
+    pub fn get_account(
+        &self,
+        index_offset: IndexOffset,
+    ) -> TieredStorageResult<Option<StoredAccountMeta<'_>>> {
+        if index_offset.0 >= self.footer.account_entry_count {
+            return Ok(None);
+        }
+
+        let account_offset = self.get_account_offset(index_offset)?;
+        let meta = self.get_account_meta_from_offset(account_offset)?;
+
+        Ok(Some(StoredAccountMeta::Hot(TieredReadableAccount {
+            meta,
+            address: self.get_account_address(index_offset)?,
+            owner: self.get_owner_address(meta.owner_offset())?,
+            index: index_offset.0 as usize,
+            account_block: self.get_account_block(account_offset, index_offset)?,
+        })))
+    }
+
+    /// Returns the account block of the account at the specified offset and
+    /// index, which spans from the end of its account meta to the start of
+    /// the next account, or to the index block for the last account.
+    fn get_account_block(
+        &self,
+        account_offset: AccountOffset,
+        index_offset: IndexOffset,
+    ) -> TieredStorageResult<&[u8]> {
+        let start = account_offset.block as usize + std::mem::size_of::<HotAccountMeta>();
+        let end = if index_offset.0.saturating_add(1) == self.footer.account_entry_count {
+            self.footer.index_block_offset as usize
+        } else {
+            self.get_account_offset(IndexOffset(index_offset.0 + 1))?
+                .block as usize
+        };
+        let (account_block, _) = get_slice(&self.mmap, start, end.saturating_sub(start))?;
+
+        Ok(account_block)
+    }
+
     /// Returns the account meta located at the specified offset.
     fn get_account_meta_from_offset(
         &self,
@@ -253,6 +305,118 @@ impl HotStorageReader {
     }
 }
 
+/// Persists the specified accounts into the specified file using the hot
+/// format, and returns the StoredAccountInfo of each persisted account in
+/// the order of the input accounts.
+///
+/// Accounts are persisted in the order of the input accounts, and the offset
+/// of each StoredAccountInfo is the IndexOffset of its account.
+pub fn write_hot_accounts<
+    'a,
+    'b,
+    T: ReadableAccount + Sync,
+    U: StorableAccounts<'a, T>,
+    V: Borrow<AccountHash>,
+>(
+    file: &TieredStorageFile,
+    format: &TieredStorageFormat,
+    accounts: &StorableAccountsWithHashesAndWriteVersions<'a, 'b, T, U, V>,
+    skip: usize,
+) -> TieredStorageResult<Vec<StoredAccountInfo>> {
+    let num_accounts = accounts.len().saturating_sub(skip);
+    let mut stored_infos = Vec::with_capacity(num_accounts);
+    let mut index_entries = Vec::with_capacity(num_accounts);
+    let mut owners_table = OwnersTable::default();
+    let mut cursor = 0;
+
+    for i in skip..accounts.len() {
+        let (account, address, account_hash, _write_version) = accounts.get(i);
+        let (lamports, owner, data, executable, rent_epoch) = account
+            .map(|account| {
+                (
+                    account.lamports(),
+                    *account.owner(),
+                    account.data(),
+                    account.executable(),
+                    account.rent_epoch(),
+                )
+            })
+            .unwrap_or((0, Pubkey::default(), &[], false, RENT_EXEMPT_RENT_EPOCH));
+
+        let optional_fields = AccountMetaOptionalFields {
+            rent_epoch: (rent_epoch != RENT_EXEMPT_RENT_EPOCH).then_some(rent_epoch),
+            account_hash: Some(*account_hash),
+        };
+        let mut flags = AccountMetaFlags::new_from(&optional_fields);
+        flags.set_executable(executable);
+        let padding =
+            (HOT_ACCOUNT_ALIGNMENT - data.len() % HOT_ACCOUNT_ALIGNMENT) % HOT_ACCOUNT_ALIGNMENT;
+        let meta = HotAccountMeta::new()
+            .with_lamports(lamports)
+            .with_account_data_padding(padding as u8)
+            .with_owner_offset(owners_table.insert(&owner))
+            .with_flags(&flags);
+
+        let mut writer = ByteBlockWriter::new(format.account_block_format);
+        writer.write_type(&meta)?;
+        writer.write(data)?;
+        writer.write(&[0u8; HOT_ACCOUNT_ALIGNMENT][..padding])?;
+        writer.write_optional_fields(&optional_fields)?;
+
+        index_entries.push(AccountIndexWriterEntry {
+            address,
+            block_offset: cursor
+                .try_into()
+                .map_err(|_| TieredStorageError::OffsetOutOfBounds(cursor, u32::MAX as usize))?,
+            intra_block_offset: 0,
+        });
+        let stored_size = file.write_bytes(&writer.finish()?)?;
+        stored_infos.push(StoredAccountInfo {
+            offset: i - skip,
+            size: stored_size,
+        });
+        cursor += stored_size;
+    }
+
+    let index_block_offset = cursor;
+    cursor += format
+        .index_block_format
+        .write_index_block(file, &index_entries)?;
+
+    let owners_block_offset = cursor;
+    OwnersBlock::write_owners_block(file, owners_table.owners())?;
+
+    let footer = TieredStorageFooter {
+        account_meta_format: format.account_meta_format,
+        owners_block_format: format.owners_block_format,
+        index_block_format: format.index_block_format,
+        account_block_format: format.account_block_format,
+        account_entry_count: index_entries
+            .len()
+            .try_into()
+            .expect("num accounts <= u32::MAX"),
+        account_meta_entry_size: format.meta_entry_size as u32,
+        owner_count: owners_table.owners().len() as u32,
+        owner_entry_size: std::mem::size_of::<Pubkey>() as u32,
+        index_block_offset: index_block_offset as u64,
+        owners_block_offset: owners_block_offset as u64,
+        min_account_address: index_entries
+            .iter()
+            .map(|entry| *entry.address)
+            .min()
+            .unwrap_or_default(),
+        max_account_address: index_entries
+            .iter()
+            .map(|entry| *entry.address)
+            .max()
+            .unwrap_or_default(),
+        ..TieredStorageFooter::default()
+    };
+    footer.write_footer_block(file)?;
+
+    Ok(stored_infos)
+}
+
 #[cfg(test)]
 pub mod tests {
     use {
@@ -51,6 +51,11 @@ pub enum IndexBlockFormat {
     /// account block entries and index block entries in the same order.
     #[default]
     AddressAndBlockOffsetOnly = 0,
+    /// This format additionally stores the offset of each account inside its
+    /// account block, which allows multiple accounts to share one account
+    /// block.  Account addresses, block offsets and intra-block offsets are
+    /// stored as three consecutive arrays.
+    AddressAndBlockAndIntraBlockOffset = 1,
 }
 
 impl IndexBlockFormat {
@@ -72,6 +77,19 @@ impl IndexBlockFormat {
                 }
                 Ok(bytes_written)
             }
+            Self::AddressAndBlockAndIntraBlockOffset => {
+                let mut bytes_written = 0;
+                for index_entry in index_entries {
+                    bytes_written += file.write_type(index_entry.address)?;
+                }
+                for index_entry in index_entries {
+                    bytes_written += file.write_type(&index_entry.block_offset)?;
+                }
+                for index_entry in index_entries {
+                    bytes_written += file.write_type(&index_entry.intra_block_offset)?;
+                }
+                Ok(bytes_written)
+            }
         }
     }
 
@@ -83,7 +101,7 @@ impl IndexBlockFormat {
         index_offset: IndexOffset,
     ) -> TieredStorageResult<&'a Pubkey> {
         let account_offset = match self {
-            Self::AddressAndBlockOffsetOnly => {
+            Self::AddressAndBlockOffsetOnly | Self::AddressAndBlockAndIntraBlockOffset => {
                 footer.index_block_offset as usize
                     + std::mem::size_of::<Pubkey>() * (index_offset.0 as usize)
             }
@@ -100,7 +118,7 @@ impl IndexBlockFormat {
         index_offset: IndexOffset,
     ) -> TieredStorageResult<AccountOffset> {
         match self {
-            Self::AddressAndBlockOffsetOnly => {
+            Self::AddressAndBlockOffsetOnly | Self::AddressAndBlockAndIntraBlockOffset => {
                 let account_offset = footer.index_block_offset as usize
                     + std::mem::size_of::<Pubkey>() * footer.account_entry_count as usize
                     + std::mem::size_of::<u32>() * index_offset.0 as usize;
@@ -113,12 +131,40 @@ impl IndexBlockFormat {
         }
     }
 
+    /// Returns the offset of the account inside its account block given the
+    /// specified index.
+    ///
+    /// Formats that do not persist intra-block offsets always return 0 as
+    /// each of their accounts owns its account block.
+    pub fn get_intra_block_offset(
+        &self,
+        mmap: &Mmap,
+        footer: &TieredStorageFooter,
+        index_offset: IndexOffset,
+    ) -> TieredStorageResult<u32> {
+        match self {
+            Self::AddressAndBlockOffsetOnly => Ok(0),
+            Self::AddressAndBlockAndIntraBlockOffset => {
+                let offset = footer.index_block_offset as usize
+                    + (std::mem::size_of::<Pubkey>() + std::mem::size_of::<u32>())
+                        * footer.account_entry_count as usize
+                    + std::mem::size_of::<u32>() * index_offset.0 as usize;
+                let (intra_block_offset, _) = get_type::<u32>(mmap, offset)?;
+
+                Ok(*intra_block_offset)
+            }
+        }
+    }
+
     /// Returns the size of one index entry.
     pub fn entry_size(&self) -> usize {
         match self {
             Self::AddressAndBlockOffsetOnly => {
                 std::mem::size_of::<Pubkey>() + std::mem::size_of::<u32>()
             }
+            Self::AddressAndBlockAndIntraBlockOffset => {
+                std::mem::size_of::<Pubkey>() + std::mem::size_of::<u32>() * 2
+            }
         }
     }
 }
@@ -176,4 +222,59 @@ mod tests {
             assert_eq!(index_entry.address, address);
         }
     }
+
+    #[test]
+    fn test_address_and_intra_block_offset_indexer() {
+        const ENTRY_COUNT: usize = 100;
+        let footer = TieredStorageFooter {
+            index_block_format: IndexBlockFormat::AddressAndBlockAndIntraBlockOffset,
+            account_entry_count: ENTRY_COUNT as u32,
+            ..TieredStorageFooter::default()
+        };
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir
+            .path()
+            .join("test_address_and_intra_block_offset_indexer");
+        let addresses: Vec<_> = std::iter::repeat_with(Pubkey::new_unique)
+            .take(ENTRY_COUNT)
+            .collect();
+        let mut rng = rand::thread_rng();
+        let index_entries: Vec<_> = addresses
+            .iter()
+            .map(|address| AccountIndexWriterEntry {
+                address,
+                block_offset: rng.gen_range(128..2048),
+                intra_block_offset: rng.gen_range(0..4096),
+            })
+            .collect();
+
+        let indexer = footer.index_block_format;
+        {
+            let file = TieredStorageFile::new_writable(&path).unwrap();
+            let bytes_written = indexer.write_index_block(&file, &index_entries).unwrap();
+            assert_eq!(bytes_written, indexer.entry_size() * ENTRY_COUNT);
+        }
+
+        let file = OpenOptions::new()
+            .read(true)
+            .create(false)
+            .open(&path)
+            .unwrap();
+        let mmap = unsafe { MmapOptions::new().map(&file).unwrap() };
+        for (i, index_entry) in index_entries.iter().enumerate() {
+            let index_offset = IndexOffset(i as u32);
+            let account_offset = indexer
+                .get_account_offset(&mmap, &footer, index_offset)
+                .unwrap();
+            assert_eq!(index_entry.block_offset, account_offset.block);
+            let intra_block_offset = indexer
+                .get_intra_block_offset(&mmap, &footer, index_offset)
+                .unwrap();
+            assert_eq!(index_entry.intra_block_offset, intra_block_offset);
+            let address = indexer
+                .get_account_address(&mmap, &footer, index_offset)
+                .unwrap();
+            assert_eq!(index_entry.address, address);
+        }
+    }
 }
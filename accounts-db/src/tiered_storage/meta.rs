@@ -15,8 +15,10 @@ pub struct AccountMetaFlags {
     pub has_rent_epoch: bool,
     /// whether the account meta has account hash
     pub has_account_hash: bool,
+    /// whether the account is executable
+    pub executable: bool,
     /// the reserved bits.
-    reserved: B30,
+    reserved: B29,
 }
 
 /// A trait that allows different implementations of the account meta that
@@ -153,6 +155,7 @@ pub mod tests {
 
         assert!(!flags.has_rent_epoch());
         assert!(!flags.has_account_hash());
+        assert!(!flags.executable());
         assert_eq!(flags.reserved(), 0u32);
 
         assert_eq!(
@@ -181,6 +184,13 @@ pub mod tests {
         assert!(flags.has_account_hash());
         verify_flags_serialization(&flags);
 
+        flags.set_executable(true);
+
+        assert!(flags.has_rent_epoch());
+        assert!(flags.has_account_hash());
+        assert!(flags.executable());
+        verify_flags_serialization(&flags);
+
         // make sure the reserved bits are untouched.
         assert_eq!(flags.reserved(), 0u32);
     }
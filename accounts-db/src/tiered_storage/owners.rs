@@ -5,6 +5,7 @@ use {
     },
     memmap2::Mmap,
     solana_sdk::pubkey::Pubkey,
+    std::collections::HashMap,
 };
 
 /// Owner block holds a set of unique addresses of account owners,
@@ -53,6 +54,33 @@ impl OwnersBlock {
     }
 }
 
+/// The in-memory table for collecting the unique owners of the accounts being
+/// written, which assigns each owner its OwnerOffset in the order the owners
+/// are first inserted.
+#[derive(Debug, Default)]
+pub struct OwnersTable {
+    offsets: HashMap<Pubkey, OwnerOffset>,
+    owners: Vec<Pubkey>,
+}
+
+impl OwnersTable {
+    /// Returns the OwnerOffset of the specified owner, inserting the owner
+    /// first if it is not yet in the table.
+    pub fn insert(&mut self, owner: &Pubkey) -> OwnerOffset {
+        let owners = &mut self.owners;
+        *self.offsets.entry(*owner).or_insert_with(|| {
+            owners.push(*owner);
+            OwnerOffset((owners.len() - 1) as u32)
+        })
+    }
+
+    /// Returns the unique owners ordered by their OwnerOffset, which is the
+    /// order they are persisted in the owners block.
+    pub fn owners(&self) -> &[Pubkey] {
+        &self.owners
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -98,4 +126,19 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_owners_table() {
+        let mut owners_table = OwnersTable::default();
+        let addresses: Vec<_> = std::iter::repeat_with(Pubkey::new_unique).take(5).collect();
+
+        for (i, address) in addresses.iter().enumerate() {
+            assert_eq!(owners_table.insert(address), OwnerOffset(i as u32));
+        }
+        // inserting existing owners again must not change their offsets
+        for (i, address) in addresses.iter().enumerate().rev() {
+            assert_eq!(owners_table.insert(address), OwnerOffset(i as u32));
+        }
+        assert_eq!(owners_table.owners(), &addresses[..]);
+    }
 }
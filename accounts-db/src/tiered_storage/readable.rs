@@ -1,9 +1,12 @@
 use {
     crate::{
+        account_storage::meta::StoredAccountMeta,
         accounts_hash::AccountHash,
         tiered_storage::{
+            cold::ColdStorageReader,
             footer::{AccountMetaFormat, TieredStorageFooter},
             hot::HotStorageReader,
+            index::IndexOffset,
             meta::TieredAccountMeta,
             TieredStorageResult,
         },
@@ -64,11 +67,8 @@ impl<'accounts_file, M: TieredAccountMeta> ReadableAccount
     }
 
     /// Returns true if the data associated to this account is executable.
-    ///
-    /// Temporarily unimplemented!() as program runtime v2 will use
-    /// a different API for executable.
     fn executable(&self) -> bool {
-        unimplemented!();
+        self.meta.flags().executable()
     }
 
     /// Returns the epoch that this account will next owe rent by parsing
@@ -90,6 +90,7 @@ impl<'accounts_file, M: TieredAccountMeta> ReadableAccount
 #[derive(Debug)]
 pub enum TieredStorageReader {
     Hot(HotStorageReader),
+    Cold(ColdStorageReader),
 }
 
 impl TieredStorageReader {
@@ -98,13 +99,15 @@ impl TieredStorageReader {
         let footer = TieredStorageFooter::new_from_path(&path)?;
         match footer.account_meta_format {
             AccountMetaFormat::Hot => Ok(Self::Hot(HotStorageReader::new_from_path(path)?)),
+            AccountMetaFormat::Cold => Ok(Self::Cold(ColdStorageReader::new_from_path(path)?)),
         }
     }
 
-    /// Returns the footer of the associated HotAccountsFile.
+    /// Returns the footer of the associated accounts file.
     pub fn footer(&self) -> &TieredStorageFooter {
         match self {
             Self::Hot(hot) => hot.footer(),
+            Self::Cold(cold) => cold.footer(),
         }
     }
 
@@ -112,6 +115,19 @@ impl TieredStorageReader {
     pub fn num_accounts(&self) -> usize {
         match self {
             Self::Hot(hot) => hot.num_accounts(),
+            Self::Cold(cold) => cold.num_accounts(),
+        }
+    }
+
+    /// Returns the account located at the specified index, or None if the
+    /// index is out of range.
+    pub fn get_account(
+        &self,
+        index_offset: IndexOffset,
+    ) -> TieredStorageResult<Option<StoredAccountMeta<'_>>> {
+        match self {
+            Self::Hot(hot) => hot.get_account(index_offset),
+            Self::Cold(cold) => cold.get_stored_account_meta(index_offset),
         }
     }
 }
@@ -6,8 +6,8 @@ use {
         accounts_hash::AccountHash,
         storable_accounts::StorableAccounts,
         tiered_storage::{
-            error::TieredStorageError, file::TieredStorageFile, footer::TieredStorageFooter,
-            TieredStorageFormat, TieredStorageResult,
+            cold::write_cold_accounts, file::TieredStorageFile, footer::AccountMetaFormat,
+            hot::write_hot_accounts, TieredStorageFormat, TieredStorageResult,
         },
     },
     solana_sdk::account::ReadableAccount,
@@ -42,22 +42,13 @@ impl<'format> TieredStorageWriter<'format> {
         accounts: &StorableAccountsWithHashesAndWriteVersions<'a, 'b, T, U, V>,
         skip: usize,
     ) -> TieredStorageResult<Vec<StoredAccountInfo>> {
-        let footer = TieredStorageFooter {
-            account_meta_format: self.format.account_meta_format,
-            owners_block_format: self.format.owners_block_format,
-            account_block_format: self.format.account_block_format,
-            index_block_format: self.format.index_block_format,
-            account_entry_count: accounts
-                .accounts
-                .len()
-                .saturating_sub(skip)
-                .try_into()
-                .expect("num accounts <= u32::MAX"),
-            ..TieredStorageFooter::default()
-        };
-
-        footer.write_footer_block(&self.storage)?;
-
-        Err(TieredStorageError::Unsupported())
+        match self.format.account_meta_format {
+            AccountMetaFormat::Hot => {
+                write_hot_accounts(&self.storage, self.format, accounts, skip)
+            }
+            AccountMetaFormat::Cold => {
+                write_cold_accounts(&self.storage, self.format, accounts, skip)
+            }
+        }
     }
 }
@@ -6,6 +6,7 @@ use {
     solana_program_runtime::loaded_programs::LoadedProgramsForTxBatch,
     solana_sdk::{
         instruction::{CompiledInstruction, TRANSACTION_LEVEL_STACK_HEIGHT},
+        pubkey::Pubkey,
         transaction::{self, TransactionError},
         transaction_context::{TransactionContext, TransactionReturnData},
     },
@@ -73,6 +74,7 @@ pub struct TransactionExecutionDetails {
     pub status: transaction::Result<()>,
     pub log_messages: Option<Vec<String>>,
     pub inner_instructions: Option<InnerInstructionsList>,
+    pub executed_instructions: Option<Vec<ExecutedInstruction>>,
     pub durable_nonce_fee: Option<DurableNonceFee>,
     pub return_data: Option<TransactionReturnData>,
     pub executed_units: u64,
@@ -173,11 +175,52 @@ pub fn inner_instructions_list_from_instruction_trace(
     outer_instructions
 }
 
+/// An instruction executed by a transaction, either a transaction instruction
+/// or a cross-program invocation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExecutedInstruction {
+    pub program_id: Pubkey,
+    /// Invocation stack height of this instruction. Instruction stack height
+    /// starts at 1 for transaction instructions.
+    pub stack_height: usize,
+    /// Including the compute units consumed by the instructions it invoked
+    pub compute_units_consumed: u64,
+}
+
+/// Extract the executed instructions, in execution order, from a
+/// TransactionContext and the compute units consumed by each instruction
+/// recorded by the LogCollector
+pub fn executed_instructions_from_instruction_trace(
+    transaction_context: &TransactionContext,
+    instruction_compute_units: &[u64],
+) -> Vec<ExecutedInstruction> {
+    (0..transaction_context.get_instruction_trace_length())
+        .filter_map(|index_in_trace| {
+            let instruction_context = transaction_context
+                .get_instruction_context_at_index_in_trace(index_in_trace)
+                .ok()?;
+            Some(ExecutedInstruction {
+                program_id: instruction_context
+                    .get_last_program_key(transaction_context)
+                    .copied()
+                    .unwrap_or_default(),
+                stack_height: instruction_context.get_stack_height(),
+                compute_units_consumed: instruction_compute_units
+                    .get(index_in_trace)
+                    .copied()
+                    .unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use {
         super::*,
-        solana_sdk::{sysvar::rent::Rent, transaction_context::TransactionContext},
+        solana_sdk::{
+            account::AccountSharedData, sysvar::rent::Rent, transaction_context::TransactionContext,
+        },
     };
 
     #[test]
@@ -225,4 +268,49 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_executed_instructions_from_instruction_trace() {
+        let program_ids = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut transaction_context = TransactionContext::new(
+            program_ids
+                .iter()
+                .map(|program_id| (*program_id, AccountSharedData::default()))
+                .collect(),
+            Rent::default(),
+            3,
+            3,
+        );
+        for (program_index, stack_height) in [(0, 1), (1, 2), (0, 1)] {
+            while stack_height <= transaction_context.get_instruction_context_stack_height() {
+                transaction_context.pop().unwrap();
+            }
+            transaction_context
+                .get_next_instruction_context()
+                .unwrap()
+                .configure(&[program_index], &[], &[]);
+            transaction_context.push().unwrap();
+        }
+
+        assert_eq!(
+            executed_instructions_from_instruction_trace(&transaction_context, &[300, 200]),
+            vec![
+                ExecutedInstruction {
+                    program_id: program_ids[0],
+                    stack_height: 1,
+                    compute_units_consumed: 300,
+                },
+                ExecutedInstruction {
+                    program_id: program_ids[1],
+                    stack_height: 2,
+                    compute_units_consumed: 200,
+                },
+                ExecutedInstruction {
+                    program_id: program_ids[0],
+                    stack_height: 1,
+                    compute_units_consumed: 0,
+                },
+            ]
+        );
+    }
 }
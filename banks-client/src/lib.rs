@@ -14,7 +14,7 @@ use {
     futures::{future::join_all, Future, FutureExt, TryFutureExt},
     solana_banks_interface::{
         BanksRequest, BanksResponse, BanksTransactionResultWithMetadata,
-        BanksTransactionResultWithSimulation,
+        BanksTransactionResultWithSimulation, BanksTransactionResultWithSimulationTrace,
     },
     solana_program::{
         clock::Slot, fee_calculator::FeeCalculator, hash::Hash, program_pack::Pack, pubkey::Pubkey,
@@ -164,6 +164,22 @@ impl BanksClient {
             .map_err(Into::into)
     }
 
+    pub fn simulate_transaction_with_trace_and_commitment_and_context(
+        &mut self,
+        ctx: Context,
+        transaction: impl Into<VersionedTransaction>,
+        commitment: CommitmentLevel,
+    ) -> impl Future<Output = Result<BanksTransactionResultWithSimulationTrace, BanksClientError>> + '_
+    {
+        self.inner
+            .simulate_transaction_with_trace_and_commitment_and_context(
+                ctx,
+                transaction.into(),
+                commitment,
+            )
+            .map_err(Into::into)
+    }
+
     pub fn get_account_with_commitment_and_context(
         &mut self,
         ctx: Context,
@@ -357,6 +373,32 @@ impl BanksClient {
         self.simulate_transaction_with_commitment(transaction, CommitmentLevel::default())
     }
 
+    /// Simulate a transaction at the given commitment level, also returning
+    /// the compute units consumed by every executed instruction, including
+    /// cross-program invocations, and the state of every writable account
+    /// before and after the transaction
+    pub fn simulate_transaction_with_trace_and_commitment(
+        &mut self,
+        transaction: impl Into<VersionedTransaction>,
+        commitment: CommitmentLevel,
+    ) -> impl Future<Output = Result<BanksTransactionResultWithSimulationTrace, BanksClientError>> + '_
+    {
+        self.simulate_transaction_with_trace_and_commitment_and_context(
+            context::current(),
+            transaction,
+            commitment,
+        )
+    }
+
+    /// Simulate a transaction with a trace at the default commitment level
+    pub fn simulate_transaction_with_trace(
+        &mut self,
+        transaction: impl Into<VersionedTransaction>,
+    ) -> impl Future<Output = Result<BanksTransactionResultWithSimulationTrace, BanksClientError>> + '_
+    {
+        self.simulate_transaction_with_trace_and_commitment(transaction, CommitmentLevel::default())
+    }
+
     /// Return the most recent rooted slot. All transactions at or below this slot
     /// are said to be finalized. The cluster will not fork to a higher slot.
     pub fn get_root_slot(&mut self) -> impl Future<Output = Result<Slot, BanksClientError>> + '_ {
@@ -558,7 +600,8 @@ mod tests {
             genesis_utils::create_genesis_config,
         },
         solana_sdk::{
-            message::Message, signature::Signer, system_instruction, transaction::Transaction,
+            message::Message, signature::Signer, system_instruction, system_program,
+            transaction::Transaction,
         },
         std::sync::{Arc, RwLock},
         tarpc::transport,
@@ -661,4 +704,79 @@ mod tests {
             Ok(())
         })
     }
+
+    #[test]
+    #[allow(clippy::result_large_err)]
+    fn test_banks_server_simulate_with_trace() -> Result<(), BanksClientError> {
+        let genesis = create_genesis_config(10);
+        let bank = Bank::new_for_tests(&genesis.genesis_config);
+        let slot = bank.slot();
+        let block_commitment_cache = Arc::new(RwLock::new(
+            BlockCommitmentCache::new_for_tests_with_slots(slot, slot),
+        ));
+        let bank_forks = BankForks::new_rw_arc(bank);
+
+        let mint_pubkey = genesis.mint_keypair.pubkey();
+        let bob_pubkey = solana_sdk::pubkey::new_rand();
+        let instructions = [
+            system_instruction::transfer(&mint_pubkey, &bob_pubkey, 1),
+            system_instruction::transfer(&mint_pubkey, &bob_pubkey, 2),
+        ];
+        let message = Message::new(&instructions, Some(&mint_pubkey));
+
+        Runtime::new()?.block_on(async {
+            let client_transport =
+                start_local_server(bank_forks, block_commitment_cache, Duration::from_millis(1))
+                    .await;
+            let mut banks_client = start_client(client_transport).await?;
+
+            let recent_blockhash = banks_client.get_latest_blockhash().await?;
+            let transaction = Transaction::new(&[&genesis.mint_keypair], message, recent_blockhash);
+            let simulation_result = banks_client
+                .simulate_transaction_with_trace(transaction.clone())
+                .await?;
+            assert!(simulation_result.result.unwrap().is_ok());
+            let simulation_details = simulation_result.simulation_details.unwrap();
+            let trace = simulation_result.trace.unwrap();
+
+            // The trace is returned alongside the regular simulation details
+            assert_eq!(
+                banks_client
+                    .simulate_transaction(transaction)
+                    .await?
+                    .simulation_details
+                    .unwrap(),
+                simulation_details
+            );
+
+            assert_eq!(trace.instructions.len(), 2);
+            for (instruction_index, instruction) in trace.instructions.iter().enumerate() {
+                assert_eq!(instruction.instruction_index, instruction_index);
+                assert_eq!(instruction.invoke_stack, vec![system_program::id()]);
+                assert!(instruction.compute_units_consumed > 0);
+            }
+            assert_eq!(
+                trace
+                    .instructions
+                    .iter()
+                    .map(|instruction| instruction.compute_units_consumed)
+                    .sum::<u64>(),
+                simulation_details.units_consumed
+            );
+
+            assert_eq!(trace.accounts.len(), 2);
+            assert_eq!(trace.accounts[0].address, mint_pubkey);
+            assert_eq!(
+                trace.accounts[0].post.lamports,
+                trace.accounts[0].pre.as_ref().unwrap().lamports - 3
+            );
+            assert_eq!(trace.accounts[1].address, bob_pubkey);
+            assert_eq!(trace.accounts[1].pre, None);
+            assert_eq!(trace.accounts[1].post.lamports, 3);
+
+            // Nothing was committed
+            assert_eq!(banks_client.get_balance(bob_pubkey).await?, 0);
+            Ok(())
+        })
+    }
 }
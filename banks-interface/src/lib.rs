@@ -39,6 +39,39 @@ pub struct TransactionSimulationDetails {
     pub return_data: Option<TransactionReturnData>,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionSimulationTrace {
+    /// Every executed instruction, in execution order
+    pub instructions: Vec<InstructionTrace>,
+    /// Every writable account of the transaction
+    pub accounts: Vec<AccountDiff>,
+}
+
+/// An instruction executed by a simulated transaction, either a transaction
+/// instruction or a cross-program invocation
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstructionTrace {
+    /// Index of the transaction instruction this instruction belongs to
+    pub instruction_index: usize,
+    /// The programs on the invoke stack, from the program of the transaction
+    /// instruction to the program of this instruction
+    pub invoke_stack: Vec<Pubkey>,
+    /// Including the compute units consumed by the instructions it invoked
+    pub compute_units_consumed: u64,
+}
+
+/// The state of an account before and after a simulated transaction
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountDiff {
+    pub address: Pubkey,
+    /// None if the account did not exist
+    pub pre: Option<Account>,
+    pub post: Account,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionMetadata {
@@ -53,6 +86,16 @@ pub struct BanksTransactionResultWithSimulation {
     pub simulation_details: Option<TransactionSimulationDetails>,
 }
 
+/// The result of a simulation requested with a trace, kept apart from
+/// `BanksTransactionResultWithSimulation` so that its wire format is unchanged
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BanksTransactionResultWithSimulationTrace {
+    pub result: Option<transaction::Result<()>>,
+    pub simulation_details: Option<TransactionSimulationDetails>,
+    /// None if the transaction could not be sanitized
+    pub trace: Option<TransactionSimulationTrace>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BanksTransactionResultWithMetadata {
     pub result: transaction::Result<()>,
@@ -100,6 +143,12 @@ pub trait Banks {
         message: Message,
         commitment: CommitmentLevel,
     ) -> Option<u64>;
+    // New methods go last, so that the request and response variants of the
+    // existing methods keep their bincode indexes
+    async fn simulate_transaction_with_trace_and_commitment_and_context(
+        transaction: VersionedTransaction,
+        commitment: CommitmentLevel,
+    ) -> BanksTransactionResultWithSimulationTrace;
 }
 
 #[cfg(test)]
@@ -2,11 +2,12 @@ use {
     bincode::{deserialize, serialize},
     crossbeam_channel::{unbounded, Receiver, Sender},
     futures::{future, prelude::stream::StreamExt},
-    solana_accounts_db::transaction_results::TransactionExecutionResult,
+    solana_accounts_db::transaction_results::{ExecutedInstruction, TransactionExecutionResult},
     solana_banks_interface::{
-        Banks, BanksRequest, BanksResponse, BanksTransactionResultWithMetadata,
-        BanksTransactionResultWithSimulation, TransactionConfirmationStatus, TransactionMetadata,
-        TransactionSimulationDetails, TransactionStatus,
+        AccountDiff, Banks, BanksRequest, BanksResponse, BanksTransactionResultWithMetadata,
+        BanksTransactionResultWithSimulation, BanksTransactionResultWithSimulationTrace,
+        InstructionTrace, TransactionConfirmationStatus, TransactionMetadata,
+        TransactionSimulationDetails, TransactionSimulationTrace, TransactionStatus,
     },
     solana_client::connection_cache::ConnectionCache,
     solana_runtime::{
@@ -21,10 +22,12 @@ use {
         feature_set::FeatureSet,
         fee_calculator::FeeCalculator,
         hash::Hash,
+        instruction::TRANSACTION_LEVEL_STACK_HEIGHT,
         message::{Message, SanitizedMessage},
         pubkey::Pubkey,
         signature::Signature,
         transaction::{self, MessageHash, SanitizedTransaction, VersionedTransaction},
+        transaction_context::TransactionAccount,
     },
     solana_send_transaction_service::{
         send_transaction_service::{SendTransactionService, TransactionInfo},
@@ -170,10 +173,16 @@ fn verify_transaction(
     Ok(())
 }
 
+/// Simulates the transaction, also returning its trace if `enable_trace` is
+/// set and the transaction could be sanitized
 fn simulate_transaction(
     bank: &Bank,
     transaction: VersionedTransaction,
-) -> BanksTransactionResultWithSimulation {
+    enable_trace: bool,
+) -> (
+    BanksTransactionResultWithSimulation,
+    Option<TransactionSimulationTrace>,
+) {
     let sanitized_transaction = match SanitizedTransaction::try_create(
         transaction,
         MessageHash::Compute,
@@ -181,28 +190,98 @@ fn simulate_transaction(
         bank,
     ) {
         Err(err) => {
-            return BanksTransactionResultWithSimulation {
+            let simulation_result = BanksTransactionResultWithSimulation {
                 result: Some(Err(err)),
                 simulation_details: None,
             };
+            return (simulation_result, None);
         }
         Ok(tx) => tx,
     };
+    let pre_accounts = enable_trace.then(|| {
+        let message = sanitized_transaction.message();
+        message
+            .account_keys()
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| message.is_writable(*index))
+            .map(|(_, address)| (*address, bank.get_account(address).map(Account::from)))
+            .collect::<Vec<_>>()
+    });
     let TransactionSimulationResult {
         result,
         logs,
-        post_simulation_accounts: _,
+        post_simulation_accounts,
         units_consumed,
         return_data,
-    } = bank.simulate_transaction_unchecked(sanitized_transaction);
+        executed_instructions,
+    } = if enable_trace {
+        bank.simulate_transaction_unchecked_with_instruction_trace(sanitized_transaction)
+    } else {
+        bank.simulate_transaction_unchecked(sanitized_transaction)
+    };
+    let trace = pre_accounts.map(|pre_accounts| {
+        simulation_trace(
+            executed_instructions.unwrap_or_default(),
+            pre_accounts,
+            &post_simulation_accounts,
+        )
+    });
     let simulation_details = TransactionSimulationDetails {
         logs,
         units_consumed,
         return_data,
     };
-    BanksTransactionResultWithSimulation {
+    let simulation_result = BanksTransactionResultWithSimulation {
         result: Some(result),
         simulation_details: Some(simulation_details),
+    };
+    (simulation_result, trace)
+}
+
+fn simulation_trace(
+    executed_instructions: Vec<ExecutedInstruction>,
+    pre_accounts: Vec<(Pubkey, Option<Account>)>,
+    post_simulation_accounts: &[TransactionAccount],
+) -> TransactionSimulationTrace {
+    let mut instruction_index = 0;
+    let mut invoke_stack = Vec::new();
+    let instructions = executed_instructions
+        .into_iter()
+        .map(|executed_instruction| {
+            if executed_instruction.stack_height == TRANSACTION_LEVEL_STACK_HEIGHT
+                && !invoke_stack.is_empty()
+            {
+                instruction_index += 1;
+            }
+            invoke_stack.truncate(executed_instruction.stack_height.saturating_sub(1));
+            invoke_stack.push(executed_instruction.program_id);
+            InstructionTrace {
+                instruction_index,
+                invoke_stack: invoke_stack.clone(),
+                compute_units_consumed: executed_instruction.compute_units_consumed,
+            }
+        })
+        .collect();
+
+    // There are no post simulation accounts if the transaction failed to load
+    let accounts = pre_accounts
+        .into_iter()
+        .filter_map(|(address, pre)| {
+            let (_, post) = post_simulation_accounts
+                .iter()
+                .find(|(post_address, _)| *post_address == address)?;
+            Some(AccountDiff {
+                address,
+                pre,
+                post: Account::from(post.clone()),
+            })
+        })
+        .collect();
+
+    TransactionSimulationTrace {
+        instructions,
+        accounts,
     }
 }
 
@@ -297,8 +376,8 @@ impl Banks for BanksServer {
         transaction: VersionedTransaction,
         commitment: CommitmentLevel,
     ) -> BanksTransactionResultWithSimulation {
-        let mut simulation_result =
-            simulate_transaction(&self.bank(commitment), transaction.clone());
+        let (mut simulation_result, _) =
+            simulate_transaction(&self.bank(commitment), transaction.clone(), false);
         // Simulation was ok, so process the real transaction and replace the
         // simulation's result with the real transaction result
         if let Some(Ok(_)) = simulation_result.result {
@@ -315,7 +394,7 @@ impl Banks for BanksServer {
         transaction: VersionedTransaction,
         commitment: CommitmentLevel,
     ) -> BanksTransactionResultWithSimulation {
-        simulate_transaction(&self.bank(commitment), transaction)
+        simulate_transaction(&self.bank(commitment), transaction, false).0
     }
 
     async fn process_transaction_with_commitment_and_context(
@@ -418,6 +497,21 @@ impl Banks for BanksServer {
         let sanitized_message = SanitizedMessage::try_from(message).ok()?;
         bank.get_fee_for_message(&sanitized_message)
     }
+
+    async fn simulate_transaction_with_trace_and_commitment_and_context(
+        self,
+        _: Context,
+        transaction: VersionedTransaction,
+        commitment: CommitmentLevel,
+    ) -> BanksTransactionResultWithSimulationTrace {
+        let (simulation_result, trace) =
+            simulate_transaction(&self.bank(commitment), transaction, true);
+        BanksTransactionResultWithSimulationTrace {
+            result: simulation_result.result,
+            simulation_details: simulation_result.simulation_details,
+            trace,
+        }
+    }
 }
 
 pub async fn start_local_server(
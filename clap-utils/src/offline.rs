@@ -27,6 +27,13 @@ pub const DUMP_TRANSACTION_MESSAGE: ArgConstant<'static> = ArgConstant {
     help: "Display the base64 encoded binary transaction message in sign-only mode",
 };
 
+pub const TRANSACTION_FILE_ARG: ArgConstant<'static> = ArgConstant {
+    name: "transaction_file",
+    long: "transaction-file",
+    help: "Write the partially signed transaction to this file in sign-only mode, \
+           to collect the signatures of the other signers",
+};
+
 pub fn blockhash_arg<'a, 'b>() -> Arg<'a, 'b> {
     Arg::with_name(BLOCKHASH_ARG.name)
         .long(BLOCKHASH_ARG.long)
@@ -63,6 +70,15 @@ pub fn dump_transaction_message<'a, 'b>() -> Arg<'a, 'b> {
         .help(DUMP_TRANSACTION_MESSAGE.help)
 }
 
+pub fn transaction_file_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name(TRANSACTION_FILE_ARG.name)
+        .long(TRANSACTION_FILE_ARG.long)
+        .takes_value(true)
+        .value_name("FILEPATH")
+        .requires(SIGN_ONLY_ARG.name)
+        .help(TRANSACTION_FILE_ARG.help)
+}
+
 pub trait ArgsConfig {
     fn blockhash_arg<'a, 'b>(&self, arg: Arg<'a, 'b>) -> Arg<'a, 'b> {
         arg
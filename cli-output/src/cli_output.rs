@@ -7,6 +7,7 @@ use {
             unix_timestamp_to_string, writeln_name_value, writeln_transaction,
             BuildBalanceMessageConfig,
         },
+        partially_signed_transaction::write_partially_signed_transaction,
         QuietDisplay, VerboseDisplay,
     },
     base64::{prelude::BASE64_STANDARD, Engine},
@@ -48,6 +49,7 @@ use {
     std::{
         collections::{BTreeMap, HashMap},
         fmt,
+        path::PathBuf,
         str::FromStr,
         time::Duration,
     },
@@ -1827,6 +1829,8 @@ pub struct CliSignOnlyData {
     pub absent: Vec<String>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub bad_sig: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_file: Option<String>,
 }
 
 impl QuietDisplay for CliSignOnlyData {}
@@ -1857,6 +1861,9 @@ impl fmt::Display for CliSignOnlyData {
                 writeln!(f, " {pubkey}")?;
             }
         }
+        if let Some(transaction_file) = self.transaction_file.as_ref() {
+            writeln_name_value(f, "Transaction File:", transaction_file)?;
+        }
         Ok(())
     }
 }
@@ -2477,6 +2484,8 @@ impl fmt::Display for CliAddressLookupTableCreated {
 #[derive(Debug, Default)]
 pub struct ReturnSignersConfig {
     pub dump_transaction_message: bool,
+    /// Write the partially signed transaction to this file
+    pub transaction_file: Option<PathBuf>,
 }
 
 pub fn return_signers(
@@ -2491,6 +2500,9 @@ pub fn return_signers_with_config(
     output_format: &OutputFormat,
     config: &ReturnSignersConfig,
 ) -> Result<String, Box<dyn std::error::Error>> {
+    if let Some(transaction_file) = config.transaction_file.as_ref() {
+        write_partially_signed_transaction(transaction_file, tx)?;
+    }
     let cli_command = return_signers_data(tx, config);
     Ok(output_format.formatted_string(&cli_command))
 }
@@ -2526,6 +2538,10 @@ pub fn return_signers_data(tx: &Transaction, config: &ReturnSignersConfig) -> Cl
         signers,
         absent,
         bad_sig,
+        transaction_file: config
+            .transaction_file
+            .as_ref()
+            .map(|transaction_file| transaction_file.display().to_string()),
     }
 }
 
@@ -3213,6 +3229,7 @@ mod tests {
                 signers: vec![format!("{}={}", present.pubkey(), tx.signatures[1])],
                 absent: vec![absent.pubkey().to_string()],
                 bad_sig: vec![bad.pubkey().to_string()],
+                transaction_file: None,
             }
         );
 
@@ -3225,6 +3242,7 @@ mod tests {
             .to_string();
         let config = ReturnSignersConfig {
             dump_transaction_message: true,
+            ..ReturnSignersConfig::default()
         };
         let res = return_signers_with_config(&tx, &OutputFormat::JsonCompact, &config).unwrap();
         let sign_only = parse_sign_only_reply_string(&res);
@@ -3243,6 +3261,7 @@ mod tests {
                 signers: vec![format!("{}={}", present.pubkey(), tx.signatures[1])],
                 absent: vec![absent.pubkey().to_string()],
                 bad_sig: vec![bad.pubkey().to_string()],
+                transaction_file: None,
             }
         );
     }
@@ -2,6 +2,7 @@
 mod cli_output;
 pub mod cli_version;
 pub mod display;
+pub mod partially_signed_transaction;
 pub use cli_output::*;
 
 pub trait QuietDisplay: std::fmt::Display {
@@ -0,0 +1,151 @@
+//! A file format to pass a transaction between the signers of an offline signing session.
+//!
+//! The file is written by a command run with `--sign-only`, each signer adds their signatures to
+//! it, and once none is absent the transaction can be submitted.
+
+use {
+    base64::{prelude::BASE64_STANDARD, Engine},
+    serde::{Deserialize, Serialize},
+    solana_sdk::{
+        packet::PACKET_DATA_SIZE, pubkey::Pubkey, sanitize::Sanitize, signature::Signature,
+        transaction::Transaction,
+    },
+    std::{error, fs, path::Path},
+};
+
+/// The version of the file format, changed on incompatible changes
+const VERSION: u8 = 1;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+struct PartiallySignedTransactionFile {
+    version: u8,
+    /// The base64 encoded transaction, with default signatures for the absent signers
+    transaction: String,
+}
+
+/// Writes `transaction` to `path`.
+///
+/// The file is replaced atomically, so an interrupted write does not lose the signatures already
+/// collected in it.
+pub fn write_partially_signed_transaction(
+    path: &Path,
+    transaction: &Transaction,
+) -> Result<(), Box<dyn error::Error>> {
+    let file = PartiallySignedTransactionFile {
+        version: VERSION,
+        transaction: BASE64_STANDARD.encode(bincode::serialize(transaction)?),
+    };
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    fs::write(&temp_path, serde_json::to_string_pretty(&file)?)?;
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+/// Reads a transaction written by [`write_partially_signed_transaction`].
+///
+/// Fails if any of the signatures present in the file is invalid, so that a corrupted or
+/// tampered file is not signed any further.
+pub fn read_partially_signed_transaction(
+    path: &Path,
+) -> Result<Transaction, Box<dyn error::Error>> {
+    let file: PartiallySignedTransactionFile = serde_json::from_str(&fs::read_to_string(path)?)
+        .map_err(|err| format!("Invalid partially signed transaction file: {err}"))?;
+    if file.version != VERSION {
+        return Err(format!(
+            "Unsupported partially signed transaction file version: {}",
+            file.version
+        )
+        .into());
+    }
+
+    let data = BASE64_STANDARD.decode(file.transaction)?;
+    if data.len() > PACKET_DATA_SIZE {
+        return Err(format!(
+            "Transaction too large: {} bytes (max: {PACKET_DATA_SIZE} bytes)",
+            data.len()
+        )
+        .into());
+    }
+    let transaction: Transaction = bincode::deserialize(&data)?;
+    transaction.sanitize()?;
+    if transaction.signatures.len()
+        != usize::from(transaction.message.header.num_required_signatures)
+    {
+        return Err("Transaction does not have one signature per signer".into());
+    }
+
+    for ((signature, pubkey), verified) in transaction
+        .signatures
+        .iter()
+        .zip(transaction.message.account_keys.iter())
+        .zip(transaction.verify_with_results())
+    {
+        if !verified && *signature != Signature::default() {
+            return Err(format!("Invalid signature for {pubkey}").into());
+        }
+    }
+    Ok(transaction)
+}
+
+/// Returns the signers whose signature is still absent from `transaction`
+pub fn absent_signers(transaction: &Transaction) -> Vec<Pubkey> {
+    transaction
+        .signatures
+        .iter()
+        .zip(transaction.message.account_keys.iter())
+        .filter(|(signature, _)| **signature == Signature::default())
+        .map(|(_, pubkey)| *pubkey)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash,
+            message::Message,
+            signature::{Keypair, Signer},
+            system_instruction,
+        },
+    };
+
+    #[test]
+    fn test_partially_signed_transaction_file() {
+        let from = Keypair::new();
+        let fee_payer = Keypair::new();
+        let mut transaction = Transaction::new_unsigned(Message::new(
+            &[system_instruction::transfer(
+                &from.pubkey(),
+                &Pubkey::new_unique(),
+                42,
+            )],
+            Some(&fee_payer.pubkey()),
+        ));
+        transaction
+            .try_partial_sign(&[&from], Hash::new_unique())
+            .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("transaction.json");
+        write_partially_signed_transaction(&path, &transaction).unwrap();
+        let read_transaction = read_partially_signed_transaction(&path).unwrap();
+        assert_eq!(read_transaction, transaction);
+        assert_eq!(absent_signers(&read_transaction), vec![fee_payer.pubkey()]);
+
+        // A signature made by another key is rejected
+        transaction.signatures[1] = fee_payer.sign_message(&transaction.message_data());
+        write_partially_signed_transaction(&path, &transaction).unwrap();
+        assert_eq!(
+            read_partially_signed_transaction(&path)
+                .unwrap_err()
+                .to_string(),
+            format!("Invalid signature for {}", from.pubkey())
+        );
+
+        fs::write(&path, r#"{"version":2,"transaction":""}"#).unwrap();
+        assert!(read_partially_signed_transaction(&path).is_err());
+    }
+}
@@ -34,7 +34,8 @@ use {
     solana_tpu_client::tpu_client::DEFAULT_TPU_ENABLE_UDP,
     solana_vote_program::vote_state::VoteAuthorize,
     std::{
-        collections::HashMap, error, io::stdout, rc::Rc, str::FromStr, sync::Arc, time::Duration,
+        collections::HashMap, error, io::stdout, path::PathBuf, rc::Rc, str::FromStr, sync::Arc,
+        time::Duration,
     },
     thiserror::Error,
 };
@@ -453,6 +454,13 @@ pub enum CliCommand {
         signature: Signature,
         message: OffchainMessage,
     },
+    SignTransaction {
+        transaction_file: PathBuf,
+    },
+    SubmitTransaction {
+        transaction_file: PathBuf,
+        no_wait: bool,
+    },
 }
 
 #[derive(Debug, PartialEq)]
@@ -519,6 +527,8 @@ pub struct CliConfig<'a> {
     pub confirm_transaction_initial_timeout: Duration,
     pub address_labels: HashMap<String, String>,
     pub use_quic: bool,
+    /// The file to write the partially signed transaction of a `--sign-only` command to
+    pub sign_only_transaction_file: Option<PathBuf>,
 }
 
 impl CliConfig<'_> {
@@ -567,6 +577,7 @@ impl Default for CliConfig<'_> {
             ),
             address_labels: HashMap::new(),
             use_quic: !DEFAULT_TPU_ENABLE_UDP,
+            sign_only_transaction_file: None,
         }
     }
 }
@@ -833,6 +844,10 @@ pub fn parse_command(
         ("verify-offchain-signature", Some(matches)) => {
             parse_verify_offchain_signature(matches, default_signer, wallet_manager)
         }
+        ("sign-transaction", Some(matches)) => {
+            parse_sign_transaction(matches, default_signer, wallet_manager)
+        }
+        ("submit", Some(matches)) => parse_submit_transaction(matches),
         //
         ("", None) => {
             eprintln!("{}", matches.usage());
@@ -1662,6 +1677,13 @@ pub fn process_command(config: &CliConfig) -> ProcessResult {
             signature,
             message,
         } => process_verify_offchain_signature(config, signer_pubkey, signature, message),
+        CliCommand::SignTransaction { transaction_file } => {
+            process_sign_transaction(config, transaction_file)
+        }
+        CliCommand::SubmitTransaction {
+            transaction_file,
+            no_wait,
+        } => process_submit_transaction(&rpc_client, config, transaction_file, *no_wait),
     }
 }
 
@@ -2043,6 +2065,63 @@ mod tests {
                 signers: vec![read_keypair_file(&keypair_file).unwrap().into()],
             }
         );
+
+        // Test SignTransaction
+        let test_sign_transaction = test_commands.clone().get_matches_from(vec![
+            "test",
+            "sign-transaction",
+            "transaction.json",
+        ]);
+        assert_eq!(
+            parse_command(&test_sign_transaction, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::SignTransaction {
+                    transaction_file: PathBuf::from("transaction.json"),
+                },
+                signers: vec![read_keypair_file(&keypair_file).unwrap().into()],
+            }
+        );
+        let other_keypair = Keypair::new();
+        let other_keypair_file = make_tmp_path("other_keypair_file");
+        write_keypair_file(&other_keypair, &other_keypair_file).unwrap();
+        let test_sign_transaction = test_commands.clone().get_matches_from(vec![
+            "test",
+            "sign-transaction",
+            "transaction.json",
+            &other_keypair_file,
+            &keypair_file,
+            &other_keypair_file,
+        ]);
+        assert_eq!(
+            parse_command(&test_sign_transaction, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::SignTransaction {
+                    transaction_file: PathBuf::from("transaction.json"),
+                },
+                signers: vec![
+                    read_keypair_file(&other_keypair_file).unwrap().into(),
+                    read_keypair_file(&keypair_file).unwrap().into(),
+                ],
+            }
+        );
+
+        // Test SubmitTransaction
+        let test_submit = test_commands.clone().get_matches_from(vec![
+            "test",
+            "submit",
+            "transaction.json",
+            "--no-wait",
+        ]);
+        assert_eq!(
+            parse_command(&test_submit, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::SubmitTransaction {
+                    transaction_file: PathBuf::from("transaction.json"),
+                    no_wait: true,
+                },
+                signers: vec![],
+            }
+        );
     }
 
     #[test]
@@ -1645,6 +1645,7 @@ pub fn process_logs(config: &CliConfig, filter: &RpcTransactionLogsFilter) -> Pr
         filter.clone(),
         RpcTransactionLogsConfig {
             commitment: Some(config.commitment),
+            from_slot: None,
         },
     )?;
 
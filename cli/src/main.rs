@@ -4,6 +4,7 @@ use {
     solana_clap_utils::{
         input_validators::normalize_to_url_if_moniker,
         keypair::{CliSigners, DefaultSigner},
+        offline::TRANSACTION_FILE_ARG,
         DisplayError,
     },
     solana_cli::{
@@ -212,6 +213,13 @@ pub fn parse_args<'a>(
         !DEFAULT_TPU_ENABLE_UDP
     };
 
+    // All the commands accepting `--sign-only` are top-level subcommands
+    let sign_only_transaction_file = matches
+        .subcommand()
+        .1
+        .and_then(|matches| matches.value_of(TRANSACTION_FILE_ARG.name))
+        .map(PathBuf::from);
+
     Ok((
         CliConfig {
             command,
@@ -231,6 +239,7 @@ pub fn parse_args<'a>(
             confirm_transaction_initial_timeout,
             address_labels,
             use_quic,
+            sign_only_transaction_file,
         },
         signers,
     ))
@@ -222,6 +222,7 @@ impl StakeSubCommands for App<'_, '_> {
                         .help("Source account of funds [default: cli config keypair]"),
                 )
                 .offline_args()
+                .arg(transaction_file_arg())
                 .nonce_args(false)
                 .arg(fee_payer_arg())
                 .arg(memo_arg())
@@ -288,6 +289,7 @@ impl StakeSubCommands for App<'_, '_> {
                         .help("Source account of funds [default: cli config keypair]"),
                 )
                 .offline_args()
+                .arg(transaction_file_arg())
                 .nonce_args(false)
                 .arg(fee_payer_arg())
                 .arg(memo_arg())
@@ -319,6 +321,7 @@ impl StakeSubCommands for App<'_, '_> {
                 ))
                 .arg(stake_authority_arg())
                 .offline_args()
+                .arg(transaction_file_arg())
                 .nonce_args(false)
                 .arg(fee_payer_arg())
                 .arg(memo_arg())
@@ -366,6 +369,7 @@ impl StakeSubCommands for App<'_, '_> {
                 )
                 .arg(stake_authority_arg())
                 .offline_args()
+                .arg(transaction_file_arg())
                 .nonce_args(false)
                 .arg(fee_payer_arg())
                 .arg(memo_arg()),
@@ -397,6 +401,7 @@ impl StakeSubCommands for App<'_, '_> {
                 .arg(stake_authority_arg())
                 .arg(withdraw_authority_arg())
                 .offline_args()
+                .arg(transaction_file_arg())
                 .nonce_args(false)
                 .arg(fee_payer_arg())
                 .arg(custodian_arg())
@@ -446,6 +451,7 @@ impl StakeSubCommands for App<'_, '_> {
                 .arg(stake_authority_arg())
                 .arg(withdraw_authority_arg())
                 .offline_args()
+                .arg(transaction_file_arg())
                 .nonce_args(false)
                 .arg(fee_payer_arg())
                 .arg(custodian_arg())
@@ -494,6 +500,7 @@ impl StakeSubCommands for App<'_, '_> {
                 )
                 .arg(stake_authority_arg())
                 .offline_args()
+                .arg(transaction_file_arg())
                 .nonce_args(false)
                 .arg(fee_payer_arg())
                 .arg(memo_arg())
@@ -539,6 +546,7 @@ impl StakeSubCommands for App<'_, '_> {
                 )
                 .arg(stake_authority_arg())
                 .offline_args_config(&SignOnlySplitNeedsRent {})
+                .arg(transaction_file_arg())
                 .nonce_args(false)
                 .arg(fee_payer_arg())
                 .arg(memo_arg())
@@ -576,6 +584,7 @@ impl StakeSubCommands for App<'_, '_> {
                 ))
                 .arg(stake_authority_arg())
                 .offline_args()
+                .arg(transaction_file_arg())
                 .nonce_args(false)
                 .arg(fee_payer_arg())
                 .arg(memo_arg())
@@ -623,6 +632,7 @@ impl StakeSubCommands for App<'_, '_> {
                 )
                 .arg(withdraw_authority_arg())
                 .offline_args()
+                .arg(transaction_file_arg())
                 .nonce_args(false)
                 .arg(fee_payer_arg())
                 .arg(custodian_arg())
@@ -681,6 +691,7 @@ impl StakeSubCommands for App<'_, '_> {
                         .help("Keypair of the existing custodian [default: cli config pubkey]"),
                 )
                 .offline_args()
+                .arg(transaction_file_arg())
                 .nonce_args(false)
                 .arg(fee_payer_arg())
                 .arg(memo_arg())
@@ -740,6 +751,7 @@ impl StakeSubCommands for App<'_, '_> {
                         .help("Keypair of the existing custodian [default: cli config pubkey]"),
                 )
                 .offline_args()
+                .arg(transaction_file_arg())
                 .nonce_args(false)
                 .arg(fee_payer_arg())
                 .arg(memo_arg())
@@ -1544,6 +1556,7 @@ pub fn process_create_stake_account(
             &config.output_format,
             &ReturnSignersConfig {
                 dump_transaction_message,
+                transaction_file: config.sign_only_transaction_file.clone(),
             },
         )
     } else {
@@ -1662,6 +1675,7 @@ pub fn process_stake_authorize(
             &config.output_format,
             &ReturnSignersConfig {
                 dump_transaction_message,
+                transaction_file: config.sign_only_transaction_file.clone(),
             },
         )
     } else {
@@ -1805,6 +1819,7 @@ pub fn process_deactivate_stake_account(
             &config.output_format,
             &ReturnSignersConfig {
                 dump_transaction_message,
+                transaction_file: config.sign_only_transaction_file.clone(),
             },
         )
     } else {
@@ -1904,6 +1919,7 @@ pub fn process_withdraw_stake(
             &config.output_format,
             &ReturnSignersConfig {
                 dump_transaction_message,
+                transaction_file: config.sign_only_transaction_file.clone(),
             },
         )
     } else {
@@ -2062,6 +2078,7 @@ pub fn process_split_stake(
             &config.output_format,
             &ReturnSignersConfig {
                 dump_transaction_message,
+                transaction_file: config.sign_only_transaction_file.clone(),
             },
         )
     } else {
@@ -2168,6 +2185,7 @@ pub fn process_merge_stake(
             &config.output_format,
             &ReturnSignersConfig {
                 dump_transaction_message,
+                transaction_file: config.sign_only_transaction_file.clone(),
             },
         )
     } else {
@@ -2263,6 +2281,7 @@ pub fn process_stake_set_lockup(
             &config.output_format,
             &ReturnSignersConfig {
                 dump_transaction_message,
+                transaction_file: config.sign_only_transaction_file.clone(),
             },
         )
     } else {
@@ -2745,6 +2764,7 @@ pub fn process_delegate_stake(
             &config.output_format,
             &ReturnSignersConfig {
                 dump_transaction_message,
+                transaction_file: config.sign_only_transaction_file.clone(),
             },
         )
     } else {
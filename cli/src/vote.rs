@@ -114,6 +114,7 @@ impl VoteSubCommands for App<'_, '_> {
                         ),
                 )
                 .offline_args()
+                .arg(transaction_file_arg())
                 .nonce_args(false)
                 .arg(fee_payer_arg())
                 .arg(memo_arg())
@@ -145,6 +146,7 @@ impl VoteSubCommands for App<'_, '_> {
                     "New authorized vote signer. "
                 ))
                 .offline_args()
+                .arg(transaction_file_arg())
                 .nonce_args(false)
                 .arg(fee_payer_arg())
                 .arg(memo_arg())
@@ -176,6 +178,7 @@ impl VoteSubCommands for App<'_, '_> {
                     "New authorized withdrawer. "
                 ))
                 .offline_args()
+                .arg(transaction_file_arg())
                 .nonce_args(false)
                 .arg(fee_payer_arg())
                 .arg(memo_arg())
@@ -211,6 +214,7 @@ impl VoteSubCommands for App<'_, '_> {
                         .help("New authorized vote signer."),
                 )
                 .offline_args()
+                .arg(transaction_file_arg())
                 .nonce_args(false)
                 .arg(fee_payer_arg())
                 .arg(memo_arg())
@@ -246,6 +250,7 @@ impl VoteSubCommands for App<'_, '_> {
                         .help("New authorized withdrawer."),
                 )
                 .offline_args()
+                .arg(transaction_file_arg())
                 .nonce_args(false)
                 .arg(fee_payer_arg())
                 .arg(memo_arg())
@@ -280,6 +285,7 @@ impl VoteSubCommands for App<'_, '_> {
                         .help("Authorized withdrawer keypair"),
                 )
                 .offline_args()
+                .arg(transaction_file_arg())
                 .nonce_args(false)
                 .arg(fee_payer_arg())
                 .arg(memo_arg())
@@ -314,6 +320,7 @@ impl VoteSubCommands for App<'_, '_> {
                         .help("Authorized withdrawer keypair"),
                 )
                 .offline_args()
+                .arg(transaction_file_arg())
                 .nonce_args(false)
                 .arg(fee_payer_arg())
                 .arg(memo_arg())
@@ -400,6 +407,7 @@ impl VoteSubCommands for App<'_, '_> {
                         .help("Authorized withdrawer [default: cli config keypair]"),
                 )
                 .offline_args()
+                .arg(transaction_file_arg())
                 .nonce_args(false)
                 .arg(fee_payer_arg())
                 .arg(memo_arg())
@@ -915,6 +923,7 @@ pub fn process_create_vote_account(
             &config.output_format,
             &ReturnSignersConfig {
                 dump_transaction_message,
+                transaction_file: config.sign_only_transaction_file.clone(),
             },
         )
     } else {
@@ -1031,6 +1040,7 @@ pub fn process_vote_authorize(
             &config.output_format,
             &ReturnSignersConfig {
                 dump_transaction_message,
+                transaction_file: config.sign_only_transaction_file.clone(),
             },
         )
     } else {
@@ -1107,6 +1117,7 @@ pub fn process_vote_update_validator(
             &config.output_format,
             &ReturnSignersConfig {
                 dump_transaction_message,
+                transaction_file: config.sign_only_transaction_file.clone(),
             },
         )
     } else {
@@ -1176,6 +1187,7 @@ pub fn process_vote_update_commission(
             &config.output_format,
             &ReturnSignersConfig {
                 dump_transaction_message,
+                transaction_file: config.sign_only_transaction_file.clone(),
             },
         )
     } else {
@@ -1370,6 +1382,7 @@ pub fn process_withdraw_from_vote_account(
             &config.output_format,
             &ReturnSignersConfig {
                 dump_transaction_message,
+                transaction_file: config.sign_only_transaction_file.clone(),
             },
         )
     } else {
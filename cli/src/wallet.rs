@@ -17,16 +17,17 @@ use {
         hidden_unless_forced,
         input_parsers::*,
         input_validators::*,
-        keypair::{DefaultSigner, SignerIndex},
+        keypair::{signer_from_path, DefaultSigner, SignerIndex},
         memo::*,
         nonce::*,
         offline::*,
     },
     solana_cli_output::{
         display::{build_balance_message, BuildBalanceMessageConfig},
+        partially_signed_transaction::{absent_signers, read_partially_signed_transaction},
         return_signers_with_config, CliAccount, CliBalance, CliFindProgramDerivedAddress,
-        CliSignatureVerificationStatus, CliTransaction, CliTransactionConfirmation, OutputFormat,
-        ReturnSignersConfig,
+        CliSignature, CliSignatureVerificationStatus, CliTransaction, CliTransactionConfirmation,
+        OutputFormat, ReturnSignersConfig,
     },
     solana_remote_wallet::remote_wallet::RemoteWalletManager,
     solana_rpc_client::rpc_client::RpcClient,
@@ -47,7 +48,14 @@ use {
         EncodableWithMeta, EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction,
         TransactionBinaryEncoding, UiTransactionEncoding,
     },
-    std::{fmt::Write as FmtWrite, fs::File, io::Write, rc::Rc, str::FromStr},
+    std::{
+        fmt::Write as FmtWrite,
+        fs::File,
+        io::Write,
+        path::{Path, PathBuf},
+        rc::Rc,
+        str::FromStr,
+    },
 };
 
 pub trait WalletSubCommands {
@@ -315,6 +323,7 @@ impl WalletSubCommands for App<'_, '_> {
                         .help("Complete the transfer even if the recipient address is not funded"),
                 )
                 .offline_args()
+                .arg(transaction_file_arg())
                 .nonce_args(false)
                 .arg(memo_arg())
                 .arg(fee_payer_arg())
@@ -385,6 +394,51 @@ impl WalletSubCommands for App<'_, '_> {
                     "The pubkey of the message signer (if different from config default)"
                 )),
         )
+        .subcommand(
+            SubCommand::with_name("sign-transaction")
+                .about("Add signatures to a partially signed transaction file")
+                .arg(
+                    Arg::with_name("file")
+                        .index(1)
+                        .value_name("TRANSACTION_FILE")
+                        .takes_value(true)
+                        .required(true)
+                        .help(
+                            "The partially signed transaction file, as written by a command \
+                             run with --sign-only --transaction-file",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("signers")
+                        .index(2)
+                        .value_name("SIGNER_KEYPAIR")
+                        .takes_value(true)
+                        .multiple(true)
+                        .validator(is_valid_signer)
+                        .help("The signers to add the signatures of [default: cli config keypair]"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("submit")
+                .about("Submit a partially signed transaction file once all signers have signed it")
+                .arg(
+                    Arg::with_name("file")
+                        .index(1)
+                        .value_name("TRANSACTION_FILE")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The partially signed transaction file"),
+                )
+                .arg(
+                    Arg::with_name("no_wait")
+                        .long("no-wait")
+                        .takes_value(false)
+                        .help(
+                            "Return signature immediately after submitting the transaction, \
+                             instead of waiting for confirmations",
+                        ),
+                ),
+        )
     }
 }
 
@@ -652,6 +706,40 @@ pub fn parse_verify_offchain_signature(
     })
 }
 
+pub fn parse_sign_transaction(
+    matches: &ArgMatches<'_>,
+    default_signer: &DefaultSigner,
+    wallet_manager: &mut Option<Rc<RemoteWalletManager>>,
+) -> Result<CliCommandInfo, CliError> {
+    let transaction_file = PathBuf::from(value_t_or_exit!(matches, "file", String));
+    let bulk_signers = match matches.values_of("signers") {
+        Some(paths) => paths
+            .map(|path| signer_from_path(matches, path, "signers", wallet_manager).map(Some))
+            .collect::<Result<Vec<_>, _>>()?,
+        None => vec![None],
+    };
+    let signer_info =
+        default_signer.generate_unique_signers(bulk_signers, matches, wallet_manager)?;
+
+    Ok(CliCommandInfo {
+        command: CliCommand::SignTransaction { transaction_file },
+        signers: signer_info.signers,
+    })
+}
+
+pub fn parse_submit_transaction(matches: &ArgMatches<'_>) -> Result<CliCommandInfo, CliError> {
+    let transaction_file = PathBuf::from(value_t_or_exit!(matches, "file", String));
+    let no_wait = matches.is_present("no_wait");
+
+    Ok(CliCommandInfo {
+        command: CliCommand::SubmitTransaction {
+            transaction_file,
+            no_wait,
+        },
+        signers: vec![],
+    })
+}
+
 pub fn process_show_account(
     rpc_client: &RpcClient,
     config: &CliConfig,
@@ -966,6 +1054,7 @@ pub fn process_transfer(
             &config.output_format,
             &ReturnSignersConfig {
                 dump_transaction_message,
+                transaction_file: config.sign_only_transaction_file.clone(),
             },
         )
     } else {
@@ -1013,3 +1102,58 @@ pub fn process_verify_offchain_signature(
         Err(CliError::InvalidSignature.into())
     }
 }
+
+pub fn process_sign_transaction(config: &CliConfig, transaction_file: &Path) -> ProcessResult {
+    let mut transaction = read_partially_signed_transaction(transaction_file)?;
+    let signer_keys = transaction.message.signer_keys();
+    for signer in config.signers.iter() {
+        let pubkey = signer.try_pubkey()?;
+        if !signer_keys.contains(&&pubkey) {
+            return Err(CliError::BadParameter(format!(
+                "{pubkey} is not a signer of the transaction"
+            ))
+            .into());
+        }
+    }
+
+    let blockhash = transaction.message.recent_blockhash;
+    transaction.try_partial_sign(&config.signers, blockhash)?;
+    return_signers_with_config(
+        &transaction,
+        &config.output_format,
+        &ReturnSignersConfig {
+            dump_transaction_message: false,
+            transaction_file: Some(transaction_file.to_path_buf()),
+        },
+    )
+}
+
+pub fn process_submit_transaction(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    transaction_file: &Path,
+    no_wait: bool,
+) -> ProcessResult {
+    let transaction = read_partially_signed_transaction(transaction_file)?;
+    let absent_signers = absent_signers(&transaction);
+    if !absent_signers.is_empty() {
+        return Err(CliError::BadParameter(format!(
+            "Transaction is missing the signatures of {}",
+            absent_signers
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+        .into());
+    }
+
+    let signature = if no_wait {
+        rpc_client.send_transaction(&transaction)?
+    } else {
+        rpc_client.send_and_confirm_transaction_with_spinner(&transaction)?
+    };
+    Ok(config.output_format.formatted_string(&CliSignature {
+        signature: signature.to_string(),
+    }))
+}
@@ -474,6 +474,121 @@ fn test_transfer_multisession_signing() {
     check_balance!(sol_to_lamports(42.0), &rpc_client, &to_pubkey);
 }
 
+#[test]
+fn test_transfer_multisession_signing_with_transaction_file() {
+    solana_logger::setup();
+    let fee = FeeStructure::default().get_max_fee(2, 0);
+    let mint_keypair = Keypair::new();
+    let mint_pubkey = mint_keypair.pubkey();
+    let faucet_addr = run_local_faucet(mint_keypair, None);
+    let test_validator = TestValidator::with_custom_fees(
+        mint_pubkey,
+        1,
+        Some(faucet_addr),
+        SocketAddrSpace::Unspecified,
+    );
+
+    let to_pubkey = Pubkey::from([1u8; 32]);
+    let offline_from_signer = keypair_from_seed(&[2u8; 32]).unwrap();
+    let offline_fee_payer_signer = keypair_from_seed(&[3u8; 32]).unwrap();
+    let from_null_signer = NullSigner::new(&offline_from_signer.pubkey());
+    let fee_payer_null_signer = NullSigner::new(&offline_fee_payer_signer.pubkey());
+    let transaction_dir = tempfile::tempdir().unwrap();
+    let transaction_file = transaction_dir.path().join("transaction.json");
+
+    // Setup accounts
+    let rpc_client =
+        RpcClient::new_with_commitment(test_validator.rpc_url(), CommitmentConfig::processed());
+    request_and_confirm_airdrop(
+        &rpc_client,
+        &CliConfig::recent_for_tests(),
+        &offline_from_signer.pubkey(),
+        sol_to_lamports(43.0),
+    )
+    .unwrap();
+    request_and_confirm_airdrop(
+        &rpc_client,
+        &CliConfig::recent_for_tests(),
+        &offline_fee_payer_signer.pubkey(),
+        sol_to_lamports(1.0) + fee,
+    )
+    .unwrap();
+    check_ready(&rpc_client);
+
+    let blockhash = rpc_client.get_latest_blockhash().unwrap();
+
+    // The transaction is written without any signature
+    let mut config = CliConfig::recent_for_tests();
+    config.json_rpc_url = String::default();
+    config.signers = vec![&fee_payer_null_signer, &from_null_signer];
+    config.sign_only_transaction_file = Some(transaction_file.clone());
+    config.command = CliCommand::Transfer {
+        amount: SpendAmount::Some(sol_to_lamports(42.0)),
+        to: to_pubkey,
+        from: 1,
+        sign_only: true,
+        dump_transaction_message: false,
+        allow_unfunded_recipient: true,
+        no_wait: false,
+        blockhash_query: BlockhashQuery::None(blockhash),
+        nonce_account: None,
+        nonce_authority: 0,
+        memo: None,
+        fee_payer: 0,
+        derived_address_seed: None,
+        derived_address_program_id: None,
+        compute_unit_price: None,
+    };
+    config.output_format = OutputFormat::JsonCompact;
+    let sign_only = parse_sign_only_reply_string(&process_command(&config).unwrap());
+    assert_eq!(sign_only.absent_signers.len(), 2);
+
+    // A key which is not a signer of the transaction cannot sign it
+    let other_signer = Keypair::new();
+    let mut config = CliConfig::recent_for_tests();
+    config.json_rpc_url = String::default();
+    config.signers = vec![&other_signer];
+    config.command = CliCommand::SignTransaction {
+        transaction_file: transaction_file.clone(),
+    };
+    process_command(&config).unwrap_err();
+
+    // The offline fee-payer signs first
+    config.signers = vec![&offline_fee_payer_signer];
+    config.output_format = OutputFormat::JsonCompact;
+    let sign_only = parse_sign_only_reply_string(&process_command(&config).unwrap());
+    assert_eq!(sign_only.absent_signers, vec![offline_from_signer.pubkey()]);
+
+    // The transaction cannot be submitted until all signers have signed it
+    let mut submit_config = CliConfig::recent_for_tests();
+    submit_config.json_rpc_url = test_validator.rpc_url();
+    submit_config.command = CliCommand::SubmitTransaction {
+        transaction_file: transaction_file.clone(),
+        no_wait: false,
+    };
+    process_command(&submit_config).unwrap_err();
+
+    // Now the offline fund source
+    config.signers = vec![&offline_from_signer];
+    let sign_only = parse_sign_only_reply_string(&process_command(&config).unwrap());
+    assert!(sign_only.has_all_signers());
+
+    // Finally submit to the cluster
+    process_command(&submit_config).unwrap();
+
+    check_balance!(
+        sol_to_lamports(1.0),
+        &rpc_client,
+        &offline_from_signer.pubkey(),
+    );
+    check_balance!(
+        sol_to_lamports(1.0),
+        &rpc_client,
+        &offline_fee_payer_signer.pubkey(),
+    );
+    check_balance!(sol_to_lamports(42.0), &rpc_client, &to_pubkey);
+}
+
 #[test]
 fn test_transfer_all() {
     solana_logger::setup();
@@ -0,0 +1,609 @@
+//! Offline replay of banking trace events against a bank restored from a snapshot.
+//!
+//! `BankingTracer` records every packet batch handed to banking stage together with
+//! `BlockAndBankHash` markers emitted whenever a leader slot is started on top of a frozen
+//! parent. `BankingSimulator` reads those events back, spins up a real `BankingStage` on a child
+//! of the parent bank, re-sends the packet batches with their original relative timing and
+//! compares the resulting blocks with the ones recorded in the blockstore.
+use {
+    crate::{
+        banking_stage::BankingStage,
+        banking_trace::{
+            BankingPacketBatch, BankingTracer, ChannelLabel, TimedTracedEvent, TracedEvent,
+            TracedSender, BASENAME,
+        },
+        validator::BlockProductionMethod,
+    },
+    crossbeam_channel::unbounded,
+    solana_client::connection_cache::ConnectionCache,
+    solana_cost_model::cost_model::CostModel,
+    solana_gossip::cluster_info::{ClusterInfo, Node},
+    solana_ledger::{blockstore::Blockstore, leader_schedule_cache::LeaderScheduleCache},
+    solana_poh::{
+        poh_recorder::{PohRecorder, WorkingBankEntry},
+        poh_service::{self, PohService},
+    },
+    solana_runtime::{
+        bank::Bank, bank_forks::BankForks, installed_scheduler_pool::BankWithScheduler,
+        prioritization_fee_cache::PrioritizationFeeCache,
+    },
+    solana_sdk::{
+        clock::{Slot, NUM_CONSECUTIVE_LEADER_SLOTS},
+        genesis_config::GenesisConfig,
+        hash::Hash,
+        pubkey::Pubkey,
+        signature::{Keypair, Signature, Signer},
+        transaction::{MessageHash, SanitizedTransaction, VersionedTransaction},
+    },
+    solana_streamer::socket::SocketAddrSpace,
+    std::{
+        collections::{HashMap, HashSet},
+        fs::File,
+        io::{self, BufReader},
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, RwLock,
+        },
+        thread::{self, sleep, JoinHandle},
+        time::{Duration, Instant, SystemTime},
+    },
+    thiserror::Error,
+};
+
+/// Packet batches received this long before the first simulated slot started are sent to
+/// banking stage up front, so that they are buffered just like they were on the traced leader.
+const PRE_LEADER_SLOT_PACKET_WINDOW: Duration = Duration::from_millis(800);
+const WAIT_FOR_WORKING_BANK_INTERVAL: Duration = Duration::from_millis(1);
+
+#[derive(Error, Debug)]
+pub enum SimulateError {
+    #[error("IO Error: {0}")]
+    IoError(#[from] io::Error),
+
+    #[error("Deserialization Error: {0}")]
+    DeserializeError(#[from] bincode::Error),
+
+    #[error("Blockstore Error: {0}")]
+    BlockstoreError(#[from] solana_ledger::blockstore::BlockstoreError),
+
+    #[error("No frozen parent bank for the first simulated slot {0}")]
+    MissingParentBank(Slot),
+
+    #[error("No leader is known for slot {0}")]
+    UnknownLeader(Slot),
+
+    #[error("Banking trace has no block and bank hash event for parent slot {0}")]
+    MissingParentHashEvent(Slot),
+
+    #[error("Failed to join a simulation thread")]
+    ThreadJoinError,
+}
+
+/// All events read from one or more banking trace files, ordered by their recording time.
+#[derive(Default)]
+pub struct BankingTraceEvents {
+    packet_batches: Vec<(SystemTime, ChannelLabel, BankingPacketBatch)>,
+    hash_events_by_slot: HashMap<Slot, (SystemTime, Hash, Hash)>,
+}
+
+impl BankingTraceEvents {
+    /// Returns the trace files found in `dir`, oldest first, as written by `BankingTracer`.
+    pub fn event_file_paths_in_dir(dir: &Path) -> Result<Vec<PathBuf>, SimulateError> {
+        let mut paths = std::fs::read_dir(dir)?
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                let file_name = path.file_name()?.to_str()?;
+                let rotation = if file_name == BASENAME {
+                    0
+                } else {
+                    file_name
+                        .strip_prefix(BASENAME)?
+                        .strip_prefix('.')?
+                        .parse::<u64>()
+                        .ok()?
+                };
+                Some((rotation, path))
+            })
+            .collect::<Vec<_>>();
+        // rotated files have larger suffixes the older they are
+        paths.sort_unstable_by(|(a, _), (b, _)| b.cmp(a));
+        Ok(paths.into_iter().map(|(_, path)| path).collect())
+    }
+
+    pub fn load(event_file_paths: &[PathBuf]) -> Result<Self, SimulateError> {
+        let mut events = Self::default();
+        for event_file_path in event_file_paths {
+            let event_count = events.read_event_file(event_file_path)?;
+            info!("read {event_count} banking trace events from {event_file_path:?}");
+        }
+        events
+            .packet_batches
+            .sort_by_key(|(event_time, _, _)| *event_time);
+        Ok(events)
+    }
+
+    fn read_event_file(&mut self, event_file_path: &Path) -> Result<usize, SimulateError> {
+        let mut reader = BufReader::new(File::open(event_file_path)?);
+        let mut event_count = 0;
+        loop {
+            match bincode::deserialize_from::<_, TimedTracedEvent>(&mut reader) {
+                Ok(TimedTracedEvent(event_time, event)) => {
+                    event_count += 1;
+                    match event {
+                        TracedEvent::PacketBatch(label, batch) => {
+                            self.packet_batches.push((event_time, label, batch));
+                        }
+                        TracedEvent::BlockAndBankHash(slot, blockhash, bank_hash) => {
+                            self.hash_events_by_slot
+                                .insert(slot, (event_time, blockhash, bank_hash));
+                        }
+                    }
+                }
+                Err(err) => match *err {
+                    // the tracer may have been killed in the middle of writing the last event
+                    bincode::ErrorKind::Io(ref io_err)
+                        if io_err.kind() == io::ErrorKind::UnexpectedEof =>
+                    {
+                        break;
+                    }
+                    _ => return Err(err.into()),
+                },
+            }
+        }
+        Ok(event_count)
+    }
+
+    pub fn packet_batch_count(&self) -> usize {
+        self.packet_batches.len()
+    }
+
+    pub fn hash_event(&self, slot: Slot) -> Option<&(SystemTime, Hash, Hash)> {
+        self.hash_events_by_slot.get(&slot)
+    }
+}
+
+/// Summary of a single block, either as recorded on the traced leader or as simulated.
+#[derive(Debug, Default)]
+pub struct BlockSummary {
+    pub blockhash: Option<Hash>,
+    pub bank_hash: Option<Hash>,
+    /// First signature of every transaction, in block order.
+    pub signatures: Vec<Signature>,
+    /// Sum of the cost model's estimate over all the block's transactions.
+    pub estimated_compute_units: u64,
+}
+
+impl BlockSummary {
+    fn new(bank: &Bank, transactions: impl IntoIterator<Item = VersionedTransaction>) -> Self {
+        let mut summary = Self::default();
+        for transaction in transactions {
+            summary.signatures.push(transaction.signatures[0]);
+            match SanitizedTransaction::try_create(transaction, MessageHash::Compute, None, bank) {
+                Ok(transaction) => {
+                    summary.estimated_compute_units +=
+                        CostModel::calculate_cost(&transaction, &bank.feature_set).sum();
+                }
+                Err(err) => warn!("Failed to compute cost of transaction: {err:?}"),
+            }
+        }
+        summary
+    }
+}
+
+#[derive(Debug)]
+pub struct SimulatedSlotReport {
+    pub slot: Slot,
+    pub recorded: BlockSummary,
+    pub simulated: BlockSummary,
+}
+
+impl SimulatedSlotReport {
+    /// Transactions which landed in the recorded block but not in the simulated one.
+    pub fn missing_signatures(&self) -> Vec<Signature> {
+        Self::difference(&self.recorded.signatures, &self.simulated.signatures)
+    }
+
+    /// Transactions which landed in the simulated block but not in the recorded one.
+    pub fn extra_signatures(&self) -> Vec<Signature> {
+        Self::difference(&self.simulated.signatures, &self.recorded.signatures)
+    }
+
+    /// Whether the transactions included in both blocks appear in the same relative order.
+    pub fn is_common_ordering_preserved(&self) -> bool {
+        let recorded = self.recorded.signatures.iter().collect::<HashSet<_>>();
+        let simulated = self.simulated.signatures.iter().collect::<HashSet<_>>();
+        self.recorded
+            .signatures
+            .iter()
+            .filter(|signature| simulated.contains(signature))
+            .eq(self
+                .simulated
+                .signatures
+                .iter()
+                .filter(|signature| recorded.contains(signature)))
+    }
+
+    pub fn is_bank_hash_matched(&self) -> bool {
+        self.recorded.bank_hash.is_some() && self.recorded.bank_hash == self.simulated.bank_hash
+    }
+
+    fn difference(signatures: &[Signature], others: &[Signature]) -> Vec<Signature> {
+        let others = others.iter().collect::<HashSet<_>>();
+        signatures
+            .iter()
+            .filter(|signature| !others.contains(signature))
+            .copied()
+            .collect()
+    }
+}
+
+pub struct BankingSimulator {
+    banking_trace_events: BankingTraceEvents,
+    first_simulated_slot: Slot,
+}
+
+impl BankingSimulator {
+    pub fn new(banking_trace_events: BankingTraceEvents, first_simulated_slot: Slot) -> Self {
+        Self {
+            banking_trace_events,
+            first_simulated_slot,
+        }
+    }
+
+    /// Simulates the traced leader's consecutive slots starting at `first_simulated_slot`.
+    ///
+    /// `bank_forks` must contain the frozen parent of `first_simulated_slot`, as replayed from
+    /// `blockstore`. The recorded blocks are read back from `blockstore` for comparison.
+    pub fn simulate(
+        self,
+        genesis_config: &GenesisConfig,
+        bank_forks: Arc<RwLock<BankForks>>,
+        blockstore: Arc<Blockstore>,
+        block_production_method: BlockProductionMethod,
+    ) -> Result<Vec<SimulatedSlotReport>, SimulateError> {
+        let first_slot = self.first_simulated_slot;
+        let parent_slot = blockstore
+            .meta(first_slot)?
+            .and_then(|meta| meta.parent_slot)
+            .unwrap_or_else(|| first_slot.saturating_sub(1));
+        let parent_bank = bank_forks
+            .read()
+            .unwrap()
+            .get(parent_slot)
+            .filter(|bank| bank.is_frozen())
+            .ok_or(SimulateError::MissingParentBank(first_slot))?;
+        let (base_event_time, ..) = *self
+            .banking_trace_events
+            .hash_event(parent_slot)
+            .ok_or(SimulateError::MissingParentHashEvent(parent_slot))?;
+
+        let leader_schedule_cache = Arc::new(LeaderScheduleCache::new_from_bank(&parent_bank));
+        let leader = leader_schedule_cache
+            .slot_leader_at(first_slot, Some(&parent_bank))
+            .ok_or(SimulateError::UnknownLeader(first_slot))?;
+        let simulated_slots = (first_slot..first_slot + NUM_CONSECUTIVE_LEADER_SLOTS)
+            .take_while(|slot| {
+                leader_schedule_cache.slot_leader_at(*slot, Some(&parent_bank)) == Some(leader)
+            })
+            .collect::<Vec<_>>();
+        let last_slot = *simulated_slots.last().unwrap();
+        info!(
+            "simulating slots {first_slot}..={last_slot} led by {leader} on top of {parent_slot}"
+        );
+
+        let slot_duration =
+            genesis_config.poh_config.target_tick_duration * parent_bank.ticks_per_slot() as u32;
+        let end_event_time = self
+            .banking_trace_events
+            .hash_event(last_slot)
+            .map(|(event_time, ..)| *event_time)
+            .unwrap_or_else(|| base_event_time + slot_duration * simulated_slots.len() as u32);
+
+        let exit = Arc::new(AtomicBool::default());
+        let (poh_recorder, entry_receiver, record_receiver) = PohRecorder::new(
+            parent_bank.tick_height(),
+            parent_bank.last_blockhash(),
+            parent_bank.clone(),
+            Some((first_slot, last_slot)),
+            parent_bank.ticks_per_slot(),
+            &leader,
+            blockstore.clone(),
+            &leader_schedule_cache,
+            &genesis_config.poh_config,
+            exit.clone(),
+        );
+        let poh_recorder = Arc::new(RwLock::new(poh_recorder));
+        let poh_service = PohService::new(
+            poh_recorder.clone(),
+            &genesis_config.poh_config,
+            exit.clone(),
+            parent_bank.ticks_per_slot(),
+            poh_service::DEFAULT_PINNED_CPU_CORE,
+            poh_service::DEFAULT_HASHES_PER_BATCH,
+            record_receiver,
+        );
+
+        // The traced leader's keypair isn't available here. Banking stage only falls back to the
+        // cluster info identity when there is no working bank and the poh recorder (which does
+        // carry the leader's identity) doesn't expect one shortly, which never happens while
+        // simulating.
+        let cluster_info = {
+            let keypair = Arc::new(Keypair::new());
+            let node = Node::new_localhost_with_pubkey(&keypair.pubkey());
+            Arc::new(ClusterInfo::new(
+                node.info,
+                keypair,
+                SocketAddrSpace::Unspecified,
+            ))
+        };
+
+        // Disabled tracer: simulated packet batches must not be traced again.
+        let banking_tracer = BankingTracer::new_disabled();
+        let (non_vote_sender, non_vote_receiver) = banking_tracer.create_channel_non_vote();
+        let (tpu_vote_sender, tpu_vote_receiver) = banking_tracer.create_channel_tpu_vote();
+        let (gossip_vote_sender, gossip_vote_receiver) =
+            banking_tracer.create_channel_gossip_vote();
+        let (replay_vote_sender, _replay_vote_receiver) = unbounded();
+        let connection_cache = Arc::new(ConnectionCache::with_udp(
+            "connection_cache_banking_simulation",
+            1,
+        ));
+        let banking_stage = BankingStage::new(
+            block_production_method,
+            &cluster_info,
+            &poh_recorder,
+            non_vote_receiver,
+            tpu_vote_receiver,
+            gossip_vote_receiver,
+            None,
+            replay_vote_sender,
+            None,
+            connection_cache,
+            bank_forks.clone(),
+            &Arc::new(PrioritizationFeeCache::new(0u64)),
+        );
+
+        let mut bank = Self::new_leader_bank(&bank_forks, parent_bank, &leader, first_slot);
+        poh_recorder.write().unwrap().set_bank(
+            BankWithScheduler::new_without_scheduler(bank.clone()),
+            false,
+        );
+
+        let sender_thread = Self::spawn_sender_thread(
+            self.banking_trace_events.packet_batches,
+            base_event_time,
+            end_event_time,
+            [non_vote_sender, tpu_vote_sender, gossip_vote_sender],
+            exit.clone(),
+        )?;
+
+        let mut simulated_entries = HashMap::<Slot, Vec<VersionedTransaction>>::new();
+        let mut reports = Vec::with_capacity(simulated_slots.len());
+        for (index, &slot) in simulated_slots.iter().enumerate() {
+            while poh_recorder.read().unwrap().bank().is_some() {
+                Self::collect_entries(&entry_receiver, &mut simulated_entries);
+                sleep(WAIT_FOR_WORKING_BANK_INTERVAL);
+            }
+            // any in-flight commit holds the freeze lock, so this waits for them to finish
+            bank.freeze();
+            Self::collect_entries(&entry_receiver, &mut simulated_entries);
+
+            let recorded_bank_hashes = self.banking_trace_events.hash_event(slot);
+            let mut recorded = BlockSummary::new(
+                &bank,
+                blockstore
+                    .get_slot_entries(slot, 0)?
+                    .into_iter()
+                    .flat_map(|entry| entry.transactions),
+            );
+            recorded.blockhash = recorded_bank_hashes.map(|(_, blockhash, _)| *blockhash);
+            recorded.bank_hash = recorded_bank_hashes.map(|(_, _, bank_hash)| *bank_hash);
+            let mut simulated =
+                BlockSummary::new(&bank, simulated_entries.remove(&slot).unwrap_or_default());
+            simulated.blockhash = Some(bank.last_blockhash());
+            simulated.bank_hash = Some(bank.hash());
+            reports.push(SimulatedSlotReport {
+                slot,
+                recorded,
+                simulated,
+            });
+
+            if let Some(&next_slot) = simulated_slots.get(index + 1) {
+                poh_recorder
+                    .write()
+                    .unwrap()
+                    .reset(bank.clone(), Some((next_slot, last_slot)));
+                bank = Self::new_leader_bank(&bank_forks, bank, &leader, next_slot);
+                poh_recorder.write().unwrap().set_bank(
+                    BankWithScheduler::new_without_scheduler(bank.clone()),
+                    false,
+                );
+            }
+        }
+
+        exit.store(true, Ordering::Relaxed);
+        // dropping the senders held by the sender thread terminates banking stage as well
+        sender_thread
+            .join()
+            .map_err(|_| SimulateError::ThreadJoinError)?;
+        banking_stage
+            .join()
+            .map_err(|_| SimulateError::ThreadJoinError)?;
+        poh_service
+            .join()
+            .map_err(|_| SimulateError::ThreadJoinError)?;
+
+        Ok(reports)
+    }
+
+    fn new_leader_bank(
+        bank_forks: &RwLock<BankForks>,
+        parent: Arc<Bank>,
+        leader: &Pubkey,
+        slot: Slot,
+    ) -> Arc<Bank> {
+        let bank = Bank::new_from_parent(parent, leader, slot);
+        bank_forks
+            .write()
+            .unwrap()
+            .insert(bank)
+            .clone_without_scheduler()
+    }
+
+    fn collect_entries(
+        entry_receiver: &crossbeam_channel::Receiver<WorkingBankEntry>,
+        simulated_entries: &mut HashMap<Slot, Vec<VersionedTransaction>>,
+    ) {
+        for (bank, (entry, _tick_height)) in entry_receiver.try_iter() {
+            simulated_entries
+                .entry(bank.slot())
+                .or_default()
+                .extend(entry.transactions);
+        }
+    }
+
+    /// Re-sends the traced packet batches, preserving their original timing relative to the
+    /// start of the first simulated slot.
+    fn spawn_sender_thread(
+        packet_batches: Vec<(SystemTime, ChannelLabel, BankingPacketBatch)>,
+        base_event_time: SystemTime,
+        end_event_time: SystemTime,
+        [non_vote_sender, tpu_vote_sender, gossip_vote_sender]: [TracedSender; 3],
+        exit: Arc<AtomicBool>,
+    ) -> Result<JoinHandle<()>, SimulateError> {
+        let start_event_time = base_event_time - PRE_LEADER_SLOT_PACKET_WINDOW;
+        let thread = thread::Builder::new()
+            .name("solSimSender".into())
+            .spawn(move || {
+                let simulation_start = Instant::now();
+                let (mut sent_batch_count, mut sent_packet_count) = (0, 0);
+                for (event_time, label, batch) in packet_batches
+                    .into_iter()
+                    .skip_while(|(event_time, _, _)| *event_time < start_event_time)
+                    .take_while(|(event_time, _, _)| *event_time <= end_event_time)
+                {
+                    let offset = event_time
+                        .duration_since(base_event_time)
+                        .unwrap_or_default();
+                    while simulation_start.elapsed() < offset {
+                        if exit.load(Ordering::Relaxed) {
+                            return;
+                        }
+                        sleep(WAIT_FOR_WORKING_BANK_INTERVAL);
+                    }
+                    let sender = match label {
+                        ChannelLabel::NonVote => &non_vote_sender,
+                        ChannelLabel::TpuVote => &tpu_vote_sender,
+                        ChannelLabel::GossipVote => &gossip_vote_sender,
+                        ChannelLabel::Dummy => continue,
+                    };
+                    let packet_count = batch.0.iter().map(|batch| batch.len()).sum::<usize>();
+                    if sender.send(batch).is_err() {
+                        break;
+                    }
+                    sent_packet_count += packet_count;
+                    sent_batch_count += 1;
+                }
+                info!("sent {sent_batch_count} packet batches ({sent_packet_count} packets)");
+                // keep the channels connected until the last simulated slot is done
+                while !exit.load(Ordering::Relaxed) {
+                    sleep(WAIT_FOR_WORKING_BANK_INTERVAL);
+                }
+            })?;
+        Ok(thread)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::banking_trace::{for_test, DirByteLimit},
+        solana_sdk::signature::Signature,
+        std::str::FromStr,
+        tempfile::TempDir,
+    };
+
+    fn report(recorded: &[Signature], simulated: &[Signature]) -> SimulatedSlotReport {
+        SimulatedSlotReport {
+            slot: 0,
+            recorded: BlockSummary {
+                signatures: recorded.to_vec(),
+                ..BlockSummary::default()
+            },
+            simulated: BlockSummary {
+                signatures: simulated.to_vec(),
+                ..BlockSummary::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_slot_report_diff() {
+        let [a, b, c, d] = std::array::from_fn(|_| Signature::new_unique());
+
+        let report1 = report(&[a, b, c], &[a, b, c]);
+        assert!(report1.missing_signatures().is_empty());
+        assert!(report1.extra_signatures().is_empty());
+        assert!(report1.is_common_ordering_preserved());
+
+        let report2 = report(&[a, b, c], &[d, c, a]);
+        assert_eq!(report2.missing_signatures(), vec![b]);
+        assert_eq!(report2.extra_signatures(), vec![d]);
+        assert!(!report2.is_common_ordering_preserved());
+
+        let report3 = report(&[a, b, c], &[a, d, c]);
+        assert!(report3.is_common_ordering_preserved());
+        assert!(!report3.is_bank_hash_matched());
+    }
+
+    #[test]
+    fn test_load_recorded_events() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("banking-trace");
+        let exit = Arc::<AtomicBool>::default();
+        let (tracer, tracer_thread) =
+            BankingTracer::new(Some((&path, exit.clone(), DirByteLimit::max_value()))).unwrap();
+        let (non_vote_sender, non_vote_receiver) = tracer.create_channel_non_vote();
+
+        let dummy_main_thread = thread::spawn(move || {
+            crate::banking_trace::receiving_loop_with_minimized_sender_overhead::<
+                _,
+                crate::banking_trace::TraceError,
+                0,
+            >(exit, non_vote_receiver, |_packet_batch| Ok(()))
+        });
+
+        non_vote_sender
+            .send(for_test::sample_packet_batch())
+            .unwrap();
+        non_vote_sender
+            .send(for_test::sample_packet_batch())
+            .unwrap();
+        let blockhash = Hash::from_str("B1ockhash1111111111111111111111111111111111").unwrap();
+        let bank_hash = Hash::from_str("BankHash11111111111111111111111111111111111").unwrap();
+        tracer.hash_event(4, &blockhash, &bank_hash);
+
+        for_test::terminate_tracer(
+            tracer,
+            tracer_thread,
+            dummy_main_thread,
+            non_vote_sender,
+            None,
+        );
+
+        let event_file_paths = BankingTraceEvents::event_file_paths_in_dir(&path).unwrap();
+        assert_eq!(event_file_paths, vec![path.join(BASENAME)]);
+        let events = BankingTraceEvents::load(&event_file_paths).unwrap();
+        assert_eq!(events.packet_batch_count(), 2);
+        assert_matches!(
+            events.hash_event(4),
+            Some((_, actual_blockhash, actual_bank_hash))
+                if *actual_blockhash == blockhash && *actual_bank_hash == bank_hash
+        );
+        assert!(events.hash_event(5).is_none());
+
+        for_test::drop_and_clean_temp_dir_unless_suppressed(temp_dir);
+    }
+}
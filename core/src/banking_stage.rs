@@ -20,8 +20,9 @@ use {
             consume_worker::ConsumeWorker,
             packet_deserializer::PacketDeserializer,
             transaction_scheduler::{
+                greedy_scheduler::{GreedyOrder, GreedyScheduler},
                 prio_graph_scheduler::PrioGraphScheduler,
-                scheduler_controller::SchedulerController, scheduler_error::SchedulerError,
+                scheduler_controller::SchedulerController,
             },
         },
         banking_trace::BankingPacketReceiver,
@@ -74,6 +75,15 @@ mod read_write_account_set;
 mod scheduler_messages;
 mod transaction_scheduler;
 
+// Below are pub to allow custom central schedulers, see `BankingStage::new_central_scheduler_with`
+pub use {
+    scheduler_messages::{ConsumeWork, FinishedConsumeWork, TransactionBatchId, TransactionId},
+    transaction_scheduler::{
+        SanitizedTransactionTTL, Scheduler, SchedulerError, SchedulingSummary,
+        TransactionPriorityId, TransactionState, TransactionStateContainer,
+    },
+};
+
 // Fixed thread size seems to be fastest on GCP setup
 pub const NUM_THREADS: u32 = 6;
 
@@ -401,6 +411,30 @@ impl BankingStage {
                 bank_forks,
                 prioritization_fee_cache,
             ),
+            BlockProductionMethod::CentralSchedulerGreedy
+            | BlockProductionMethod::CentralSchedulerFifo => {
+                let order = match block_production_method {
+                    BlockProductionMethod::CentralSchedulerFifo => GreedyOrder::Arrival,
+                    _ => GreedyOrder::FeePerComputeUnit,
+                };
+                Self::new_central_scheduler_with(
+                    cluster_info,
+                    poh_recorder,
+                    non_vote_receiver,
+                    tpu_vote_receiver,
+                    gossip_vote_receiver,
+                    num_threads,
+                    transaction_status_sender,
+                    replay_vote_sender,
+                    log_messages_bytes_limit,
+                    connection_cache,
+                    bank_forks,
+                    prioritization_fee_cache,
+                    |work_senders, finished_work_receiver| {
+                        GreedyScheduler::new(work_senders, finished_work_receiver, order)
+                    },
+                )
+            }
         }
     }
 
@@ -502,6 +536,41 @@ impl BankingStage {
         connection_cache: Arc<ConnectionCache>,
         bank_forks: Arc<RwLock<BankForks>>,
         prioritization_fee_cache: &Arc<PrioritizationFeeCache>,
+    ) -> Self {
+        Self::new_central_scheduler_with(
+            cluster_info,
+            poh_recorder,
+            non_vote_receiver,
+            tpu_vote_receiver,
+            gossip_vote_receiver,
+            num_threads,
+            transaction_status_sender,
+            replay_vote_sender,
+            log_messages_bytes_limit,
+            connection_cache,
+            bank_forks,
+            prioritization_fee_cache,
+            PrioGraphScheduler::new,
+        )
+    }
+
+    /// Same as `new_central_scheduler`, but the central scheduler thread drives the `Scheduler`
+    /// returned by `new_scheduler` for the given worker channels.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_central_scheduler_with<S: Scheduler + Send + 'static>(
+        cluster_info: &Arc<ClusterInfo>,
+        poh_recorder: &Arc<RwLock<PohRecorder>>,
+        non_vote_receiver: BankingPacketReceiver,
+        tpu_vote_receiver: BankingPacketReceiver,
+        gossip_vote_receiver: BankingPacketReceiver,
+        num_threads: u32,
+        transaction_status_sender: Option<TransactionStatusSender>,
+        replay_vote_sender: ReplayVoteSender,
+        log_messages_bytes_limit: Option<usize>,
+        connection_cache: Arc<ConnectionCache>,
+        bank_forks: Arc<RwLock<BankForks>>,
+        prioritization_fee_cache: &Arc<PrioritizationFeeCache>,
+        new_scheduler: impl FnOnce(Vec<Sender<ConsumeWork>>, Receiver<FinishedConsumeWork>) -> S,
     ) -> Self {
         assert!(num_threads >= MIN_TOTAL_THREADS);
         // Single thread to generate entries from many banks.
@@ -587,7 +656,7 @@ impl BankingStage {
         bank_thread_hdls.push({
             let packet_deserializer =
                 PacketDeserializer::new(non_vote_receiver, bank_forks.clone());
-            let scheduler = PrioGraphScheduler::new(work_senders, finished_work_receiver);
+            let scheduler = new_scheduler(work_senders, finished_work_receiver);
             let scheduler_controller = SchedulerController::new(
                 decision_maker.clone(),
                 packet_deserializer,
@@ -1093,6 +1162,16 @@ mod tests {
         test_banking_stage_entries_only(BlockProductionMethod::CentralScheduler);
     }
 
+    #[test]
+    fn test_banking_stage_entries_only_central_scheduler_greedy() {
+        test_banking_stage_entries_only(BlockProductionMethod::CentralSchedulerGreedy);
+    }
+
+    #[test]
+    fn test_banking_stage_entries_only_central_scheduler_fifo() {
+        test_banking_stage_entries_only(BlockProductionMethod::CentralSchedulerFifo);
+    }
+
     #[test]
     fn test_banking_stage_entryfication() {
         solana_logger::setup();
@@ -552,6 +552,7 @@ impl Consumer {
                 transaction_status_sender_enabled,
                 transaction_status_sender_enabled,
                 transaction_status_sender_enabled,
+                false, // enable_instruction_trace_recording
                 &mut execute_and_commit_timings.execute_timings,
                 None, // account_overrides
                 self.log_messages_bytes_limit
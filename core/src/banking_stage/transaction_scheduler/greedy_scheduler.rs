@@ -0,0 +1,354 @@
+use {
+    super::{
+        scheduler::{Scheduler, SchedulingSummary},
+        scheduler_common::{Batches, SchedulingCommon},
+        scheduler_error::SchedulerError,
+        thread_aware_account_locks::ThreadSet,
+        transaction_state_container::TransactionStateContainer,
+    },
+    crate::banking_stage::{
+        read_write_account_set::ReadWriteAccountSet,
+        scheduler_messages::{ConsumeWork, FinishedConsumeWork},
+    },
+    crossbeam_channel::{Receiver, Sender},
+    solana_measure::measure_us,
+    solana_runtime::transaction_priority_details::TransactionPriorityDetails,
+    solana_sdk::{saturating_add_assign, transaction::SanitizedTransaction},
+};
+
+/// Order in which a `GreedyScheduler` considers buffered transactions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum GreedyOrder {
+    /// Highest compute unit price first.
+    FeePerComputeUnit,
+    /// First received first.
+    Arrival,
+}
+
+/// Scheduler which walks the buffered transactions strictly in `GreedyOrder`, without any
+/// look-ahead, and assigns each one to the least loaded thread it can be locked on.
+///
+/// A transaction that cannot be scheduled because of conflicts with in-flight work blocks all
+/// later transactions that conflict with it, so that the order is preserved per account.
+pub(crate) struct GreedyScheduler {
+    common: SchedulingCommon,
+    order: GreedyOrder,
+    num_received: u64,
+}
+
+impl GreedyScheduler {
+    pub(crate) fn new(
+        consume_work_senders: Vec<Sender<ConsumeWork>>,
+        finished_consume_work_receiver: Receiver<FinishedConsumeWork>,
+        order: GreedyOrder,
+    ) -> Self {
+        Self {
+            common: SchedulingCommon::new(consume_work_senders, finished_consume_work_receiver),
+            order,
+            num_received: 0,
+        }
+    }
+}
+
+impl Scheduler for GreedyScheduler {
+    fn schedule(
+        &mut self,
+        container: &mut TransactionStateContainer,
+        filter: impl Fn(&[&SanitizedTransaction], &mut [bool]),
+    ) -> Result<SchedulingSummary, SchedulerError> {
+        let num_threads = self.common.num_threads();
+        let mut batches = Batches::new(num_threads);
+        // Transactions conflicting with an unschedulable transaction must not be scheduled
+        // ahead of it.
+        let mut unschedulable_ids = Vec::new();
+        let mut blocking_locks = ReadWriteAccountSet::default();
+
+        let mut num_scheduled: usize = 0;
+        let mut num_sent: usize = 0;
+        let mut num_unschedulable: usize = 0;
+        let mut num_filtered_out: usize = 0;
+        let mut total_filter_time_us: u64 = 0;
+
+        const MAX_TRANSACTIONS_PER_SCHEDULING_PASS: usize = 100_000;
+        const MAX_FILTER_CHUNK_SIZE: usize = 128;
+        while num_scheduled < MAX_TRANSACTIONS_PER_SCHEDULING_PASS {
+            let ids = container
+                .take_top_n(MAX_FILTER_CHUNK_SIZE)
+                .collect::<Vec<_>>();
+            if ids.is_empty() {
+                break;
+            }
+
+            let mut filter_array = [true; MAX_FILTER_CHUNK_SIZE];
+            let txs = ids
+                .iter()
+                .map(|id| &container.get_transaction_ttl(&id.id).unwrap().transaction)
+                .collect::<Vec<_>>();
+            let (_, filter_us) = measure_us!(filter(&txs, &mut filter_array[..ids.len()]));
+            saturating_add_assign!(total_filter_time_us, filter_us);
+
+            for (id, filter_result) in ids.into_iter().zip(filter_array) {
+                if !filter_result {
+                    saturating_add_assign!(num_filtered_out, 1);
+                    container.remove_by_id(&id.id);
+                    continue;
+                }
+
+                let Some(transaction_state) = container.get_mut_transaction_state(&id.id) else {
+                    panic!("transaction state must exist")
+                };
+                let transaction = &transaction_state.transaction_ttl().transaction;
+
+                if !blocking_locks.check_locks(transaction.message()) {
+                    blocking_locks.take_locks(transaction.message());
+                    unschedulable_ids.push(id);
+                    saturating_add_assign!(num_unschedulable, 1);
+                    continue;
+                }
+
+                let transaction_locks = transaction.get_account_locks_unchecked();
+                let Some(thread_id) = self.common.account_locks.try_lock_accounts(
+                    transaction_locks.writable.into_iter(),
+                    transaction_locks.readonly.into_iter(),
+                    ThreadSet::any(num_threads),
+                    |thread_set| {
+                        SchedulingCommon::select_thread(
+                            thread_set,
+                            None,
+                            &batches.transactions,
+                            self.common.in_flight_tracker.num_in_flight_per_thread(),
+                        )
+                    },
+                ) else {
+                    blocking_locks.take_locks(transaction.message());
+                    unschedulable_ids.push(id);
+                    saturating_add_assign!(num_unschedulable, 1);
+                    continue;
+                };
+
+                saturating_add_assign!(num_scheduled, 1);
+                let sanitized_transaction_ttl = transaction_state.transition_to_pending();
+                let cu_limit = transaction_state
+                    .transaction_priority_details()
+                    .compute_unit_limit;
+                if batches.push(thread_id, id.id, sanitized_transaction_ttl, cu_limit) {
+                    saturating_add_assign!(
+                        num_sent,
+                        self.common.send_batch(&mut batches, thread_id)?
+                    );
+                }
+            }
+        }
+
+        // Send batches for any remaining transactions
+        saturating_add_assign!(num_sent, self.common.send_batches(&mut batches)?);
+
+        // Push unschedulable ids back into the container
+        for id in unschedulable_ids {
+            container.push_id_into_queue(id);
+        }
+
+        assert_eq!(
+            num_scheduled, num_sent,
+            "number of scheduled and sent transactions must match"
+        );
+
+        Ok(SchedulingSummary {
+            num_scheduled,
+            num_unschedulable,
+            num_filtered_out,
+            filter_time_us: total_filter_time_us,
+        })
+    }
+
+    fn receive_completed(
+        &mut self,
+        container: &mut TransactionStateContainer,
+    ) -> Result<(usize, usize), SchedulerError> {
+        self.common.receive_completed(container)
+    }
+
+    fn queue_priority(&mut self, priority_details: &TransactionPriorityDetails) -> u64 {
+        match self.order {
+            GreedyOrder::FeePerComputeUnit => priority_details.priority,
+            GreedyOrder::Arrival => {
+                // earlier arrivals are popped first
+                let priority = u64::MAX.saturating_sub(self.num_received);
+                self.num_received = self.num_received.wrapping_add(1);
+                priority
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::banking_stage::{
+            scheduler_messages::TransactionId,
+            transaction_scheduler::transaction_state::SanitizedTransactionTTL,
+        },
+        crossbeam_channel::unbounded,
+        solana_sdk::{
+            hash::Hash, pubkey::Pubkey, signature::Keypair, signer::Signer, slot_history::Slot,
+            system_transaction,
+        },
+    };
+
+    fn create_test_frame(
+        num_threads: usize,
+        order: GreedyOrder,
+    ) -> (
+        GreedyScheduler,
+        Vec<Receiver<ConsumeWork>>,
+        Sender<FinishedConsumeWork>,
+    ) {
+        let (consume_work_senders, consume_work_receivers) =
+            (0..num_threads).map(|_| unbounded()).unzip();
+        let (finished_consume_work_sender, finished_consume_work_receiver) = unbounded();
+        let scheduler =
+            GreedyScheduler::new(consume_work_senders, finished_consume_work_receiver, order);
+        (
+            scheduler,
+            consume_work_receivers,
+            finished_consume_work_sender,
+        )
+    }
+
+    /// Inserts transfers from the given keypairs to `to` with the given compute unit prices,
+    /// in that order.
+    fn insert_transfers(
+        scheduler: &mut GreedyScheduler,
+        container: &mut TransactionStateContainer,
+        transfers: &[(&Keypair, Pubkey, u64)],
+    ) {
+        for (index, (from_keypair, to, compute_unit_price)) in transfers.iter().enumerate() {
+            let transaction = SanitizedTransaction::from_transaction_for_tests(
+                system_transaction::transfer(from_keypair, to, 1, Hash::default()),
+            );
+            let priority_details = TransactionPriorityDetails {
+                priority: *compute_unit_price,
+                compute_unit_limit: 1,
+            };
+            let queue_priority = scheduler.queue_priority(&priority_details);
+            container.insert_new_transaction(
+                TransactionId::new(index as u64),
+                SanitizedTransactionTTL {
+                    transaction,
+                    max_age_slot: Slot::MAX,
+                },
+                priority_details,
+                queue_priority,
+            );
+        }
+    }
+
+    fn collect_ids(receiver: &Receiver<ConsumeWork>) -> Vec<Vec<TransactionId>> {
+        receiver.try_iter().map(|work| work.ids).collect()
+    }
+
+    fn test_filter(_txs: &[&SanitizedTransaction], results: &mut [bool]) {
+        results.fill(true);
+    }
+
+    #[test]
+    fn test_schedule_fee_per_compute_unit_order() {
+        let (mut scheduler, work_receivers, _finished_work_sender) =
+            create_test_frame(1, GreedyOrder::FeePerComputeUnit);
+        let mut container = TransactionStateContainer::with_capacity(1024);
+        let keypairs = [Keypair::new(), Keypair::new(), Keypair::new()];
+        insert_transfers(
+            &mut scheduler,
+            &mut container,
+            &[
+                (&keypairs[0], Pubkey::new_unique(), 1),
+                (&keypairs[1], Pubkey::new_unique(), 3),
+                (&keypairs[2], Pubkey::new_unique(), 2),
+            ],
+        );
+
+        let scheduling_summary = scheduler.schedule(&mut container, test_filter).unwrap();
+        assert_eq!(scheduling_summary.num_scheduled, 3);
+        assert_eq!(scheduling_summary.num_unschedulable, 0);
+        assert_eq!(
+            collect_ids(&work_receivers[0]),
+            vec![vec![
+                TransactionId::new(1),
+                TransactionId::new(2),
+                TransactionId::new(0)
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_schedule_arrival_order() {
+        let (mut scheduler, work_receivers, _finished_work_sender) =
+            create_test_frame(1, GreedyOrder::Arrival);
+        let mut container = TransactionStateContainer::with_capacity(1024);
+        let keypairs = [Keypair::new(), Keypair::new(), Keypair::new()];
+        insert_transfers(
+            &mut scheduler,
+            &mut container,
+            &[
+                (&keypairs[0], Pubkey::new_unique(), 1),
+                (&keypairs[1], Pubkey::new_unique(), 3),
+                (&keypairs[2], Pubkey::new_unique(), 2),
+            ],
+        );
+
+        let scheduling_summary = scheduler.schedule(&mut container, test_filter).unwrap();
+        assert_eq!(scheduling_summary.num_scheduled, 3);
+        assert_eq!(
+            collect_ids(&work_receivers[0]),
+            vec![vec![
+                TransactionId::new(0),
+                TransactionId::new(1),
+                TransactionId::new(2)
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_schedule_blocked_by_unschedulable() {
+        let (mut scheduler, work_receivers, finished_work_sender) =
+            create_test_frame(2, GreedyOrder::Arrival);
+        let mut container = TransactionStateContainer::with_capacity(1024);
+        let keypairs = [Keypair::new(), Keypair::new(), Keypair::new()];
+        // [0] and [1] are sent to different threads; [2] conflicts with both, so it cannot be
+        // scheduled until one of them completes.
+        insert_transfers(
+            &mut scheduler,
+            &mut container,
+            &[
+                (&keypairs[0], Pubkey::new_unique(), 0),
+                (&keypairs[1], Pubkey::new_unique(), 0),
+                (&keypairs[0], keypairs[1].pubkey(), 0),
+            ],
+        );
+
+        let scheduling_summary = scheduler.schedule(&mut container, test_filter).unwrap();
+        assert_eq!(scheduling_summary.num_scheduled, 2);
+        assert_eq!(scheduling_summary.num_unschedulable, 1);
+        let thread_0_work = work_receivers[0].try_iter().collect::<Vec<_>>();
+        assert_eq!(thread_0_work.len(), 1);
+        assert_eq!(
+            collect_ids(&work_receivers[1]),
+            vec![vec![TransactionId::new(1)]]
+        );
+
+        finished_work_sender
+            .send(FinishedConsumeWork {
+                work: thread_0_work.into_iter().next().unwrap(),
+                retryable_indexes: vec![],
+            })
+            .unwrap();
+        scheduler.receive_completed(&mut container).unwrap();
+        let scheduling_summary = scheduler.schedule(&mut container, test_filter).unwrap();
+        assert_eq!(scheduling_summary.num_scheduled, 1);
+        assert_eq!(
+            collect_ids(&work_receivers[1]),
+            vec![vec![TransactionId::new(2)]]
+        );
+    }
+}
@@ -1,7 +1,10 @@
 mod batch_id_generator;
+pub(crate) mod greedy_scheduler;
 #[allow(dead_code)]
 mod in_flight_tracker;
 pub(crate) mod prio_graph_scheduler;
+pub(crate) mod scheduler;
+mod scheduler_common;
 pub(crate) mod scheduler_controller;
 pub(crate) mod scheduler_error;
 mod thread_aware_account_locks;
@@ -11,3 +14,11 @@ mod transaction_priority_id;
 mod transaction_state;
 #[allow(dead_code)]
 mod transaction_state_container;
+
+pub use {
+    scheduler::{Scheduler, SchedulingSummary},
+    scheduler_error::SchedulerError,
+    transaction_priority_id::TransactionPriorityId,
+    transaction_state::{SanitizedTransactionTTL, TransactionState},
+    transaction_state_container::TransactionStateContainer,
+};
@@ -1,33 +1,28 @@
 use {
     super::{
-        in_flight_tracker::InFlightTracker,
+        scheduler::{Scheduler, SchedulingSummary},
+        scheduler_common::{Batches, SchedulingCommon},
         scheduler_error::SchedulerError,
-        thread_aware_account_locks::{ThreadAwareAccountLocks, ThreadId, ThreadSet},
+        thread_aware_account_locks::ThreadSet,
         transaction_state::SanitizedTransactionTTL,
         transaction_state_container::TransactionStateContainer,
     },
     crate::banking_stage::{
         consumer::TARGET_NUM_TRANSACTIONS_PER_BATCH,
         read_write_account_set::ReadWriteAccountSet,
-        scheduler_messages::{ConsumeWork, FinishedConsumeWork, TransactionBatchId, TransactionId},
+        scheduler_messages::{ConsumeWork, FinishedConsumeWork},
         transaction_scheduler::transaction_priority_id::TransactionPriorityId,
     },
-    crossbeam_channel::{Receiver, Sender, TryRecvError},
-    itertools::izip,
+    crossbeam_channel::{Receiver, Sender},
     prio_graph::{AccessKind, PrioGraph},
     solana_measure::measure_us,
-    solana_sdk::{
-        pubkey::Pubkey, saturating_add_assign, slot_history::Slot,
-        transaction::SanitizedTransaction,
-    },
+    solana_sdk::{pubkey::Pubkey, saturating_add_assign, transaction::SanitizedTransaction},
     std::collections::HashMap,
 };
 
+/// Account-contention-aware scheduler.
 pub(crate) struct PrioGraphScheduler {
-    in_flight_tracker: InFlightTracker,
-    account_locks: ThreadAwareAccountLocks,
-    consume_work_senders: Vec<Sender<ConsumeWork>>,
-    finished_consume_work_receiver: Receiver<FinishedConsumeWork>,
+    common: SchedulingCommon,
     look_ahead_window_size: usize,
 }
 
@@ -36,31 +31,42 @@ impl PrioGraphScheduler {
         consume_work_senders: Vec<Sender<ConsumeWork>>,
         finished_consume_work_receiver: Receiver<FinishedConsumeWork>,
     ) -> Self {
-        let num_threads = consume_work_senders.len();
         Self {
-            in_flight_tracker: InFlightTracker::new(num_threads),
-            account_locks: ThreadAwareAccountLocks::new(num_threads),
-            consume_work_senders,
-            finished_consume_work_receiver,
+            common: SchedulingCommon::new(consume_work_senders, finished_consume_work_receiver),
             look_ahead_window_size: 2048,
         }
     }
 
-    /// Schedule transactions from the given `TransactionStateContainer` to be consumed by the
-    /// worker threads. Returns summary of scheduling, or an error.
-    /// `filter` is used to filter out transactions that should be skipped and dropped, and
-    /// should set `false` for transactions that should be dropped, and `true` otherwise.
-    ///
+    /// Gets accessed accounts (resources) for use in `PrioGraph`.
+    fn get_transaction_account_access(
+        transaction: &SanitizedTransactionTTL,
+    ) -> impl Iterator<Item = (Pubkey, AccessKind)> + '_ {
+        let message = transaction.transaction.message();
+        message
+            .account_keys()
+            .iter()
+            .enumerate()
+            .map(|(index, key)| {
+                if message.is_writable(index) {
+                    (*key, AccessKind::Write)
+                } else {
+                    (*key, AccessKind::Read)
+                }
+            })
+    }
+}
+
+impl Scheduler for PrioGraphScheduler {
     /// Uses a `PrioGraph` to perform look-ahead during the scheduling of transactions.
     /// This, combined with internal tracking of threads' in-flight transactions, allows
     /// for load-balancing while prioritizing scheduling transactions onto threads that will
     /// not cause conflicts in the near future.
-    pub(crate) fn schedule(
+    fn schedule(
         &mut self,
         container: &mut TransactionStateContainer,
         filter: impl Fn(&[&SanitizedTransaction], &mut [bool]),
     ) -> Result<SchedulingSummary, SchedulerError> {
-        let num_threads = self.consume_work_senders.len();
+        let num_threads = self.common.num_threads();
         let mut batches = Batches::new(num_threads);
         let mut chain_id_to_thread_index = HashMap::new();
         // Some transactions may be unschedulable due to multi-thread conflicts.
@@ -127,7 +133,7 @@ impl PrioGraphScheduler {
         chunked_pops(container, &mut prio_graph, &mut window_budget);
 
         let mut unblock_this_batch =
-            Vec::with_capacity(self.consume_work_senders.len() * TARGET_NUM_TRANSACTIONS_PER_BATCH);
+            Vec::with_capacity(num_threads * TARGET_NUM_TRANSACTIONS_PER_BATCH);
         const MAX_TRANSACTIONS_PER_SCHEDULING_PASS: usize = 100_000;
         let mut num_scheduled: usize = 0;
         let mut num_sent: usize = 0;
@@ -163,16 +169,16 @@ impl PrioGraphScheduler {
 
                 // Schedule the transaction if it can be.
                 let transaction_locks = transaction.get_account_locks_unchecked();
-                let Some(thread_id) = self.account_locks.try_lock_accounts(
+                let Some(thread_id) = self.common.account_locks.try_lock_accounts(
                     transaction_locks.writable.into_iter(),
                     transaction_locks.readonly.into_iter(),
                     ThreadSet::any(num_threads),
                     |thread_set| {
-                        Self::select_thread(
+                        SchedulingCommon::select_thread(
                             thread_set,
                             maybe_chain_thread,
                             &batches.transactions,
-                            self.in_flight_tracker.num_in_flight_per_thread(),
+                            self.common.in_flight_tracker.num_in_flight_per_thread(),
                         )
                     },
                 ) else {
@@ -192,19 +198,12 @@ impl PrioGraphScheduler {
                     .transaction_priority_details()
                     .compute_unit_limit;
 
-                let SanitizedTransactionTTL {
-                    transaction,
-                    max_age_slot,
-                } = sanitized_transaction_ttl;
-
-                batches.transactions[thread_id].push(transaction);
-                batches.ids[thread_id].push(id.id);
-                batches.max_age_slots[thread_id].push(max_age_slot);
-                saturating_add_assign!(batches.total_cus[thread_id], cu_limit);
-
                 // If target batch size is reached, send only this batch.
-                if batches.ids[thread_id].len() >= TARGET_NUM_TRANSACTIONS_PER_BATCH {
-                    saturating_add_assign!(num_sent, self.send_batch(&mut batches, thread_id)?);
+                if batches.push(thread_id, id.id, sanitized_transaction_ttl, cu_limit) {
+                    saturating_add_assign!(
+                        num_sent,
+                        self.common.send_batch(&mut batches, thread_id)?
+                    );
                 }
 
                 if num_scheduled >= MAX_TRANSACTIONS_PER_SCHEDULING_PASS {
@@ -213,7 +212,7 @@ impl PrioGraphScheduler {
             }
 
             // Send all non-empty batches
-            saturating_add_assign!(num_sent, self.send_batches(&mut batches)?);
+            saturating_add_assign!(num_sent, self.common.send_batches(&mut batches)?);
 
             // Refresh window budget and do chunked pops
             saturating_add_assign!(window_budget, unblock_this_batch.len());
@@ -226,7 +225,7 @@ impl PrioGraphScheduler {
         }
 
         // Send batches for any remaining transactions
-        saturating_add_assign!(num_sent, self.send_batches(&mut batches)?);
+        saturating_add_assign!(num_sent, self.common.send_batches(&mut batches)?);
 
         // Push unschedulable ids back into the container
         for id in unschedulable_ids {
@@ -251,241 +250,11 @@ impl PrioGraphScheduler {
         })
     }
 
-    /// Receive completed batches of transactions without blocking.
-    /// Returns (num_transactions, num_retryable_transactions) on success.
-    pub fn receive_completed(
+    fn receive_completed(
         &mut self,
         container: &mut TransactionStateContainer,
     ) -> Result<(usize, usize), SchedulerError> {
-        let mut total_num_transactions: usize = 0;
-        let mut total_num_retryable: usize = 0;
-        loop {
-            let (num_transactions, num_retryable) = self.try_receive_completed(container)?;
-            if num_transactions == 0 {
-                break;
-            }
-            saturating_add_assign!(total_num_transactions, num_transactions);
-            saturating_add_assign!(total_num_retryable, num_retryable);
-        }
-        Ok((total_num_transactions, total_num_retryable))
-    }
-
-    /// Receive completed batches of transactions.
-    /// Returns `Ok((num_transactions, num_retryable))` if a batch was received, `Ok((0, 0))` if no batch was received.
-    fn try_receive_completed(
-        &mut self,
-        container: &mut TransactionStateContainer,
-    ) -> Result<(usize, usize), SchedulerError> {
-        match self.finished_consume_work_receiver.try_recv() {
-            Ok(FinishedConsumeWork {
-                work:
-                    ConsumeWork {
-                        batch_id,
-                        ids,
-                        transactions,
-                        max_age_slots,
-                    },
-                retryable_indexes,
-            }) => {
-                let num_transactions = ids.len();
-                let num_retryable = retryable_indexes.len();
-
-                // Free the locks
-                self.complete_batch(batch_id, &transactions);
-
-                // Retryable transactions should be inserted back into the container
-                let mut retryable_iter = retryable_indexes.into_iter().peekable();
-                for (index, (id, transaction, max_age_slot)) in
-                    izip!(ids, transactions, max_age_slots).enumerate()
-                {
-                    if let Some(retryable_index) = retryable_iter.peek() {
-                        if *retryable_index == index {
-                            container.retry_transaction(
-                                id,
-                                SanitizedTransactionTTL {
-                                    transaction,
-                                    max_age_slot,
-                                },
-                            );
-                            retryable_iter.next();
-                            continue;
-                        }
-                    }
-                    container.remove_by_id(&id);
-                }
-
-                Ok((num_transactions, num_retryable))
-            }
-            Err(TryRecvError::Empty) => Ok((0, 0)),
-            Err(TryRecvError::Disconnected) => Err(SchedulerError::DisconnectedRecvChannel(
-                "finished consume work",
-            )),
-        }
-    }
-
-    /// Mark a given `TransactionBatchId` as completed.
-    /// This will update the internal tracking, including account locks.
-    fn complete_batch(
-        &mut self,
-        batch_id: TransactionBatchId,
-        transactions: &[SanitizedTransaction],
-    ) {
-        let thread_id = self.in_flight_tracker.complete_batch(batch_id);
-        for transaction in transactions {
-            let account_locks = transaction.get_account_locks_unchecked();
-            self.account_locks.unlock_accounts(
-                account_locks.writable.into_iter(),
-                account_locks.readonly.into_iter(),
-                thread_id,
-            );
-        }
-    }
-
-    /// Send all batches of transactions to the worker threads.
-    /// Returns the number of transactions sent.
-    fn send_batches(&mut self, batches: &mut Batches) -> Result<usize, SchedulerError> {
-        (0..self.consume_work_senders.len())
-            .map(|thread_index| self.send_batch(batches, thread_index))
-            .sum()
-    }
-
-    /// Send a batch of transactions to the given thread's `ConsumeWork` channel.
-    /// Returns the number of transactions sent.
-    fn send_batch(
-        &mut self,
-        batches: &mut Batches,
-        thread_index: usize,
-    ) -> Result<usize, SchedulerError> {
-        if batches.ids[thread_index].is_empty() {
-            return Ok(0);
-        }
-
-        let (ids, transactions, max_age_slots, total_cus) = batches.take_batch(thread_index);
-
-        let batch_id = self
-            .in_flight_tracker
-            .track_batch(ids.len(), total_cus, thread_index);
-
-        let num_scheduled = ids.len();
-        let work = ConsumeWork {
-            batch_id,
-            ids,
-            transactions,
-            max_age_slots,
-        };
-        self.consume_work_senders[thread_index]
-            .send(work)
-            .map_err(|_| SchedulerError::DisconnectedSendChannel("consume work sender"))?;
-
-        Ok(num_scheduled)
-    }
-
-    /// Given the schedulable `thread_set`, select the thread with the least amount
-    /// of work queued up.
-    /// Currently, "work" is just defined as the number of transactions.
-    ///
-    /// If the `chain_thread` is available, this thread will be selected, regardless of
-    /// load-balancing.
-    ///
-    /// Panics if the `thread_set` is empty.
-    fn select_thread(
-        thread_set: ThreadSet,
-        chain_thread: Option<ThreadId>,
-        batches_per_thread: &[Vec<SanitizedTransaction>],
-        in_flight_per_thread: &[usize],
-    ) -> ThreadId {
-        if let Some(chain_thread) = chain_thread {
-            if thread_set.contains(chain_thread) {
-                return chain_thread;
-            }
-        }
-
-        thread_set
-            .contained_threads_iter()
-            .map(|thread_id| {
-                (
-                    thread_id,
-                    batches_per_thread[thread_id].len() + in_flight_per_thread[thread_id],
-                )
-            })
-            .min_by(|a, b| a.1.cmp(&b.1))
-            .map(|(thread_id, _)| thread_id)
-            .unwrap()
-    }
-
-    /// Gets accessed accounts (resources) for use in `PrioGraph`.
-    fn get_transaction_account_access(
-        transaction: &SanitizedTransactionTTL,
-    ) -> impl Iterator<Item = (Pubkey, AccessKind)> + '_ {
-        let message = transaction.transaction.message();
-        message
-            .account_keys()
-            .iter()
-            .enumerate()
-            .map(|(index, key)| {
-                if message.is_writable(index) {
-                    (*key, AccessKind::Write)
-                } else {
-                    (*key, AccessKind::Read)
-                }
-            })
-    }
-}
-
-/// Metrics from scheduling transactions.
-#[derive(Debug, PartialEq, Eq)]
-pub(crate) struct SchedulingSummary {
-    /// Number of transactions scheduled.
-    pub num_scheduled: usize,
-    /// Number of transactions that were not scheduled due to conflicts.
-    pub num_unschedulable: usize,
-    /// Number of transactions that were dropped due to filter.
-    pub num_filtered_out: usize,
-    /// Time spent filtering transactions
-    pub filter_time_us: u64,
-}
-
-struct Batches {
-    ids: Vec<Vec<TransactionId>>,
-    transactions: Vec<Vec<SanitizedTransaction>>,
-    max_age_slots: Vec<Vec<Slot>>,
-    total_cus: Vec<u64>,
-}
-
-impl Batches {
-    fn new(num_threads: usize) -> Self {
-        Self {
-            ids: vec![Vec::with_capacity(TARGET_NUM_TRANSACTIONS_PER_BATCH); num_threads],
-            transactions: vec![Vec::with_capacity(TARGET_NUM_TRANSACTIONS_PER_BATCH); num_threads],
-            max_age_slots: vec![Vec::with_capacity(TARGET_NUM_TRANSACTIONS_PER_BATCH); num_threads],
-            total_cus: vec![0; num_threads],
-        }
-    }
-
-    fn take_batch(
-        &mut self,
-        thread_id: ThreadId,
-    ) -> (
-        Vec<TransactionId>,
-        Vec<SanitizedTransaction>,
-        Vec<Slot>,
-        u64,
-    ) {
-        (
-            core::mem::replace(
-                &mut self.ids[thread_id],
-                Vec::with_capacity(TARGET_NUM_TRANSACTIONS_PER_BATCH),
-            ),
-            core::mem::replace(
-                &mut self.transactions[thread_id],
-                Vec::with_capacity(TARGET_NUM_TRANSACTIONS_PER_BATCH),
-            ),
-            core::mem::replace(
-                &mut self.max_age_slots[thread_id],
-                Vec::with_capacity(TARGET_NUM_TRANSACTIONS_PER_BATCH),
-            ),
-            core::mem::replace(&mut self.total_cus[thread_id], 0),
-        )
+        self.common.receive_completed(container)
     }
 }
 
@@ -493,13 +262,16 @@ impl Batches {
 mod tests {
     use {
         super::*,
-        crate::banking_stage::consumer::TARGET_NUM_TRANSACTIONS_PER_BATCH,
+        crate::banking_stage::{
+            consumer::TARGET_NUM_TRANSACTIONS_PER_BATCH, scheduler_messages::TransactionId,
+        },
         crossbeam_channel::{unbounded, Receiver},
         itertools::Itertools,
         solana_runtime::transaction_priority_details::TransactionPriorityDetails,
         solana_sdk::{
             compute_budget::ComputeBudgetInstruction, hash::Hash, message::Message, pubkey::Pubkey,
-            signature::Keypair, signer::Signer, system_instruction, transaction::Transaction,
+            signature::Keypair, signer::Signer, slot_history::Slot, system_instruction,
+            transaction::Transaction,
         },
         std::borrow::Borrow,
     };
@@ -583,6 +355,7 @@ mod tests {
                     priority,
                     compute_unit_limit: 1,
                 },
+                priority,
             );
         }
 
@@ -0,0 +1,52 @@
+use {
+    super::{
+        scheduler_error::SchedulerError, transaction_state_container::TransactionStateContainer,
+    },
+    solana_runtime::transaction_priority_details::TransactionPriorityDetails,
+    solana_sdk::transaction::SanitizedTransaction,
+};
+
+/// A `Scheduler` decides which of the transactions buffered in a `TransactionStateContainer`
+/// are sent to which of the consume worker threads, and in what order.
+///
+/// `SchedulerController` drives a `Scheduler`: it buffers incoming transactions, asks the
+/// scheduler to schedule while the node is leader, and hands finished work back to it.
+pub trait Scheduler {
+    /// Schedule transactions from the given `TransactionStateContainer` to be consumed by the
+    /// worker threads. Returns summary of scheduling, or an error.
+    /// `filter` is used to filter out transactions that should be skipped and dropped, and
+    /// should set `false` for transactions that should be dropped, and `true` otherwise.
+    fn schedule(
+        &mut self,
+        container: &mut TransactionStateContainer,
+        filter: impl Fn(&[&SanitizedTransaction], &mut [bool]),
+    ) -> Result<SchedulingSummary, SchedulerError>;
+
+    /// Receive completed batches of transactions without blocking.
+    /// Returns (num_transactions, num_retryable_transactions) on success.
+    fn receive_completed(
+        &mut self,
+        container: &mut TransactionStateContainer,
+    ) -> Result<(usize, usize), SchedulerError>;
+
+    /// Returns the priority a newly received transaction is queued with. Transactions are
+    /// popped from the container in descending priority order, while their
+    /// `TransactionPriorityDetails` are kept as received.
+    /// By default, this is the transaction's compute unit price.
+    fn queue_priority(&mut self, priority_details: &TransactionPriorityDetails) -> u64 {
+        priority_details.priority
+    }
+}
+
+/// Metrics from scheduling transactions.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SchedulingSummary {
+    /// Number of transactions scheduled.
+    pub num_scheduled: usize,
+    /// Number of transactions that were not scheduled due to conflicts.
+    pub num_unschedulable: usize,
+    /// Number of transactions that were dropped due to filter.
+    pub num_filtered_out: usize,
+    /// Time spent filtering transactions
+    pub filter_time_us: u64,
+}
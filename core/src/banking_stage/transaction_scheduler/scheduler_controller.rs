@@ -3,7 +3,7 @@
 
 use {
     super::{
-        prio_graph_scheduler::PrioGraphScheduler, scheduler_error::SchedulerError,
+        scheduler::Scheduler, scheduler_error::SchedulerError,
         transaction_id_generator::TransactionIdGenerator,
         transaction_state::SanitizedTransactionTTL,
         transaction_state_container::TransactionStateContainer,
@@ -30,7 +30,7 @@ use {
 };
 
 /// Controls packet and transaction flow into scheduler, and scheduling execution.
-pub(crate) struct SchedulerController {
+pub(crate) struct SchedulerController<S: Scheduler> {
     /// Decision maker for determining what should be done with transactions.
     decision_maker: DecisionMaker,
     /// Packet/Transaction ingress.
@@ -42,7 +42,7 @@ pub(crate) struct SchedulerController {
     /// Shared resource between `packet_receiver` and `scheduler`.
     container: TransactionStateContainer,
     /// State for scheduling and communicating with worker threads.
-    scheduler: PrioGraphScheduler,
+    scheduler: S,
     /// Metrics tracking counts on transactions in different states.
     count_metrics: SchedulerCountMetrics,
     /// Metrics tracking time spent in different code sections.
@@ -51,12 +51,12 @@ pub(crate) struct SchedulerController {
     worker_metrics: Vec<Arc<ConsumeWorkerMetrics>>,
 }
 
-impl SchedulerController {
+impl<S: Scheduler> SchedulerController<S> {
     pub fn new(
         decision_maker: DecisionMaker,
         packet_deserializer: PacketDeserializer,
         bank_forks: Arc<RwLock<BankForks>>,
-        scheduler: PrioGraphScheduler,
+        scheduler: S,
         worker_metrics: Vec<Arc<ConsumeWorkerMetrics>>,
     ) -> Self {
         Self {
@@ -321,10 +321,12 @@ impl SchedulerController {
                 max_age_slot: last_slot_in_epoch,
             };
             let transaction_priority_details = packet.priority_details();
+            let queue_priority = self.scheduler.queue_priority(&transaction_priority_details);
             if self.container.insert_new_transaction(
                 transaction_id,
                 transaction_ttl,
                 transaction_priority_details,
+                queue_priority,
             ) {
                 saturating_add_assign!(self.count_metrics.num_dropped_on_capacity, 1);
             }
@@ -517,6 +519,7 @@ mod tests {
                 consumer::TARGET_NUM_TRANSACTIONS_PER_BATCH,
                 scheduler_messages::{ConsumeWork, FinishedConsumeWork, TransactionBatchId},
                 tests::create_slow_genesis_config,
+                transaction_scheduler::prio_graph_scheduler::PrioGraphScheduler,
             },
             banking_trace::BankingPacketBatch,
             sigverify::SigverifyTracerPacketStats,
@@ -559,7 +562,9 @@ mod tests {
         finished_consume_work_sender: Sender<FinishedConsumeWork>,
     }
 
-    fn create_test_frame(num_threads: usize) -> (TestFrame, SchedulerController) {
+    fn create_test_frame(
+        num_threads: usize,
+    ) -> (TestFrame, SchedulerController<PrioGraphScheduler>) {
         let GenesisConfigInfo { genesis_config, .. } = create_slow_genesis_config(10_000);
         let (bank, bank_forks) = Bank::new_no_wallclock_throttle_for_tests(&genesis_config);
 
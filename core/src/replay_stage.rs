@@ -36,7 +36,10 @@ use {
     lazy_static::lazy_static,
     rayon::{prelude::*, ThreadPool},
     solana_entry::entry::VerifyRecyclers,
-    solana_geyser_plugin_manager::block_metadata_notifier_interface::BlockMetadataNotifierArc,
+    solana_geyser_plugin_manager::{
+        block_metadata_notifier_interface::BlockMetadataNotifierArc,
+        slot_accounts_diff_notifier_interface::SlotAccountsDiffNotifierArc,
+    },
     solana_gossip::cluster_info::ClusterInfo,
     solana_ledger::{
         block_error::BlockError,
@@ -242,6 +245,7 @@ pub struct ReplayStageConfig {
     pub cache_block_meta_sender: Option<CacheBlockMetaSender>,
     pub entry_notification_sender: Option<EntryNotifierSender>,
     pub bank_notification_sender: Option<BankNotificationSenderConfig>,
+    pub slot_accounts_diff_notifier: Option<SlotAccountsDiffNotifierArc>,
     pub wait_for_vote_to_start_leader: bool,
     pub ancestor_hashes_replay_update_sender: AncestorHashesReplayUpdateSender,
     pub tower_storage: Arc<dyn TowerStorage>,
@@ -512,6 +516,7 @@ impl ReplayStage {
             cache_block_meta_sender,
             entry_notification_sender,
             bank_notification_sender,
+            slot_accounts_diff_notifier,
             wait_for_vote_to_start_leader,
             ancestor_hashes_replay_update_sender,
             tower_storage,
@@ -624,6 +629,7 @@ impl ReplayStage {
                     &mut duplicate_slots_to_repair,
                     &ancestor_hashes_replay_update_sender,
                     block_metadata_notifier.clone(),
+                    slot_accounts_diff_notifier.as_ref(),
                     &mut replay_timing,
                     log_messages_bytes_limit,
                     replay_slots_concurrently,
@@ -2774,6 +2780,7 @@ impl ReplayStage {
         duplicate_slots_to_repair: &mut DuplicateSlotsToRepair,
         ancestor_hashes_replay_update_sender: &AncestorHashesReplayUpdateSender,
         block_metadata_notifier: Option<BlockMetadataNotifierArc>,
+        slot_accounts_diff_notifier: Option<&SlotAccountsDiffNotifierArc>,
         replay_result_vec: &[ReplaySlotFromBlockstore],
         purge_repair_slot_counter: &mut PurgeRepairSlotCounter,
     ) -> bool {
@@ -2980,6 +2987,9 @@ impl ReplayStage {
                         r_replay_progress.num_entries as u64,
                     )
                 }
+                if let Some(slot_accounts_diff_notifier) = slot_accounts_diff_notifier {
+                    slot_accounts_diff_notifier.notify_slot_accounts_diff(bank);
+                }
                 bank_complete_time.stop();
 
                 r_replay_stats.report_stats(
@@ -3029,6 +3039,7 @@ impl ReplayStage {
         duplicate_slots_to_repair: &mut DuplicateSlotsToRepair,
         ancestor_hashes_replay_update_sender: &AncestorHashesReplayUpdateSender,
         block_metadata_notifier: Option<BlockMetadataNotifierArc>,
+        slot_accounts_diff_notifier: Option<&SlotAccountsDiffNotifierArc>,
         replay_timing: &mut ReplayTiming,
         log_messages_bytes_limit: Option<usize>,
         replay_slots_concurrently: bool,
@@ -3102,6 +3113,7 @@ impl ReplayStage {
                 duplicate_slots_to_repair,
                 ancestor_hashes_replay_update_sender,
                 block_metadata_notifier,
+                slot_accounts_diff_notifier,
                 &replay_result_vec,
                 purge_repair_slot_counter,
             )
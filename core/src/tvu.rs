@@ -25,7 +25,10 @@ use {
     bytes::Bytes,
     crossbeam_channel::{unbounded, Receiver, Sender},
     solana_client::connection_cache::ConnectionCache,
-    solana_geyser_plugin_manager::block_metadata_notifier_interface::BlockMetadataNotifierArc,
+    solana_geyser_plugin_manager::{
+        block_metadata_notifier_interface::BlockMetadataNotifierArc,
+        slot_accounts_diff_notifier_interface::SlotAccountsDiffNotifierArc,
+    },
     solana_gossip::{
         cluster_info::ClusterInfo, duplicate_shred_handler::DuplicateShredHandler,
         duplicate_shred_listener::DuplicateShredListener,
@@ -129,6 +132,7 @@ impl Tvu {
         tvu_config: TvuConfig,
         max_slots: &Arc<MaxSlots>,
         block_metadata_notifier: Option<BlockMetadataNotifierArc>,
+        slot_accounts_diff_notifier: Option<SlotAccountsDiffNotifierArc>,
         wait_to_vote_slot: Option<Slot>,
         accounts_background_request_sender: AbsRequestSender,
         log_messages_bytes_limit: Option<usize>,
@@ -256,6 +260,7 @@ impl Tvu {
             cache_block_meta_sender,
             entry_notification_sender,
             bank_notification_sender,
+            slot_accounts_diff_notifier,
             wait_for_vote_to_start_leader: tvu_config.wait_for_vote_to_start_leader,
             ancestor_hashes_replay_update_sender,
             tower_storage: tower_storage.clone(),
@@ -488,6 +493,7 @@ pub mod tests {
             &Arc::new(MaxSlots::default()),
             None,
             None,
+            None,
             AbsRequestSender::default(),
             None,
             &Arc::new(ConnectionCache::new("connection_cache_test")),
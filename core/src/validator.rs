@@ -80,6 +80,7 @@ use {
         },
         rpc::JsonRpcConfig,
         rpc_completed_slots_service::RpcCompletedSlotsService,
+        rpc_grpc_service::GrpcService,
         rpc_pubsub_service::{PubSubConfig, PubSubService},
         rpc_service::JsonRpcService,
         rpc_subscriptions::RpcSubscriptions,
@@ -168,7 +169,12 @@ impl BlockVerificationMethod {
 pub enum BlockProductionMethod {
     #[default]
     ThreadLocalMultiIterator,
+    /// Central scheduler with an account-contention-aware look-ahead
     CentralScheduler,
+    /// Central scheduler greedily scheduling the highest fee-per-CU transactions first
+    CentralSchedulerGreedy,
+    /// Central scheduler scheduling transactions in the order they were received
+    CentralSchedulerFifo,
 }
 
 impl BlockProductionMethod {
@@ -208,6 +214,7 @@ pub struct ValidatorConfig {
     /// Specifies which plugins to start up with
     pub on_start_geyser_plugin_config_files: Option<Vec<PathBuf>>,
     pub rpc_addrs: Option<(SocketAddr, SocketAddr)>, // (JsonRpc, JsonRpcPubSub)
+    pub rpc_grpc_addr: Option<SocketAddr>,
     pub pubsub_config: PubSubConfig,
     pub snapshot_config: SnapshotConfig,
     pub max_ledger_shreds: Option<u64>,
@@ -280,6 +287,7 @@ impl Default for ValidatorConfig {
             rpc_config: JsonRpcConfig::default(),
             on_start_geyser_plugin_config_files: None,
             rpc_addrs: None,
+            rpc_grpc_addr: None,
             pubsub_config: PubSubConfig::default(),
             snapshot_config: SnapshotConfig::new_load_only(),
             broadcast_stage_type: BroadcastStageType::Standard,
@@ -443,6 +451,7 @@ pub struct Validator {
     validator_exit: Arc<RwLock<Exit>>,
     json_rpc_service: Option<JsonRpcService>,
     pubsub_service: Option<PubSubService>,
+    grpc_service: Option<GrpcService>,
     rpc_completed_slots_service: JoinHandle<()>,
     optimistically_confirmed_bank_tracker: Option<OptimisticallyConfirmedBankTracker>,
     transaction_status_service: Option<TransactionStatusService>,
@@ -652,6 +661,13 @@ impl Validator {
             .as_ref()
             .and_then(|geyser_plugin_service| geyser_plugin_service.get_block_metadata_notifier());
 
+        let slot_accounts_diff_notifier =
+            geyser_plugin_service
+                .as_ref()
+                .and_then(|geyser_plugin_service| {
+                    geyser_plugin_service.get_slot_accounts_diff_notifier()
+                });
+
         info!(
             "Geyser plugin: accounts_update_notifier: {}, \
             transaction_notifier: {}, \
@@ -945,6 +961,7 @@ impl Validator {
         let (
             json_rpc_service,
             pubsub_service,
+            grpc_service,
             optimistically_confirmed_bank_tracker,
             bank_notification_sender,
         ) = if let Some((rpc_addr, rpc_pubsub_addr)) = config.rpc_addrs {
@@ -989,6 +1006,19 @@ impl Validator {
                 max_complete_rewards_slot,
                 prioritization_fee_cache.clone(),
             )?;
+            let grpc_service = config.rpc_grpc_addr.map(|rpc_grpc_addr| {
+                let (trigger, grpc_service) = GrpcService::new(
+                    rpc_grpc_addr,
+                    json_rpc_service.request_processor().clone(),
+                    rpc_subscriptions.control().clone(),
+                );
+                config
+                    .validator_exit
+                    .write()
+                    .unwrap()
+                    .register_exit(Box::new(move || trigger.cancel()));
+                grpc_service
+            });
 
             (
                 Some(json_rpc_service),
@@ -1008,6 +1038,7 @@ impl Validator {
 
                     Some(pubsub_service)
                 },
+                grpc_service,
                 Some(OptimisticallyConfirmedBankTracker::new(
                     bank_notification_receiver,
                     exit.clone(),
@@ -1023,7 +1054,7 @@ impl Validator {
                 }),
             )
         } else {
-            (None, None, None, None)
+            (None, None, None, None, None)
         };
 
         if config.halt_at_slot.is_some() {
@@ -1269,6 +1300,7 @@ impl Validator {
             },
             &max_slots,
             block_metadata_notifier,
+            slot_accounts_diff_notifier,
             config.wait_to_vote_slot,
             accounts_background_request_sender,
             config.runtime_config.log_messages_bytes_limit,
@@ -1352,6 +1384,7 @@ impl Validator {
             serve_repair_service,
             json_rpc_service,
             pubsub_service,
+            grpc_service,
             rpc_completed_slots_service,
             optimistically_confirmed_bank_tracker,
             transaction_status_service,
@@ -1438,6 +1471,10 @@ impl Validator {
             pubsub_service.join().expect("pubsub_service");
         }
 
+        if let Some(grpc_service) = self.grpc_service {
+            grpc_service.join().expect("grpc_service");
+        }
+
         self.rpc_completed_slots_service
             .join()
             .expect("rpc_completed_slots_service");
@@ -474,6 +474,16 @@ module.exports = {
           href: "#slotsupdatesunsubscribe",
           label: "slotsUpdatesUnsubscribe",
         },
+        {
+          type: "link",
+          href: "#transactionsubscribe",
+          label: "transactionSubscribe",
+        },
+        {
+          type: "link",
+          href: "#transactionunsubscribe",
+          label: "transactionUnsubscribe",
+        },
         {
           type: "link",
           href: "#votesubscribe",
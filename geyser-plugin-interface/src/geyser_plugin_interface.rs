@@ -5,11 +5,12 @@
 use {
     solana_sdk::{
         clock::{Slot, UnixTimestamp},
+        pubkey::Pubkey,
         signature::Signature,
         transaction::SanitizedTransaction,
     },
     solana_transaction_status::{Reward, TransactionStatusMeta},
-    std::{any::Any, error, io},
+    std::{any::Any, collections::HashSet, error, io},
     thiserror::Error,
 };
 
@@ -258,6 +259,76 @@ pub enum ReplicaBlockInfoVersions<'a> {
     V0_0_3(&'a ReplicaBlockInfoV3<'a>),
 }
 
+/// The state change of an account modified in a slot
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct ReplicaAccountDiffInfo<'a> {
+    /// The Pubkey for the account
+    pub pubkey: &'a [u8],
+
+    /// The lamports of the account in the parent slot, 0 if the account did
+    /// not exist
+    pub previous_lamports: u64,
+
+    /// The Pubkey of the owner program account in the parent slot, the
+    /// default Pubkey if the account did not exist
+    pub previous_owner: &'a [u8],
+
+    /// The SHA-256 hash of the account data in the parent slot, the hash of
+    /// empty data if the account did not exist
+    pub previous_data_hash: &'a [u8],
+
+    /// The lamports of the account at the end of the slot
+    pub lamports: u64,
+
+    /// The Pubkey of the owner program account at the end of the slot
+    pub owner: &'a [u8],
+
+    /// The SHA-256 hash of the account data at the end of the slot
+    pub data_hash: &'a [u8],
+}
+
+/// All the accounts modified in a frozen slot, in their final state
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct ReplicaSlotAccountsDiffInfo<'a> {
+    pub parent_slot: Slot,
+    pub slot: Slot,
+    pub blockhash: &'a str,
+    /// The modified accounts, sorted by their Pubkeys
+    pub accounts: &'a [ReplicaAccountDiffInfo<'a>],
+}
+
+/// A wrapper to future-proof ReplicaSlotAccountsDiffInfo handling. To make a
+/// change to the structure of ReplicaSlotAccountsDiffInfo, add an new enum
+/// variant wrapping a newer version, which will force plugin implementations
+/// to handle the change.
+#[repr(u32)]
+pub enum ReplicaSlotAccountsDiffInfoVersions<'a> {
+    V0_0_1(&'a ReplicaSlotAccountsDiffInfo<'a>),
+}
+
+/// A filter on the data of an account, like the `RpcFilterType` filters of
+/// the getProgramAccounts RPC method
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AccountDataFilter {
+    /// The account data is exactly this many bytes long
+    DataSize(u64),
+    /// The account data contains `bytes` at `offset`
+    Memcmp { offset: usize, bytes: Vec<u8> },
+}
+
+/// The accounts a plugin is notified of through `update_account`. An account
+/// is selected if its Pubkey is in `pubkeys`, or if it is owned by one of
+/// `owners` and matches all `data_filters`. When both `pubkeys` and `owners`
+/// are empty, every account matching all `data_filters` is selected.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountsFilter {
+    pub pubkeys: HashSet<Pubkey>,
+    pub owners: HashSet<Pubkey>,
+    pub data_filters: Vec<AccountDataFilter>,
+}
+
 /// Errors returned by plugin calls
 #[derive(Error, Debug)]
 #[repr(u32)]
@@ -418,6 +489,18 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
         Ok(())
     }
 
+    /// Called when a bank is frozen with all the accounts modified in its
+    /// slot.  Unlike update_account, which is called for every account
+    /// write, this is called once per slot and each account is included once
+    /// with its state in the parent slot and at the end of the slot.
+    #[allow(unused_variables)]
+    fn notify_slot_accounts_diff(
+        &self,
+        slot_accounts_diff: ReplicaSlotAccountsDiffInfoVersions,
+    ) -> Result<()> {
+        Ok(())
+    }
+
     /// Check if the plugin is interested in account data
     /// Default is true -- if the plugin is not interested in
     /// account data, please return false.
@@ -425,6 +508,14 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
         true
     }
 
+    /// Returns the accounts the plugin is interested in. The filter is
+    /// applied by the validator before `update_account` is called, and is
+    /// queried once after `on_load`.
+    /// Default is None -- the plugin is notified of every account.
+    fn accounts_filter(&self) -> Option<AccountsFilter> {
+        None
+    }
+
     /// Check if the plugin is interested in transaction data
     /// Default is false -- if the plugin is interested in
     /// transaction data, please return true.
@@ -438,4 +529,11 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
     fn entry_notifications_enabled(&self) -> bool {
         false
     }
+
+    /// Check if the plugin is interested in the accounts diff of each slot
+    /// Default is false -- if the plugin is interested in
+    /// the accounts diffs, return true.
+    fn slot_accounts_diff_notifications_enabled(&self) -> bool {
+        false
+    }
 }
@@ -0,0 +1,119 @@
+/// Module responsible for selecting the accounts a plugin is notified of
+use {
+    solana_geyser_plugin_interface::geyser_plugin_interface::{
+        AccountDataFilter, AccountsFilter, ReplicaAccountInfoV3,
+    },
+    solana_sdk::pubkey::Pubkey,
+    std::collections::HashSet,
+};
+
+/// Returns true if the specified account is selected by the filter
+pub(crate) fn is_account_selected(filter: &AccountsFilter, account: &ReplicaAccountInfoV3) -> bool {
+    if contains(&filter.pubkeys, account.pubkey) {
+        return true;
+    }
+    if filter.owners.is_empty() {
+        if !filter.pubkeys.is_empty() {
+            return false;
+        }
+    } else if !contains(&filter.owners, account.owner) {
+        return false;
+    }
+    filter
+        .data_filters
+        .iter()
+        .all(|data_filter| is_data_selected(data_filter, account.data))
+}
+
+fn contains(pubkeys: &HashSet<Pubkey>, pubkey: &[u8]) -> bool {
+    Pubkey::try_from(pubkey).map_or(false, |pubkey| pubkeys.contains(&pubkey))
+}
+
+fn is_data_selected(data_filter: &AccountDataFilter, data: &[u8]) -> bool {
+    match data_filter {
+        AccountDataFilter::DataSize(size) => data.len() as u64 == *size,
+        AccountDataFilter::Memcmp { offset, bytes } => offset
+            .checked_add(bytes.len())
+            .and_then(|end| data.get(*offset..end))
+            .map_or(false, |data| data == bytes.as_slice()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_account<'a>(
+        pubkey: &'a Pubkey,
+        owner: &'a Pubkey,
+        data: &'a [u8],
+    ) -> ReplicaAccountInfoV3<'a> {
+        ReplicaAccountInfoV3 {
+            pubkey: pubkey.as_ref(),
+            lamports: 1,
+            owner: owner.as_ref(),
+            executable: false,
+            rent_epoch: 0,
+            data,
+            write_version: 0,
+            txn: None,
+        }
+    }
+
+    #[test]
+    fn test_is_account_selected() {
+        let pubkey = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let data = [1, 2, 3, 4];
+        let account = new_account(&pubkey, &owner, &data);
+        let other_account = new_account(&other, &other, &data);
+
+        // An empty filter selects every account
+        let filter = AccountsFilter::default();
+        assert!(is_account_selected(&filter, &account));
+
+        // Pubkeys only select themselves
+        let filter = AccountsFilter {
+            pubkeys: [pubkey].into(),
+            data_filters: vec![AccountDataFilter::DataSize(0)],
+            ..AccountsFilter::default()
+        };
+        assert!(is_account_selected(&filter, &account));
+        assert!(!is_account_selected(&filter, &other_account));
+
+        // Owners select the accounts matching all the data filters
+        let mut filter = AccountsFilter {
+            owners: [owner].into(),
+            data_filters: vec![
+                AccountDataFilter::DataSize(4),
+                AccountDataFilter::Memcmp {
+                    offset: 1,
+                    bytes: vec![2, 3],
+                },
+            ],
+            ..AccountsFilter::default()
+        };
+        assert!(is_account_selected(&filter, &account));
+        assert!(!is_account_selected(&filter, &other_account));
+        filter.data_filters.push(AccountDataFilter::Memcmp {
+            offset: 3,
+            bytes: vec![4, 5],
+        });
+        assert!(!is_account_selected(&filter, &account));
+
+        // Data filters alone apply to every account
+        let filter = AccountsFilter {
+            data_filters: vec![AccountDataFilter::Memcmp {
+                offset: 0,
+                bytes: vec![1],
+            }],
+            ..AccountsFilter::default()
+        };
+        assert!(is_account_selected(&filter, &other_account));
+        assert!(!is_account_selected(
+            &filter,
+            &new_account(&pubkey, &owner, &[])
+        ));
+    }
+}
@@ -1,6 +1,9 @@
 /// Module responsible for notifying plugins of account updates
 use {
-    crate::geyser_plugin_manager::GeyserPluginManager,
+    crate::{
+        accounts_filter::is_account_selected, geyser_plugin_manager::GeyserPluginManager,
+        notification_queue::BlockedNotification,
+    },
     log::*,
     solana_accounts_db::{
         account_storage::meta::StoredAccountMeta,
@@ -73,6 +76,7 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
             return;
         }
 
+        let mut blocked_notifications = vec![];
         for plugin in plugin_manager.plugins.iter() {
             let mut measure = Measure::start("geyser-plugin-end-of-restore-from-snapshot");
             match plugin.notify_end_of_startup() {
@@ -83,7 +87,8 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
                         plugin.name()
                     )
                 }
-                Ok(_) => {
+                Ok(blocked_notification) => {
+                    blocked_notifications.extend(blocked_notification);
                     trace!(
                         "Successfully notified the end of restore from snapshot to plugin {}",
                         plugin.name()
@@ -96,6 +101,10 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
                 measure.as_us() as usize
             );
         }
+        drop(plugin_manager);
+        blocked_notifications
+            .into_iter()
+            .for_each(BlockedNotification::send);
     }
 }
 
@@ -151,7 +160,13 @@ impl AccountsUpdateNotifierImpl {
         if plugin_manager.plugins.is_empty() {
             return;
         }
+        let mut blocked_notifications = vec![];
         for plugin in plugin_manager.plugins.iter() {
+            if let Some(accounts_filter) = plugin.accounts_filter() {
+                if !is_account_selected(accounts_filter, &account) {
+                    continue;
+                }
+            }
             let mut measure = Measure::start("geyser-plugin-update-account");
             match plugin.update_account(
                 ReplicaAccountInfoVersions::V0_0_3(&account),
@@ -167,7 +182,8 @@ impl AccountsUpdateNotifierImpl {
                         plugin.name()
                     )
                 }
-                Ok(_) => {
+                Ok(blocked_notification) => {
+                    blocked_notifications.extend(blocked_notification);
                     trace!(
                         "Successfully updated account {} at slot {} to plugin {}",
                         bs58::encode(account.pubkey).into_string(),
@@ -184,6 +200,10 @@ impl AccountsUpdateNotifierImpl {
                 100000
             );
         }
+        drop(plugin_manager);
+        blocked_notifications
+            .into_iter()
+            .for_each(BlockedNotification::send);
         measure2.stop();
         inc_new_counter_debug!(
             "geyser-plugin-notify_plugins_of_account_update-us",
@@ -1,7 +1,7 @@
 use {
     crate::{
         block_metadata_notifier_interface::BlockMetadataNotifier,
-        geyser_plugin_manager::GeyserPluginManager,
+        geyser_plugin_manager::GeyserPluginManager, notification_queue::BlockedNotification,
     },
     log::*,
     solana_accounts_db::stake_rewards::RewardInfo,
@@ -39,6 +39,7 @@ impl BlockMetadataNotifier for BlockMetadataNotifierImpl {
         }
         let rewards = Self::build_rewards(rewards);
 
+        let mut blocked_notifications = vec![];
         for plugin in plugin_manager.plugins.iter() {
             let mut measure = Measure::start("geyser-plugin-update-slot");
             let block_info = Self::build_replica_block_info(
@@ -62,7 +63,8 @@ impl BlockMetadataNotifier for BlockMetadataNotifierImpl {
                         plugin.name()
                     )
                 }
-                Ok(_) => {
+                Ok(blocked_notification) => {
+                    blocked_notifications.extend(blocked_notification);
                     trace!(
                         "Successfully updated block metadata at slot {} to plugin {}",
                         slot,
@@ -78,6 +80,10 @@ impl BlockMetadataNotifier for BlockMetadataNotifierImpl {
                 1000
             );
         }
+        drop(plugin_manager);
+        blocked_notifications
+            .into_iter()
+            .for_each(BlockedNotification::send);
     }
 }
 
@@ -1,6 +1,6 @@
 /// Module responsible for notifying plugins about entries
 use {
-    crate::geyser_plugin_manager::GeyserPluginManager,
+    crate::{geyser_plugin_manager::GeyserPluginManager, notification_queue::BlockedNotification},
     log::*,
     solana_entry::entry::EntrySummary,
     solana_geyser_plugin_interface::geyser_plugin_interface::{
@@ -35,6 +35,7 @@ impl EntryNotifier for EntryNotifierImpl {
         let entry_info =
             Self::build_replica_entry_info(slot, index, entry, starting_transaction_index);
 
+        let mut blocked_notifications = vec![];
         for plugin in plugin_manager.plugins.iter() {
             if !plugin.entry_notifications_enabled() {
                 continue;
@@ -47,11 +48,16 @@ impl EntryNotifier for EntryNotifierImpl {
                         plugin.name()
                     )
                 }
-                Ok(_) => {
+                Ok(blocked_notification) => {
+                    blocked_notifications.extend(blocked_notification);
                     trace!("Successfully notified entry to plugin {}", plugin.name());
                 }
             }
         }
+        drop(plugin_manager);
+        blocked_notifications
+            .into_iter()
+            .for_each(BlockedNotification::send);
         measure.stop();
         inc_new_counter_debug!(
             "geyser-plugin-notify_plugins_of_entry_info-us",
@@ -1,4 +1,8 @@
 use {
+    crate::{
+        loaded_geyser_plugin::{GeyserPluginInfo, LoadedGeyserPlugin},
+        notification_queue::NotificationQueueConfig,
+    },
     jsonrpc_core::{ErrorCode, Result as JsonRpcResult},
     jsonrpc_server_utils::tokio::sync::oneshot::Sender as OneShotSender,
     libloading::Library,
@@ -9,7 +13,7 @@ use {
 
 #[derive(Default, Debug)]
 pub struct GeyserPluginManager {
-    pub plugins: Vec<Box<dyn GeyserPlugin>>,
+    pub plugins: Vec<LoadedGeyserPlugin>,
     libs: Vec<Library>,
 }
 
@@ -24,13 +28,23 @@ impl GeyserPluginManager {
     /// Unload all plugins and loaded plugin libraries, making sure to fire
     /// their `on_plugin_unload()` methods so they can do any necessary cleanup.
     pub fn unload(&mut self) {
+        let mut in_use = false;
         for mut plugin in self.plugins.drain(..) {
             info!("Unloading plugin for {:?}", plugin.name());
-            plugin.on_unload();
+            if let Err(err) = plugin.on_unload() {
+                error!("Failed to unload plugin {}: {err}", plugin.name());
+                // The plugin may still be running, so keep its code loaded.
+                std::mem::forget(plugin);
+                in_use = true;
+            }
         }
 
         for lib in self.libs.drain(..) {
-            drop(lib);
+            if in_use {
+                std::mem::forget(lib);
+            } else {
+                drop(lib);
+            }
         }
     }
 
@@ -64,11 +78,26 @@ impl GeyserPluginManager {
         false
     }
 
+    /// Check if there is any plugin interested in the accounts diff of each slot
+    pub fn slot_accounts_diff_notifications_enabled(&self) -> bool {
+        for plugin in &self.plugins {
+            if plugin.slot_accounts_diff_notifications_enabled() {
+                return true;
+            }
+        }
+        false
+    }
+
     /// Admin RPC request handler
     pub(crate) fn list_plugins(&self) -> JsonRpcResult<Vec<String>> {
         Ok(self.plugins.iter().map(|p| p.name().to_owned()).collect())
     }
 
+    /// Admin RPC request handler
+    pub(crate) fn list_plugins_with_info(&self) -> JsonRpcResult<Vec<GeyserPluginInfo>> {
+        Ok(self.plugins.iter().map(LoadedGeyserPlugin::info).collect())
+    }
+
     /// Admin RPC request handler
     /// # Safety
     ///
@@ -82,7 +111,7 @@ impl GeyserPluginManager {
         geyser_plugin_config_file: impl AsRef<Path>,
     ) -> JsonRpcResult<String> {
         // First load plugin
-        let (mut new_plugin, new_lib, new_config_file) =
+        let (mut new_plugin, new_lib, new_config_file, notification_queue_config) =
             load_plugin_from_config(geyser_plugin_config_file.as_ref()).map_err(|e| {
                 jsonrpc_core::Error {
                     code: ErrorCode::InvalidRequest,
@@ -121,7 +150,10 @@ impl GeyserPluginManager {
                 data: None,
             })?;
         let name = new_plugin.name().to_string();
-        self.plugins.push(new_plugin);
+        self.plugins.push(LoadedGeyserPlugin::new(
+            new_plugin,
+            notification_queue_config,
+        ));
         self.libs.push(new_lib);
 
         Ok(name)
@@ -143,7 +175,7 @@ impl GeyserPluginManager {
         };
 
         // Unload and drop plugin and lib
-        self._drop_plugin(idx);
+        self._drop_plugin(idx)?;
 
         Ok(())
     }
@@ -168,11 +200,11 @@ impl GeyserPluginManager {
 
         // Unload and drop current plugin first in case plugin requires exclusive access to resource,
         // such as a particular port or database.
-        self._drop_plugin(idx);
+        self._drop_plugin(idx)?;
 
         // Try to load plugin, library
         // SAFETY: It is up to the validator to ensure this is a valid plugin library.
-        let (mut new_plugin, new_lib, new_parsed_config_file) =
+        let (mut new_plugin, new_lib, new_parsed_config_file, notification_queue_config) =
             load_plugin_from_config(config_file.as_ref()).map_err(|err| jsonrpc_core::Error {
                 code: ErrorCode::InvalidRequest,
                 message: err.to_string(),
@@ -201,7 +233,10 @@ impl GeyserPluginManager {
         match new_plugin.on_load(new_parsed_config_file, true) {
             // On success, push plugin and library
             Ok(()) => {
-                self.plugins.push(new_plugin);
+                self.plugins.push(LoadedGeyserPlugin::new(
+                    new_plugin,
+                    notification_queue_config,
+                ));
                 self.libs.push(new_lib);
             }
 
@@ -220,15 +255,25 @@ impl GeyserPluginManager {
         Ok(())
     }
 
-    fn _drop_plugin(&mut self, idx: usize) {
+    fn _drop_plugin(&mut self, idx: usize) -> JsonRpcResult<()> {
         let current_lib = self.libs.remove(idx);
         let mut current_plugin = self.plugins.remove(idx);
         let name = current_plugin.name().to_string();
-        current_plugin.on_unload();
+        if let Err(err) = current_plugin.on_unload() {
+            // The plugin may still be running, so keep it and its library loaded.
+            std::mem::forget(current_plugin);
+            std::mem::forget(current_lib);
+            return Err(jsonrpc_core::Error {
+                code: ErrorCode::InternalError,
+                message: format!("Failed to unload plugin {name}: {err}"),
+                data: None,
+            });
+        }
         // The plugin must be dropped before the library to avoid a crash.
         drop(current_plugin);
         drop(current_lib);
         info!("Unloaded plugin {name} at idx {idx}");
+        Ok(())
     }
 }
 
@@ -264,6 +309,9 @@ pub enum GeyserPluginManagerRequest {
     ListPlugins {
         response_sender: OneShotSender<JsonRpcResult<Vec<String>>>,
     },
+    ListPluginsWithInfo {
+        response_sender: OneShotSender<JsonRpcResult<Vec<GeyserPluginInfo>>>,
+    },
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -298,12 +346,13 @@ pub enum GeyserPluginManagerError {
 /// This function loads the dynamically linked library specified in the path. The library
 /// must do necessary initializations.
 ///
-/// This returns the geyser plugin, the dynamic library, and the parsed config file as a &str.
-/// (The geyser plugin interface requires a &str for the on_load method).
+/// This returns the geyser plugin, the dynamic library, the parsed config file as a &str
+/// (the geyser plugin interface requires a &str for the on_load method), and the config of
+/// the plugin's notification queue if it has one.
 #[cfg(not(test))]
 pub(crate) fn load_plugin_from_config(
     geyser_plugin_config_file: &Path,
-) -> Result<(Box<dyn GeyserPlugin>, Library, &str), GeyserPluginManagerError> {
+) -> Result<LoadedPluginFromConfig, GeyserPluginManagerError> {
     use std::{fs::File, io::Read, path::PathBuf};
     type PluginConstructor = unsafe fn() -> *mut dyn GeyserPlugin;
     use libloading::Symbol;
@@ -346,6 +395,13 @@ pub(crate) fn load_plugin_from_config(
         libpath = config_dir.join(libpath);
     }
 
+    let notification_queue_config =
+        parse_notification_queue_config(&result).map_err(|err| {
+            GeyserPluginManagerError::InvalidConfigFileFormat(format!(
+                "The config file {geyser_plugin_config_file:?} has an invalid notification_queue, error: {err}"
+            ))
+        })?;
+
     let config_file = geyser_plugin_config_file
         .as_os_str()
         .to_str()
@@ -360,7 +416,29 @@ pub(crate) fn load_plugin_from_config(
         let plugin_raw = constructor();
         (Box::from_raw(plugin_raw), lib)
     };
-    Ok((plugin, lib, config_file))
+    Ok((plugin, lib, config_file, notification_queue_config))
+}
+
+type LoadedPluginFromConfig<'a> = (
+    Box<dyn GeyserPlugin>,
+    Library,
+    &'a str,
+    Option<NotificationQueueConfig>,
+);
+
+/// Parses the optional `notification_queue` section of a plugin config
+fn parse_notification_queue_config(
+    config: &serde_json::Value,
+) -> Result<Option<NotificationQueueConfig>, String> {
+    let Some(notification_queue) = config.get("notification_queue") else {
+        return Ok(None);
+    };
+    let notification_queue_config: NotificationQueueConfig =
+        serde_json::from_value(notification_queue.clone()).map_err(|err| err.to_string())?;
+    if notification_queue_config.capacity == 0 {
+        return Err("the capacity must be greater than 0".to_string());
+    }
+    Ok(Some(notification_queue_config))
 }
 
 #[cfg(test)]
@@ -371,12 +449,13 @@ const TESTPLUGIN2_CONFIG: &str = "TESTPLUGIN2_CONFIG";
 // This is mocked for tests to avoid having to do IO with a dynamically linked library
 // across different architectures at test time
 //
-/// This returns mocked values for the geyser plugin, the dynamic library, and the parsed config file as a &str.
-/// (The geyser plugin interface requires a &str for the on_load method).
+/// This returns mocked values for the geyser plugin, the dynamic library, the parsed config file
+/// as a &str (the geyser plugin interface requires a &str for the on_load method), and the config
+/// of the plugin's notification queue.
 #[cfg(test)]
 pub(crate) fn load_plugin_from_config(
     geyser_plugin_config_file: &Path,
-) -> Result<(Box<dyn GeyserPlugin>, Library, &str), GeyserPluginManagerError> {
+) -> Result<LoadedPluginFromConfig, GeyserPluginManagerError> {
     if geyser_plugin_config_file.ends_with(TESTPLUGIN_CONFIG) {
         Ok(tests::dummy_plugin_and_library(
             tests::TestPlugin,
@@ -397,8 +476,13 @@ pub(crate) fn load_plugin_from_config(
 #[cfg(test)]
 mod tests {
     use {
-        crate::geyser_plugin_manager::{
-            GeyserPluginManager, TESTPLUGIN2_CONFIG, TESTPLUGIN_CONFIG,
+        crate::{
+            geyser_plugin_manager::{
+                parse_notification_queue_config, GeyserPluginManager, LoadedPluginFromConfig,
+                TESTPLUGIN2_CONFIG, TESTPLUGIN_CONFIG,
+            },
+            loaded_geyser_plugin::LoadedGeyserPlugin,
+            notification_queue::{NotificationQueueConfig, OverflowPolicy},
         },
         libloading::Library,
         solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin,
@@ -408,11 +492,12 @@ mod tests {
     pub(super) fn dummy_plugin_and_library<P: GeyserPlugin>(
         plugin: P,
         config_path: &'static str,
-    ) -> (Box<dyn GeyserPlugin>, Library, &'static str) {
+    ) -> LoadedPluginFromConfig<'static> {
         (
             Box::new(plugin),
             Library::from(libloading::os::unix::Library::this()),
             config_path,
+            None,
         )
     }
 
@@ -452,9 +537,12 @@ mod tests {
         );
 
         // Mock having loaded plugin (TestPlugin)
-        let (mut plugin, lib, config) = dummy_plugin_and_library(TestPlugin, DUMMY_CONFIG);
+        let (mut plugin, lib, config, notification_queue_config) =
+            dummy_plugin_and_library(TestPlugin, DUMMY_CONFIG);
         plugin.on_load(config, false).unwrap();
-        plugin_manager_lock.plugins.push(plugin);
+        plugin_manager_lock
+            .plugins
+            .push(LoadedGeyserPlugin::new(plugin, notification_queue_config));
         plugin_manager_lock.libs.push(lib);
         // plugin_manager_lock.libs.push(lib);
         assert_eq!(plugin_manager_lock.plugins[0].name(), DUMMY_NAME);
@@ -487,20 +575,71 @@ mod tests {
 
         // Load two plugins
         // First
-        let (mut plugin, lib, config) = dummy_plugin_and_library(TestPlugin, TESTPLUGIN_CONFIG);
+        let (mut plugin, lib, config, notification_queue_config) =
+            dummy_plugin_and_library(TestPlugin, TESTPLUGIN_CONFIG);
         plugin.on_load(config, false).unwrap();
-        plugin_manager_lock.plugins.push(plugin);
+        plugin_manager_lock
+            .plugins
+            .push(LoadedGeyserPlugin::new(plugin, notification_queue_config));
         plugin_manager_lock.libs.push(lib);
         // Second
-        let (mut plugin, lib, config) = dummy_plugin_and_library(TestPlugin2, TESTPLUGIN2_CONFIG);
+        let (mut plugin, lib, config, _notification_queue_config) =
+            dummy_plugin_and_library(TestPlugin2, TESTPLUGIN2_CONFIG);
         plugin.on_load(config, false).unwrap();
-        plugin_manager_lock.plugins.push(plugin);
+        plugin_manager_lock.plugins.push(LoadedGeyserPlugin::new(
+            plugin,
+            Some(NotificationQueueConfig::default()),
+        ));
         plugin_manager_lock.libs.push(lib);
 
         // Check that both plugins are returned in the list
         let plugins = plugin_manager_lock.list_plugins().unwrap();
         assert!(plugins.iter().any(|name| name.eq(DUMMY_NAME)));
         assert!(plugins.iter().any(|name| name.eq(ANOTHER_DUMMY_NAME)));
+
+        // and with their info
+        let plugins = plugin_manager_lock.list_plugins_with_info().unwrap();
+        assert!(plugins
+            .iter()
+            .any(|plugin| plugin.name.eq(DUMMY_NAME) && plugin.notification_queue.is_none()));
+        assert!(plugins.iter().any(
+            |plugin| plugin.name.eq(ANOTHER_DUMMY_NAME) && plugin.notification_queue.is_some()
+        ));
+    }
+
+    #[test]
+    fn test_parse_notification_queue_config() {
+        let config: serde_json::Value = json5::from_str(r#"{ libpath: "libplugin.so" }"#).unwrap();
+        assert_eq!(parse_notification_queue_config(&config), Ok(None));
+
+        let config: serde_json::Value =
+            json5::from_str(r#"{ libpath: "libplugin.so", notification_queue: {} }"#).unwrap();
+        assert_eq!(
+            parse_notification_queue_config(&config),
+            Ok(Some(NotificationQueueConfig::default()))
+        );
+
+        let config: serde_json::Value = json5::from_str(
+            r#"{
+                libpath: "libplugin.so",
+                notification_queue: { capacity: 16, overflow_policy: "disconnect" },
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            parse_notification_queue_config(&config),
+            Ok(Some(NotificationQueueConfig {
+                capacity: 16,
+                overflow_policy: OverflowPolicy::Disconnect,
+            }))
+        );
+
+        let config: serde_json::Value =
+            json5::from_str(r#"{ notification_queue: { capacity: 0 } }"#).unwrap();
+        assert!(parse_notification_queue_config(&config).is_err());
+        let config: serde_json::Value =
+            json5::from_str(r#"{ notification_queue: { overflow_policy: "retry" } }"#).unwrap();
+        assert!(parse_notification_queue_config(&config).is_err());
     }
 
     #[test]
@@ -5,6 +5,8 @@ use {
         block_metadata_notifier_interface::BlockMetadataNotifierArc,
         entry_notifier::EntryNotifierImpl,
         geyser_plugin_manager::{GeyserPluginManager, GeyserPluginManagerRequest},
+        slot_accounts_diff_notifier::SlotAccountsDiffNotifierImpl,
+        slot_accounts_diff_notifier_interface::SlotAccountsDiffNotifierArc,
         slot_status_notifier::SlotStatusNotifierImpl,
         slot_status_observer::SlotStatusObserver,
         transaction_notifier::TransactionNotifierImpl,
@@ -37,6 +39,7 @@ pub struct GeyserPluginService {
     transaction_notifier: Option<TransactionNotifierArc>,
     entry_notifier: Option<EntryNotifierArc>,
     block_metadata_notifier: Option<BlockMetadataNotifierArc>,
+    slot_accounts_diff_notifier: Option<SlotAccountsDiffNotifierArc>,
 }
 
 impl GeyserPluginService {
@@ -81,6 +84,8 @@ impl GeyserPluginService {
             plugin_manager.account_data_notifications_enabled();
         let transaction_notifications_enabled = plugin_manager.transaction_notifications_enabled();
         let entry_notifications_enabled = plugin_manager.entry_notifications_enabled();
+        let slot_accounts_diff_notifications_enabled =
+            plugin_manager.slot_accounts_diff_notifications_enabled();
         let plugin_manager = Arc::new(RwLock::new(plugin_manager));
 
         let accounts_update_notifier: Option<AccountsUpdateNotifier> =
@@ -107,6 +112,15 @@ impl GeyserPluginService {
             None
         };
 
+        let slot_accounts_diff_notifier: Option<SlotAccountsDiffNotifierArc> =
+            if slot_accounts_diff_notifications_enabled {
+                let slot_accounts_diff_notifier =
+                    SlotAccountsDiffNotifierImpl::new(plugin_manager.clone());
+                Some(Arc::new(slot_accounts_diff_notifier))
+            } else {
+                None
+            };
+
         let (slot_status_observer, block_metadata_notifier): (
             Option<SlotStatusObserver>,
             Option<BlockMetadataNotifierArc>,
@@ -143,6 +157,7 @@ impl GeyserPluginService {
             transaction_notifier,
             entry_notifier,
             block_metadata_notifier,
+            slot_accounts_diff_notifier,
         })
     }
 
@@ -172,6 +187,10 @@ impl GeyserPluginService {
         self.block_metadata_notifier.clone()
     }
 
+    pub fn get_slot_accounts_diff_notifier(&self) -> Option<SlotAccountsDiffNotifierArc> {
+        self.slot_accounts_diff_notifier.clone()
+    }
+
     pub fn join(self) -> thread::Result<()> {
         if let Some(mut slot_status_observer) = self.slot_status_observer {
             slot_status_observer.join()?;
@@ -197,6 +216,14 @@ impl GeyserPluginService {
                                 .expect("Admin rpc service will be waiting for response");
                         }
 
+                        GeyserPluginManagerRequest::ListPluginsWithInfo { response_sender } => {
+                            let plugin_list =
+                                plugin_manager.read().unwrap().list_plugins_with_info();
+                            response_sender
+                                .send(plugin_list)
+                                .expect("Admin rpc service will be waiting for response");
+                        }
+
                         GeyserPluginManagerRequest::ReloadPlugin {
                             ref name,
                             ref config_file,
@@ -1,9 +1,14 @@
+pub mod accounts_filter;
 pub mod accounts_update_notifier;
 pub mod block_metadata_notifier;
 pub mod block_metadata_notifier_interface;
 pub mod entry_notifier;
 pub mod geyser_plugin_manager;
 pub mod geyser_plugin_service;
+pub mod loaded_geyser_plugin;
+pub mod notification_queue;
+pub mod slot_accounts_diff_notifier;
+pub mod slot_accounts_diff_notifier_interface;
 pub mod slot_status_notifier;
 pub mod slot_status_observer;
 pub mod transaction_notifier;
@@ -0,0 +1,439 @@
+/// Module wrapping a loaded plugin, so that notifications are either delivered
+/// to it directly or through its notification queue, and recording its
+/// notification statistics.
+use {
+    crate::notification_queue::{
+        BlockedNotification, Notification, NotificationQueue, NotificationQueueConfig,
+        OverflowPolicy,
+    },
+    serde_derive::{Deserialize, Serialize},
+    solana_geyser_plugin_interface::geyser_plugin_interface::{
+        AccountsFilter, GeyserPlugin, GeyserPluginError, ReplicaAccountInfoVersions,
+        ReplicaBlockInfoVersions, ReplicaEntryInfoVersions, ReplicaSlotAccountsDiffInfoVersions,
+        ReplicaTransactionInfoVersions, Result, SlotStatus,
+    },
+    solana_measure::measure::Measure,
+    solana_sdk::clock::Slot,
+    std::sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+/// The notification statistics of a plugin
+#[derive(Debug, Default)]
+pub(crate) struct GeyserPluginStats {
+    /// The number of notifications delivered to the plugin
+    notifications: AtomicU64,
+    /// The total time spent by the plugin handling notifications
+    total_latency_us: AtomicU64,
+    /// The longest time spent by the plugin handling a notification
+    max_latency_us: AtomicU64,
+    /// The number of notifications dropped because the queue was full
+    dropped_notifications: AtomicU64,
+}
+
+impl GeyserPluginStats {
+    pub(crate) fn record_latency(&self, latency_us: u64) {
+        self.notifications.fetch_add(1, Ordering::Relaxed);
+        self.total_latency_us
+            .fetch_add(latency_us, Ordering::Relaxed);
+        self.max_latency_us.fetch_max(latency_us, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_dropped(&self) {
+        self.dropped_notifications.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// The notification queue of a plugin, as reported by the list_plugins_with_info admin
+/// RPC
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationQueueInfo {
+    pub capacity: usize,
+    pub overflow_policy: OverflowPolicy,
+    /// The number of notifications waiting to be delivered
+    pub depth: usize,
+    /// Whether the plugin stopped receiving notifications after its queue
+    /// overflowed
+    pub disconnected: bool,
+}
+
+/// A loaded plugin and its notification statistics, as reported by the
+/// list_plugins_with_info admin RPC
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GeyserPluginInfo {
+    pub name: String,
+    pub notifications: u64,
+    pub average_latency_us: u64,
+    pub max_latency_us: u64,
+    pub dropped_notifications: u64,
+    pub notification_queue: Option<NotificationQueueInfo>,
+}
+
+/// A loaded plugin.  Without a notification queue, notifications are
+/// delivered on the calling thread.  With one, the notifications produced by
+/// the notifiers are queued, and the plugin's result is only logged by the
+/// queue's worker thread.  A notification which has to wait for room in the
+/// queue is returned to the notifier, which sends it once it has released
+/// the plugin manager lock.
+#[derive(Debug)]
+pub struct LoadedGeyserPlugin {
+    plugin: Arc<Box<dyn GeyserPlugin>>,
+    notification_queue: Option<NotificationQueue>,
+    stats: Arc<GeyserPluginStats>,
+    accounts_filter: Option<AccountsFilter>,
+}
+
+impl LoadedGeyserPlugin {
+    pub(crate) fn new(
+        plugin: Box<dyn GeyserPlugin>,
+        notification_queue_config: Option<NotificationQueueConfig>,
+    ) -> Self {
+        let plugin = Arc::new(plugin);
+        let stats = Arc::<GeyserPluginStats>::default();
+        let notification_queue = notification_queue_config
+            .map(|config| NotificationQueue::new(config, plugin.clone(), stats.clone()));
+        let accounts_filter = plugin.accounts_filter();
+        Self {
+            plugin,
+            notification_queue,
+            stats,
+            accounts_filter,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.plugin.name()
+    }
+
+    /// The accounts the plugin is interested in, as returned by the plugin
+    /// when it was loaded
+    pub fn accounts_filter(&self) -> Option<&AccountsFilter> {
+        self.accounts_filter.as_ref()
+    }
+
+    pub(crate) fn info(&self) -> GeyserPluginInfo {
+        let notifications = self.stats.notifications.load(Ordering::Relaxed);
+        let total_latency_us = self.stats.total_latency_us.load(Ordering::Relaxed);
+        GeyserPluginInfo {
+            name: self.name().to_string(),
+            notifications,
+            average_latency_us: total_latency_us
+                .checked_div(notifications)
+                .unwrap_or_default(),
+            max_latency_us: self.stats.max_latency_us.load(Ordering::Relaxed),
+            dropped_notifications: self.stats.dropped_notifications.load(Ordering::Relaxed),
+            notification_queue: self.notification_queue.as_ref().map(|queue| {
+                NotificationQueueInfo {
+                    capacity: queue.config().capacity,
+                    overflow_policy: queue.config().overflow_policy,
+                    depth: queue.len(),
+                    disconnected: queue.is_disconnected(),
+                }
+            }),
+        }
+    }
+
+    /// Stops the notification queue, delivering the queued notifications,
+    /// before unloading the plugin.  Fails without unloading the plugin if it
+    /// is still shared with the queue's worker thread.
+    pub fn on_unload(&mut self) -> Result<()> {
+        if let Some(mut notification_queue) = self.notification_queue.take() {
+            notification_queue.shutdown();
+        }
+        let name = self.name();
+        let plugin = Arc::get_mut(&mut self.plugin).ok_or_else(|| {
+            GeyserPluginError::Custom(
+                format!("plugin {name} is still in use by its notification worker").into(),
+            )
+        })?;
+        plugin.on_unload();
+        Ok(())
+    }
+
+    /// Queues the specified notification, returning None if the plugin has
+    /// no notification queue.  Otherwise, returns the notification if it has
+    /// to wait for room in the queue.
+    fn enqueue(
+        &self,
+        notification: impl FnOnce() -> Notification,
+    ) -> Option<Option<BlockedNotification>> {
+        self.notification_queue.as_ref().map(|notification_queue| {
+            notification_queue.push(notification(), self.name(), &self.stats)
+        })
+    }
+
+    /// Delivers a notification to the plugin on the calling thread.
+    fn notify_directly(
+        &self,
+        notify: impl FnOnce(&dyn GeyserPlugin) -> Result<()>,
+    ) -> Result<Option<BlockedNotification>> {
+        let mut measure = Measure::start("geyser-plugin-notification");
+        let result = notify(self.plugin.as_ref().as_ref());
+        measure.stop();
+        self.stats.record_latency(measure.as_us());
+        result.map(|()| None)
+    }
+
+    pub fn update_account(
+        &self,
+        account: ReplicaAccountInfoVersions,
+        slot: Slot,
+        is_startup: bool,
+    ) -> Result<Option<BlockedNotification>> {
+        if let ReplicaAccountInfoVersions::V0_0_3(info) = &account {
+            if let Some(blocked_notification) = self.enqueue(|| Notification::UpdateAccount {
+                account: (*info).into(),
+                slot,
+                is_startup,
+            }) {
+                return Ok(blocked_notification);
+            }
+        }
+        self.notify_directly(|plugin| plugin.update_account(account, slot, is_startup))
+    }
+
+    pub fn notify_end_of_startup(&self) -> Result<Option<BlockedNotification>> {
+        if let Some(blocked_notification) = self.enqueue(|| Notification::EndOfStartup) {
+            return Ok(blocked_notification);
+        }
+        self.notify_directly(|plugin| plugin.notify_end_of_startup())
+    }
+
+    pub fn update_slot_status(
+        &self,
+        slot: Slot,
+        parent: Option<u64>,
+        status: SlotStatus,
+    ) -> Result<Option<BlockedNotification>> {
+        if let Some(blocked_notification) = self.enqueue(|| Notification::UpdateSlotStatus {
+            slot,
+            parent,
+            status,
+        }) {
+            return Ok(blocked_notification);
+        }
+        self.notify_directly(|plugin| plugin.update_slot_status(slot, parent, status))
+    }
+
+    pub fn notify_transaction(
+        &self,
+        transaction: ReplicaTransactionInfoVersions,
+        slot: Slot,
+    ) -> Result<Option<BlockedNotification>> {
+        if let ReplicaTransactionInfoVersions::V0_0_2(info) = &transaction {
+            if let Some(blocked_notification) = self.enqueue(|| Notification::Transaction {
+                transaction: (*info).into(),
+                slot,
+            }) {
+                return Ok(blocked_notification);
+            }
+        }
+        self.notify_directly(|plugin| plugin.notify_transaction(transaction, slot))
+    }
+
+    pub fn notify_entry(
+        &self,
+        entry: ReplicaEntryInfoVersions,
+    ) -> Result<Option<BlockedNotification>> {
+        if let ReplicaEntryInfoVersions::V0_0_2(info) = &entry {
+            if let Some(blocked_notification) = self.enqueue(|| Notification::Entry((*info).into()))
+            {
+                return Ok(blocked_notification);
+            }
+        }
+        self.notify_directly(|plugin| plugin.notify_entry(entry))
+    }
+
+    pub fn notify_block_metadata(
+        &self,
+        blockinfo: ReplicaBlockInfoVersions,
+    ) -> Result<Option<BlockedNotification>> {
+        if let ReplicaBlockInfoVersions::V0_0_3(info) = &blockinfo {
+            if let Some(blocked_notification) =
+                self.enqueue(|| Notification::BlockMetadata((*info).into()))
+            {
+                return Ok(blocked_notification);
+            }
+        }
+        self.notify_directly(|plugin| plugin.notify_block_metadata(blockinfo))
+    }
+
+    pub fn notify_slot_accounts_diff(
+        &self,
+        slot_accounts_diff: ReplicaSlotAccountsDiffInfoVersions,
+    ) -> Result<Option<BlockedNotification>> {
+        let ReplicaSlotAccountsDiffInfoVersions::V0_0_1(info) = &slot_accounts_diff;
+        if let Some(blocked_notification) =
+            self.enqueue(|| Notification::SlotAccountsDiff((*info).into()))
+        {
+            return Ok(blocked_notification);
+        }
+        self.notify_directly(|plugin| plugin.notify_slot_accounts_diff(slot_accounts_diff))
+    }
+
+    pub fn account_data_notifications_enabled(&self) -> bool {
+        self.plugin.account_data_notifications_enabled()
+    }
+
+    pub fn transaction_notifications_enabled(&self) -> bool {
+        self.plugin.transaction_notifications_enabled()
+    }
+
+    pub fn entry_notifications_enabled(&self) -> bool {
+        self.plugin.entry_notifications_enabled()
+    }
+
+    pub fn slot_accounts_diff_notifications_enabled(&self) -> bool {
+        self.plugin.slot_accounts_diff_notifications_enabled()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crossbeam_channel::{bounded, Receiver, Sender},
+        std::sync::Mutex,
+    };
+
+    /// A plugin whose slot status notifications wait for a permit, and are
+    /// then recorded.
+    #[derive(Debug)]
+    struct BlockingPlugin {
+        permits: Receiver<()>,
+        slots: Arc<Mutex<Vec<Slot>>>,
+    }
+
+    impl GeyserPlugin for BlockingPlugin {
+        fn name(&self) -> &'static str {
+            "blocking"
+        }
+
+        fn update_slot_status(
+            &self,
+            slot: Slot,
+            _parent: Option<u64>,
+            _status: SlotStatus,
+        ) -> Result<()> {
+            self.permits.recv().unwrap();
+            self.slots.lock().unwrap().push(slot);
+            Ok(())
+        }
+    }
+
+    fn new_blocking_plugin(
+        overflow_policy: OverflowPolicy,
+    ) -> (LoadedGeyserPlugin, Sender<()>, Arc<Mutex<Vec<Slot>>>) {
+        let (permit_sender, permits) = bounded(0);
+        let slots = Arc::<Mutex<Vec<Slot>>>::default();
+        let plugin = LoadedGeyserPlugin::new(
+            Box::new(BlockingPlugin {
+                permits,
+                slots: slots.clone(),
+            }),
+            Some(NotificationQueueConfig {
+                capacity: 2,
+                overflow_policy,
+            }),
+        );
+        (plugin, permit_sender, slots)
+    }
+
+    #[test]
+    fn test_notification_queue_drop() {
+        let (mut plugin, permit_sender, slots) = new_blocking_plugin(OverflowPolicy::Drop);
+
+        // The worker takes slot 0 and waits for a permit, slots 1 and 2 fill
+        // the queue, and slot 3 is dropped.
+        plugin
+            .update_slot_status(0, None, SlotStatus::Processed)
+            .unwrap();
+        while plugin.info().notification_queue.unwrap().depth != 0 {
+            std::thread::yield_now();
+        }
+        for slot in 1..4 {
+            plugin
+                .update_slot_status(slot, None, SlotStatus::Processed)
+                .unwrap();
+        }
+        let info = plugin.info();
+        assert_eq!(info.dropped_notifications, 1);
+        assert_eq!(
+            info.notification_queue,
+            Some(NotificationQueueInfo {
+                capacity: 2,
+                overflow_policy: OverflowPolicy::Drop,
+                depth: 2,
+                disconnected: false,
+            })
+        );
+
+        // Later notifications are still queued once there is room.
+        for _ in 0..3 {
+            permit_sender.send(()).unwrap();
+        }
+        plugin
+            .update_slot_status(4, None, SlotStatus::Processed)
+            .unwrap();
+        permit_sender.send(()).unwrap();
+        plugin.on_unload().unwrap();
+        assert_eq!(*slots.lock().unwrap(), vec![0, 1, 2, 4]);
+        assert_eq!(plugin.info().notifications, 4);
+    }
+
+    #[test]
+    fn test_notification_queue_disconnect() {
+        let (mut plugin, permit_sender, slots) = new_blocking_plugin(OverflowPolicy::Disconnect);
+
+        plugin
+            .update_slot_status(0, None, SlotStatus::Processed)
+            .unwrap();
+        while plugin.info().notification_queue.unwrap().depth != 0 {
+            std::thread::yield_now();
+        }
+        for slot in 1..4 {
+            plugin
+                .update_slot_status(slot, None, SlotStatus::Processed)
+                .unwrap();
+        }
+        assert!(plugin.info().notification_queue.unwrap().disconnected);
+
+        // Once disconnected, notifications are dropped even if there is room.
+        for _ in 0..3 {
+            permit_sender.send(()).unwrap();
+        }
+        while plugin.info().notification_queue.unwrap().depth != 0 {
+            std::thread::yield_now();
+        }
+        plugin
+            .update_slot_status(4, None, SlotStatus::Processed)
+            .unwrap();
+        plugin.on_unload().unwrap();
+        assert_eq!(*slots.lock().unwrap(), vec![0, 1, 2]);
+        assert_eq!(plugin.info().dropped_notifications, 2);
+    }
+
+    #[test]
+    fn test_notification_queue_block() {
+        let (mut plugin, permit_sender, slots) = new_blocking_plugin(OverflowPolicy::Block);
+        let permits = std::thread::spawn(move || {
+            for _ in 0..10 {
+                permit_sender.send(()).unwrap();
+            }
+        });
+        for slot in 0..10 {
+            if let Some(blocked_notification) = plugin
+                .update_slot_status(slot, None, SlotStatus::Processed)
+                .unwrap()
+            {
+                blocked_notification.send();
+            }
+        }
+        permits.join().unwrap();
+        plugin.on_unload().unwrap();
+        assert_eq!(*slots.lock().unwrap(), (0..10).collect::<Vec<_>>());
+        assert_eq!(plugin.info().dropped_notifications, 0);
+    }
+}
@@ -0,0 +1,527 @@
+/// Module responsible for delivering notifications to a plugin on a dedicated
+/// worker thread, so that a slow plugin does not stall the replay, banking and
+/// accounts-db threads producing the notifications.
+use {
+    crate::loaded_geyser_plugin::GeyserPluginStats,
+    crossbeam_channel::{bounded, Sender, TrySendError},
+    log::*,
+    serde_derive::{Deserialize, Serialize},
+    solana_geyser_plugin_interface::geyser_plugin_interface::{
+        GeyserPlugin, ReplicaAccountDiffInfo, ReplicaAccountInfoV3, ReplicaAccountInfoVersions,
+        ReplicaBlockInfoV3, ReplicaBlockInfoVersions, ReplicaEntryInfoV2, ReplicaEntryInfoVersions,
+        ReplicaSlotAccountsDiffInfo, ReplicaSlotAccountsDiffInfoVersions, ReplicaTransactionInfoV2,
+        ReplicaTransactionInfoVersions, Result, SlotStatus,
+    },
+    solana_measure::measure::Measure,
+    solana_sdk::{
+        clock::{Slot, UnixTimestamp},
+        signature::Signature,
+        transaction::SanitizedTransaction,
+    },
+    solana_transaction_status::{Reward, TransactionStatusMeta},
+    std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread::{self, JoinHandle},
+    },
+};
+
+const DEFAULT_NOTIFICATION_QUEUE_CAPACITY: usize = 100_000;
+
+/// What to do with a notification when the queue of a plugin is full
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverflowPolicy {
+    /// Wait until the plugin catches up
+    #[default]
+    Block,
+    /// Drop the notification
+    Drop,
+    /// Drop the notification, and stop notifying the plugin until it is
+    /// reloaded
+    Disconnect,
+}
+
+/// The optional `notification_queue` section of a plugin config file.  When
+/// it is present, notifications are delivered to the plugin on a dedicated
+/// worker thread instead of on the threads producing them.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotificationQueueConfig {
+    /// The maximum number of notifications waiting to be delivered
+    pub capacity: usize,
+    /// What to do with a notification when the queue is full
+    pub overflow_policy: OverflowPolicy,
+}
+
+impl Default for NotificationQueueConfig {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_NOTIFICATION_QUEUE_CAPACITY,
+            overflow_policy: OverflowPolicy::default(),
+        }
+    }
+}
+
+/// An owned ReplicaAccountInfoV3
+#[derive(Debug)]
+pub(crate) struct OwnedReplicaAccountInfo {
+    pubkey: Vec<u8>,
+    lamports: u64,
+    owner: Vec<u8>,
+    executable: bool,
+    rent_epoch: u64,
+    data: Vec<u8>,
+    write_version: u64,
+    txn: Option<SanitizedTransaction>,
+}
+
+impl From<&ReplicaAccountInfoV3<'_>> for OwnedReplicaAccountInfo {
+    fn from(account: &ReplicaAccountInfoV3) -> Self {
+        Self {
+            pubkey: account.pubkey.to_vec(),
+            lamports: account.lamports,
+            owner: account.owner.to_vec(),
+            executable: account.executable,
+            rent_epoch: account.rent_epoch,
+            data: account.data.to_vec(),
+            write_version: account.write_version,
+            txn: account.txn.cloned(),
+        }
+    }
+}
+
+impl OwnedReplicaAccountInfo {
+    fn as_replica_account_info(&self) -> ReplicaAccountInfoV3 {
+        ReplicaAccountInfoV3 {
+            pubkey: &self.pubkey,
+            lamports: self.lamports,
+            owner: &self.owner,
+            executable: self.executable,
+            rent_epoch: self.rent_epoch,
+            data: &self.data,
+            write_version: self.write_version,
+            txn: self.txn.as_ref(),
+        }
+    }
+}
+
+/// An owned ReplicaTransactionInfoV2
+#[derive(Debug)]
+pub(crate) struct OwnedReplicaTransactionInfo {
+    signature: Signature,
+    is_vote: bool,
+    transaction: SanitizedTransaction,
+    transaction_status_meta: TransactionStatusMeta,
+    index: usize,
+}
+
+impl From<&ReplicaTransactionInfoV2<'_>> for OwnedReplicaTransactionInfo {
+    fn from(transaction: &ReplicaTransactionInfoV2) -> Self {
+        Self {
+            signature: *transaction.signature,
+            is_vote: transaction.is_vote,
+            transaction: transaction.transaction.clone(),
+            transaction_status_meta: transaction.transaction_status_meta.clone(),
+            index: transaction.index,
+        }
+    }
+}
+
+impl OwnedReplicaTransactionInfo {
+    fn as_replica_transaction_info(&self) -> ReplicaTransactionInfoV2 {
+        ReplicaTransactionInfoV2 {
+            signature: &self.signature,
+            is_vote: self.is_vote,
+            transaction: &self.transaction,
+            transaction_status_meta: &self.transaction_status_meta,
+            index: self.index,
+        }
+    }
+}
+
+/// An owned ReplicaEntryInfoV2
+#[derive(Debug)]
+pub(crate) struct OwnedReplicaEntryInfo {
+    slot: Slot,
+    index: usize,
+    num_hashes: u64,
+    hash: Vec<u8>,
+    executed_transaction_count: u64,
+    starting_transaction_index: usize,
+}
+
+impl From<&ReplicaEntryInfoV2<'_>> for OwnedReplicaEntryInfo {
+    fn from(entry: &ReplicaEntryInfoV2) -> Self {
+        Self {
+            slot: entry.slot,
+            index: entry.index,
+            num_hashes: entry.num_hashes,
+            hash: entry.hash.to_vec(),
+            executed_transaction_count: entry.executed_transaction_count,
+            starting_transaction_index: entry.starting_transaction_index,
+        }
+    }
+}
+
+impl OwnedReplicaEntryInfo {
+    fn as_replica_entry_info(&self) -> ReplicaEntryInfoV2 {
+        ReplicaEntryInfoV2 {
+            slot: self.slot,
+            index: self.index,
+            num_hashes: self.num_hashes,
+            hash: &self.hash,
+            executed_transaction_count: self.executed_transaction_count,
+            starting_transaction_index: self.starting_transaction_index,
+        }
+    }
+}
+
+/// An owned ReplicaBlockInfoV3
+#[derive(Debug)]
+pub(crate) struct OwnedReplicaBlockInfo {
+    parent_slot: Slot,
+    parent_blockhash: String,
+    slot: Slot,
+    blockhash: String,
+    rewards: Vec<Reward>,
+    block_time: Option<UnixTimestamp>,
+    block_height: Option<u64>,
+    executed_transaction_count: u64,
+    entry_count: u64,
+}
+
+impl From<&ReplicaBlockInfoV3<'_>> for OwnedReplicaBlockInfo {
+    fn from(block: &ReplicaBlockInfoV3) -> Self {
+        Self {
+            parent_slot: block.parent_slot,
+            parent_blockhash: block.parent_blockhash.to_string(),
+            slot: block.slot,
+            blockhash: block.blockhash.to_string(),
+            rewards: block.rewards.to_vec(),
+            block_time: block.block_time,
+            block_height: block.block_height,
+            executed_transaction_count: block.executed_transaction_count,
+            entry_count: block.entry_count,
+        }
+    }
+}
+
+impl OwnedReplicaBlockInfo {
+    fn as_replica_block_info(&self) -> ReplicaBlockInfoV3 {
+        ReplicaBlockInfoV3 {
+            parent_slot: self.parent_slot,
+            parent_blockhash: &self.parent_blockhash,
+            slot: self.slot,
+            blockhash: &self.blockhash,
+            rewards: &self.rewards,
+            block_time: self.block_time,
+            block_height: self.block_height,
+            executed_transaction_count: self.executed_transaction_count,
+            entry_count: self.entry_count,
+        }
+    }
+}
+
+/// An owned ReplicaAccountDiffInfo
+#[derive(Debug)]
+pub(crate) struct OwnedReplicaAccountDiffInfo {
+    pubkey: Vec<u8>,
+    previous_lamports: u64,
+    previous_owner: Vec<u8>,
+    previous_data_hash: Vec<u8>,
+    lamports: u64,
+    owner: Vec<u8>,
+    data_hash: Vec<u8>,
+}
+
+impl From<&ReplicaAccountDiffInfo<'_>> for OwnedReplicaAccountDiffInfo {
+    fn from(account: &ReplicaAccountDiffInfo) -> Self {
+        Self {
+            pubkey: account.pubkey.to_vec(),
+            previous_lamports: account.previous_lamports,
+            previous_owner: account.previous_owner.to_vec(),
+            previous_data_hash: account.previous_data_hash.to_vec(),
+            lamports: account.lamports,
+            owner: account.owner.to_vec(),
+            data_hash: account.data_hash.to_vec(),
+        }
+    }
+}
+
+impl OwnedReplicaAccountDiffInfo {
+    fn as_replica_account_diff_info(&self) -> ReplicaAccountDiffInfo {
+        ReplicaAccountDiffInfo {
+            pubkey: &self.pubkey,
+            previous_lamports: self.previous_lamports,
+            previous_owner: &self.previous_owner,
+            previous_data_hash: &self.previous_data_hash,
+            lamports: self.lamports,
+            owner: &self.owner,
+            data_hash: &self.data_hash,
+        }
+    }
+}
+
+/// An owned ReplicaSlotAccountsDiffInfo
+#[derive(Debug)]
+pub(crate) struct OwnedReplicaSlotAccountsDiffInfo {
+    parent_slot: Slot,
+    slot: Slot,
+    blockhash: String,
+    accounts: Vec<OwnedReplicaAccountDiffInfo>,
+}
+
+impl From<&ReplicaSlotAccountsDiffInfo<'_>> for OwnedReplicaSlotAccountsDiffInfo {
+    fn from(slot_accounts_diff: &ReplicaSlotAccountsDiffInfo) -> Self {
+        Self {
+            parent_slot: slot_accounts_diff.parent_slot,
+            slot: slot_accounts_diff.slot,
+            blockhash: slot_accounts_diff.blockhash.to_string(),
+            accounts: slot_accounts_diff
+                .accounts
+                .iter()
+                .map(OwnedReplicaAccountDiffInfo::from)
+                .collect(),
+        }
+    }
+}
+
+/// A notification waiting in the queue of a plugin.  Only the latest
+/// versions of the replica infos, which are the ones produced by the
+/// notifiers, are queued.
+#[derive(Debug)]
+pub(crate) enum Notification {
+    UpdateAccount {
+        account: OwnedReplicaAccountInfo,
+        slot: Slot,
+        is_startup: bool,
+    },
+    EndOfStartup,
+    UpdateSlotStatus {
+        slot: Slot,
+        parent: Option<u64>,
+        status: SlotStatus,
+    },
+    Transaction {
+        transaction: OwnedReplicaTransactionInfo,
+        slot: Slot,
+    },
+    Entry(OwnedReplicaEntryInfo),
+    BlockMetadata(OwnedReplicaBlockInfo),
+    SlotAccountsDiff(OwnedReplicaSlotAccountsDiffInfo),
+}
+
+impl Notification {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::UpdateAccount { .. } => "update_account",
+            Self::EndOfStartup => "notify_end_of_startup",
+            Self::UpdateSlotStatus { .. } => "update_slot_status",
+            Self::Transaction { .. } => "notify_transaction",
+            Self::Entry(_) => "notify_entry",
+            Self::BlockMetadata(_) => "notify_block_metadata",
+            Self::SlotAccountsDiff(_) => "notify_slot_accounts_diff",
+        }
+    }
+
+    /// Delivers this notification to the specified plugin.
+    pub(crate) fn deliver(&self, plugin: &dyn GeyserPlugin) -> Result<()> {
+        match self {
+            Self::UpdateAccount {
+                account,
+                slot,
+                is_startup,
+            } => plugin.update_account(
+                ReplicaAccountInfoVersions::V0_0_3(&account.as_replica_account_info()),
+                *slot,
+                *is_startup,
+            ),
+            Self::EndOfStartup => plugin.notify_end_of_startup(),
+            Self::UpdateSlotStatus {
+                slot,
+                parent,
+                status,
+            } => plugin.update_slot_status(*slot, *parent, *status),
+            Self::Transaction { transaction, slot } => plugin.notify_transaction(
+                ReplicaTransactionInfoVersions::V0_0_2(&transaction.as_replica_transaction_info()),
+                *slot,
+            ),
+            Self::Entry(entry) => plugin.notify_entry(ReplicaEntryInfoVersions::V0_0_2(
+                &entry.as_replica_entry_info(),
+            )),
+            Self::BlockMetadata(block) => plugin.notify_block_metadata(
+                ReplicaBlockInfoVersions::V0_0_3(&block.as_replica_block_info()),
+            ),
+            Self::SlotAccountsDiff(slot_accounts_diff) => {
+                let accounts: Vec<_> = slot_accounts_diff
+                    .accounts
+                    .iter()
+                    .map(OwnedReplicaAccountDiffInfo::as_replica_account_diff_info)
+                    .collect();
+                plugin.notify_slot_accounts_diff(ReplicaSlotAccountsDiffInfoVersions::V0_0_1(
+                    &ReplicaSlotAccountsDiffInfo {
+                        parent_slot: slot_accounts_diff.parent_slot,
+                        slot: slot_accounts_diff.slot,
+                        blockhash: &slot_accounts_diff.blockhash,
+                        accounts: &accounts,
+                    },
+                ))
+            }
+        }
+    }
+}
+
+/// A notification which did not fit in a full queue with
+/// OverflowPolicy::Block.  It holds its own sender, so the queue's worker
+/// keeps running, and the plugin stays loaded, until it is sent.
+#[must_use]
+#[derive(Debug)]
+pub struct BlockedNotification {
+    sender: Sender<Notification>,
+    notification: Notification,
+    plugin_name: String,
+    stats: Arc<GeyserPluginStats>,
+}
+
+impl BlockedNotification {
+    /// Waits until the queue has room for the notification.  This must not
+    /// be called while holding the plugin manager lock, as the plugin could
+    /// otherwise not be unloaded while its queue is full.
+    pub fn send(self) {
+        if self.sender.send(self.notification).is_err() {
+            self.stats.record_dropped();
+            warn!(
+                "The notification worker of plugin {} has exited",
+                self.plugin_name
+            );
+        }
+    }
+}
+
+/// A bounded queue of notifications which are delivered to a plugin by a
+/// dedicated worker thread.
+#[derive(Debug)]
+pub(crate) struct NotificationQueue {
+    config: NotificationQueueConfig,
+    sender: Option<Sender<Notification>>,
+    worker: Option<JoinHandle<()>>,
+    disconnected: AtomicBool,
+}
+
+impl NotificationQueue {
+    pub(crate) fn new(
+        config: NotificationQueueConfig,
+        plugin: Arc<Box<dyn GeyserPlugin>>,
+        stats: Arc<GeyserPluginStats>,
+    ) -> Self {
+        let (sender, receiver) = bounded::<Notification>(config.capacity);
+        let worker = thread::Builder::new()
+            .name("solGeyserQueue".to_string())
+            .spawn(move || {
+                for notification in receiver.iter() {
+                    let mut measure = Measure::start("geyser-plugin-queued-notification");
+                    let result = notification.deliver(plugin.as_ref().as_ref());
+                    measure.stop();
+                    stats.record_latency(measure.as_us());
+                    if let Err(err) = result {
+                        error!(
+                            "Failed to deliver {} notification, error: {} to plugin {}",
+                            notification.name(),
+                            err,
+                            plugin.name()
+                        );
+                    }
+                }
+            })
+            .unwrap();
+
+        Self {
+            config,
+            sender: Some(sender),
+            worker: Some(worker),
+            disconnected: AtomicBool::default(),
+        }
+    }
+
+    pub(crate) fn config(&self) -> &NotificationQueueConfig {
+        &self.config
+    }
+
+    /// Returns the number of notifications waiting to be delivered.
+    pub(crate) fn len(&self) -> usize {
+        self.sender.as_ref().map(Sender::len).unwrap_or_default()
+    }
+
+    /// Returns true if the plugin stopped receiving notifications because its
+    /// queue overflowed with OverflowPolicy::Disconnect.
+    pub(crate) fn is_disconnected(&self) -> bool {
+        self.disconnected.load(Ordering::Relaxed)
+    }
+
+    /// Queues the specified notification, applying the overflow policy if
+    /// the queue is full.  Dropped notifications are recorded in `stats`.
+    /// With OverflowPolicy::Block, a notification which does not fit is
+    /// returned instead, so that the caller can wait for room once it has
+    /// released the plugin manager lock.
+    pub(crate) fn push(
+        &self,
+        notification: Notification,
+        plugin_name: &str,
+        stats: &Arc<GeyserPluginStats>,
+    ) -> Option<BlockedNotification> {
+        let sender = self.sender.as_ref()?;
+        if self.is_disconnected() {
+            stats.record_dropped();
+            return None;
+        }
+
+        match sender.try_send(notification) {
+            Ok(()) => {}
+            Err(TrySendError::Full(notification)) => {
+                if self.config.overflow_policy == OverflowPolicy::Block {
+                    return Some(BlockedNotification {
+                        sender: sender.clone(),
+                        notification,
+                        plugin_name: plugin_name.to_string(),
+                        stats: stats.clone(),
+                    });
+                }
+                stats.record_dropped();
+                if self.config.overflow_policy == OverflowPolicy::Disconnect
+                    && !self.disconnected.swap(true, Ordering::Relaxed)
+                {
+                    error!(
+                        "Disconnected plugin {plugin_name} as its notification queue of {} \
+                         is full",
+                        self.config.capacity
+                    );
+                }
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                stats.record_dropped();
+                warn!("The notification worker of plugin {plugin_name} has exited");
+            }
+        }
+        None
+    }
+
+    /// Stops accepting notifications, and waits for the worker thread to
+    /// deliver the queued ones.
+    pub(crate) fn shutdown(&mut self) {
+        drop(self.sender.take());
+        if let Some(worker) = self.worker.take() {
+            if worker.join().is_err() {
+                error!("The notification worker panicked");
+            }
+        }
+    }
+}
+
+impl Drop for NotificationQueue {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
@@ -0,0 +1,233 @@
+/// Module responsible for notifying plugins of the accounts modified in a slot
+use {
+    crate::{
+        geyser_plugin_manager::GeyserPluginManager, notification_queue::BlockedNotification,
+        slot_accounts_diff_notifier_interface::SlotAccountsDiffNotifier,
+    },
+    log::*,
+    solana_geyser_plugin_interface::geyser_plugin_interface::{
+        ReplicaAccountDiffInfo, ReplicaSlotAccountsDiffInfo, ReplicaSlotAccountsDiffInfoVersions,
+    },
+    solana_measure::measure::Measure,
+    solana_metrics::*,
+    solana_runtime::bank::Bank,
+    solana_sdk::{
+        account::ReadableAccount,
+        hash::{hash, Hash},
+        pubkey::Pubkey,
+    },
+    std::sync::{Arc, RwLock},
+};
+
+/// The state change of an account modified in a slot
+#[derive(Debug, PartialEq, Eq)]
+struct AccountDiff {
+    pubkey: Pubkey,
+    previous_lamports: u64,
+    previous_owner: Pubkey,
+    previous_data_hash: Hash,
+    lamports: u64,
+    owner: Pubkey,
+    data_hash: Hash,
+}
+
+pub(crate) struct SlotAccountsDiffNotifierImpl {
+    plugin_manager: Arc<RwLock<GeyserPluginManager>>,
+}
+
+impl SlotAccountsDiffNotifier for SlotAccountsDiffNotifierImpl {
+    fn notify_slot_accounts_diff(&self, bank: &Bank) {
+        let plugin_manager = self.plugin_manager.read().unwrap();
+        if plugin_manager.plugins.is_empty() {
+            return;
+        }
+
+        let mut measure = Measure::start("geyser-plugin-build-slot-accounts-diff");
+        let account_diffs = Self::build_account_diffs(bank);
+        let accounts: Vec<_> = account_diffs
+            .iter()
+            .map(Self::build_replica_account_diff_info)
+            .collect();
+        let blockhash = bank.last_blockhash().to_string();
+        let slot_accounts_diff = ReplicaSlotAccountsDiffInfo {
+            parent_slot: bank.parent_slot(),
+            slot: bank.slot(),
+            blockhash: &blockhash,
+            accounts: &accounts,
+        };
+        measure.stop();
+        inc_new_counter_debug!(
+            "geyser-plugin-build-slot-accounts-diff-us",
+            measure.as_us() as usize,
+            1000,
+            1000
+        );
+
+        let mut blocked_notifications = vec![];
+        for plugin in plugin_manager.plugins.iter() {
+            if !plugin.slot_accounts_diff_notifications_enabled() {
+                continue;
+            }
+            let mut measure = Measure::start("geyser-plugin-notify-slot-accounts-diff");
+            match plugin.notify_slot_accounts_diff(ReplicaSlotAccountsDiffInfoVersions::V0_0_1(
+                &slot_accounts_diff,
+            )) {
+                Err(err) => {
+                    error!(
+                        "Failed to notify accounts diff at slot {}, error: {} to plugin {}",
+                        bank.slot(),
+                        err,
+                        plugin.name()
+                    )
+                }
+                Ok(blocked_notification) => {
+                    blocked_notifications.extend(blocked_notification);
+                    trace!(
+                        "Successfully notified accounts diff at slot {} to plugin {}",
+                        bank.slot(),
+                        plugin.name()
+                    );
+                }
+            }
+            measure.stop();
+            inc_new_counter_debug!(
+                "geyser-plugin-notify-slot-accounts-diff-us",
+                measure.as_us() as usize,
+                1000,
+                1000
+            );
+        }
+        drop(plugin_manager);
+        blocked_notifications
+            .into_iter()
+            .for_each(BlockedNotification::send);
+    }
+}
+
+impl SlotAccountsDiffNotifierImpl {
+    /// Returns the accounts modified in the slot of the specified bank,
+    /// sorted by their pubkeys.  The previous state of each account is the
+    /// one in the parent bank, so that the diff follows the bank's fork.
+    fn build_account_diffs(bank: &Bank) -> Vec<AccountDiff> {
+        let parent = bank.parent();
+        let mut account_diffs: Vec<_> = bank
+            .get_all_accounts_modified_since_parent()
+            .into_iter()
+            .map(|(pubkey, account)| {
+                let (previous_lamports, previous_owner, previous_data_hash) = parent
+                    .as_ref()
+                    .and_then(|parent| parent.get_account_with_fixed_root(&pubkey))
+                    .map(|previous| {
+                        (
+                            previous.lamports(),
+                            *previous.owner(),
+                            hash(previous.data()),
+                        )
+                    })
+                    .unwrap_or_else(|| (0, Pubkey::default(), hash(&[])));
+                AccountDiff {
+                    pubkey,
+                    previous_lamports,
+                    previous_owner,
+                    previous_data_hash,
+                    lamports: account.lamports(),
+                    owner: *account.owner(),
+                    data_hash: hash(account.data()),
+                }
+            })
+            .collect();
+        account_diffs.sort_unstable_by(|a, b| a.pubkey.cmp(&b.pubkey));
+        account_diffs
+    }
+
+    fn build_replica_account_diff_info(account_diff: &AccountDiff) -> ReplicaAccountDiffInfo {
+        ReplicaAccountDiffInfo {
+            pubkey: account_diff.pubkey.as_ref(),
+            previous_lamports: account_diff.previous_lamports,
+            previous_owner: account_diff.previous_owner.as_ref(),
+            previous_data_hash: account_diff.previous_data_hash.as_ref(),
+            lamports: account_diff.lamports,
+            owner: account_diff.owner.as_ref(),
+            data_hash: account_diff.data_hash.as_ref(),
+        }
+    }
+
+    pub fn new(plugin_manager: Arc<RwLock<GeyserPluginManager>>) -> Self {
+        Self { plugin_manager }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            account::{AccountSharedData, WritableAccount},
+            genesis_config::create_genesis_config,
+            system_program,
+        },
+    };
+
+    #[test]
+    fn test_build_account_diffs() {
+        let (genesis_config, _mint_keypair) = create_genesis_config(1_000_000);
+        let parent = Arc::new(Bank::new_for_tests(&genesis_config));
+
+        let existing_pubkey = Pubkey::new_unique();
+        parent.store_account(
+            &existing_pubkey,
+            &AccountSharedData::new(100, 0, &system_program::id()),
+        );
+        parent.freeze();
+
+        let bank = Bank::new_from_parent(parent, &Pubkey::default(), 1);
+        let new_pubkey = Pubkey::new_unique();
+        let new_owner = Pubkey::new_unique();
+        let mut new_account = AccountSharedData::new(42, 3, &new_owner);
+        new_account.set_data_from_slice(&[1, 2, 3]);
+        bank.store_account(&new_pubkey, &new_account);
+        bank.store_account(
+            &existing_pubkey,
+            &AccountSharedData::new(200, 0, &system_program::id()),
+        );
+
+        let account_diffs = SlotAccountsDiffNotifierImpl::build_account_diffs(&bank);
+        assert!(account_diffs
+            .windows(2)
+            .all(|diffs| diffs[0].pubkey < diffs[1].pubkey));
+
+        let new_account_diff = account_diffs
+            .iter()
+            .find(|diff| diff.pubkey == new_pubkey)
+            .unwrap();
+        assert_eq!(
+            *new_account_diff,
+            AccountDiff {
+                pubkey: new_pubkey,
+                previous_lamports: 0,
+                previous_owner: Pubkey::default(),
+                previous_data_hash: hash(&[]),
+                lamports: 42,
+                owner: new_owner,
+                data_hash: hash(&[1, 2, 3]),
+            }
+        );
+
+        let existing_account_diff = account_diffs
+            .iter()
+            .find(|diff| diff.pubkey == existing_pubkey)
+            .unwrap();
+        assert_eq!(
+            *existing_account_diff,
+            AccountDiff {
+                pubkey: existing_pubkey,
+                previous_lamports: 100,
+                previous_owner: system_program::id(),
+                previous_data_hash: hash(&[]),
+                lamports: 200,
+                owner: system_program::id(),
+                data_hash: hash(&[]),
+            }
+        );
+    }
+}
@@ -0,0 +1,10 @@
+use {solana_runtime::bank::Bank, std::sync::Arc};
+
+/// Interface for notifying the accounts modified in a slot once its bank is
+/// frozen
+pub trait SlotAccountsDiffNotifier {
+    /// Notify the accounts modified in the slot of the specified frozen bank
+    fn notify_slot_accounts_diff(&self, bank: &Bank);
+}
+
+pub type SlotAccountsDiffNotifierArc = Arc<dyn SlotAccountsDiffNotifier + Sync + Send>;
@@ -1,5 +1,5 @@
 use {
-    crate::geyser_plugin_manager::GeyserPluginManager,
+    crate::{geyser_plugin_manager::GeyserPluginManager, notification_queue::BlockedNotification},
     log::*,
     solana_geyser_plugin_interface::geyser_plugin_interface::SlotStatus,
     solana_measure::measure::Measure,
@@ -50,6 +50,7 @@ impl SlotStatusNotifierImpl {
             return;
         }
 
+        let mut blocked_notifications = vec![];
         for plugin in plugin_manager.plugins.iter() {
             let mut measure = Measure::start("geyser-plugin-update-slot");
             match plugin.update_slot_status(slot, parent, slot_status) {
@@ -61,7 +62,8 @@ impl SlotStatusNotifierImpl {
                         plugin.name()
                     )
                 }
-                Ok(_) => {
+                Ok(blocked_notification) => {
+                    blocked_notifications.extend(blocked_notification);
                     trace!(
                         "Successfully updated slot status at slot {} to plugin {}",
                         slot,
@@ -77,5 +79,9 @@ impl SlotStatusNotifierImpl {
                 1000
             );
         }
+        drop(plugin_manager);
+        blocked_notifications
+            .into_iter()
+            .for_each(BlockedNotification::send);
     }
 }
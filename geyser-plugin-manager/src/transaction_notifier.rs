@@ -1,6 +1,6 @@
 /// Module responsible for notifying plugins of transactions
 use {
-    crate::geyser_plugin_manager::GeyserPluginManager,
+    crate::{geyser_plugin_manager::GeyserPluginManager, notification_queue::BlockedNotification},
     log::*,
     solana_geyser_plugin_interface::geyser_plugin_interface::{
         ReplicaTransactionInfoV2, ReplicaTransactionInfoVersions,
@@ -44,6 +44,7 @@ impl TransactionNotifier for TransactionNotifierImpl {
             return;
         }
 
+        let mut blocked_notifications = vec![];
         for plugin in plugin_manager.plugins.iter() {
             if !plugin.transaction_notifications_enabled() {
                 continue;
@@ -59,7 +60,8 @@ impl TransactionNotifier for TransactionNotifierImpl {
                         plugin.name()
                     )
                 }
-                Ok(_) => {
+                Ok(blocked_notification) => {
+                    blocked_notifications.extend(blocked_notification);
                     trace!(
                         "Successfully notified transaction to plugin {}",
                         plugin.name()
@@ -67,6 +69,10 @@ impl TransactionNotifier for TransactionNotifierImpl {
                 }
             }
         }
+        drop(plugin_manager);
+        blocked_notifications
+            .into_iter()
+            .for_each(BlockedNotification::send);
         measure.stop();
         inc_new_counter_debug!(
             "geyser-plugin-notify_plugins_of_transaction_info-us",
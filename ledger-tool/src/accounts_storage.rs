@@ -0,0 +1,349 @@
+//! Offline conversion of accounts storage files between the append vec format
+//! and the tiered storage hot format.
+//!
+//! Every conversion is validated by computing the accounts hash, via
+//! AccountsHasher, of both the source and the converted storage files.
+
+use {
+    log::*,
+    solana_accounts_db::{
+        account_storage::meta::{StorableAccountsWithHashesAndWriteVersions, StoredAccountMeta},
+        accounts_db::{AccountStorageEntry, AccountsDb},
+        accounts_file::AccountsFile,
+        accounts_hash::{AccountHash, AccountsHash, AccountsHasher},
+        append_vec::{aligned_stored_size, AppendVec},
+        tiered_storage::{
+            hot::{HotStorageReader, HOT_FORMAT},
+            index::IndexOffset,
+            writer::TieredStorageWriter,
+        },
+    },
+    solana_sdk::{account::ReadableAccount, clock::Slot, pubkey::Pubkey},
+    std::{
+        collections::HashMap,
+        fs,
+        path::{Path, PathBuf},
+        sync::Arc,
+    },
+};
+
+/// The result of converting a set of accounts storage files.
+#[derive(Debug)]
+pub struct ConversionSummary {
+    /// The number of converted accounts storage files
+    pub num_files: usize,
+    /// The number of converted accounts, including all versions of an account
+    pub num_accounts: usize,
+    /// The accounts hash of the source accounts storage files
+    pub source_accounts_hash: AccountsHash,
+    /// The accounts hash of the converted accounts storage files
+    pub converted_accounts_hash: AccountsHash,
+}
+
+impl ConversionSummary {
+    pub fn is_accounts_hash_matched(&self) -> bool {
+        self.source_accounts_hash == self.converted_accounts_hash
+    }
+}
+
+/// Accumulates the latest version of every account across accounts storage
+/// files in order to compute their accounts hash.
+#[derive(Debug, Default)]
+struct AccountsHashAccumulator {
+    /// The slot, lamports, and hash of the latest version of each account
+    latest_accounts: HashMap<Pubkey, (Slot, u64, AccountHash)>,
+}
+
+impl AccountsHashAccumulator {
+    /// Adds the specified account stored in the specified slot.  Within the
+    /// same slot, an account added later replaces the one added earlier.
+    fn add_account(&mut self, slot: Slot, account: &StoredAccountMeta) {
+        let pubkey = account.pubkey();
+        if let Some((latest_slot, ..)) = self.latest_accounts.get(pubkey) {
+            if *latest_slot > slot {
+                return;
+            }
+        }
+        self.latest_accounts.insert(
+            *pubkey,
+            (
+                slot,
+                account.lamports(),
+                AccountsDb::hash_account(account, pubkey),
+            ),
+        );
+    }
+
+    /// Returns the accounts hash of all the accounts added so far.  Like the
+    /// accounts hash calculated by AccountsDb, zero-lamport accounts are
+    /// excluded.
+    fn accounts_hash(self) -> AccountsHash {
+        let hashes = self
+            .latest_accounts
+            .into_iter()
+            .filter(|(_, (_, lamports, _))| *lamports != 0)
+            .map(|(pubkey, (_, _, hash))| (pubkey, hash))
+            .collect();
+        AccountsHash(AccountsHasher::accumulate_account_hashes(hashes))
+    }
+}
+
+/// Parses the slot and the id of an accounts storage file from its file name.
+fn parse_accounts_file_name(path: &Path) -> Option<(Slot, u32)> {
+    let (slot, id) = path.file_name()?.to_str()?.split_once('.')?;
+    Some((slot.parse().ok()?, id.parse().ok()?))
+}
+
+/// Returns all accounts stored in the specified hot storage file.
+fn hot_storage_accounts(reader: &HotStorageReader) -> Result<Vec<StoredAccountMeta>, String> {
+    (0..reader.num_accounts())
+        .map(|index| {
+            reader
+                .get_account(IndexOffset(index as u32))
+                .map_err(|err| format!("failed to read account {index}: {err}"))?
+                .ok_or_else(|| format!("account {index} is missing"))
+        })
+        .collect()
+}
+
+/// Writes the specified accounts into a new hot storage file.
+fn write_hot_storage(
+    slot: Slot,
+    accounts: &[StoredAccountMeta],
+    path: &Path,
+) -> Result<(), String> {
+    let account_refs: Vec<_> = accounts.iter().collect();
+    let accounts = (slot, &account_refs[..]);
+    let storable_accounts =
+        StorableAccountsWithHashesAndWriteVersions::<'_, '_, _, _, &AccountHash>::new(&accounts);
+    TieredStorageWriter::new(path, &HOT_FORMAT)
+        .and_then(|writer| writer.write_accounts(&storable_accounts, 0))
+        .map_err(|err| format!("failed to write {}: {err}", path.display()))?;
+    Ok(())
+}
+
+/// Writes the specified accounts into a new append vec which is kept on
+/// disk after it is dropped.
+fn write_append_vec(
+    slot: Slot,
+    accounts: &[StoredAccountMeta],
+    path: &Path,
+) -> Result<AppendVec, String> {
+    let file_size = accounts
+        .iter()
+        .map(|account| aligned_stored_size(account.data().len()))
+        .sum();
+    let mut append_vec = AppendVec::new(path, true, file_size);
+    append_vec.set_no_remove_on_drop();
+
+    let account_refs: Vec<_> = accounts.iter().collect();
+    let accounts = (slot, &account_refs[..]);
+    let storable_accounts =
+        StorableAccountsWithHashesAndWriteVersions::<'_, '_, _, _, &AccountHash>::new(&accounts);
+    append_vec
+        .append_accounts(&storable_accounts, 0)
+        .ok_or_else(|| format!("failed to append accounts to {}", path.display()))?;
+    append_vec
+        .flush()
+        .map_err(|err| format!("failed to flush {}: {err}", path.display()))?;
+    Ok(append_vec)
+}
+
+/// Converts the specified append vec storages into hot storage files inside
+/// `output_dir`, keeping their file names.
+pub fn convert_storages_to_hot(
+    storages: &[Arc<AccountStorageEntry>],
+    output_dir: &Path,
+) -> Result<ConversionSummary, String> {
+    fs::create_dir_all(output_dir)
+        .map_err(|err| format!("failed to create {}: {err}", output_dir.display()))?;
+
+    let mut source_accumulator = AccountsHashAccumulator::default();
+    let mut converted_accumulator = AccountsHashAccumulator::default();
+    let mut num_accounts = 0;
+    for storage in storages {
+        let slot = storage.slot();
+        let accounts: Vec<_> = storage.accounts.account_iter().collect();
+        accounts
+            .iter()
+            .for_each(|account| source_accumulator.add_account(slot, account));
+
+        let path = output_dir.join(AccountsFile::file_name(slot, storage.append_vec_id()));
+        write_hot_storage(slot, &accounts, &path)?;
+
+        let reader = HotStorageReader::new_from_path(&path)
+            .map_err(|err| format!("failed to open {}: {err}", path.display()))?;
+        let converted_accounts = hot_storage_accounts(&reader)?;
+        if converted_accounts.len() != accounts.len() {
+            return Err(format!(
+                "{} has {} accounts, expected {}",
+                path.display(),
+                converted_accounts.len(),
+                accounts.len(),
+            ));
+        }
+        converted_accounts
+            .iter()
+            .for_each(|account| converted_accumulator.add_account(slot, account));
+        num_accounts += accounts.len();
+        debug!(
+            "Converted {} accounts into {}",
+            accounts.len(),
+            path.display()
+        );
+    }
+
+    Ok(ConversionSummary {
+        num_files: storages.len(),
+        num_accounts,
+        source_accounts_hash: source_accumulator.accounts_hash(),
+        converted_accounts_hash: converted_accumulator.accounts_hash(),
+    })
+}
+
+/// Converts the hot storage files inside `input_dir` into append vecs inside
+/// `output_dir`, keeping their file names.  Files whose names are not in the
+/// `<slot>.<id>` form of accounts storage files are ignored.
+pub fn convert_hot_storages_to_append_vecs(
+    input_dir: &Path,
+    output_dir: &Path,
+) -> Result<ConversionSummary, String> {
+    let mut input_paths: Vec<(Slot, PathBuf)> = fs::read_dir(input_dir)
+        .map_err(|err| format!("failed to read {}: {err}", input_dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter_map(|path| parse_accounts_file_name(&path).map(|(slot, _id)| (slot, path)))
+        .collect();
+    input_paths.sort_unstable();
+    fs::create_dir_all(output_dir)
+        .map_err(|err| format!("failed to create {}: {err}", output_dir.display()))?;
+
+    let mut source_accumulator = AccountsHashAccumulator::default();
+    let mut converted_accumulator = AccountsHashAccumulator::default();
+    let mut num_accounts = 0;
+    let mut num_files = 0;
+    for (slot, input_path) in input_paths {
+        let reader = HotStorageReader::new_from_path(&input_path)
+            .map_err(|err| format!("failed to open {}: {err}", input_path.display()))?;
+        let accounts = hot_storage_accounts(&reader)?;
+        if accounts.is_empty() {
+            warn!("Skipping {} as it has no accounts", input_path.display());
+            continue;
+        }
+        accounts
+            .iter()
+            .for_each(|account| source_accumulator.add_account(slot, account));
+
+        let path = output_dir.join(input_path.file_name().unwrap());
+        let append_vec = write_append_vec(slot, &accounts, &path)?;
+        let (mut converted, num_converted_accounts) =
+            AppendVec::new_from_file(&path, append_vec.len())
+                .map_err(|err| format!("failed to open {}: {err}", path.display()))?;
+        converted.set_no_remove_on_drop();
+        if num_converted_accounts != accounts.len() {
+            return Err(format!(
+                "{} has {num_converted_accounts} accounts, expected {}",
+                path.display(),
+                accounts.len(),
+            ));
+        }
+        converted
+            .account_iter()
+            .for_each(|account| converted_accumulator.add_account(slot, &account));
+        num_accounts += accounts.len();
+        num_files += 1;
+        debug!(
+            "Converted {} accounts into {}",
+            accounts.len(),
+            path.display()
+        );
+    }
+
+    Ok(ConversionSummary {
+        num_files,
+        num_accounts,
+        source_accounts_hash: source_accumulator.accounts_hash(),
+        converted_accounts_hash: converted_accumulator.accounts_hash(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_sdk::account::AccountSharedData, tempfile::TempDir};
+
+    fn new_storage(
+        dir: &Path,
+        slot: Slot,
+        id: u32,
+        accounts: &[(Pubkey, AccountSharedData)],
+    ) -> Arc<AccountStorageEntry> {
+        let storage = AccountStorageEntry::new(dir, slot, id, 1024 * 1024);
+        let account_refs: Vec<_> = accounts
+            .iter()
+            .map(|(pubkey, account)| (pubkey, account))
+            .collect();
+        let accounts_to_store = (slot, &account_refs[..]);
+        let hashes = accounts
+            .iter()
+            .map(|(pubkey, account)| AccountsDb::hash_account(account, pubkey))
+            .collect();
+        let storable_accounts =
+            StorableAccountsWithHashesAndWriteVersions::new_with_hashes_and_write_versions(
+                &accounts_to_store,
+                hashes,
+                vec![0; accounts.len()],
+            );
+        storage
+            .accounts
+            .append_accounts(&storable_accounts, 0)
+            .unwrap();
+        Arc::new(storage)
+    }
+
+    #[test]
+    fn test_convert_round_trip() {
+        let append_vec_dir = TempDir::new().unwrap();
+        let hot_storage_dir = TempDir::new().unwrap();
+        let round_trip_dir = TempDir::new().unwrap();
+
+        let owner = Pubkey::new_unique();
+        let pubkeys: Vec<_> = std::iter::repeat_with(Pubkey::new_unique).take(4).collect();
+        let first_slot_accounts: Vec<_> = pubkeys
+            .iter()
+            .enumerate()
+            .map(|(i, pubkey)| {
+                (
+                    *pubkey,
+                    AccountSharedData::new(i as u64 + 1, i * 10, &owner),
+                )
+            })
+            .collect();
+        // the second slot updates one account of the first slot and closes another
+        let second_slot_accounts = vec![
+            (
+                pubkeys[0],
+                AccountSharedData::new(42, 3, &Pubkey::new_unique()),
+            ),
+            (pubkeys[1], AccountSharedData::default()),
+        ];
+        let storages = [
+            new_storage(append_vec_dir.path(), 1, 0, &first_slot_accounts),
+            new_storage(append_vec_dir.path(), 2, 1, &second_slot_accounts),
+        ];
+
+        let to_hot = convert_storages_to_hot(&storages, hot_storage_dir.path()).unwrap();
+        assert_eq!(to_hot.num_files, 2);
+        assert_eq!(to_hot.num_accounts, 6);
+        assert!(to_hot.is_accounts_hash_matched());
+
+        let to_append_vec =
+            convert_hot_storages_to_append_vecs(hot_storage_dir.path(), round_trip_dir.path())
+                .unwrap();
+        assert_eq!(to_append_vec.num_files, 2);
+        assert_eq!(to_append_vec.num_accounts, 6);
+        assert!(to_append_vec.is_accounts_hash_matched());
+        assert_eq!(
+            to_append_vec.converted_accounts_hash,
+            to_hot.source_accounts_hash
+        );
+    }
+}
@@ -24,12 +24,12 @@ use {
     solana_storage_bigtable::CredentialType,
     solana_transaction_status::{
         BlockEncodingOptions, ConfirmedBlock, EncodeError, TransactionDetails,
-        UiTransactionEncoding, VersionedConfirmedBlock,
+        TransactionTokenBalance, UiTransactionEncoding, VersionedConfirmedBlock,
     },
     std::{
         cmp::min,
         collections::HashSet,
-        path::Path,
+        path::{Path, PathBuf},
         process::exit,
         result::Result,
         str::FromStr,
@@ -361,6 +361,71 @@ pub async fn transaction_history(
     Ok(())
 }
 
+pub async fn account_history(
+    address: &Pubkey,
+    mut limit: usize,
+    mut before: Option<Signature>,
+    until: Option<Signature>,
+    slot: Option<Slot>,
+    query_chunk_size: usize,
+    config: solana_storage_bigtable::LedgerStorageConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let bigtable = solana_storage_bigtable::LedgerStorage::new_with_config(config).await?;
+
+    while limit > 0 {
+        let results = bigtable
+            .get_account_history(
+                address,
+                before.as_ref(),
+                until.as_ref(),
+                slot,
+                limit.min(query_chunk_size),
+            )
+            .await?;
+
+        if results.is_empty() {
+            break;
+        }
+        before = Some(results.last().unwrap().signature);
+        limit = limit.saturating_sub(results.len());
+
+        for change in results {
+            println!(
+                "{}, slot={}, balance={} -> {}, status={}",
+                change.signature,
+                change.slot,
+                change.pre_balance,
+                change.post_balance,
+                match change.err {
+                    None => "Confirmed".to_string(),
+                    Some(err) => format!("Failed: {err:?}"),
+                }
+            );
+            if change.pre_token_balance.is_some() || change.post_token_balance.is_some() {
+                let ui_amount = |balance: &Option<TransactionTokenBalance>| {
+                    balance
+                        .as_ref()
+                        .map(|balance| balance.ui_token_amount.ui_amount_string.clone())
+                        .unwrap_or_else(|| "-".to_string())
+                };
+                let mint = change
+                    .post_token_balance
+                    .as_ref()
+                    .or(change.pre_token_balance.as_ref())
+                    .map(|balance| balance.mint.clone())
+                    .unwrap_or_default();
+                println!(
+                    "  token balance={} -> {}, mint={}",
+                    ui_amount(&change.pre_token_balance),
+                    ui_amount(&change.post_token_balance),
+                    mint,
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
 struct CopyArgs {
     from_slot: Slot,
     to_slot: Option<Slot>,
@@ -621,6 +686,7 @@ async fn get_bigtable(
                 credential_type: CredentialType::Filepath(Some(args.crediential_path.unwrap())),
                 instance_name: args.instance_name,
                 app_profile_id: args.app_profile_id,
+                local_storage_path: None,
             },
         )
         .await
@@ -656,6 +722,14 @@ impl BigTableSubCommand for App<'_, '_> {
                         .default_value(solana_storage_bigtable::DEFAULT_APP_PROFILE_ID)
                         .help("Bigtable application profile id to use in requests"),
                 )
+                .arg(
+                    Arg::with_name("rpc_bigtable_local_storage")
+                        .global(true)
+                        .long("rpc-bigtable-local-storage")
+                        .takes_value(true)
+                        .value_name("DIR")
+                        .help("Use this local RocksDB directory instead of a Bigtable instance"),
+                )
                 .subcommand(
                     SubCommand::with_name("upload")
                         .about("Upload the ledger to BigTable")
@@ -894,6 +968,68 @@ impl BigTableSubCommand for App<'_, '_> {
                                 .help("Display the full transactions"),
                         ),
                 )
+                .subcommand(
+                    SubCommand::with_name("account-history")
+                        .about(
+                            "Show the balance changes of the given address from newest to oldest",
+                        )
+                        .arg(
+                            Arg::with_name("address")
+                                .index(1)
+                                .value_name("ADDRESS")
+                                .required(true)
+                                .validator(is_valid_pubkey)
+                                .help("Account address"),
+                        )
+                        .arg(
+                            Arg::with_name("limit")
+                                .long("limit")
+                                .takes_value(true)
+                                .value_name("LIMIT")
+                                .validator(is_slot)
+                                .index(2)
+                                .default_value("18446744073709551615")
+                                .help("Maximum number of balance changes to return"),
+                        )
+                        .arg(
+                            Arg::with_name("query_chunk_size")
+                                .long("query-chunk-size")
+                                .takes_value(true)
+                                .value_name("AMOUNT")
+                                .validator(is_slot)
+                                .default_value("1000")
+                                .help(
+                                    "Number of transactions to query at once. \
+                                     Smaller: more responsive/lower throughput. \
+                                     Larger: less responsive/higher throughput",
+                                ),
+                        )
+                        .arg(
+                            Arg::with_name("before")
+                                .long("before")
+                                .value_name("TRANSACTION_SIGNATURE")
+                                .takes_value(true)
+                                .help("Start with the first signature older than this one"),
+                        )
+                        .arg(
+                            Arg::with_name("until")
+                                .long("until")
+                                .value_name("TRANSACTION_SIGNATURE")
+                                .takes_value(true)
+                                .help("End with the last signature newer than this one"),
+                        )
+                        .arg(
+                            Arg::with_name("slot")
+                                .long("slot")
+                                .value_name("SLOT")
+                                .takes_value(true)
+                                .validator(is_slot)
+                                .help(
+                                    "Start with the last balance change at or before this slot, \
+                                     whose new balance is the balance at this slot",
+                                ),
+                        ),
+                )
                 .subcommand(
                     SubCommand::with_name("copy")
                         .about("Copy blocks from a Bigtable to another Bigtable")
@@ -1054,6 +1190,10 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
         "rpc_bigtable_app_profile_id",
         solana_storage_bigtable::DEFAULT_APP_PROFILE_ID,
     );
+    let local_storage_path = matches
+        .value_of("rpc_bigtable_local_storage")
+        .or_else(|| sub_matches?.value_of("rpc_bigtable_local_storage"))
+        .map(PathBuf::from);
 
     let future = match (subcommand, sub_matches) {
         ("upload", Some(arg_matches)) => {
@@ -1071,6 +1211,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: false,
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(upload(
@@ -1087,6 +1228,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: !arg_matches.is_present("force"),
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(delete_slots(slots, config))
@@ -1096,6 +1238,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(first_available_block(config))
@@ -1106,6 +1249,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: false,
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(block(slot, output_format, config))
@@ -1116,6 +1260,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(entries(slot, output_format, config))
@@ -1127,6 +1272,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: false,
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
@@ -1139,6 +1285,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: false,
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
@@ -1172,6 +1319,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: false,
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
@@ -1192,6 +1340,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
@@ -1206,6 +1355,35 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 config,
             ))
         }
+        ("account-history", Some(arg_matches)) => {
+            let address = pubkey_of(arg_matches, "address").unwrap();
+            let limit = value_t_or_exit!(arg_matches, "limit", usize);
+            let query_chunk_size = value_t_or_exit!(arg_matches, "query_chunk_size", usize);
+            let before = arg_matches
+                .value_of("before")
+                .map(|signature| signature.parse().expect("Invalid signature"));
+            let until = arg_matches
+                .value_of("until")
+                .map(|signature| signature.parse().expect("Invalid signature"));
+            let slot = value_t!(arg_matches, "slot", Slot).ok();
+            let config = solana_storage_bigtable::LedgerStorageConfig {
+                read_only: true,
+                instance_name,
+                app_profile_id,
+                local_storage_path,
+                ..solana_storage_bigtable::LedgerStorageConfig::default()
+            };
+
+            runtime.block_on(account_history(
+                &address,
+                limit,
+                before,
+                until,
+                slot,
+                query_chunk_size,
+                config,
+            ))
+        }
         ("copy", Some(arg_matches)) => runtime.block_on(copy(CopyArgs::process(arg_matches))),
         _ => unreachable!(),
     };
@@ -0,0 +1,882 @@
+//! Export of the rooted blocks of a blockstore to columnar files, in the
+//! Parquet or the Arrow IPC file format.
+//!
+//! Each table is written to `<OUTPUT_DIR>/<TABLE>/slot_partition=<SLOT>/data.<EXT>`,
+//! where `SLOT` is the first slot of the partition, so that the output can be
+//! read as a partitioned dataset.  The tables are:
+//! * `blocks`: one row per block
+//! * `transactions`: one row per transaction
+//! * `instructions`: one row per instruction, including the inner
+//!   instructions, with the instructions of the parsable programs also parsed
+//!   to JSON
+//! * `token_balance_changes`: one row per token account whose balance is
+//!   changed by a transaction
+//! * `rewards`: one row per block reward
+
+use {
+    arrow::{
+        array::{
+            ArrayRef, BinaryArray, Int64Array, ListBuilder, StringArray, StringBuilder,
+            UInt32Array, UInt64Array, UInt8Array,
+        },
+        datatypes::SchemaRef,
+        error::ArrowError,
+        ipc::writer::FileWriter,
+        record_batch::RecordBatch,
+    },
+    log::*,
+    parquet::{arrow::ArrowWriter, basic::Compression, file::properties::WriterProperties},
+    solana_ledger::blockstore::Blockstore,
+    solana_sdk::{
+        clock::Slot, instruction::CompiledInstruction, message::AccountKeys,
+        transaction::TransactionVersion,
+    },
+    solana_transaction_status::{
+        parse_instruction, TransactionTokenBalance, VersionedConfirmedBlock,
+        VersionedTransactionWithStatusMeta,
+    },
+    std::{
+        error::Error,
+        fs::{self, File},
+        mem,
+        path::{Path, PathBuf},
+        str::FromStr,
+        sync::Arc,
+    },
+};
+
+/// The maximum number of rows of a table buffered before they are written
+const MAX_BATCH_ROWS: usize = 65_536;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Parquet,
+    Arrow,
+}
+
+impl ExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Parquet => "parquet",
+            Self::Arrow => "arrow",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "parquet" => Ok(Self::Parquet),
+            "arrow" => Ok(Self::Arrow),
+            _ => Err(format!("Unsupported export format: {s}")),
+        }
+    }
+}
+
+/// The result of exporting a range of blocks.
+#[derive(Debug, Default)]
+pub struct ExportSummary {
+    /// The number of exported blocks
+    pub num_blocks: usize,
+    /// The number of exported transactions
+    pub num_transactions: usize,
+    /// The number of written files, across all the tables
+    pub num_files: usize,
+}
+
+enum TableFileWriter {
+    Parquet(ArrowWriter<File>),
+    Arrow(FileWriter<File>),
+}
+
+impl TableFileWriter {
+    fn create(
+        path: &Path,
+        format: ExportFormat,
+        schema: SchemaRef,
+    ) -> Result<Self, Box<dyn Error>> {
+        let file = File::create(path)?;
+        Ok(match format {
+            ExportFormat::Parquet => {
+                let properties = WriterProperties::builder()
+                    .set_compression(Compression::SNAPPY)
+                    .build();
+                Self::Parquet(ArrowWriter::try_new(file, schema, Some(properties))?)
+            }
+            ExportFormat::Arrow => Self::Arrow(FileWriter::try_new(file, &schema)?),
+        })
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<(), Box<dyn Error>> {
+        match self {
+            Self::Parquet(writer) => writer.write(batch)?,
+            Self::Arrow(writer) => writer.write(batch)?,
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<(), Box<dyn Error>> {
+        match self {
+            Self::Parquet(writer) => {
+                writer.close()?;
+            }
+            Self::Arrow(mut writer) => writer.finish()?,
+        }
+        Ok(())
+    }
+}
+
+/// The rows of a table buffered in memory, one vector per column
+trait Table: Default {
+    const NAME: &'static str;
+
+    fn num_rows(&self) -> usize;
+
+    /// Moves the buffered rows into a record batch
+    fn take_record_batch(&mut self) -> Result<RecordBatch, ArrowError>;
+}
+
+fn record_batch<const N: usize>(
+    columns: [(&str, ArrayRef, bool); N],
+) -> Result<RecordBatch, ArrowError> {
+    RecordBatch::try_from_iter_with_nullable(columns)
+}
+
+fn string_list_array(lists: Vec<Option<Vec<String>>>) -> ArrayRef {
+    let mut builder = ListBuilder::new(StringBuilder::new());
+    for list in lists {
+        let is_valid = list.is_some();
+        for value in list.unwrap_or_default() {
+            builder.values().append_value(value);
+        }
+        builder.append(is_valid);
+    }
+    Arc::new(builder.finish())
+}
+
+#[derive(Default)]
+struct Blocks {
+    slot: Vec<u64>,
+    parent_slot: Vec<u64>,
+    blockhash: Vec<String>,
+    previous_blockhash: Vec<String>,
+    block_time: Vec<Option<i64>>,
+    block_height: Vec<Option<u64>>,
+    transaction_count: Vec<u64>,
+}
+
+impl Table for Blocks {
+    const NAME: &'static str = "blocks";
+
+    fn num_rows(&self) -> usize {
+        self.slot.len()
+    }
+
+    fn take_record_batch(&mut self) -> Result<RecordBatch, ArrowError> {
+        let Self {
+            slot,
+            parent_slot,
+            blockhash,
+            previous_blockhash,
+            block_time,
+            block_height,
+            transaction_count,
+        } = mem::take(self);
+        record_batch([
+            ("slot", Arc::new(UInt64Array::from(slot)), false),
+            (
+                "parent_slot",
+                Arc::new(UInt64Array::from(parent_slot)),
+                false,
+            ),
+            ("blockhash", Arc::new(StringArray::from(blockhash)), false),
+            (
+                "previous_blockhash",
+                Arc::new(StringArray::from(previous_blockhash)),
+                false,
+            ),
+            ("block_time", Arc::new(Int64Array::from(block_time)), true),
+            (
+                "block_height",
+                Arc::new(UInt64Array::from(block_height)),
+                true,
+            ),
+            (
+                "transaction_count",
+                Arc::new(UInt64Array::from(transaction_count)),
+                false,
+            ),
+        ])
+    }
+}
+
+#[derive(Default)]
+struct Transactions {
+    slot: Vec<u64>,
+    transaction_index: Vec<u32>,
+    signature: Vec<String>,
+    version: Vec<String>,
+    recent_blockhash: Vec<String>,
+    fee: Vec<u64>,
+    err: Vec<Option<String>>,
+    compute_units_consumed: Vec<Option<u64>>,
+    account_keys: Vec<Option<Vec<String>>>,
+    log_messages: Vec<Option<Vec<String>>>,
+}
+
+impl Table for Transactions {
+    const NAME: &'static str = "transactions";
+
+    fn num_rows(&self) -> usize {
+        self.slot.len()
+    }
+
+    fn take_record_batch(&mut self) -> Result<RecordBatch, ArrowError> {
+        let Self {
+            slot,
+            transaction_index,
+            signature,
+            version,
+            recent_blockhash,
+            fee,
+            err,
+            compute_units_consumed,
+            account_keys,
+            log_messages,
+        } = mem::take(self);
+        record_batch([
+            ("slot", Arc::new(UInt64Array::from(slot)), false),
+            (
+                "transaction_index",
+                Arc::new(UInt32Array::from(transaction_index)),
+                false,
+            ),
+            ("signature", Arc::new(StringArray::from(signature)), false),
+            ("version", Arc::new(StringArray::from(version)), false),
+            (
+                "recent_blockhash",
+                Arc::new(StringArray::from(recent_blockhash)),
+                false,
+            ),
+            ("fee", Arc::new(UInt64Array::from(fee)), false),
+            ("err", Arc::new(StringArray::from(err)), true),
+            (
+                "compute_units_consumed",
+                Arc::new(UInt64Array::from(compute_units_consumed)),
+                true,
+            ),
+            ("account_keys", string_list_array(account_keys), false),
+            ("log_messages", string_list_array(log_messages), true),
+        ])
+    }
+}
+
+#[derive(Default)]
+struct Instructions {
+    slot: Vec<u64>,
+    transaction_index: Vec<u32>,
+    signature: Vec<String>,
+    instruction_index: Vec<u32>,
+    inner_instruction_index: Vec<Option<u32>>,
+    stack_height: Vec<Option<u32>>,
+    program_id: Vec<String>,
+    accounts: Vec<Option<Vec<String>>>,
+    data: Vec<Vec<u8>>,
+    program: Vec<Option<String>>,
+    parsed: Vec<Option<String>>,
+}
+
+impl Instructions {
+    #[allow(clippy::too_many_arguments)]
+    fn push(
+        &mut self,
+        slot: Slot,
+        transaction_index: u32,
+        signature: &str,
+        instruction_index: u32,
+        inner_instruction_index: Option<u32>,
+        stack_height: Option<u32>,
+        instruction: &CompiledInstruction,
+        account_keys: &AccountKeys,
+    ) {
+        let Some(program_id) = account_keys.get(instruction.program_id_index as usize) else {
+            warn!("Instruction {instruction_index} of {signature} has an invalid program id index");
+            return;
+        };
+        let parsed_instruction =
+            parse_instruction::parse(program_id, instruction, account_keys, stack_height).ok();
+
+        self.slot.push(slot);
+        self.transaction_index.push(transaction_index);
+        self.signature.push(signature.to_string());
+        self.instruction_index.push(instruction_index);
+        self.inner_instruction_index.push(inner_instruction_index);
+        self.stack_height.push(stack_height);
+        self.program_id.push(program_id.to_string());
+        self.accounts.push(Some(
+            instruction
+                .accounts
+                .iter()
+                .filter_map(|index| account_keys.get(*index as usize))
+                .map(ToString::to_string)
+                .collect(),
+        ));
+        self.data.push(instruction.data.clone());
+        self.program.push(
+            parsed_instruction
+                .as_ref()
+                .map(|parsed_instruction| parsed_instruction.program.clone()),
+        );
+        self.parsed.push(
+            parsed_instruction.map(|parsed_instruction| parsed_instruction.parsed.to_string()),
+        );
+    }
+}
+
+impl Table for Instructions {
+    const NAME: &'static str = "instructions";
+
+    fn num_rows(&self) -> usize {
+        self.slot.len()
+    }
+
+    fn take_record_batch(&mut self) -> Result<RecordBatch, ArrowError> {
+        let Self {
+            slot,
+            transaction_index,
+            signature,
+            instruction_index,
+            inner_instruction_index,
+            stack_height,
+            program_id,
+            accounts,
+            data,
+            program,
+            parsed,
+        } = mem::take(self);
+        record_batch([
+            ("slot", Arc::new(UInt64Array::from(slot)), false),
+            (
+                "transaction_index",
+                Arc::new(UInt32Array::from(transaction_index)),
+                false,
+            ),
+            ("signature", Arc::new(StringArray::from(signature)), false),
+            (
+                "instruction_index",
+                Arc::new(UInt32Array::from(instruction_index)),
+                false,
+            ),
+            (
+                "inner_instruction_index",
+                Arc::new(UInt32Array::from(inner_instruction_index)),
+                true,
+            ),
+            (
+                "stack_height",
+                Arc::new(UInt32Array::from(stack_height)),
+                true,
+            ),
+            ("program_id", Arc::new(StringArray::from(program_id)), false),
+            ("accounts", string_list_array(accounts), false),
+            ("data", Arc::new(BinaryArray::from_iter_values(data)), false),
+            ("program", Arc::new(StringArray::from(program)), true),
+            ("parsed", Arc::new(StringArray::from(parsed)), true),
+        ])
+    }
+}
+
+#[derive(Default)]
+struct TokenBalanceChanges {
+    slot: Vec<u64>,
+    transaction_index: Vec<u32>,
+    signature: Vec<String>,
+    account: Vec<String>,
+    mint: Vec<String>,
+    owner: Vec<Option<String>>,
+    program_id: Vec<Option<String>>,
+    decimals: Vec<u8>,
+    pre_amount: Vec<Option<u64>>,
+    post_amount: Vec<Option<u64>>,
+}
+
+fn find_token_balance(
+    balances: &[TransactionTokenBalance],
+    account_index: u8,
+) -> Option<&TransactionTokenBalance> {
+    balances
+        .iter()
+        .find(|balance| balance.account_index == account_index)
+}
+
+impl TokenBalanceChanges {
+    fn push(
+        &mut self,
+        slot: Slot,
+        transaction_index: u32,
+        signature: &str,
+        tx_with_meta: &VersionedTransactionWithStatusMeta,
+    ) {
+        let meta = &tx_with_meta.meta;
+        let account_keys = tx_with_meta.account_keys();
+        let pre_token_balances = meta.pre_token_balances.as_deref().unwrap_or_default();
+        let post_token_balances = meta.post_token_balances.as_deref().unwrap_or_default();
+        let amount = |balance: Option<&TransactionTokenBalance>| {
+            balance.and_then(|balance| balance.ui_token_amount.amount.parse::<u64>().ok())
+        };
+
+        let mut account_indexes = pre_token_balances
+            .iter()
+            .chain(post_token_balances)
+            .map(|balance| balance.account_index)
+            .collect::<Vec<_>>();
+        account_indexes.sort_unstable();
+        account_indexes.dedup();
+        for account_index in account_indexes {
+            let pre_token_balance = find_token_balance(pre_token_balances, account_index);
+            let post_token_balance = find_token_balance(post_token_balances, account_index);
+            let pre_amount = amount(pre_token_balance);
+            let post_amount = amount(post_token_balance);
+            if pre_amount == post_amount {
+                continue;
+            }
+            let Some(balance) = post_token_balance.or(pre_token_balance) else {
+                continue;
+            };
+            let Some(account) = account_keys.get(account_index as usize) else {
+                continue;
+            };
+            let non_empty = |value: &String| (!value.is_empty()).then(|| value.clone());
+
+            self.slot.push(slot);
+            self.transaction_index.push(transaction_index);
+            self.signature.push(signature.to_string());
+            self.account.push(account.to_string());
+            self.mint.push(balance.mint.clone());
+            self.owner.push(non_empty(&balance.owner));
+            self.program_id.push(non_empty(&balance.program_id));
+            self.decimals.push(balance.ui_token_amount.decimals);
+            self.pre_amount.push(pre_amount);
+            self.post_amount.push(post_amount);
+        }
+    }
+}
+
+impl Table for TokenBalanceChanges {
+    const NAME: &'static str = "token_balance_changes";
+
+    fn num_rows(&self) -> usize {
+        self.slot.len()
+    }
+
+    fn take_record_batch(&mut self) -> Result<RecordBatch, ArrowError> {
+        let Self {
+            slot,
+            transaction_index,
+            signature,
+            account,
+            mint,
+            owner,
+            program_id,
+            decimals,
+            pre_amount,
+            post_amount,
+        } = mem::take(self);
+        record_batch([
+            ("slot", Arc::new(UInt64Array::from(slot)), false),
+            (
+                "transaction_index",
+                Arc::new(UInt32Array::from(transaction_index)),
+                false,
+            ),
+            ("signature", Arc::new(StringArray::from(signature)), false),
+            ("account", Arc::new(StringArray::from(account)), false),
+            ("mint", Arc::new(StringArray::from(mint)), false),
+            ("owner", Arc::new(StringArray::from(owner)), true),
+            ("program_id", Arc::new(StringArray::from(program_id)), true),
+            ("decimals", Arc::new(UInt8Array::from(decimals)), false),
+            ("pre_amount", Arc::new(UInt64Array::from(pre_amount)), true),
+            (
+                "post_amount",
+                Arc::new(UInt64Array::from(post_amount)),
+                true,
+            ),
+        ])
+    }
+}
+
+#[derive(Default)]
+struct Rewards {
+    slot: Vec<u64>,
+    pubkey: Vec<String>,
+    lamports: Vec<i64>,
+    post_balance: Vec<u64>,
+    reward_type: Vec<Option<String>>,
+    commission: Vec<Option<u8>>,
+}
+
+impl Table for Rewards {
+    const NAME: &'static str = "rewards";
+
+    fn num_rows(&self) -> usize {
+        self.slot.len()
+    }
+
+    fn take_record_batch(&mut self) -> Result<RecordBatch, ArrowError> {
+        let Self {
+            slot,
+            pubkey,
+            lamports,
+            post_balance,
+            reward_type,
+            commission,
+        } = mem::take(self);
+        record_batch([
+            ("slot", Arc::new(UInt64Array::from(slot)), false),
+            ("pubkey", Arc::new(StringArray::from(pubkey)), false),
+            ("lamports", Arc::new(Int64Array::from(lamports)), false),
+            (
+                "post_balance",
+                Arc::new(UInt64Array::from(post_balance)),
+                false,
+            ),
+            (
+                "reward_type",
+                Arc::new(StringArray::from(reward_type)),
+                true,
+            ),
+            ("commission", Arc::new(UInt8Array::from(commission)), true),
+        ])
+    }
+}
+
+/// The buffered rows of a table, and the file of the current partition they
+/// are written to
+#[derive(Default)]
+struct TableWriter<T> {
+    rows: T,
+    file: Option<TableFileWriter>,
+}
+
+impl<T: Table> TableWriter<T> {
+    /// Writes the buffered rows once there are enough of them, or always if
+    /// `finish` is set, in which case the file of the partition is also
+    /// finished.  Returns the number of finished files.
+    fn write(
+        &mut self,
+        output_dir: &Path,
+        partition: Slot,
+        format: ExportFormat,
+        finish: bool,
+    ) -> Result<usize, Box<dyn Error>> {
+        let num_rows = self.rows.num_rows();
+        if num_rows >= MAX_BATCH_ROWS || (finish && num_rows > 0) {
+            let batch = self.rows.take_record_batch()?;
+            if self.file.is_none() {
+                let partition_dir = output_dir
+                    .join(T::NAME)
+                    .join(format!("slot_partition={partition}"));
+                fs::create_dir_all(&partition_dir)?;
+                let path = partition_dir.join(format!("data.{}", format.extension()));
+                self.file = Some(TableFileWriter::create(&path, format, batch.schema())?);
+            }
+            self.file.as_mut().unwrap().write(&batch)?;
+        }
+
+        match self.file.take() {
+            Some(file) if finish => {
+                file.finish()?;
+                Ok(1)
+            }
+            file => {
+                self.file = file;
+                Ok(0)
+            }
+        }
+    }
+}
+
+/// Writes blocks, in ascending slot order, to the files of their partitions.
+pub struct BlockExporter {
+    output_dir: PathBuf,
+    format: ExportFormat,
+    partition_slots: u64,
+    /// The first slot of the current partition
+    partition: Option<Slot>,
+    blocks: TableWriter<Blocks>,
+    transactions: TableWriter<Transactions>,
+    instructions: TableWriter<Instructions>,
+    token_balance_changes: TableWriter<TokenBalanceChanges>,
+    rewards: TableWriter<Rewards>,
+    summary: ExportSummary,
+}
+
+impl BlockExporter {
+    pub fn new(output_dir: &Path, format: ExportFormat, partition_slots: u64) -> Self {
+        assert!(partition_slots > 0);
+        Self {
+            output_dir: output_dir.to_path_buf(),
+            format,
+            partition_slots,
+            partition: None,
+            blocks: TableWriter::default(),
+            transactions: TableWriter::default(),
+            instructions: TableWriter::default(),
+            token_balance_changes: TableWriter::default(),
+            rewards: TableWriter::default(),
+            summary: ExportSummary::default(),
+        }
+    }
+
+    pub fn add_block(
+        &mut self,
+        slot: Slot,
+        block: VersionedConfirmedBlock,
+    ) -> Result<(), Box<dyn Error>> {
+        let partition = slot - slot % self.partition_slots;
+        if self.partition != Some(partition) {
+            self.write(true)?;
+            self.partition = Some(partition);
+        }
+
+        let VersionedConfirmedBlock {
+            previous_blockhash,
+            blockhash,
+            parent_slot,
+            transactions,
+            rewards,
+            block_time,
+            block_height,
+        } = block;
+
+        let rows = &mut self.blocks.rows;
+        rows.slot.push(slot);
+        rows.parent_slot.push(parent_slot);
+        rows.blockhash.push(blockhash);
+        rows.previous_blockhash.push(previous_blockhash);
+        rows.block_time.push(block_time);
+        rows.block_height.push(block_height);
+        rows.transaction_count.push(transactions.len() as u64);
+
+        for (transaction_index, tx_with_meta) in transactions.iter().enumerate() {
+            self.add_transaction(slot, transaction_index as u32, tx_with_meta);
+        }
+
+        let rows = &mut self.rewards.rows;
+        for reward in rewards {
+            rows.slot.push(slot);
+            rows.pubkey.push(reward.pubkey);
+            rows.lamports.push(reward.lamports);
+            rows.post_balance.push(reward.post_balance);
+            rows.reward_type.push(
+                reward
+                    .reward_type
+                    .map(|reward_type| reward_type.to_string()),
+            );
+            rows.commission.push(reward.commission);
+        }
+
+        self.summary.num_blocks += 1;
+        self.summary.num_transactions += transactions.len();
+        self.write(false)
+    }
+
+    fn add_transaction(
+        &mut self,
+        slot: Slot,
+        transaction_index: u32,
+        tx_with_meta: &VersionedTransactionWithStatusMeta,
+    ) {
+        let VersionedTransactionWithStatusMeta { transaction, meta } = tx_with_meta;
+        let signature = transaction.signatures[0].to_string();
+        let account_keys = tx_with_meta.account_keys();
+
+        let rows = &mut self.transactions.rows;
+        rows.slot.push(slot);
+        rows.transaction_index.push(transaction_index);
+        rows.signature.push(signature.clone());
+        rows.version.push(match transaction.version() {
+            TransactionVersion::Legacy(_) => "legacy".to_string(),
+            TransactionVersion::Number(version) => version.to_string(),
+        });
+        rows.recent_blockhash
+            .push(transaction.message.recent_blockhash().to_string());
+        rows.fee.push(meta.fee);
+        rows.err
+            .push(meta.status.as_ref().err().map(ToString::to_string));
+        rows.compute_units_consumed
+            .push(meta.compute_units_consumed);
+        rows.account_keys
+            .push(Some(account_keys.iter().map(ToString::to_string).collect()));
+        rows.log_messages.push(meta.log_messages.clone());
+
+        let rows = &mut self.instructions.rows;
+        for (instruction_index, instruction) in
+            transaction.message.instructions().iter().enumerate()
+        {
+            rows.push(
+                slot,
+                transaction_index,
+                &signature,
+                instruction_index as u32,
+                None,
+                Some(1),
+                instruction,
+                &account_keys,
+            );
+        }
+        for inner_instructions in meta.inner_instructions.iter().flatten() {
+            for (inner_instruction_index, inner_instruction) in
+                inner_instructions.instructions.iter().enumerate()
+            {
+                rows.push(
+                    slot,
+                    transaction_index,
+                    &signature,
+                    u32::from(inner_instructions.index),
+                    Some(inner_instruction_index as u32),
+                    inner_instruction.stack_height,
+                    &inner_instruction.instruction,
+                    &account_keys,
+                );
+            }
+        }
+
+        self.token_balance_changes
+            .rows
+            .push(slot, transaction_index, &signature, tx_with_meta);
+    }
+
+    /// Writes the tables of the current partition
+    fn write(&mut self, finish: bool) -> Result<(), Box<dyn Error>> {
+        let Some(partition) = self.partition else {
+            return Ok(());
+        };
+        let output_dir = &self.output_dir;
+        let format = self.format;
+        self.summary.num_files += self.blocks.write(output_dir, partition, format, finish)?
+            + self
+                .transactions
+                .write(output_dir, partition, format, finish)?
+            + self
+                .instructions
+                .write(output_dir, partition, format, finish)?
+            + self
+                .token_balance_changes
+                .write(output_dir, partition, format, finish)?
+            + self.rewards.write(output_dir, partition, format, finish)?;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<ExportSummary, Box<dyn Error>> {
+        self.write(true)?;
+        Ok(self.summary)
+    }
+}
+
+/// Exports the rooted blocks from `starting_slot` to `ending_slot` included.
+pub fn export_blocks(
+    blockstore: &Blockstore,
+    starting_slot: Slot,
+    ending_slot: Slot,
+    mut exporter: BlockExporter,
+) -> Result<ExportSummary, Box<dyn Error>> {
+    for slot in blockstore.rooted_slot_iterator(starting_slot)? {
+        if slot > ending_slot {
+            break;
+        }
+        match blockstore.get_rooted_block(slot, false) {
+            Ok(block) => exporter.add_block(slot, block)?,
+            Err(err) => warn!("Skipping slot {slot}: {err}"),
+        }
+    }
+    exporter.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        parquet::file::reader::{FileReader, SerializedFileReader},
+        solana_sdk::{
+            hash::Hash, message::v0::LoadedAddresses, pubkey::Pubkey, signature::Keypair,
+            system_transaction, transaction::VersionedTransaction,
+        },
+        solana_transaction_status::{Reward, RewardType, TransactionStatusMeta},
+    };
+
+    fn test_block(parent_slot: Slot) -> VersionedConfirmedBlock {
+        let transaction = system_transaction::transfer(
+            &Keypair::new(),
+            &Pubkey::new_unique(),
+            42,
+            Hash::default(),
+        );
+        VersionedConfirmedBlock {
+            previous_blockhash: Hash::default().to_string(),
+            blockhash: Hash::new_unique().to_string(),
+            parent_slot,
+            transactions: vec![VersionedTransactionWithStatusMeta {
+                transaction: VersionedTransaction::from(transaction),
+                meta: TransactionStatusMeta {
+                    status: Ok(()),
+                    fee: 5000,
+                    pre_balances: vec![5042, 0, 1],
+                    post_balances: vec![0, 42, 1],
+                    inner_instructions: None,
+                    log_messages: None,
+                    pre_token_balances: None,
+                    post_token_balances: None,
+                    rewards: None,
+                    loaded_addresses: LoadedAddresses::default(),
+                    return_data: None,
+                    compute_units_consumed: Some(150),
+                },
+            }],
+            rewards: vec![Reward {
+                pubkey: Pubkey::new_unique().to_string(),
+                lamports: 2500,
+                post_balance: 1_000_000,
+                reward_type: Some(RewardType::Fee),
+                commission: None,
+            }],
+            block_time: Some(1_700_000_000),
+            block_height: Some(parent_slot + 1),
+        }
+    }
+
+    fn num_rows(path: &Path) -> i64 {
+        let reader = SerializedFileReader::new(File::open(path).unwrap()).unwrap();
+        reader.metadata().file_metadata().num_rows()
+    }
+
+    #[test]
+    fn test_block_exporter() {
+        let output_dir = tempfile::tempdir().unwrap();
+        let mut exporter = BlockExporter::new(output_dir.path(), ExportFormat::Parquet, 10);
+        for slot in [1, 2, 11] {
+            exporter.add_block(slot, test_block(slot - 1)).unwrap();
+        }
+        let summary = exporter.finish().unwrap();
+        assert_eq!(summary.num_blocks, 3);
+        assert_eq!(summary.num_transactions, 3);
+        // token_balance_changes has no rows, so no file
+        assert_eq!(summary.num_files, 8);
+
+        let path = |partition: Slot, table: &str| {
+            output_dir
+                .path()
+                .join(table)
+                .join(format!("slot_partition={partition}"))
+                .join("data.parquet")
+        };
+        assert_eq!(num_rows(&path(0, "blocks")), 2);
+        assert_eq!(num_rows(&path(10, "blocks")), 1);
+        assert_eq!(num_rows(&path(0, "transactions")), 2);
+        assert_eq!(num_rows(&path(0, "instructions")), 2);
+        assert_eq!(num_rows(&path(10, "rewards")), 1);
+        assert!(!path(0, "token_balance_changes").exists());
+    }
+}
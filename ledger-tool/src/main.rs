@@ -31,8 +31,9 @@ use {
     },
     solana_cli_output::{CliAccount, CliAccountNewConfig, OutputFormat},
     solana_core::{
+        banking_simulation::{BankingSimulator, BankingTraceEvents, SimulatedSlotReport},
         system_monitor_service::{SystemMonitorService, SystemMonitorStatsReportConfig},
-        validator::BlockVerificationMethod,
+        validator::{BlockProductionMethod, BlockVerificationMethod},
     },
     solana_cost_model::{cost_model::CostModel, cost_tracker::CostTracker},
     solana_entry::entry::Entry,
@@ -102,8 +103,10 @@ use {
     },
 };
 
+mod accounts_storage;
 mod args;
 mod bigtable;
+mod block_export;
 mod ledger_path;
 mod ledger_utils;
 mod output;
@@ -964,6 +967,50 @@ fn compute_slot_cost(blockstore: &Blockstore, slot: Slot) -> Result<(), String>
     Ok(())
 }
 
+fn output_simulated_slot_report(report: &SimulatedSlotReport) {
+    let missing_signatures = report.missing_signatures();
+    let extra_signatures = report.extra_signatures();
+    println!(
+        "Slot: {}, bank hash {}",
+        report.slot,
+        if report.is_bank_hash_matched() {
+            "matched"
+        } else {
+            "mismatched"
+        },
+    );
+    for (label, block) in [
+        ("Recorded", &report.recorded),
+        ("Simulated", &report.simulated),
+    ] {
+        println!(
+            "  {label}: Transactions: {}, Estimated CUs: {}, Blockhash: {}, Bank hash: {}",
+            block.signatures.len(),
+            block.estimated_compute_units,
+            block
+                .blockhash
+                .map(|hash| hash.to_string())
+                .unwrap_or_else(|| "unknown".to_string()),
+            block
+                .bank_hash
+                .map(|hash| hash.to_string())
+                .unwrap_or_else(|| "unknown".to_string()),
+        );
+    }
+    println!(
+        "  Only recorded: {}, Only simulated: {}, Common ordering preserved: {}",
+        missing_signatures.len(),
+        extra_signatures.len(),
+        report.is_common_ordering_preserved(),
+    );
+    for signature in missing_signatures {
+        debug!("  only recorded: {signature}");
+    }
+    for signature in extra_signatures {
+        debug!("  only simulated: {signature}");
+    }
+}
+
 /// Returns true if the supplied slot contains any nonvote transactions
 fn slot_contains_nonvote_tx(blockstore: &Blockstore, slot: Slot) -> bool {
     let (entries, _, _) = blockstore
@@ -1597,6 +1644,51 @@ fn main() {
                 .arg(&accounts_db_skip_initial_hash_calc_arg)
                 .arg(&accounts_db_test_skip_rewrites_but_include_in_bank_hash),
         )
+        .subcommand(
+            SubCommand::with_name("simulate-block-production")
+                .about(
+                    "Replay the packets recorded by the banking tracer through a banking stage \
+                     and compare the produced blocks with the recorded ones",
+                )
+                .arg(&max_genesis_archive_unpacked_size_arg)
+                .arg(&accounts_index_bins)
+                .arg(&accounts_index_limit)
+                .arg(&disable_disk_index)
+                .arg(&accountsdb_verify_refcounts)
+                .arg(&accounts_db_skip_initial_hash_calc_arg)
+                .arg(
+                    Arg::with_name("first_simulated_slot")
+                        .long("first-simulated-slot")
+                        .value_name("SLOT")
+                        .validator(is_slot)
+                        .takes_value(true)
+                        .required(true)
+                        .help(
+                            "First leader slot to simulate. The ledger is replayed up to its \
+                             parent slot, then the traced leader's consecutive slots are \
+                             simulated",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("banking_trace_events")
+                        .long("banking-trace-events")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .multiple(true)
+                        .help(
+                            "Banking trace event files or directories containing them \
+                             [default: the banking_trace directory of the ledger]",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("block_production_method")
+                        .long("block-production-method")
+                        .value_name("METHOD")
+                        .takes_value(true)
+                        .possible_values(BlockProductionMethod::cli_names())
+                        .help(BlockProductionMethod::cli_message()),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("bounds")
                 .about(
@@ -1617,6 +1709,44 @@ fn main() {
                 .arg(&starting_slot_arg)
                 .arg(&allow_dead_slots_arg),
         )
+        .subcommand(
+            SubCommand::with_name("export-blocks")
+                .about(
+                    "Export the rooted blocks of the ledger to columnar files, partitioned by \
+                     slot",
+                )
+                .arg(&starting_slot_arg)
+                .arg(&ending_slot_arg)
+                .arg(
+                    Arg::with_name("output_dir")
+                        .long("output-dir")
+                        .value_name("DIR")
+                        .takes_value(true)
+                        .required(true)
+                        .help(
+                            "Directory to write the blocks, transactions, instructions, \
+                             token_balance_changes and rewards tables to",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .value_name("FORMAT")
+                        .takes_value(true)
+                        .possible_values(&["parquet", "arrow"])
+                        .default_value("parquet")
+                        .help("Format of the exported files"),
+                )
+                .arg(
+                    Arg::with_name("partition_slots")
+                        .long("partition-slots")
+                        .value_name("NUM_SLOTS")
+                        .takes_value(true)
+                        .default_value("100000")
+                        .validator(is_parsable::<u64>)
+                        .help("Number of slots of each partition of the exported tables"),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("verify")
                 .about("Verify the ledger")
@@ -2010,6 +2140,56 @@ fn main() {
                 )
                 .arg(&max_genesis_archive_unpacked_size_arg),
         )
+        .subcommand(
+            SubCommand::with_name("convert-accounts-storage")
+                .about(
+                    "Convert accounts storage files between the append vec and the hot tiered \
+                     storage formats, and verify that the accounts hash is unchanged",
+                )
+                .arg(&no_snapshot_arg)
+                .arg(&account_paths_arg)
+                .arg(&accounts_hash_cache_path_arg)
+                .arg(&accounts_index_bins)
+                .arg(&accounts_index_limit)
+                .arg(&disable_disk_index)
+                .arg(&accountsdb_verify_refcounts)
+                .arg(&accounts_db_test_skip_rewrites_but_include_in_bank_hash)
+                .arg(&accounts_db_skip_initial_hash_calc_arg)
+                .arg(&halt_at_slot_arg)
+                .arg(&hard_forks_arg)
+                .arg(&use_snapshot_archives_at_startup)
+                .arg(&max_genesis_archive_unpacked_size_arg)
+                .arg(
+                    Arg::with_name("target_format")
+                        .long("target-format")
+                        .value_name("FORMAT")
+                        .takes_value(true)
+                        .possible_values(&["hot", "append-vec"])
+                        .default_value("hot")
+                        .help(
+                            "Format of the converted accounts storage files. Converting to hot \
+                             rewrites the storages of the bank after processing the ledger, \
+                             while converting to append-vec rewrites the hot storage files \
+                             inside --input-dir",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("input_dir")
+                        .long("input-dir")
+                        .value_name("DIR")
+                        .takes_value(true)
+                        .required_if("target_format", "append-vec")
+                        .help("Directory containing the hot storage files to convert"),
+                )
+                .arg(
+                    Arg::with_name("output_dir")
+                        .long("output-dir")
+                        .value_name("DIR")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Directory to write the converted accounts storage files into"),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("capitalization")
                 .about("Print capitalization (aka, total supply) while checksumming it")
@@ -2528,6 +2708,96 @@ fn main() {
                     }
                 }
             }
+            ("simulate-block-production", Some(arg_matches)) => {
+                let first_simulated_slot =
+                    value_t_or_exit!(arg_matches, "first_simulated_slot", Slot);
+                let block_production_method = value_t!(
+                    arg_matches,
+                    "block_production_method",
+                    BlockProductionMethod
+                )
+                .unwrap_or_default();
+                let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+                let mut process_options = ProcessOptions {
+                    new_hard_forks: hardforks_of(arg_matches, "hard_forks"),
+                    run_verification: false,
+                    accounts_db_config: Some(get_accounts_db_config(&ledger_path, arg_matches)),
+                    ..ProcessOptions::default()
+                };
+                let blockstore = Arc::new(open_blockstore(
+                    &ledger_path,
+                    get_access_type(&process_options),
+                    wal_recovery_mode,
+                    force_update_to_open,
+                    enforce_ulimit_nofile,
+                ));
+                let parent_slot = blockstore
+                    .meta(first_simulated_slot)
+                    .ok()
+                    .flatten()
+                    .and_then(|meta| meta.parent_slot)
+                    .unwrap_or_else(|| {
+                        eprintln!("Slot {first_simulated_slot} isn't connected in the ledger");
+                        exit(1);
+                    });
+                process_options.halt_at_slot = Some(parent_slot);
+
+                let event_file_paths = arg_matches
+                    .values_of("banking_trace_events")
+                    .map(|paths| paths.map(PathBuf::from).collect::<Vec<_>>())
+                    .unwrap_or_else(|| vec![blockstore.banking_trace_path()])
+                    .into_iter()
+                    .flat_map(|path| {
+                        if path.is_dir() {
+                            BankingTraceEvents::event_file_paths_in_dir(&path).unwrap_or_else(
+                                |err| {
+                                    eprintln!("Failed to read {path:?}: {err}");
+                                    exit(1);
+                                },
+                            )
+                        } else {
+                            vec![path]
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let banking_trace_events = BankingTraceEvents::load(&event_file_paths)
+                    .unwrap_or_else(|err| {
+                        eprintln!("Failed to load banking trace events: {err}");
+                        exit(1);
+                    });
+                info!(
+                    "Loaded {} packet batches from {} banking trace files",
+                    banking_trace_events.packet_batch_count(),
+                    event_file_paths.len(),
+                );
+
+                let (bank_forks, ..) = load_and_process_ledger(
+                    arg_matches,
+                    &genesis_config,
+                    blockstore.clone(),
+                    process_options,
+                    snapshot_archive_path,
+                    incremental_snapshot_archive_path,
+                )
+                .unwrap_or_else(|err| {
+                    eprintln!("Failed to load ledger: {err:?}");
+                    exit(1);
+                });
+
+                let simulator = BankingSimulator::new(banking_trace_events, first_simulated_slot);
+                match simulator.simulate(
+                    &genesis_config,
+                    bank_forks,
+                    blockstore,
+                    block_production_method,
+                ) {
+                    Ok(reports) => reports.iter().for_each(output_simulated_slot_report),
+                    Err(err) => {
+                        eprintln!("Failed to simulate block production: {err}");
+                        exit(1);
+                    }
+                }
+            }
             ("slot", Some(arg_matches)) => {
                 let slots = values_t_or_exit!(arg_matches, "slots", Slot);
                 let allow_dead_slots = arg_matches.is_present("allow_dead_slots");
@@ -2572,6 +2842,39 @@ fn main() {
                     true,
                 );
             }
+            ("export-blocks", Some(arg_matches)) => {
+                let starting_slot = value_t_or_exit!(arg_matches, "starting_slot", Slot);
+                let ending_slot = value_t!(arg_matches, "ending_slot", Slot).unwrap_or(Slot::MAX);
+                let output_dir = PathBuf::from(value_t_or_exit!(arg_matches, "output_dir", String));
+                let format = value_t_or_exit!(arg_matches, "format", block_export::ExportFormat);
+                let partition_slots = value_t_or_exit!(arg_matches, "partition_slots", u64);
+                if partition_slots == 0 {
+                    eprintln!("--partition-slots must be greater than 0");
+                    exit(1);
+                }
+                let blockstore = open_blockstore(
+                    &ledger_path,
+                    AccessType::Secondary,
+                    wal_recovery_mode,
+                    force_update_to_open,
+                    enforce_ulimit_nofile,
+                );
+                let exporter =
+                    block_export::BlockExporter::new(&output_dir, format, partition_slots);
+                let summary =
+                    block_export::export_blocks(&blockstore, starting_slot, ending_slot, exporter)
+                        .unwrap_or_else(|err| {
+                            eprintln!("Failed to export blocks: {err}");
+                            exit(1);
+                        });
+                println!(
+                    "Exported {} blocks and {} transactions in {} files into {}",
+                    summary.num_blocks,
+                    summary.num_transactions,
+                    summary.num_files,
+                    output_dir.display(),
+                );
+            }
             ("dead-slots", Some(arg_matches)) => {
                 let blockstore = open_blockstore(
                     &ledger_path,
@@ -3474,6 +3777,110 @@ fn main() {
                     println!("\n{total_accounts_stats:#?}");
                 }
             }
+            ("convert-accounts-storage", Some(arg_matches)) => {
+                let output_dir = PathBuf::from(value_t_or_exit!(arg_matches, "output_dir", String));
+                let summary = match arg_matches.value_of("target_format").unwrap() {
+                    "append-vec" => {
+                        let input_dir =
+                            PathBuf::from(value_t_or_exit!(arg_matches, "input_dir", String));
+                        accounts_storage::convert_hot_storages_to_append_vecs(
+                            &input_dir,
+                            &output_dir,
+                        )
+                    }
+                    _ => {
+                        let halt_at_slot = value_t!(arg_matches, "halt_at_slot", Slot).ok();
+                        let process_options = ProcessOptions {
+                            new_hard_forks: hardforks_of(arg_matches, "hard_forks"),
+                            halt_at_slot,
+                            run_verification: false,
+                            accounts_db_config: Some(get_accounts_db_config(
+                                &ledger_path,
+                                arg_matches,
+                            )),
+                            use_snapshot_archives_at_startup: value_t_or_exit!(
+                                arg_matches,
+                                use_snapshot_archives_at_startup::cli::NAME,
+                                UseSnapshotArchivesAtStartup
+                            ),
+                            ..ProcessOptions::default()
+                        };
+                        let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+                        let blockstore = open_blockstore(
+                            &ledger_path,
+                            get_access_type(&process_options),
+                            wal_recovery_mode,
+                            force_update_to_open,
+                            enforce_ulimit_nofile,
+                        );
+                        let (bank_forks, ..) = load_and_process_ledger(
+                            arg_matches,
+                            &genesis_config,
+                            Arc::new(blockstore),
+                            process_options,
+                            snapshot_archive_path,
+                            incremental_snapshot_archive_path,
+                        )
+                        .unwrap_or_else(|err| {
+                            eprintln!("Failed to load ledger: {err:?}");
+                            exit(1);
+                        });
+
+                        let bank = bank_forks.read().unwrap().working_bank();
+                        // need to flush the write cache so that all accounts are in storages,
+                        // and need to root `bank` before flushing the cache
+                        bank.rc.accounts.accounts_db.add_root(bank.slot());
+                        bank.force_flush_accounts_cache();
+                        let bank_accounts_hash = bank.update_accounts_hash(
+                            CalcAccountsHashDataSource::Storages,
+                            false,
+                            false,
+                        );
+                        let (storages, _slots) = bank
+                            .rc
+                            .accounts
+                            .accounts_db
+                            .get_snapshot_storages(..=bank.slot());
+                        accounts_storage::convert_storages_to_hot(&storages, &output_dir).map(
+                            |summary| {
+                                println!(
+                                    "Bank accounts hash at slot {}: {}",
+                                    bank.slot(),
+                                    bank_accounts_hash.0
+                                );
+                                if summary.source_accounts_hash != bank_accounts_hash {
+                                    eprintln!(
+                                        "Error: accounts hash of the source storages does not \
+                                         match the bank accounts hash"
+                                    );
+                                    exit(1);
+                                }
+                                summary
+                            },
+                        )
+                    }
+                }
+                .unwrap_or_else(|err| {
+                    eprintln!("Failed to convert accounts storage: {err}");
+                    exit(1);
+                });
+
+                println!(
+                    "Converted {} accounts in {} files into {}",
+                    summary.num_accounts,
+                    summary.num_files,
+                    output_dir.display(),
+                );
+                println!("Source accounts hash: {}", summary.source_accounts_hash.0);
+                println!(
+                    "Converted accounts hash: {}",
+                    summary.converted_accounts_hash.0
+                );
+                if !summary.is_accounts_hash_matched() {
+                    eprintln!("Error: accounts hash mismatch after conversion");
+                    exit(1);
+                }
+            }
             ("capitalization", Some(arg_matches)) => {
                 let halt_at_slot = value_t!(arg_matches, "halt_at_slot", Slot).ok();
                 let process_options = ProcessOptions {
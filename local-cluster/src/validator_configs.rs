@@ -17,6 +17,7 @@ pub fn safe_clone_config(config: &ValidatorConfig) -> ValidatorConfig {
         rpc_config: config.rpc_config.clone(),
         on_start_geyser_plugin_config_files: config.on_start_geyser_plugin_config_files.clone(),
         rpc_addrs: config.rpc_addrs,
+        rpc_grpc_addr: config.rpc_grpc_addr,
         pubsub_config: config.pubsub_config.clone(),
         snapshot_config: config.snapshot_config.clone(),
         max_ledger_shreds: config.max_ledger_shreds,
@@ -1,7 +1,7 @@
 //! The `metrics` module enables sending measurements to an `InfluxDB` instance
 
 use {
-    crate::{counter::CounterPoint, datapoint::DataPoint},
+    crate::{counter::CounterPoint, datapoint::DataPoint, prometheus::PrometheusExporter},
     crossbeam_channel::{unbounded, Receiver, RecvTimeoutError, Sender},
     gethostname::gethostname,
     lazy_static::lazy_static,
@@ -179,10 +179,11 @@ impl Default for MetricsAgent {
             })
             .unwrap_or(4000);
 
-        Self::new(
+        Self::new_with_prometheus_exporter(
             Arc::new(InfluxDbMetricsWriter::new()),
             Duration::from_secs(10),
             max_points_per_sec,
+            PrometheusExporter::start_from_env(),
         )
     }
 }
@@ -192,12 +193,31 @@ impl MetricsAgent {
         writer: Arc<dyn MetricsWriter + Send + Sync>,
         write_frequency: Duration,
         max_points_per_sec: usize,
+    ) -> Self {
+        Self::new_with_prometheus_exporter(writer, write_frequency, max_points_per_sec, None)
+    }
+
+    /// Like `new`, but also records every submitted datapoint and counter in
+    /// `prometheus_exporter`
+    pub fn new_with_prometheus_exporter(
+        writer: Arc<dyn MetricsWriter + Send + Sync>,
+        write_frequency: Duration,
+        max_points_per_sec: usize,
+        prometheus_exporter: Option<Arc<PrometheusExporter>>,
     ) -> Self {
         let (sender, receiver) = unbounded::<MetricsCommand>();
 
         thread::Builder::new()
             .name("solMetricsAgent".into())
-            .spawn(move || Self::run(&receiver, &writer, write_frequency, max_points_per_sec))
+            .spawn(move || {
+                Self::run(
+                    &receiver,
+                    &writer,
+                    write_frequency,
+                    max_points_per_sec,
+                    prometheus_exporter.as_deref(),
+                )
+            })
             .unwrap();
 
         Self { sender }
@@ -255,6 +275,7 @@ impl MetricsAgent {
         writer: &Arc<dyn MetricsWriter + Send + Sync>,
         write_frequency: Duration,
         max_points_per_sec: usize,
+        prometheus_exporter: Option<&PrometheusExporter>,
     ) {
         trace!("run: enter");
         let mut last_write_time = Instant::now();
@@ -281,10 +302,16 @@ impl MetricsAgent {
                     }
                     MetricsCommand::Submit(point, level) => {
                         log!(level, "{}", point);
+                        if let Some(prometheus_exporter) = prometheus_exporter {
+                            prometheus_exporter.record_point(&point);
+                        }
                         points.push(point);
                     }
                     MetricsCommand::SubmitCounter(counter, _level, bucket) => {
                         debug!("{:?}", counter);
+                        if let Some(prometheus_exporter) = prometheus_exporter {
+                            prometheus_exporter.record_counter(&counter);
+                        }
                         let key = (counter.name, bucket);
                         if let Some(value) = counters.get_mut(&key) {
                             value.count += counter.count;
@@ -356,7 +383,7 @@ fn get_singleton_agent() -> &'static MetricsAgent {
 }
 
 lazy_static! {
-    static ref HOST_ID: Arc<RwLock<String>> = {
+    pub(crate) static ref HOST_ID: Arc<RwLock<String>> = {
         Arc::new(RwLock::new({
             let hostname: String = gethostname()
                 .into_string()
@@ -686,6 +713,45 @@ mod test {
         assert_eq!(writer.points_written(), 2);
     }
 
+    #[test]
+    fn test_prometheus_exporter() {
+        let writer = Arc::new(MockMetricsWriter::new());
+        let prometheus_exporter =
+            PrometheusExporter::start("127.0.0.1:0".parse().unwrap()).unwrap();
+        let agent = MetricsAgent::new_with_prometheus_exporter(
+            writer.clone(),
+            Duration::from_secs(10),
+            1000,
+            Some(prometheus_exporter.clone()),
+        );
+
+        for i in 0..10 {
+            agent.submit_counter(
+                CounterPoint {
+                    name: "counter",
+                    count: 3,
+                    timestamp: UNIX_EPOCH,
+                },
+                Level::Info,
+                i,
+            );
+            agent.submit(
+                DataPoint::new("measurement")
+                    .add_field_i64("i", i as i64)
+                    .to_owned(),
+                Level::Info,
+            );
+        }
+        agent.flush();
+
+        let metrics = prometheus_exporter.render();
+        assert!(metrics.contains("\nsolana_counter_total{host_id=\""));
+        assert!(metrics.contains("\"} 30\n"));
+        assert!(metrics.contains("\nsolana_measurement_i{host_id=\""));
+        assert!(metrics.contains("\"} 9\n"));
+        assert_eq!(writer.points_written(), 21);
+    }
+
     #[test]
     fn test_live_submit() {
         let agent = MetricsAgent::default();
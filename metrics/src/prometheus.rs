@@ -0,0 +1,358 @@
+//! The `prometheus` module exposes the datapoints and counters submitted to
+//! the metrics agent on a local HTTP `/metrics` endpoint, in the Prometheus
+//! text exposition format.  It is enabled by setting
+//! `SOLANA_METRICS_PROMETHEUS_BIND_ADDRESS` to the address to listen on, and
+//! works with or without `SOLANA_METRICS_CONFIG`.
+//!
+//! The datapoints and counters are mapped as follows:
+//! * the counter `name` becomes the counter `solana_<name>_total`
+//! * each numeric or bool field `field` of the datapoint `name` becomes the
+//!   gauge `solana_<name>_<field>`, holding the latest value, with bools
+//!   mapped to 0 and 1
+//! * the tags and the string fields of a datapoint become labels of its
+//!   gauges, as does the host id
+//!
+//! Names are sanitized by replacing any character invalid in a Prometheus
+//! name, like `-`, with `_`.
+
+use {
+    crate::{counter::CounterPoint, datapoint::DataPoint, metrics::HOST_ID},
+    log::*,
+    std::{
+        collections::BTreeMap,
+        env,
+        fmt::Write as _,
+        io::{self, BufRead, BufReader, Write},
+        net::{SocketAddr, TcpListener, TcpStream},
+        sync::{Arc, Mutex},
+        thread,
+        time::Duration,
+    },
+};
+
+/// The maximum number of series kept, to bound the memory used by string
+/// fields with many distinct values
+const MAX_SERIES: usize = 100_000;
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+type Labels = Vec<(String, String)>;
+
+#[derive(Debug, Default)]
+struct Registry {
+    /// The value of each counter
+    counters: BTreeMap<String, i64>,
+    /// The latest value of each gauge, by labels
+    gauges: BTreeMap<String, BTreeMap<Labels, f64>>,
+    num_series: usize,
+    dropped_series: u64,
+}
+
+impl Registry {
+    /// Returns false if the series does not exist and there are already
+    /// MAX_SERIES series.
+    fn reserve_series(&mut self, exists: bool) -> bool {
+        if exists {
+            return true;
+        }
+        if self.num_series >= MAX_SERIES {
+            if self.dropped_series == 0 {
+                warn!("Dropping new Prometheus series, as there are already {MAX_SERIES} series");
+            }
+            self.dropped_series += 1;
+            return false;
+        }
+        self.num_series += 1;
+        true
+    }
+
+    fn record_counter(&mut self, counter: &CounterPoint) {
+        let name = format!("solana_{}_total", sanitize_name(counter.name));
+        let exists = self.counters.contains_key(&name);
+        if self.reserve_series(exists) {
+            *self.counters.entry(name).or_default() += counter.count;
+        }
+    }
+
+    fn record_point(&mut self, point: &DataPoint) {
+        let mut labels: Labels = point
+            .tags
+            .iter()
+            .map(|(name, value)| (sanitize_name(name), value.clone()))
+            .collect();
+        let mut values = vec![];
+        for (name, value) in &point.fields {
+            match parse_field_value(value) {
+                FieldValue::Number(value) => values.push((name, value)),
+                FieldValue::String(value) => labels.push((sanitize_name(name), value)),
+            }
+        }
+        labels.sort_unstable();
+
+        for (field, value) in values {
+            let name = format!(
+                "solana_{}_{}",
+                sanitize_name(point.name),
+                sanitize_name(field)
+            );
+            let exists = self
+                .gauges
+                .get(&name)
+                .map_or(false, |series| series.contains_key(&labels));
+            if self.reserve_series(exists) {
+                self.gauges
+                    .entry(name)
+                    .or_default()
+                    .insert(labels.clone(), value);
+            }
+        }
+    }
+
+    fn render(&self, host_id: &str) -> String {
+        let host_id = escape_label_value(host_id);
+        let mut text = String::new();
+        for (name, value) in &self.counters {
+            let _ = writeln!(text, "# TYPE {name} counter");
+            let _ = writeln!(text, "{name}{{host_id=\"{host_id}\"}} {value}");
+        }
+        for (name, series) in &self.gauges {
+            let _ = writeln!(text, "# TYPE {name} gauge");
+            for (labels, value) in series {
+                let _ = write!(text, "{name}{{host_id=\"{host_id}\"");
+                for (label, label_value) in labels {
+                    let _ = write!(text, ",{label}=\"{}\"", escape_label_value(label_value));
+                }
+                let _ = writeln!(text, "}} {}", format_value(*value));
+            }
+        }
+        let name = "solana_metrics_prometheus_dropped_series_total";
+        let _ = writeln!(text, "# TYPE {name} counter");
+        let _ = writeln!(
+            text,
+            "{name}{{host_id=\"{host_id}\"}} {}",
+            self.dropped_series
+        );
+        text
+    }
+}
+
+enum FieldValue {
+    Number(f64),
+    String(String),
+}
+
+/// Parses a field value formatted by `DataPoint` for the InfluxDB line
+/// protocol
+fn parse_field_value(value: &str) -> FieldValue {
+    if let Some(value) = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    {
+        return FieldValue::String(value.replace("\\\"", "\""));
+    }
+    if let Some(Ok(value)) = value.strip_suffix('i').map(str::parse::<i64>) {
+        return FieldValue::Number(value as f64);
+    }
+    match value {
+        "true" => FieldValue::Number(1.),
+        "false" => FieldValue::Number(0.),
+        _ => value
+            .parse()
+            .map(FieldValue::Number)
+            .unwrap_or_else(|_| FieldValue::String(value.to_string())),
+    }
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0. { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Replaces the characters which are not allowed in a Prometheus metric or
+/// label name with `_`
+fn sanitize_name(name: &str) -> String {
+    let mut sanitized: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if sanitized.starts_with(|c: char| c.is_ascii_digit()) {
+        sanitized.insert(0, '_');
+    }
+    sanitized
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Serves the latest values of the submitted datapoints and counters on
+/// `/metrics`
+#[derive(Debug)]
+pub struct PrometheusExporter {
+    registry: Mutex<Registry>,
+    local_addr: SocketAddr,
+}
+
+impl PrometheusExporter {
+    /// Starts an exporter listening on `SOLANA_METRICS_PROMETHEUS_BIND_ADDRESS`,
+    /// if it is set
+    pub fn start_from_env() -> Option<Arc<Self>> {
+        let bind_address = env::var("SOLANA_METRICS_PROMETHEUS_BIND_ADDRESS").ok()?;
+        let bind_address = match bind_address.parse() {
+            Ok(bind_address) => bind_address,
+            Err(err) => {
+                warn!("SOLANA_METRICS_PROMETHEUS_BIND_ADDRESS is invalid: {err}");
+                return None;
+            }
+        };
+        match Self::start(bind_address) {
+            Ok(exporter) => Some(exporter),
+            Err(err) => {
+                warn!("Failed to start the Prometheus exporter on {bind_address}: {err}");
+                None
+            }
+        }
+    }
+
+    pub fn start(bind_address: SocketAddr) -> io::Result<Arc<Self>> {
+        let listener = TcpListener::bind(bind_address)?;
+        let exporter = Arc::new(Self {
+            registry: Mutex::default(),
+            local_addr: listener.local_addr()?,
+        });
+        info!("Prometheus exporter listening on {}", exporter.local_addr);
+
+        let server_exporter = exporter.clone();
+        thread::Builder::new()
+            .name("solMetricsProm".into())
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    let result = stream.and_then(|stream| server_exporter.serve(stream));
+                    if let Err(err) = result {
+                        debug!("Prometheus exporter request failed: {err}");
+                    }
+                }
+            })?;
+        Ok(exporter)
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub fn record_counter(&self, counter: &CounterPoint) {
+        self.registry.lock().unwrap().record_counter(counter);
+    }
+
+    pub fn record_point(&self, point: &DataPoint) {
+        self.registry.lock().unwrap().record_point(point);
+    }
+
+    /// Returns the current metrics in the Prometheus text format
+    pub fn render(&self) -> String {
+        let host_id = HOST_ID.read().unwrap().clone();
+        self.registry.lock().unwrap().render(&host_id)
+    }
+
+    fn serve(&self, mut stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        // Skip the headers
+        let mut line = String::new();
+        while reader.read_line(&mut line)? > 2 {
+            line.clear();
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let (status, content_type, body) = match (parts.next(), parts.next()) {
+            (Some("GET"), Some("/metrics")) => (
+                "200 OK",
+                "text/plain; version=0.0.4; charset=utf-8",
+                self.render(),
+            ),
+            _ => ("404 Not Found", "text/plain", "Not Found\n".to_string()),
+        };
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\
+             Connection: close\r\n\r\n{body}",
+            body.len()
+        )?;
+        stream.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::io::Read};
+
+    #[test]
+    fn test_render() {
+        let mut registry = Registry::default();
+        let counter = CounterPoint {
+            name: "bank-forks_set_root-ms",
+            count: 2,
+            timestamp: std::time::UNIX_EPOCH,
+        };
+        registry.record_counter(&counter);
+        registry.record_counter(&counter);
+        registry.record_point(
+            DataPoint::new("replay-slot-stats")
+                .add_tag("kind", "fork")
+                .add_field_i64("slot", 42)
+                .add_field_f64("ratio", 0.5)
+                .add_field_bool("is_leader", true)
+                .add_field_str("error", "invalid \"hash\""),
+        );
+        registry.record_point(
+            DataPoint::new("replay-slot-stats")
+                .add_tag("kind", "fork")
+                .add_field_i64("slot", 43)
+                .add_field_str("error", "invalid \"hash\""),
+        );
+
+        let labels = r#"host_id="host",error="invalid \"hash\"",kind="fork""#;
+        assert_eq!(
+            registry.render("host"),
+            format!(
+                "# TYPE solana_bank_forks_set_root_ms_total counter\n\
+                 solana_bank_forks_set_root_ms_total{{host_id=\"host\"}} 4\n\
+                 # TYPE solana_replay_slot_stats_is_leader gauge\n\
+                 solana_replay_slot_stats_is_leader{{{labels}}} 1\n\
+                 # TYPE solana_replay_slot_stats_ratio gauge\n\
+                 solana_replay_slot_stats_ratio{{{labels}}} 0.5\n\
+                 # TYPE solana_replay_slot_stats_slot gauge\n\
+                 solana_replay_slot_stats_slot{{{labels}}} 43\n\
+                 # TYPE solana_metrics_prometheus_dropped_series_total counter\n\
+                 solana_metrics_prometheus_dropped_series_total{{host_id=\"host\"}} 0\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_serve() {
+        let exporter = PrometheusExporter::start("127.0.0.1:0".parse().unwrap()).unwrap();
+        exporter.record_point(DataPoint::new("test").add_field_i64("value", 7));
+
+        let get = |path: &str| {
+            let mut stream = TcpStream::connect(exporter.local_addr()).unwrap();
+            write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let response = get("/metrics");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("\r\n# TYPE solana_test_value gauge\nsolana_test_value{"));
+        assert!(get("/").starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+}
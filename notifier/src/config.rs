@@ -0,0 +1,182 @@
+//! The notifier config file, which sets all the notification channels instead
+//! of the per-channel environment variables.
+//!
+//! An example config file, in YAML:
+//! ```yaml
+//! slack_webhook: https://hooks.slack.com/services/...
+//! log_level: warn
+//! webhooks:
+//!   - url: https://api.opsgenie.com/v2/alerts
+//!     headers:
+//!       Authorization: GenieKey ...
+//!     body_template: '{"message": "{{message}}", "alias": "{{incident}}"}'
+//! smtp:
+//!   - host: smtp.example.com
+//!     username: watchtower
+//!     password: ...
+//!     from: watchtower@example.com
+//!     to: [oncall@example.com]
+//! ```
+
+use {
+    serde_derive::Deserialize,
+    std::{collections::BTreeMap, fs::File, path::Path},
+};
+
+const DEFAULT_WEBHOOK_BODY_TEMPLATE: &str =
+    r#"{"message": "{{message}}", "event": "{{event}}", "incident": "{{incident}}"}"#;
+const DEFAULT_SMTP_SUBJECT: &str = "Solana notification: {{event}}";
+
+#[derive(Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotifierConfig {
+    pub discord_webhook: Option<String>,
+    pub slack_webhook: Option<String>,
+    pub pagerduty_integration_key: Option<String>,
+    pub telegram: Option<TelegramConfig>,
+    pub twilio: Option<TwilioConfig>,
+    /// Also log notifications at this level
+    pub log_level: Option<String>,
+    pub webhooks: Vec<WebhookConfig>,
+    pub smtp: Vec<SmtpConfig>,
+}
+
+impl NotifierConfig {
+    pub fn load(config_file: &Path) -> Result<Self, String> {
+        let file = File::open(config_file)
+            .map_err(|err| format!("Unable to open {}: {err}", config_file.display()))?;
+        serde_yaml::from_reader(file)
+            .map_err(|err| format!("Unable to parse {}: {err}", config_file.display()))
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct TelegramConfig {
+    pub bot_token: String,
+    pub chat_id: String,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct TwilioConfig {
+    pub account: String,
+    pub token: String,
+    pub to: String,
+    pub from: String,
+}
+
+/// A generic JSON webhook.  The `{{message}}`, `{{event}}` (`trigger` or
+/// `resolve`) and `{{incident}}` placeholders of the body template are
+/// replaced by their JSON-escaped values, so they must be quoted in the
+/// template.
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookConfig {
+    pub url: String,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default = "default_webhook_body_template")]
+    pub body_template: String,
+}
+
+fn default_webhook_body_template() -> String {
+    DEFAULT_WEBHOOK_BODY_TEMPLATE.to_string()
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// Connect with TLS, on port 465 by default
+    #[default]
+    Tls,
+    /// Upgrade the connection with STARTTLS, on port 587 by default
+    StartTls,
+    /// Send everything in plaintext, on port 25 by default
+    None,
+}
+
+/// An email sent through an SMTP server.  The subject supports the same
+/// placeholders as a webhook body template.
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub security: SmtpSecurity,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+    #[serde(default = "default_smtp_subject")]
+    pub subject: String,
+}
+
+fn default_smtp_subject() -> String {
+    DEFAULT_SMTP_SUBJECT.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::io::Write};
+
+    #[test]
+    fn test_load() {
+        let mut config_file = tempfile::NamedTempFile::new().unwrap();
+        config_file
+            .write_all(
+                br#"
+slack_webhook: https://slack
+log_level: warn
+webhooks:
+  - url: https://opsgenie
+    headers:
+      Authorization: GenieKey key
+  - url: https://mattermost
+    body_template: '{"text": "{{message}}"}'
+smtp:
+  - host: smtp.example.com
+    security: starttls
+    from: watchtower@example.com
+    to: [oncall@example.com]
+"#,
+            )
+            .unwrap();
+
+        let config = NotifierConfig::load(config_file.path()).unwrap();
+        assert_eq!(
+            config,
+            NotifierConfig {
+                slack_webhook: Some("https://slack".to_string()),
+                log_level: Some("warn".to_string()),
+                webhooks: vec![
+                    WebhookConfig {
+                        url: "https://opsgenie".to_string(),
+                        headers: [("Authorization".to_string(), "GenieKey key".to_string())].into(),
+                        body_template: DEFAULT_WEBHOOK_BODY_TEMPLATE.to_string(),
+                    },
+                    WebhookConfig {
+                        url: "https://mattermost".to_string(),
+                        headers: BTreeMap::default(),
+                        body_template: r#"{"text": "{{message}}"}"#.to_string(),
+                    },
+                ],
+                smtp: vec![SmtpConfig {
+                    host: "smtp.example.com".to_string(),
+                    port: None,
+                    security: SmtpSecurity::StartTls,
+                    username: None,
+                    password: None,
+                    from: "watchtower@example.com".to_string(),
+                    to: vec!["oncall@example.com".to_string()],
+                    subject: DEFAULT_SMTP_SUBJECT.to_string(),
+                }],
+                ..NotifierConfig::default()
+            }
+        );
+
+        config_file.write_all(b"unknown: field\n").unwrap();
+        assert!(NotifierConfig::load(config_file.path()).is_err());
+    }
+}
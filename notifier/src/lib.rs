@@ -23,14 +23,29 @@
 /// ```bash
 /// export TWILIO_CONFIG='ACCOUNT=<account>,TOKEN=<securityToken>,TO=<receivingNumber>,FROM=<sendingNumber>'
 /// ```
+///
+/// Alternatively, all the notification channels, including generic JSON webhooks and email via
+/// SMTP, can be set from a config file (see the `config` module):
+/// ```bash
+/// export NOTIFIER_CONFIG=/path/to/notifier.yml
+/// ```
 use log::*;
 use {
+    crate::{
+        config::{NotifierConfig, TelegramConfig, TwilioConfig},
+        smtp::Smtp,
+        webhook::Webhook,
+    },
     reqwest::{blocking::Client, StatusCode},
     serde_json::json,
     solana_sdk::hash::Hash,
-    std::{env, str::FromStr, thread::sleep, time::Duration},
+    std::{env, path::Path, str::FromStr, thread::sleep, time::Duration},
 };
 
+pub mod config;
+mod smtp;
+mod webhook;
+
 struct TelegramWebHook {
     bot_token: String,
     chat_id: String,
@@ -91,6 +106,8 @@ enum NotificationChannel {
     Telegram(TelegramWebHook),
     Twilio(TwilioWebHook),
     Log(Level),
+    Webhook(Webhook),
+    Smtp(Smtp),
 }
 
 #[derive(Clone)]
@@ -99,6 +116,40 @@ pub enum NotificationType {
     Resolve { incident: Hash },
 }
 
+impl NotificationType {
+    fn event(&self) -> &'static str {
+        match self {
+            NotificationType::Trigger { .. } => "trigger",
+            NotificationType::Resolve { .. } => "resolve",
+        }
+    }
+
+    fn incident(&self) -> &Hash {
+        match self {
+            NotificationType::Trigger { incident } | NotificationType::Resolve { incident } => {
+                incident
+            }
+        }
+    }
+}
+
+/// Replaces the `{{message}}`, `{{event}}` and `{{incident}}` placeholders of
+/// the template by their values, escaped by `escape`
+pub(crate) fn render_template(
+    template: &str,
+    msg: &str,
+    notification_type: &NotificationType,
+    escape: impl Fn(&str) -> String,
+) -> String {
+    template
+        .replace("{{event}}", &escape(notification_type.event()))
+        .replace(
+            "{{incident}}",
+            &escape(&notification_type.incident().to_string()),
+        )
+        .replace("{{message}}", &escape(msg))
+}
+
 pub struct Notifier {
     client: Client,
     notifiers: Vec<NotificationChannel>,
@@ -111,9 +162,23 @@ impl Default for Notifier {
 }
 
 impl Notifier {
+    /// Creates a notifier from the `NOTIFIER_CONFIG` config file if it's set,
+    /// or from the notification channel environment variables otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the config file can't be read or is invalid, rather than
+    /// silently running without the configured notification channels.
     pub fn new(env_prefix: &str) -> Self {
         info!("Initializing {}Notifier", env_prefix);
 
+        if let Ok(config_file) = env::var(format!("{env_prefix}NOTIFIER_CONFIG")) {
+            return Self::from_config_file(Path::new(&config_file)).unwrap_or_else(|err| {
+                error!("Invalid notifier config file {config_file}: {err}");
+                panic!("Invalid notifier config file {config_file}: {err}");
+            });
+        }
+
         let mut notifiers = vec![];
 
         if let Ok(webhook) = env::var(format!("{env_prefix}DISCORD_WEBHOOK")) {
@@ -158,6 +223,68 @@ impl Notifier {
         }
     }
 
+    /// Creates a notifier with the notification channels of the specified
+    /// config file, ignoring the environment variables
+    pub fn from_config_file(config_file: &Path) -> Result<Self, String> {
+        Self::from_config(NotifierConfig::load(config_file)?)
+    }
+
+    pub fn from_config(config: NotifierConfig) -> Result<Self, String> {
+        let mut notifiers = vec![];
+
+        if let Some(webhook) = config.discord_webhook {
+            notifiers.push(NotificationChannel::Discord(webhook));
+        }
+        if let Some(webhook) = config.slack_webhook {
+            notifiers.push(NotificationChannel::Slack(webhook));
+        }
+        if let Some(routing_key) = config.pagerduty_integration_key {
+            notifiers.push(NotificationChannel::PagerDuty(routing_key));
+        }
+        if let Some(TelegramConfig { bot_token, chat_id }) = config.telegram {
+            notifiers.push(NotificationChannel::Telegram(TelegramWebHook {
+                bot_token,
+                chat_id,
+            }));
+        }
+        if let Some(TwilioConfig {
+            account,
+            token,
+            to,
+            from,
+        }) = config.twilio
+        {
+            let webhook = TwilioWebHook {
+                account,
+                token,
+                to,
+                from,
+            };
+            if !webhook.complete() {
+                return Err("The Twilio config is incomplete".to_string());
+            }
+            notifiers.push(NotificationChannel::Twilio(webhook));
+        }
+        if let Some(log_level) = config.log_level {
+            let level = Level::from_str(&log_level)
+                .map_err(|err| format!("Invalid log level {log_level}: {err}"))?;
+            notifiers.push(NotificationChannel::Log(level));
+        }
+        for webhook in config.webhooks {
+            notifiers.push(NotificationChannel::Webhook(Webhook::new(webhook)?));
+        }
+        for smtp in config.smtp {
+            notifiers.push(NotificationChannel::Smtp(Smtp::new(smtp)?));
+        }
+
+        info!("{} notifiers", notifiers.len());
+
+        Ok(Notifier {
+            client: Client::new(),
+            notifiers,
+        })
+    }
+
     pub fn is_empty(&self) -> bool {
         self.notifiers.is_empty()
     }
@@ -242,7 +369,129 @@ impl Notifier {
                 NotificationChannel::Log(level) => {
                     log!(*level, "{}", msg)
                 }
+                NotificationChannel::Webhook(webhook) => {
+                    if let Err(err) = webhook.send(&self.client, msg, notification_type) {
+                        warn!("Failed to send webhook: {}", err);
+                    }
+                }
+                NotificationChannel::Smtp(smtp) => {
+                    if let Err(err) = smtp.send(msg, notification_type) {
+                        warn!("Failed to send email: {}", err);
+                    }
+                }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::config::WebhookConfig,
+        std::{
+            io::{BufRead, BufReader, Read, Write},
+            net::{SocketAddr, TcpListener},
+            sync::mpsc::{channel, Receiver},
+            thread,
+        },
+    };
+
+    /// Starts an HTTP server accepting a single request, which sends the
+    /// request's head and body.
+    fn start_http_stub() -> (SocketAddr, Receiver<(String, String)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (sender, receiver) = channel();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut head = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+                head.push_str(&line);
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+            sender
+                .send((head, String::from_utf8(body).unwrap()))
+                .unwrap();
+        });
+        (addr, receiver)
+    }
+
+    #[test]
+    fn test_webhook() {
+        let (addr, receiver) = start_http_stub();
+        let notifier = Notifier::from_config(NotifierConfig {
+            webhooks: vec![WebhookConfig {
+                url: format!("http://{addr}/alerts"),
+                headers: [("Authorization".to_string(), "GenieKey key".to_string())].into(),
+                body_template: r#"{"message": "{{message}}", "alias": "{{incident}}"}"#.to_string(),
+            }],
+            ..NotifierConfig::default()
+        })
+        .unwrap();
+        assert!(!notifier.is_empty());
+
+        let incident = Hash::new_unique();
+        notifier.send(
+            "solana-watchtower: \"v1\" is delinquent",
+            &NotificationType::Trigger { incident },
+        );
+        let (head, body) = receiver.recv().unwrap();
+        assert!(head.starts_with("POST /alerts HTTP/1.1\r\n"));
+        let head = head.to_ascii_lowercase();
+        assert!(head.contains("authorization: geniekey key\r\n"));
+        assert!(head.contains("content-type: application/json\r\n"));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+            json!({
+                "message": "solana-watchtower: \"v1\" is delinquent",
+                "alias": incident.to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_from_config_invalid() {
+        assert!(Notifier::from_config(NotifierConfig {
+            log_level: Some("loud".to_string()),
+            ..NotifierConfig::default()
+        })
+        .is_err());
+        assert!(Notifier::from_config(NotifierConfig {
+            twilio: Some(TwilioConfig {
+                account: "account".to_string(),
+                token: String::default(),
+                to: "to".to_string(),
+                from: "from".to_string(),
+            }),
+            ..NotifierConfig::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid notifier config file")]
+    fn test_new_invalid_config_file() {
+        env::set_var(
+            "TEST_INVALID_CONFIG_NOTIFIER_CONFIG",
+            "/nonexistent/notifier.yml",
+        );
+        Notifier::new("TEST_INVALID_CONFIG_");
+    }
+}
@@ -0,0 +1,200 @@
+use {
+    crate::{
+        config::{SmtpConfig, SmtpSecurity},
+        render_template, NotificationType,
+    },
+    lettre::{
+        message::{header::ContentType, Mailbox},
+        transport::smtp::authentication::Credentials,
+        Message, SmtpTransport, Transport,
+    },
+};
+
+/// An email sent through an SMTP server
+pub(crate) struct Smtp {
+    transport: SmtpTransport,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+    subject: String,
+}
+
+impl Smtp {
+    pub(crate) fn new(config: SmtpConfig) -> Result<Self, String> {
+        let builder = match config.security {
+            SmtpSecurity::Tls => SmtpTransport::relay(&config.host),
+            SmtpSecurity::StartTls => SmtpTransport::starttls_relay(&config.host),
+            SmtpSecurity::None => Ok(SmtpTransport::builder_dangerous(&config.host)),
+        }
+        .map_err(|err| format!("Invalid SMTP server {}: {err}", config.host))?;
+        let builder = match config.port {
+            Some(port) => builder.port(port),
+            None => builder,
+        };
+        let builder = match (config.username, config.password) {
+            (Some(username), Some(password)) => {
+                builder.credentials(Credentials::new(username, password))
+            }
+            (None, None) => builder,
+            _ => {
+                return Err(format!(
+                    "Both the username and the password of SMTP server {} must be set",
+                    config.host
+                ))
+            }
+        };
+
+        let parse_mailbox = |address: &str| {
+            address
+                .parse::<Mailbox>()
+                .map_err(|err| format!("Invalid email address {address}: {err}"))
+        };
+        let from = parse_mailbox(&config.from)?;
+        let to = config
+            .to
+            .iter()
+            .map(|address| parse_mailbox(address))
+            .collect::<Result<Vec<_>, _>>()?;
+        if to.is_empty() {
+            return Err(format!(
+                "No recipient is set for SMTP server {}",
+                config.host
+            ));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from,
+            to,
+            subject: config.subject,
+        })
+    }
+
+    pub(crate) fn send(
+        &self,
+        msg: &str,
+        notification_type: &NotificationType,
+    ) -> Result<(), String> {
+        let subject = render_template(&self.subject, msg, notification_type, str::to_string);
+        let email = self
+            .to
+            .iter()
+            .fold(Message::builder(), |builder, to| builder.to(to.clone()))
+            .from(self.from.clone())
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(msg.to_string())
+            .map_err(|err| err.to_string())?;
+        self.transport.send(&email).map_err(|err| err.to_string())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::hash::Hash,
+        std::{
+            io::{BufRead, BufReader, Write},
+            net::{SocketAddr, TcpListener},
+            sync::mpsc::{channel, Receiver},
+            thread,
+        },
+    };
+
+    /// Starts an SMTP server accepting a single connection, which sends the
+    /// data of every email it receives.
+    fn start_smtp_stub() -> (SocketAddr, Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (sender, receiver) = channel();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            stream.write_all(b"220 localhost ESMTP stub\r\n").unwrap();
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() != 0 {
+                let command = line.to_ascii_uppercase();
+                let reply: &[u8] = if command.starts_with("EHLO") || command.starts_with("HELO") {
+                    b"250 localhost\r\n"
+                } else if command.starts_with("DATA") {
+                    stream
+                        .write_all(b"354 End data with <CR><LF>.<CR><LF>\r\n")
+                        .unwrap();
+                    let mut data = String::new();
+                    loop {
+                        let mut data_line = String::new();
+                        reader.read_line(&mut data_line).unwrap();
+                        if data_line == ".\r\n" {
+                            break;
+                        }
+                        data.push_str(&data_line);
+                    }
+                    sender.send(data).unwrap();
+                    b"250 OK\r\n"
+                } else if command.starts_with("QUIT") {
+                    stream.write_all(b"221 Bye\r\n").unwrap();
+                    break;
+                } else {
+                    b"250 OK\r\n"
+                };
+                stream.write_all(reply).unwrap();
+                line.clear();
+            }
+        });
+        (addr, receiver)
+    }
+
+    #[test]
+    fn test_send() {
+        let (addr, receiver) = start_smtp_stub();
+        let smtp = Smtp::new(SmtpConfig {
+            host: addr.ip().to_string(),
+            port: Some(addr.port()),
+            security: SmtpSecurity::None,
+            username: None,
+            password: None,
+            from: "watchtower@example.com".to_string(),
+            to: vec![
+                "oncall@example.com".to_string(),
+                "Ops <ops@example.com>".to_string(),
+            ],
+            subject: "Alert: {{event}}".to_string(),
+        })
+        .unwrap();
+
+        smtp.send(
+            "validator is delinquent",
+            &NotificationType::Trigger {
+                incident: Hash::new_unique(),
+            },
+        )
+        .unwrap();
+        let data = receiver.recv().unwrap();
+        assert!(data.contains("Subject: Alert: trigger\r\n"));
+        assert!(data.contains("From: watchtower@example.com\r\n"));
+        assert!(data.contains("oncall@example.com"));
+        assert!(data.contains("ops@example.com"));
+        assert!(data.contains("\r\n\r\nvalidator is delinquent"));
+    }
+
+    #[test]
+    fn test_new_invalid() {
+        let new_smtp = |username: Option<&str>, to: &[&str]| {
+            Smtp::new(SmtpConfig {
+                host: "localhost".to_string(),
+                port: None,
+                security: SmtpSecurity::None,
+                username: username.map(str::to_string),
+                password: None,
+                from: "watchtower@example.com".to_string(),
+                to: to.iter().map(|to| to.to_string()).collect(),
+                subject: String::default(),
+            })
+        };
+        assert!(new_smtp(None, &["oncall@example.com"]).is_ok());
+        assert!(new_smtp(Some("user"), &["oncall@example.com"]).is_err());
+        assert!(new_smtp(None, &["oncall"]).is_err());
+        assert!(new_smtp(None, &[]).is_err());
+    }
+}
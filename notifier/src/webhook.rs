@@ -0,0 +1,116 @@
+use {
+    crate::{config::WebhookConfig, render_template, NotificationType},
+    reqwest::{
+        blocking::Client,
+        header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE},
+    },
+    solana_sdk::hash::Hash,
+};
+
+/// A generic JSON webhook
+pub(crate) struct Webhook {
+    url: String,
+    headers: HeaderMap,
+    body_template: String,
+}
+
+impl Webhook {
+    pub(crate) fn new(config: WebhookConfig) -> Result<Self, String> {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        for (name, value) in config.headers {
+            let name = HeaderName::try_from(name.as_str())
+                .map_err(|err| format!("Invalid header name {name}: {err}"))?;
+            let value = HeaderValue::try_from(value)
+                .map_err(|err| format!("Invalid value of header {name}: {err}"))?;
+            headers.insert(name, value);
+        }
+
+        // Catch templates producing invalid JSON now rather than when alerting
+        let body = render_body(
+            &config.body_template,
+            "\"message\"\n",
+            &NotificationType::Trigger {
+                incident: Hash::default(),
+            },
+        );
+        serde_json::from_str::<serde_json::Value>(&body).map_err(|err| {
+            format!(
+                "The body template of {} is not valid JSON: {err}",
+                config.url
+            )
+        })?;
+
+        Ok(Self {
+            url: config.url,
+            headers,
+            body_template: config.body_template,
+        })
+    }
+
+    pub(crate) fn send(
+        &self,
+        client: &Client,
+        msg: &str,
+        notification_type: &NotificationType,
+    ) -> Result<(), String> {
+        let body = render_body(&self.body_template, msg, notification_type);
+        let response = client
+            .post(&self.url)
+            .headers(self.headers.clone())
+            .body(body)
+            .send()
+            .map_err(|err| err.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("{} responded with {}", self.url, response.status()));
+        }
+        Ok(())
+    }
+}
+
+fn render_body(template: &str, msg: &str, notification_type: &NotificationType) -> String {
+    render_template(template, msg, notification_type, |value| {
+        let quoted = serde_json::to_string(value).unwrap();
+        quoted[1..quoted.len() - 1].to_string()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::collections::BTreeMap};
+
+    #[test]
+    fn test_render_body() {
+        let incident = Hash::new_unique();
+        let body = render_body(
+            r#"{"text": "{{message}}", "action": "{{event}}", "id": "{{incident}}"}"#,
+            "validator \"v1\" is\ndelinquent",
+            &NotificationType::Resolve { incident },
+        );
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+            serde_json::json!({
+                "text": "validator \"v1\" is\ndelinquent",
+                "action": "resolve",
+                "id": incident.to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_new_invalid() {
+        let new_webhook = |headers: &[(&str, &str)], body_template: &str| {
+            Webhook::new(WebhookConfig {
+                url: "http://localhost".to_string(),
+                headers: headers
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect::<BTreeMap<_, _>>(),
+                body_template: body_template.to_string(),
+            })
+        };
+        assert!(new_webhook(&[("X-Key", "key")], r#"{"text": "{{message}}"}"#).is_ok());
+        assert!(new_webhook(&[("X Key", "key")], r#"{"text": "{{message}}"}"#).is_err());
+        assert!(new_webhook(&[], r#"{"text": {{message}}}"#).is_err());
+    }
+}
@@ -483,6 +483,11 @@ impl<'a> InvokeContext<'a> {
             .set_return_data(program_id, Vec::new())?;
         let logger = self.get_log_collector();
         stable_log::program_invoke(&logger, &program_id, self.get_stack_height());
+        // Nested invocations append to the trace, so get the index of this one now
+        let index_in_trace = self
+            .transaction_context
+            .get_instruction_trace_length()
+            .saturating_sub(1);
         let pre_remaining_units = self.get_remaining();
         // In program-runtime v2 we will create this VM instance only once per transaction.
         // `program_runtime_environment_v2.get_config()` will be used instead of `mock_config`.
@@ -525,6 +530,11 @@ impl<'a> InvokeContext<'a> {
         };
         let post_remaining_units = self.get_remaining();
         *compute_units_consumed = pre_remaining_units.saturating_sub(post_remaining_units);
+        if let Some(logger) = logger.as_ref() {
+            if let Ok(mut logger) = logger.try_borrow_mut() {
+                logger.record_instruction_compute_units(index_in_trace, *compute_units_consumed);
+            }
+        }
 
         if builtin_id == program_id
             && result.is_ok()
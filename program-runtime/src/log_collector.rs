@@ -8,6 +8,9 @@ pub struct LogCollector {
     bytes_written: usize,
     bytes_limit: Option<usize>,
     limit_warning: bool,
+    /// The compute units consumed by each instruction, by index in the
+    /// instruction trace, if recorded
+    instruction_compute_units: Option<Vec<u64>>,
 }
 
 impl Default for LogCollector {
@@ -17,6 +20,7 @@ impl Default for LogCollector {
             bytes_written: 0,
             bytes_limit: Some(LOG_MESSAGES_BYTES_LIMIT),
             limit_warning: false,
+            instruction_compute_units: None,
         }
     }
 }
@@ -40,6 +44,25 @@ impl LogCollector {
         }
     }
 
+    /// Also record the compute units consumed by each instruction, which are
+    /// not subject to the bytes limit
+    pub fn enable_instruction_compute_units_recording(&mut self) {
+        self.instruction_compute_units.get_or_insert_with(Vec::new);
+    }
+
+    pub fn record_instruction_compute_units(
+        &mut self,
+        index_in_trace: usize,
+        compute_units_consumed: u64,
+    ) {
+        if let Some(instruction_compute_units) = self.instruction_compute_units.as_mut() {
+            if instruction_compute_units.len() <= index_in_trace {
+                instruction_compute_units.resize(index_in_trace.saturating_add(1), 0);
+            }
+            instruction_compute_units[index_in_trace] = compute_units_consumed;
+        }
+    }
+
     pub fn get_recorded_content(&self) -> &[String] {
         self.messages.as_slice()
     }
@@ -58,6 +81,12 @@ impl LogCollector {
     pub fn into_messages(self) -> Vec<String> {
         self.messages
     }
+
+    /// Returns the messages, and the compute units consumed by each
+    /// instruction if they were recorded
+    pub fn into_messages_and_instruction_compute_units(self) -> (Vec<String>, Option<Vec<u64>>) {
+        (self.messages, self.instruction_compute_units)
+    }
 }
 
 /// Convenience macro to log a message with an `Option<Rc<RefCell<LogCollector>>>`
@@ -119,4 +148,24 @@ pub(crate) mod tests {
         }
         assert_eq!(logs.last(), Some(&"Log truncated".to_string()));
     }
+
+    #[test]
+    fn test_instruction_compute_units() {
+        let mut lc = LogCollector::default();
+        lc.record_instruction_compute_units(0, 100);
+        assert_eq!(
+            lc.into_messages_and_instruction_compute_units(),
+            (vec![], None)
+        );
+
+        let mut lc = LogCollector::default();
+        lc.enable_instruction_compute_units_recording();
+        lc.record_instruction_compute_units(2, 30);
+        lc.record_instruction_compute_units(1, 20);
+        lc.record_instruction_compute_units(0, 100);
+        assert_eq!(
+            lc.into_messages_and_instruction_compute_units(),
+            (vec![], Some(vec![100, 20, 30]))
+        );
+    }
 }
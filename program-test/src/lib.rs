@@ -81,6 +81,10 @@ pub enum ProgramTestError {
     /// The chosen warp slot is not in the future, so warp is not performed
     #[error("Warp slot not in the future")]
     InvalidWarpSlot,
+    /// The snapshot was taken by another context, or before a warp rooted the
+    /// working bank, so it cannot be restored
+    #[error("Snapshot cannot be restored")]
+    InvalidSnapshot,
 }
 
 thread_local! {
@@ -999,6 +1003,10 @@ impl<T> Drop for DroppableTask<T> {
     }
 }
 
+/// Identifies a state of the working bank saved by `ProgramTestContext::snapshot()`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ProgramTestSnapshotId(Slot);
+
 pub struct ProgramTestContext {
     pub banks_client: BanksClient,
     pub last_blockhash: Hash,
@@ -1006,6 +1014,8 @@ pub struct ProgramTestContext {
     genesis_config: GenesisConfig,
     bank_forks: Arc<RwLock<BankForks>>,
     block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
+    /// The frozen banks saved by `snapshot()`, by slot
+    snapshots: HashMap<Slot, Arc<Bank>>,
     _bank_task: DroppableTask<()>,
 }
 
@@ -1052,6 +1062,7 @@ impl ProgramTestContext {
             genesis_config: genesis_config_info.genesis_config,
             bank_forks,
             block_commitment_cache,
+            snapshots: HashMap::new(),
             _bank_task: bank_task,
         }
     }
@@ -1234,6 +1245,96 @@ impl ProgramTestContext {
         Ok(blockhash)
     }
 
+    /// Save the state of the working bank, to be restored later with `restore()`
+    ///
+    /// The working bank is frozen and kept as the snapshot, and a child bank at
+    /// the next slot becomes the working bank.  Warping roots the working bank,
+    /// so a snapshot taken before a warp cannot be restored after it.
+    pub fn snapshot(&mut self) -> ProgramTestSnapshotId {
+        let mut bank_forks = self.bank_forks.write().unwrap();
+        let bank = bank_forks.working_bank();
+
+        // Fill ticks until a new blockhash is recorded, otherwise retried transactions will have
+        // the same signature
+        bank.fill_bank_with_ticks_for_tests();
+        bank.freeze();
+
+        let snapshot_slot = bank.slot();
+        bank_forks.insert(Bank::new_from_parent(
+            bank.clone(),
+            &Pubkey::default(),
+            snapshot_slot + 1,
+        ));
+        self.snapshots.insert(snapshot_slot, bank);
+        Self::update_working_slot(
+            &bank_forks,
+            &self.block_commitment_cache,
+            &mut self.last_blockhash,
+        );
+        ProgramTestSnapshotId(snapshot_slot)
+    }
+
+    /// Return to the state saved by `snapshot()`, discarding every change made
+    /// since then
+    ///
+    /// The new working bank is a child of the snapshot bank at the next unused
+    /// slot, so the slot keeps increasing across restores.  A snapshot can be
+    /// restored any number of times.
+    pub fn restore(&mut self, snapshot_id: ProgramTestSnapshotId) -> Result<(), ProgramTestError> {
+        let mut bank_forks = self.bank_forks.write().unwrap();
+        let snapshot_bank = self
+            .snapshots
+            .get(&snapshot_id.0)
+            .ok_or(ProgramTestError::InvalidSnapshot)?;
+        if snapshot_bank.slot() < bank_forks.root() {
+            self.snapshots.remove(&snapshot_id.0);
+            return Err(ProgramTestError::InvalidSnapshot);
+        }
+
+        let working_slot = bank_forks.highest_slot() + 1;
+        let working_bank = bank_forks.insert(Bank::new_from_parent(
+            snapshot_bank.clone(),
+            &Pubkey::default(),
+            working_slot,
+        ));
+        Self::update_working_slot(
+            &bank_forks,
+            &self.block_commitment_cache,
+            &mut self.last_blockhash,
+        );
+
+        // Drop the banks of the abandoned fork, the snapshots keep their own banks alive
+        let abandoned_slots: Vec<Slot> = bank_forks
+            .banks()
+            .keys()
+            .filter(|slot| !working_bank.ancestors.contains_key(slot))
+            .copied()
+            .collect();
+        for slot in abandoned_slots {
+            bank_forks.remove(slot);
+        }
+        Ok(())
+    }
+
+    /// Point the block commitment cache and the last blockhash at the working bank
+    ///
+    /// This takes the fields it updates rather than `&mut self`, so that it can
+    /// be called while the `bank_forks` write lock is held.
+    fn update_working_slot(
+        bank_forks: &BankForks,
+        block_commitment_cache: &RwLock<BlockCommitmentCache>,
+        last_blockhash: &mut Hash,
+    ) {
+        let bank = bank_forks.working_bank();
+        // Update block commitment cache, otherwise banks server will poll at
+        // the wrong slot
+        block_commitment_cache
+            .write()
+            .unwrap()
+            .set_all_slots(bank.slot(), bank.slot());
+        *last_blockhash = bank.last_blockhash();
+    }
+
     /// record a hard fork slot in working bank; should be in the past
     pub fn register_hard_fork(&mut self, hard_fork_slot: Slot) {
         self.bank_forks
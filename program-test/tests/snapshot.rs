@@ -0,0 +1,78 @@
+use {
+    solana_program_test::{ProgramTest, ProgramTestError},
+    solana_sdk::{
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        system_instruction,
+        transaction::Transaction,
+    },
+};
+
+#[tokio::test]
+async fn snapshot_and_restore() {
+    let mut context = ProgramTest::default().start_with_context().await;
+    let recipient = Pubkey::new_unique();
+    let transfer = |payer: &Keypair, lamports: u64, blockhash| {
+        Transaction::new_signed_with_payer(
+            &[system_instruction::transfer(
+                &payer.pubkey(),
+                &recipient,
+                lamports,
+            )],
+            Some(&payer.pubkey()),
+            &[payer],
+            blockhash,
+        )
+    };
+
+    context
+        .banks_client
+        .process_transaction(transfer(&context.payer, 1_000_000, context.last_blockhash))
+        .await
+        .unwrap();
+    let snapshot_id = context.snapshot();
+    let snapshot_slot = context.banks_client.get_root_slot().await.unwrap();
+
+    let transaction = transfer(&context.payer, 2_000_000, context.last_blockhash);
+    context
+        .banks_client
+        .process_transaction(transaction.clone())
+        .await
+        .unwrap();
+    assert_eq!(
+        context.banks_client.get_balance(recipient).await.unwrap(),
+        3_000_000
+    );
+
+    // Changes since the snapshot are discarded, including processed transactions
+    for _ in 0..2 {
+        context.restore(snapshot_id).unwrap();
+        assert!(context.banks_client.get_root_slot().await.unwrap() > snapshot_slot);
+        assert_eq!(
+            context.banks_client.get_balance(recipient).await.unwrap(),
+            1_000_000
+        );
+        context
+            .banks_client
+            .process_transaction(transaction.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            context.banks_client.get_balance(recipient).await.unwrap(),
+            3_000_000
+        );
+    }
+
+    // Warping roots the working bank, which invalidates earlier snapshots
+    let later_snapshot_id = context.snapshot();
+    let slot = context.banks_client.get_root_slot().await.unwrap();
+    context.warp_to_slot(slot + 10).unwrap();
+    assert_eq!(
+        context.restore(snapshot_id),
+        Err(ProgramTestError::InvalidSnapshot)
+    );
+    assert_eq!(
+        context.restore(later_snapshot_id),
+        Err(ProgramTestError::InvalidSnapshot)
+    );
+}
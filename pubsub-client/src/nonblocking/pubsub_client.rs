@@ -30,14 +30,16 @@
 //!
 //! This pattern is illustrated in the example below.
 //!
-//! By default the [`block_subscribe`] and [`vote_subscribe`] events are
-//! disabled on RPC nodes. They can be enabled by passing
-//! `--rpc-pubsub-enable-block-subscription` and
+//! By default the [`block_subscribe`], [`transaction_subscribe`] and
+//! [`vote_subscribe`] events are disabled on RPC nodes. They can be enabled by
+//! passing `--rpc-pubsub-enable-block-subscription`,
+//! `--rpc-pubsub-enable-transaction-subscription` and
 //! `--rpc-pubsub-enable-vote-subscription` to `solana-validator`. When these
 //! methods are disabled, the RPC server will return a "Method not found" error
 //! message.
 //!
 //! [`block_subscribe`]: https://docs.rs/solana-rpc/latest/solana_rpc/rpc_pubsub/trait.RpcSolPubSub.html#tymethod.block_subscribe
+//! [`transaction_subscribe`]: https://docs.rs/solana-rpc/latest/solana_rpc/rpc_pubsub/trait.RpcSolPubSub.html#tymethod.transaction_subscribe
 //! [`vote_subscribe`]: https://docs.rs/solana-rpc/latest/solana_rpc/rpc_pubsub/trait.RpcSolPubSub.html#tymethod.vote_subscribe
 //!
 //! # Examples
@@ -180,13 +182,14 @@ use {
         config::{
             RpcAccountInfoConfig, RpcBlockSubscribeConfig, RpcBlockSubscribeFilter,
             RpcProgramAccountsConfig, RpcSignatureSubscribeConfig, RpcTransactionLogsConfig,
-            RpcTransactionLogsFilter,
+            RpcTransactionLogsFilter, RpcTransactionSubscribeConfig, RpcTransactionSubscribeFilter,
         },
         error_object::RpcErrorObject,
         filter::maybe_map_filters,
         response::{
             Response as RpcResponse, RpcBlockUpdate, RpcKeyedAccount, RpcLogsResponse,
-            RpcSignatureResult, RpcVersionInfo, RpcVote, SlotInfo, SlotUpdate,
+            RpcSignatureResult, RpcTransactionUpdate, RpcVersionInfo, RpcVote, SlotInfo,
+            SlotUpdate,
         },
     },
     solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature},
@@ -396,6 +399,25 @@ impl PubsubClient {
         self.subscribe("block", json!([filter, config])).await
     }
 
+    /// Subscribe to transaction events.
+    ///
+    /// Receives messages of type [`RpcTransactionUpdate`] for each transaction
+    /// matching the filter, when its block reaches the requested commitment.
+    ///
+    /// This method is disabled by default. It can be enabled by passing
+    /// `--rpc-pubsub-enable-transaction-subscription` to `solana-validator`.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `transactionSubscribe` RPC method.
+    pub async fn transaction_subscribe(
+        &self,
+        filter: RpcTransactionSubscribeFilter,
+        config: Option<RpcTransactionSubscribeConfig>,
+    ) -> SubscribeResult<'_, RpcResponse<RpcTransactionUpdate>> {
+        self.subscribe("transaction", json!([filter, config])).await
+    }
+
     /// Subscribe to transaction log events.
     ///
     /// Receives messages of type [`RpcLogsResponse`] when a transaction is committed.
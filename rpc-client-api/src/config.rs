@@ -162,6 +162,36 @@ pub struct RpcProgramAccountsConfig {
     #[serde(flatten)]
     pub account_config: RpcAccountInfoConfig,
     pub with_context: Option<bool>,
+    /// Return at most this many accounts, in pubkey order
+    pub limit: Option<usize>,
+    /// Only return the accounts with a pubkey greater than this base-58
+    /// encoded cursor, usually the last pubkey of the previous page
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountSubscribeConfig {
+    #[serde(flatten)]
+    pub account_config: RpcAccountInfoConfig,
+    /// Replay the notifications missed since this slot, inclusive
+    pub from_slot: Option<Slot>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcProgramSubscribeConfig {
+    #[serde(flatten)]
+    pub program_config: RpcProgramAccountsConfig,
+    /// Replay the notifications missed since this slot, inclusive
+    pub from_slot: Option<Slot>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSlotSubscribeConfig {
+    /// Replay the notifications missed since this slot, inclusive
+    pub from_slot: Option<Slot>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -177,6 +207,8 @@ pub enum RpcTransactionLogsFilter {
 pub struct RpcTransactionLogsConfig {
     #[serde(flatten)]
     pub commitment: Option<CommitmentConfig>,
+    /// Replay the notifications missed since this slot, inclusive
+    pub from_slot: Option<Slot>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -212,6 +244,30 @@ pub struct RpcBlockSubscribeConfig {
     pub max_supported_transaction_version: Option<u8>,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransactionSubscribeFilter {
+    /// Notify transactions that mention any of these accounts; all if empty
+    pub account_include: Option<Vec<String>>, // Pubkeys as base-58 strings
+    /// Skip transactions that mention any of these accounts
+    pub account_exclude: Option<Vec<String>>, // Pubkeys as base-58 strings
+    /// Only notify transactions that mention all of these accounts
+    pub account_required: Option<Vec<String>>, // Pubkeys as base-58 strings
+    /// Include vote transactions, false by default
+    pub vote: Option<bool>,
+    /// Include failed transactions, true by default
+    pub failed: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransactionSubscribeConfig {
+    #[serde(flatten)]
+    pub commitment: Option<CommitmentConfig>,
+    pub encoding: Option<UiTransactionEncoding>,
+    pub max_supported_transaction_version: Option<u8>,
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcSignaturesForAddressConfig {
@@ -223,6 +279,17 @@ pub struct RpcSignaturesForAddressConfig {
     pub min_context_slot: Option<Slot>,
 }
 
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountHistoryConfig {
+    pub before: Option<String>, // Signature as base-58 string
+    pub until: Option<String>,  // Signature as base-58 string
+    /// Start with the last change at or before this slot, whose post-balance
+    /// is the balance of the account at this slot
+    pub slot: Option<Slot>,
+    pub limit: Option<usize>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum RpcEncodingConfigWrapper<T> {
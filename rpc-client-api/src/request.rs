@@ -12,6 +12,7 @@ pub enum RpcRequest {
         method: &'static str,
     },
     DeregisterNode,
+    GetAccountHistory,
     GetAccountInfo,
     GetBalance,
     GetBlock,
@@ -122,6 +123,7 @@ impl fmt::Display for RpcRequest {
         let method = match self {
             RpcRequest::Custom { method } => method,
             RpcRequest::DeregisterNode => "deregisterNode",
+            RpcRequest::GetAccountHistory => "getAccountHistory",
             RpcRequest::GetAccountInfo => "getAccountInfo",
             RpcRequest::GetBalance => "getBalance",
             RpcRequest::GetBlock => "getBlock",
@@ -204,6 +206,7 @@ pub const MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT: usize = 1_000;
 pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
 pub const NUM_LARGEST_ACCOUNTS: usize = 20;
 pub const MAX_GET_PROGRAM_ACCOUNT_FILTERS: usize = 4;
+pub const MAX_GET_PROGRAM_ACCOUNTS_LIMIT: usize = 10_000;
 pub const MAX_GET_SLOT_LEADERS: usize = 5000;
 
 // Limit the length of the `epoch_credits` array for each validator in a `get_vote_accounts`
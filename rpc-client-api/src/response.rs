@@ -10,8 +10,9 @@ use {
         transaction::{Result, TransactionError},
     },
     solana_transaction_status::{
-        ConfirmedTransactionStatusWithSignature, TransactionConfirmationStatus, UiConfirmedBlock,
-        UiTransactionReturnData,
+        AccountBalanceChange, ConfirmedTransactionStatusWithSignature,
+        EncodedTransactionWithStatusMeta, TransactionConfirmationStatus, UiConfirmedBlock,
+        UiTransactionReturnData, UiTransactionTokenBalance,
     },
     std::{collections::HashMap, fmt, net::SocketAddr, str::FromStr},
     thiserror::Error,
@@ -484,6 +485,45 @@ pub struct RpcConfirmedTransactionStatusWithSignature {
     pub confirmation_status: Option<TransactionConfirmationStatus>,
 }
 
+/// The balances of an account before and after one of its transactions
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountBalanceChange {
+    pub signature: String,
+    pub slot: Slot,
+    pub block_time: Option<UnixTimestamp>,
+    pub err: Option<TransactionError>,
+    pub pre_balance: u64,  // lamports
+    pub post_balance: u64, // lamports
+    pub pre_token_balance: Option<UiTransactionTokenBalance>,
+    pub post_token_balance: Option<UiTransactionTokenBalance>,
+}
+
+impl From<AccountBalanceChange> for RpcAccountBalanceChange {
+    fn from(value: AccountBalanceChange) -> Self {
+        let AccountBalanceChange {
+            signature,
+            slot,
+            block_time,
+            err,
+            pre_balance,
+            post_balance,
+            pre_token_balance,
+            post_token_balance,
+        } = value;
+        Self {
+            signature: signature.to_string(),
+            slot,
+            block_time,
+            err,
+            pre_balance,
+            post_balance,
+            pre_token_balance: pre_token_balance.map(Into::into),
+            post_token_balance: post_token_balance.map(Into::into),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcPerfSample {
@@ -521,6 +561,16 @@ pub struct RpcBlockUpdate {
     pub err: Option<RpcBlockUpdateError>,
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransactionUpdate {
+    pub signature: String, // Signature as base-58 string
+    pub slot: Slot,
+    #[serde(flatten)]
+    pub transaction: EncodedTransactionWithStatusMeta,
+    pub block_time: Option<UnixTimestamp>,
+}
+
 impl From<ConfirmedTransactionStatusWithSignature> for RpcConfirmedTransactionStatusWithSignature {
     fn from(value: ConfirmedTransactionStatusWithSignature) -> Self {
         let ConfirmedTransactionStatusWithSignature {
@@ -11,11 +11,12 @@ use {
         config::RpcBlockProductionConfig,
         request::RpcRequest,
         response::{
-            Response, RpcAccountBalance, RpcBlockProduction, RpcBlockProductionRange, RpcBlockhash,
-            RpcConfirmedTransactionStatusWithSignature, RpcContactInfo, RpcFees, RpcIdentity,
-            RpcInflationGovernor, RpcInflationRate, RpcInflationReward, RpcKeyedAccount,
-            RpcPerfSample, RpcPrioritizationFee, RpcResponseContext, RpcSimulateTransactionResult,
-            RpcSnapshotSlotInfo, RpcStakeActivation, RpcSupply, RpcVersionInfo, RpcVoteAccountInfo,
+            Response, RpcAccountBalance, RpcAccountBalanceChange, RpcBlockProduction,
+            RpcBlockProductionRange, RpcBlockhash, RpcConfirmedTransactionStatusWithSignature,
+            RpcContactInfo, RpcFees, RpcIdentity, RpcInflationGovernor, RpcInflationRate,
+            RpcInflationReward, RpcKeyedAccount, RpcPerfSample, RpcPrioritizationFee,
+            RpcResponseContext, RpcSimulateTransactionResult, RpcSnapshotSlotInfo,
+            RpcStakeActivation, RpcSupply, RpcVersionInfo, RpcVoteAccountInfo,
             RpcVoteAccountStatus, StakeActivationState,
         },
     },
@@ -414,6 +415,16 @@ impl RpcSender for MockSender {
                     confirmation_status: Some(TransactionConfirmationStatus::Finalized),
                 }])?
             }
+            "getAccountHistory" => serde_json::to_value(vec![RpcAccountBalanceChange {
+                signature: crate::mock_sender_for_cli::SIGNATURE.to_string(),
+                slot: 123,
+                block_time: None,
+                err: None,
+                pre_balance: 50,
+                post_balance: 20,
+                pre_token_balance: None,
+                post_token_balance: None,
+            }])?,
             "getBlockTime" => serde_json::to_value(UnixTimestamp::default())?,
             "getEpochSchedule" => serde_json::to_value(EpochSchedule::default())?,
             "getRecentPerformanceSamples" => serde_json::to_value(vec![RpcPerfSample {
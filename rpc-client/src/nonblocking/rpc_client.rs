@@ -3062,6 +3062,50 @@ impl RpcClient {
         Ok(result)
     }
 
+    /// Returns the balance changes of an address, in descending ledger order,
+    /// from the metadata of its finalized transactions in long-term storage.
+    ///
+    /// Each change holds the lamport balance of the address before and after
+    /// the transaction, and its token balance if it is a token account.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the [`getAccountHistory`] RPC method.
+    ///
+    /// [`getAccountHistory`]: https://docs.solana.com/api/http#getaccounthistory
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::{client_error::Error, config::RpcAccountHistoryConfig};
+    /// # use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+    /// # use solana_sdk::{signature::Signer, signer::keypair::Keypair};
+    /// # futures::executor::block_on(async {
+    /// #     let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// #     let alice = Keypair::new();
+    /// let config = RpcAccountHistoryConfig {
+    ///     limit: Some(10),
+    ///     ..RpcAccountHistoryConfig::default()
+    /// };
+    /// let balance_changes = rpc_client
+    ///     .get_account_history_with_config(&alice.pubkey(), config)
+    ///     .await?;
+    /// #     Ok::<(), Error>(())
+    /// # })?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub async fn get_account_history_with_config(
+        &self,
+        address: &Pubkey,
+        config: RpcAccountHistoryConfig,
+    ) -> ClientResult<Vec<RpcAccountBalanceChange>> {
+        self.send(
+            RpcRequest::GetAccountHistory,
+            json!([address.to_string(), config]),
+        )
+        .await
+    }
+
     #[deprecated(
         since = "1.7.0",
         note = "Please use RpcClient::get_signatures_for_address() instead"
@@ -4441,6 +4485,8 @@ impl RpcClient {
     ///         min_context_slot: Some(1234),
     ///     },
     ///     with_context: Some(false),
+    ///     limit: None,
+    ///     after: None,
     /// };
     /// let accounts = rpc_client.get_program_accounts_with_config(
     ///     &alice.pubkey(),
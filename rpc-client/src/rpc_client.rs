@@ -2521,6 +2521,41 @@ impl RpcClient {
         )
     }
 
+    /// Returns the balance changes of an address, in descending ledger order,
+    /// from the metadata of its finalized transactions in long-term storage.
+    ///
+    /// Each change holds the lamport balance of the address before and after
+    /// the transaction, and its token balance if it is a token account.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the [`getAccountHistory`] RPC method.
+    ///
+    /// [`getAccountHistory`]: https://docs.solana.com/api/http#getaccounthistory
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::{client_error::Error, config::RpcAccountHistoryConfig};
+    /// # use solana_rpc_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{signature::Signer, signer::keypair::Keypair};
+    /// # let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// # let alice = Keypair::new();
+    /// let config = RpcAccountHistoryConfig {
+    ///     limit: Some(10),
+    ///     ..RpcAccountHistoryConfig::default()
+    /// };
+    /// let balance_changes = rpc_client.get_account_history_with_config(&alice.pubkey(), config)?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn get_account_history_with_config(
+        &self,
+        address: &Pubkey,
+        config: RpcAccountHistoryConfig,
+    ) -> ClientResult<Vec<RpcAccountBalanceChange>> {
+        self.invoke((self.rpc_client.as_ref()).get_account_history_with_config(address, config))
+    }
+
     #[deprecated(
         since = "1.7.0",
         note = "Please use RpcClient::get_signatures_for_address() instead"
@@ -3620,6 +3655,8 @@ impl RpcClient {
     ///         min_context_slot: Some(1234),
     ///     },
     ///     with_context: Some(false),
+    ///     limit: None,
+    ///     after: None,
     /// };
     /// let accounts = rpc_client.get_program_accounts_with_config(
     ///     &alice.pubkey(),
@@ -4385,6 +4422,8 @@ mod tests {
                             min_context_slot: None,
                         },
                         with_context: None,
+                        limit: None,
+                        after: None,
                     },
                 )
                 .unwrap();
@@ -4419,6 +4458,8 @@ mod tests {
                             min_context_slot: None,
                         },
                         with_context: Some(true),
+                        limit: None,
+                        after: None,
                     },
                 )
                 .unwrap();
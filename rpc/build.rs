@@ -0,0 +1,27 @@
+fn main() -> Result<(), std::io::Error> {
+    const PROTOC_ENVAR: &str = "PROTOC";
+    if std::env::var(PROTOC_ENVAR).is_err() {
+        #[cfg(not(windows))]
+        std::env::set_var(PROTOC_ENVAR, protobuf_src::protoc());
+    }
+
+    let proto_base_path = std::path::PathBuf::from("proto");
+    // `confirmed_block.proto` is a copy of the one of solana-storage-proto, whose generated types
+    // are used through `extern_path`
+    for proto_file in ["rpc.proto", "confirmed_block.proto"] {
+        println!(
+            "cargo:rerun-if-changed={}",
+            proto_base_path.join(proto_file).display()
+        );
+    }
+    let proto = proto_base_path.join("rpc.proto");
+
+    tonic_build::configure()
+        .build_client(true)
+        .build_server(true)
+        .extern_path(
+            ".solana.storage.ConfirmedBlock",
+            "::solana_storage_proto::convert::generated",
+        )
+        .compile(&[proto], &[proto_base_path])
+}
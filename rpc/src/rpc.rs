@@ -15,7 +15,9 @@ use {
     },
     solana_accounts_db::{
         accounts::AccountAddressFilter,
-        accounts_index::{AccountIndex, AccountSecondaryIndexes, IndexKey, ScanConfig},
+        accounts_index::{
+            AccountIndex, AccountSecondaryIndexes, IndexKey, MemcmpIndex, ScanConfig,
+        },
         inline_spl_token::{SPL_TOKEN_ACCOUNT_MINT_OFFSET, SPL_TOKEN_ACCOUNT_OWNER_OFFSET},
         inline_spl_token_2022::{self, ACCOUNTTYPE_ACCOUNT},
     },
@@ -40,8 +42,9 @@ use {
         request::{
             TokenAccountsFilter, DELINQUENT_VALIDATOR_SLOT_DISTANCE,
             MAX_GET_CONFIRMED_BLOCKS_RANGE, MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT,
-            MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_SLOT_RANGE, MAX_GET_PROGRAM_ACCOUNT_FILTERS,
-            MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS, MAX_GET_SLOT_LEADERS, MAX_MULTIPLE_ACCOUNTS,
+            MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_SLOT_RANGE, MAX_GET_PROGRAM_ACCOUNTS_LIMIT,
+            MAX_GET_PROGRAM_ACCOUNT_FILTERS, MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS,
+            MAX_GET_SLOT_LEADERS, MAX_MULTIPLE_ACCOUNTS,
             MAX_RPC_VOTE_ACCOUNT_INFO_EPOCH_CREDITS_HISTORY, NUM_LARGEST_ACCOUNTS,
         },
         response::{Response as RpcResponse, *},
@@ -104,6 +107,7 @@ use {
         collections::{HashMap, HashSet},
         convert::TryFrom,
         net::SocketAddr,
+        path::PathBuf,
         str::FromStr,
         sync::{
             atomic::{AtomicBool, AtomicU64, Ordering},
@@ -170,6 +174,7 @@ pub struct RpcBigtableConfig {
     pub bigtable_instance_name: String,
     pub bigtable_app_profile_id: String,
     pub timeout: Option<Duration>,
+    pub local_storage_path: Option<PathBuf>,
 }
 
 impl Default for RpcBigtableConfig {
@@ -181,10 +186,19 @@ impl Default for RpcBigtableConfig {
             bigtable_instance_name,
             bigtable_app_profile_id,
             timeout: None,
+            local_storage_path: None,
         }
     }
 }
 
+/// A page of the accounts returned by `getProgramAccounts`, in pubkey order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountsPage {
+    /// Only return the accounts with a pubkey greater than this one
+    pub after: Option<Pubkey>,
+    pub limit: usize,
+}
+
 #[derive(Clone)]
 pub struct JsonRpcRequestProcessor {
     bank_forks: Arc<RwLock<BankForks>>,
@@ -456,6 +470,29 @@ impl JsonRpcRequestProcessor {
         Ok(new_response(&bank, accounts))
     }
 
+    /// Like `get_multiple_accounts`, returning the accounts without encoding them
+    pub fn get_raw_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+        config: RpcContextConfig,
+    ) -> Result<RpcResponse<Vec<Option<AccountSharedData>>>> {
+        let max_multiple_accounts = self
+            .config
+            .max_multiple_accounts
+            .unwrap_or(MAX_MULTIPLE_ACCOUNTS);
+        if pubkeys.len() > max_multiple_accounts {
+            return Err(Error::invalid_params(format!(
+                "Too many inputs provided; max {max_multiple_accounts}"
+            )));
+        }
+        let bank = self.get_bank_with_config(config)?;
+        let accounts = pubkeys
+            .iter()
+            .map(|pubkey| bank.get_account(pubkey))
+            .collect();
+        Ok(new_response(&bank, accounts))
+    }
+
     pub fn get_minimum_balance_for_rent_exemption(
         &self,
         data_len: usize,
@@ -469,8 +506,9 @@ impl JsonRpcRequestProcessor {
         &self,
         program_id: &Pubkey,
         config: Option<RpcAccountInfoConfig>,
-        mut filters: Vec<RpcFilterType>,
+        filters: Vec<RpcFilterType>,
         with_context: bool,
+        page: Option<AccountsPage>,
     ) -> Result<OptionalContext<Vec<RpcKeyedAccount>>> {
         let RpcAccountInfoConfig {
             encoding,
@@ -483,16 +521,7 @@ impl JsonRpcRequestProcessor {
             min_context_slot,
         })?;
         let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
-        optimize_filters(&mut filters);
-        let keyed_accounts = {
-            if let Some(owner) = get_spl_token_owner_filter(program_id, &filters) {
-                self.get_filtered_spl_token_accounts_by_owner(&bank, program_id, &owner, filters)?
-            } else if let Some(mint) = get_spl_token_mint_filter(program_id, &filters) {
-                self.get_filtered_spl_token_accounts_by_mint(&bank, program_id, &mint, filters)?
-            } else {
-                self.get_filtered_program_accounts(&bank, program_id, filters)?
-            }
-        };
+        let keyed_accounts = self.get_keyed_program_accounts(&bank, program_id, filters, page)?;
         let accounts = if is_known_spl_token_id(program_id)
             && encoding == UiAccountEncoding::JsonParsed
         {
@@ -514,6 +543,48 @@ impl JsonRpcRequestProcessor {
         })
     }
 
+    /// Like `get_program_accounts`, returning the accounts without encoding them
+    pub fn get_raw_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        config: RpcContextConfig,
+        filters: Vec<RpcFilterType>,
+    ) -> Result<RpcResponse<Vec<(Pubkey, AccountSharedData)>>> {
+        let bank = self.get_bank_with_config(config)?;
+        let keyed_accounts = self.get_keyed_program_accounts(&bank, program_id, filters, None)?;
+        Ok(new_response(&bank, keyed_accounts))
+    }
+
+    fn get_keyed_program_accounts(
+        &self,
+        bank: &Bank,
+        program_id: &Pubkey,
+        mut filters: Vec<RpcFilterType>,
+        page: Option<AccountsPage>,
+    ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
+        optimize_filters(&mut filters);
+        if let Some(owner) = get_spl_token_owner_filter(program_id, &filters) {
+            self.get_filtered_spl_token_accounts_by_owner(bank, program_id, &owner, filters, page)
+        } else if let Some(mint) = get_spl_token_mint_filter(program_id, &filters) {
+            self.get_filtered_spl_token_accounts_by_mint(bank, program_id, &mint, filters, page)
+        } else if let Some((memcmp_index, key)) = get_memcmp_index_filter(
+            program_id,
+            &filters,
+            &self.config.account_indexes.memcmp_indexes,
+        ) {
+            self.get_filtered_memcmp_indexed_accounts(
+                bank,
+                program_id,
+                memcmp_index,
+                key,
+                filters,
+                page,
+            )
+        } else {
+            self.get_filtered_program_accounts(bank, program_id, filters, page)
+        }
+    }
+
     pub async fn get_inflation_reward(
         &self,
         addresses: Vec<Pubkey>,
@@ -1075,17 +1146,33 @@ impl JsonRpcRequestProcessor {
         slot: Slot,
         config: Option<RpcEncodingConfigWrapper<RpcBlockConfig>>,
     ) -> Result<Option<UiConfirmedBlock>> {
+        let config = config
+            .map(|config| config.convert_to_current())
+            .unwrap_or_default();
+        let encoding = config.encoding.unwrap_or(UiTransactionEncoding::Json);
+        let encoding_options = BlockEncodingOptions {
+            transaction_details: config.transaction_details.unwrap_or_default(),
+            show_rewards: config.rewards.unwrap_or(true),
+            max_supported_transaction_version: config.max_supported_transaction_version,
+        };
+        let commitment = config.commitment.unwrap_or_default();
+        self.get_confirmed_block(slot, commitment)
+            .await?
+            .map(|confirmed_block| -> Result<UiConfirmedBlock> {
+                Ok(confirmed_block
+                    .encode_with_options(encoding, encoding_options)
+                    .map_err(RpcCustomError::from)?)
+            })
+            .transpose()
+    }
+
+    /// Like `get_block`, returning the block without encoding it
+    pub async fn get_confirmed_block(
+        &self,
+        slot: Slot,
+        commitment: CommitmentConfig,
+    ) -> Result<Option<ConfirmedBlock>> {
         if self.config.enable_rpc_transaction_history {
-            let config = config
-                .map(|config| config.convert_to_current())
-                .unwrap_or_default();
-            let encoding = config.encoding.unwrap_or(UiTransactionEncoding::Json);
-            let encoding_options = BlockEncodingOptions {
-                transaction_details: config.transaction_details.unwrap_or_default(),
-                show_rewards: config.rewards.unwrap_or(true),
-                max_supported_transaction_version: config.max_supported_transaction_version,
-            };
-            let commitment = config.commitment.unwrap_or_default();
             check_is_at_least_confirmed(commitment)?;
 
             // Block is old enough to be finalized
@@ -1099,40 +1186,36 @@ impl JsonRpcRequestProcessor {
                 self.check_blockstore_writes_complete(slot)?;
                 let result = self.blockstore.get_rooted_block(slot, true);
                 self.check_blockstore_root(&result, slot)?;
-                let encode_block = |confirmed_block: ConfirmedBlock| -> Result<UiConfirmedBlock> {
-                    let mut encoded_block = confirmed_block
-                        .encode_with_options(encoding, encoding_options)
-                        .map_err(RpcCustomError::from)?;
+                let fill_genesis_block = |mut confirmed_block: ConfirmedBlock| {
                     if slot == 0 {
-                        encoded_block.block_time = Some(self.genesis_creation_time());
-                        encoded_block.block_height = Some(0);
+                        confirmed_block.block_time = Some(self.genesis_creation_time());
+                        confirmed_block.block_height = Some(0);
                     }
-                    Ok(encoded_block)
+                    confirmed_block
                 };
                 if result.is_err() {
                     if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
                         let bigtable_result =
                             bigtable_ledger_storage.get_confirmed_block(slot).await;
                         self.check_bigtable_result(&bigtable_result)?;
-                        return bigtable_result.ok().map(encode_block).transpose();
+                        return Ok(bigtable_result.ok().map(fill_genesis_block));
                     }
                 }
                 self.check_slot_cleaned_up(&result, slot)?;
-                return result
+                return Ok(result
                     .ok()
                     .map(ConfirmedBlock::from)
-                    .map(encode_block)
-                    .transpose();
+                    .map(fill_genesis_block));
             } else if commitment.is_confirmed() {
                 // Check if block is confirmed
                 let confirmed_bank = self.bank(Some(CommitmentConfig::confirmed()));
                 if confirmed_bank.status_cache_ancestors().contains(&slot) {
                     self.check_blockstore_writes_complete(slot)?;
                     let result = self.blockstore.get_complete_block(slot, true);
-                    return result
+                    return Ok(result
                         .ok()
                         .map(ConfirmedBlock::from)
-                        .map(|mut confirmed_block| -> Result<UiConfirmedBlock> {
+                        .map(|mut confirmed_block| {
                             if confirmed_block.block_time.is_none()
                                 || confirmed_block.block_height.is_none()
                             {
@@ -1147,12 +1230,8 @@ impl JsonRpcRequestProcessor {
                                     }
                                 }
                             }
-
-                            Ok(confirmed_block
-                                .encode_with_options(encoding, encoding_options)
-                                .map_err(RpcCustomError::from)?)
-                        })
-                        .transpose();
+                            confirmed_block
+                        }));
                 }
             }
         } else {
@@ -1482,6 +1561,24 @@ impl JsonRpcRequestProcessor {
         let encoding = config.encoding.unwrap_or(UiTransactionEncoding::Json);
         let max_supported_transaction_version = config.max_supported_transaction_version;
         let commitment = config.commitment.unwrap_or_default();
+        self.get_confirmed_transaction(signature, commitment)
+            .await?
+            .map(
+                |confirmed_tx_with_meta| -> Result<EncodedConfirmedTransactionWithStatusMeta> {
+                    Ok(confirmed_tx_with_meta
+                        .encode(encoding, max_supported_transaction_version)
+                        .map_err(RpcCustomError::from)?)
+                },
+            )
+            .transpose()
+    }
+
+    /// Like `get_transaction`, returning the transaction without encoding it
+    pub async fn get_confirmed_transaction(
+        &self,
+        signature: Signature,
+        commitment: CommitmentConfig,
+    ) -> Result<Option<ConfirmedTransactionWithStatusMeta>> {
         check_is_at_least_confirmed(commitment)?;
 
         if self.config.enable_rpc_transaction_history {
@@ -1494,11 +1591,6 @@ impl JsonRpcRequestProcessor {
                 self.blockstore.get_rooted_transaction(signature)
             };
 
-            let encode_transaction =
-                |confirmed_tx_with_meta: ConfirmedTransactionWithStatusMeta| -> Result<EncodedConfirmedTransactionWithStatusMeta> {
-                    Ok(confirmed_tx_with_meta.encode(encoding, max_supported_transaction_version).map_err(RpcCustomError::from)?)
-                };
-
             match confirmed_transaction.unwrap_or(None) {
                 Some(mut confirmed_transaction) => {
                     if commitment.is_confirmed()
@@ -1512,7 +1604,7 @@ impl JsonRpcRequestProcessor {
                                 .get(confirmed_transaction.slot)
                                 .map(|bank| bank.clock().unix_timestamp);
                         }
-                        return Ok(Some(encode_transaction(confirmed_transaction)?));
+                        return Ok(Some(confirmed_transaction));
                     }
 
                     if confirmed_transaction.slot
@@ -1522,17 +1614,15 @@ impl JsonRpcRequestProcessor {
                             .unwrap()
                             .highest_super_majority_root()
                     {
-                        return Ok(Some(encode_transaction(confirmed_transaction)?));
+                        return Ok(Some(confirmed_transaction));
                     }
                 }
                 None => {
                     if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                        return bigtable_ledger_storage
+                        return Ok(bigtable_ledger_storage
                             .get_confirmed_transaction(&signature)
                             .await
-                            .unwrap_or(None)
-                            .map(encode_transaction)
-                            .transpose();
+                            .unwrap_or(None));
                     }
                 }
             }
@@ -1691,6 +1781,29 @@ impl JsonRpcRequestProcessor {
         }
     }
 
+    pub async fn get_account_history(
+        &self,
+        address: Pubkey,
+        before: Option<Signature>,
+        until: Option<Signature>,
+        slot: Option<Slot>,
+        limit: usize,
+    ) -> Result<Vec<RpcAccountBalanceChange>> {
+        // The balance changes are only served from long-term storage
+        let bigtable_ledger_storage = self
+            .bigtable_ledger_storage
+            .as_ref()
+            .ok_or(RpcCustomError::TransactionHistoryNotAvailable)?;
+        let balance_changes = bigtable_ledger_storage
+            .get_account_history(&address, before.as_ref(), until.as_ref(), slot, limit)
+            .await
+            .map_err(|err| {
+                warn!("{:?}", err);
+                Error::invalid_params(format!("BigTable query failed: {err}"))
+            })?;
+        Ok(balance_changes.into_iter().map(Into::into).collect())
+    }
+
     pub async fn get_first_available_block(&self) -> Slot {
         let slot = self
             .blockstore
@@ -1984,12 +2097,14 @@ impl JsonRpcRequestProcessor {
         Ok(new_response(&bank, accounts))
     }
 
-    /// Use a set of filters to get an iterator of keyed program accounts from a bank
+    /// Use a set of filters to get an iterator of keyed program accounts from a bank, or only
+    /// the given page of them
     fn get_filtered_program_accounts(
         &self,
         bank: &Bank,
         program_id: &Pubkey,
         mut filters: Vec<RpcFilterType>,
+        page: Option<AccountsPage>,
     ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
         optimize_filters(&mut filters);
         let filter_closure = |account: &AccountSharedData| {
@@ -2007,23 +2122,33 @@ impl JsonRpcRequestProcessor {
                     index_key: program_id.to_string(),
                 });
             }
-            Ok(bank
-                .get_filtered_indexed_accounts(
-                    &IndexKey::ProgramId(*program_id),
-                    |account| {
-                        // The program-id account index checks for Account owner on inclusion. However, due
-                        // to the current AccountsDb implementation, an account may remain in storage as a
-                        // zero-lamport AccountSharedData::Default() after being wiped and reinitialized in later
-                        // updates. We include the redundant filters here to avoid returning these
-                        // accounts.
-                        account.owner() == program_id && filter_closure(account)
-                    },
-                    &ScanConfig::default(),
-                    bank.byte_limit_for_scans(),
-                )
-                .map_err(|e| RpcCustomError::ScanError {
-                    message: e.to_string(),
-                })?)
+            let index_key = IndexKey::ProgramId(*program_id);
+            // The program-id account index checks for Account owner on inclusion. However, due
+            // to the current AccountsDb implementation, an account may remain in storage as a
+            // zero-lamport AccountSharedData::Default() after being wiped and reinitialized in later
+            // updates. We include the redundant filters here to avoid returning these
+            // accounts.
+            let owned_filter_closure = |account: &AccountSharedData| {
+                account.owner() == program_id && filter_closure(account)
+            };
+            if let Some(page) = page {
+                get_filtered_accounts_page(bank, Some(&index_key), page, owned_filter_closure)
+            } else {
+                Ok(bank
+                    .get_filtered_indexed_accounts(
+                        &index_key,
+                        owned_filter_closure,
+                        &ScanConfig::default(),
+                        bank.byte_limit_for_scans(),
+                    )
+                    .map_err(|e| RpcCustomError::ScanError {
+                        message: e.to_string(),
+                    })?)
+            }
+        } else if let Some(page) = page {
+            get_filtered_accounts_page(bank, None, page, |account| {
+                account.owner() == program_id && filter_closure(account)
+            })
         } else {
             // this path does not need to provide a mb limit because we only want to support secondary indexes
             Ok(bank
@@ -2041,6 +2166,7 @@ impl JsonRpcRequestProcessor {
         program_id: &Pubkey,
         owner_key: &Pubkey,
         mut filters: Vec<RpcFilterType>,
+        page: Option<AccountsPage>,
     ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
         // The by-owner accounts index checks for Token Account state and Owner address on
         // inclusion. However, due to the current AccountsDb implementation, an account may remain
@@ -2065,23 +2191,29 @@ impl JsonRpcRequestProcessor {
                     index_key: owner_key.to_string(),
                 });
             }
-            Ok(bank
-                .get_filtered_indexed_accounts(
-                    &IndexKey::SplTokenOwner(*owner_key),
-                    |account| {
-                        account.owner() == program_id
-                            && filters
-                                .iter()
-                                .all(|filter_type| filter_type.allows(account))
-                    },
-                    &ScanConfig::default(),
-                    bank.byte_limit_for_scans(),
-                )
-                .map_err(|e| RpcCustomError::ScanError {
-                    message: e.to_string(),
-                })?)
+            let index_key = IndexKey::SplTokenOwner(*owner_key);
+            let filter_closure = |account: &AccountSharedData| {
+                account.owner() == program_id
+                    && filters
+                        .iter()
+                        .all(|filter_type| filter_type.allows(account))
+            };
+            if let Some(page) = page {
+                get_filtered_accounts_page(bank, Some(&index_key), page, filter_closure)
+            } else {
+                Ok(bank
+                    .get_filtered_indexed_accounts(
+                        &index_key,
+                        filter_closure,
+                        &ScanConfig::default(),
+                        bank.byte_limit_for_scans(),
+                    )
+                    .map_err(|e| RpcCustomError::ScanError {
+                        message: e.to_string(),
+                    })?)
+            }
         } else {
-            self.get_filtered_program_accounts(bank, program_id, filters)
+            self.get_filtered_program_accounts(bank, program_id, filters, page)
         }
     }
 
@@ -2092,6 +2224,7 @@ impl JsonRpcRequestProcessor {
         program_id: &Pubkey,
         mint_key: &Pubkey,
         mut filters: Vec<RpcFilterType>,
+        page: Option<AccountsPage>,
     ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
         // The by-mint accounts index checks for Token Account state and Mint address on inclusion.
         // However, due to the current AccountsDb implementation, an account may remain in storage
@@ -2115,23 +2248,65 @@ impl JsonRpcRequestProcessor {
                     index_key: mint_key.to_string(),
                 });
             }
+            let index_key = IndexKey::SplTokenMint(*mint_key);
+            let filter_closure = |account: &AccountSharedData| {
+                account.owner() == program_id
+                    && filters
+                        .iter()
+                        .all(|filter_type| filter_type.allows(account))
+            };
+            if let Some(page) = page {
+                get_filtered_accounts_page(bank, Some(&index_key), page, filter_closure)
+            } else {
+                Ok(bank
+                    .get_filtered_indexed_accounts(
+                        &index_key,
+                        filter_closure,
+                        &ScanConfig::default(),
+                        bank.byte_limit_for_scans(),
+                    )
+                    .map_err(|e| RpcCustomError::ScanError {
+                        message: e.to_string(),
+                    })?)
+            }
+        } else {
+            self.get_filtered_program_accounts(bank, program_id, filters, page)
+        }
+    }
+
+    /// Get an iterator of program accounts by the key of a custom memcmp index
+    fn get_filtered_memcmp_indexed_accounts(
+        &self,
+        bank: &Bank,
+        program_id: &Pubkey,
+        memcmp_index: MemcmpIndex,
+        key: Pubkey,
+        filters: Vec<RpcFilterType>,
+        page: Option<AccountsPage>,
+    ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
+        // The memcmp index keeps an account under its former key when its indexed bytes
+        // change, like the by-owner index. The filters, which include the memcmp filter
+        // matching the key, are applied again to avoid returning these accounts.
+        let index_key = IndexKey::Memcmp(memcmp_index, key);
+        let filter_closure = |account: &AccountSharedData| {
+            account.owner() == program_id
+                && filters
+                    .iter()
+                    .all(|filter_type| filter_type.allows(account))
+        };
+        if let Some(page) = page {
+            get_filtered_accounts_page(bank, Some(&index_key), page, filter_closure)
+        } else {
             Ok(bank
                 .get_filtered_indexed_accounts(
-                    &IndexKey::SplTokenMint(*mint_key),
-                    |account| {
-                        account.owner() == program_id
-                            && filters
-                                .iter()
-                                .all(|filter_type| filter_type.allows(account))
-                    },
+                    &index_key,
+                    filter_closure,
                     &ScanConfig::default(),
                     bank.byte_limit_for_scans(),
                 )
                 .map_err(|e| RpcCustomError::ScanError {
                     message: e.to_string(),
                 })?)
-        } else {
-            self.get_filtered_program_accounts(bank, program_id, filters)
         }
     }
 
@@ -2183,6 +2358,27 @@ impl JsonRpcRequestProcessor {
     }
 }
 
+/// Scans one page of the accounts passing `filter`, through the secondary index of `index_key`
+/// if given
+fn get_filtered_accounts_page(
+    bank: &Bank,
+    index_key: Option<&IndexKey>,
+    page: AccountsPage,
+    filter: impl Fn(&AccountSharedData) -> bool,
+) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
+    bank.get_filtered_accounts_page(
+        index_key,
+        page.after.as_ref(),
+        page.limit,
+        filter,
+        &ScanConfig::default(),
+        bank.byte_limit_for_scans(),
+    )
+    .map_err(|e| RpcCustomError::ScanError {
+        message: e.to_string(),
+    })
+}
+
 fn optimize_filters(filters: &mut [RpcFilterType]) {
     filters.iter_mut().for_each(|filter_type| {
         if let RpcFilterType::Memcmp(compare) = filter_type {
@@ -2210,7 +2406,7 @@ fn verify_transaction(
     Ok(())
 }
 
-fn verify_filter(input: &RpcFilterType) -> Result<()> {
+pub(crate) fn verify_filter(input: &RpcFilterType) -> Result<()> {
     input
         .verify()
         .map_err(|e| Error::invalid_params(format!("Invalid param: {e:?}")))
@@ -2270,6 +2466,23 @@ fn verify_and_parse_signatures_for_address_params(
     Ok((address, before, until, limit))
 }
 
+fn verify_accounts_page(
+    limit: Option<usize>,
+    after: Option<String>,
+) -> Result<Option<AccountsPage>> {
+    let after = after.map(|ref after| verify_pubkey(after)).transpose()?;
+    match limit {
+        Some(limit) if limit == 0 || limit > MAX_GET_PROGRAM_ACCOUNTS_LIMIT => {
+            Err(Error::invalid_params(format!(
+                "Invalid limit; max {MAX_GET_PROGRAM_ACCOUNTS_LIMIT}"
+            )))
+        }
+        Some(limit) => Ok(Some(AccountsPage { after, limit })),
+        None if after.is_some() => Err(Error::invalid_params("after requires a limit")),
+        None => Ok(None),
+    }
+}
+
 pub(crate) fn check_is_at_least_confirmed(commitment: CommitmentConfig) -> Result<()> {
     if !commitment.is_at_least_confirmed() {
         return Err(Error::invalid_params(
@@ -2438,6 +2651,37 @@ fn get_spl_token_mint_filter(program_id: &Pubkey, filters: &[RpcFilterType]) ->
     }
 }
 
+/// Analyze custom filters to determine if the result will be a subset of the accounts of one of
+/// the custom memcmp indexes, and return the index with the key of the filtered bytes.
+/// NOTE: `optimize_filters()` should almost always be called before using this method because of
+/// the strict match on `MemcmpEncodedBytes::Bytes`.
+fn get_memcmp_index_filter(
+    program_id: &Pubkey,
+    filters: &[RpcFilterType],
+    memcmp_indexes: &HashSet<MemcmpIndex>,
+) -> Option<(MemcmpIndex, Pubkey)> {
+    memcmp_indexes
+        .iter()
+        .filter(|memcmp_index| memcmp_index.program_id == *program_id)
+        .find_map(|memcmp_index| {
+            filters.iter().find_map(|filter| match filter {
+                #[allow(deprecated)]
+                RpcFilterType::Memcmp(Memcmp {
+                    offset,
+                    bytes: MemcmpEncodedBytes::Bytes(bytes),
+                    ..
+                }) => {
+                    // The filter must cover all the indexed bytes
+                    let start = memcmp_index.offset.checked_sub(*offset)?;
+                    let end = start.checked_add(memcmp_index.length)?;
+                    let key = memcmp_index.index_key_from_bytes(bytes.get(start..end)?)?;
+                    Some((*memcmp_index, key))
+                }
+                _ => None,
+            })
+        })
+}
+
 /// Analyze a passed Pubkey that may be a Token program id or Mint address to determine the program
 /// id and optional Mint
 fn get_token_program_id_and_mint(
@@ -3173,14 +3417,16 @@ pub mod rpc_accounts_scan {
                 program_id_str
             );
             let program_id = verify_pubkey(&program_id_str)?;
-            let (config, filters, with_context) = if let Some(config) = config {
+            let (config, filters, with_context, limit, after) = if let Some(config) = config {
                 (
                     Some(config.account_config),
                     config.filters.unwrap_or_default(),
                     config.with_context.unwrap_or_default(),
+                    config.limit,
+                    config.after,
                 )
             } else {
-                (None, vec![], false)
+                (None, vec![], false, None, None)
             };
             if filters.len() > MAX_GET_PROGRAM_ACCOUNT_FILTERS {
                 return Err(Error::invalid_params(format!(
@@ -3190,7 +3436,8 @@ pub mod rpc_accounts_scan {
             for filter in &filters {
                 verify_filter(filter)?;
             }
-            meta.get_program_accounts(&program_id, config, filters, with_context)
+            let page = verify_accounts_page(limit, after)?;
+            meta.get_program_accounts(&program_id, config, filters, with_context, page)
         }
 
         fn get_largest_accounts(
@@ -3379,6 +3626,14 @@ pub mod rpc_full {
             config: Option<RpcSignaturesForAddressConfig>,
         ) -> BoxFuture<Result<Vec<RpcConfirmedTransactionStatusWithSignature>>>;
 
+        #[rpc(meta, name = "getAccountHistory")]
+        fn get_account_history(
+            &self,
+            meta: Self::Metadata,
+            address: String,
+            config: Option<RpcAccountHistoryConfig>,
+        ) -> BoxFuture<Result<Vec<RpcAccountBalanceChange>>>;
+
         #[rpc(meta, name = "getFirstAvailableBlock")]
         fn get_first_available_block(&self, meta: Self::Metadata) -> BoxFuture<Result<Slot>>;
 
@@ -3675,6 +3930,7 @@ pub mod rpc_full {
                     post_simulation_accounts: _,
                     units_consumed,
                     return_data,
+                    executed_instructions: _,
                 } = preflight_bank.simulate_transaction(transaction)
                 {
                     match err {
@@ -3760,6 +4016,7 @@ pub mod rpc_full {
                 post_simulation_accounts,
                 units_consumed,
                 return_data,
+                executed_instructions: _,
             } = bank.simulate_transaction(transaction);
 
             let accounts = if let Some(config_accounts) = config_accounts {
@@ -3922,6 +4179,31 @@ pub mod rpc_full {
             }
         }
 
+        fn get_account_history(
+            &self,
+            meta: Self::Metadata,
+            address: String,
+            config: Option<RpcAccountHistoryConfig>,
+        ) -> BoxFuture<Result<Vec<RpcAccountBalanceChange>>> {
+            debug!("get_account_history rpc request received: {:?}", address);
+            let RpcAccountHistoryConfig {
+                before,
+                until,
+                slot,
+                limit,
+            } = config.unwrap_or_default();
+            let verification =
+                verify_and_parse_signatures_for_address_params(address, before, until, limit);
+
+            match verification {
+                Err(err) => Box::pin(future::err(err)),
+                Ok((address, before, until, limit)) => Box::pin(async move {
+                    meta.get_account_history(address, before, until, slot, limit)
+                        .await
+                }),
+            }
+        }
+
         fn get_first_available_block(&self, meta: Self::Metadata) -> BoxFuture<Result<Slot>> {
             debug!("get_first_available_block rpc request received");
             Box::pin(async move { Ok(meta.get_first_available_block().await) })
@@ -5845,6 +6127,143 @@ pub mod tests {
         assert_eq!(result.len(), 0);
     }
 
+    #[test]
+    fn test_rpc_get_program_accounts_page() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+
+        let new_program_id = Pubkey::new_unique();
+        let mut new_program_account_keys = (0..5)
+            .map(|_| {
+                let pubkey = solana_sdk::pubkey::new_rand();
+                bank.store_account(&pubkey, &AccountSharedData::new(42, 0, &new_program_id));
+                pubkey
+            })
+            .collect::<Vec<_>>();
+        new_program_account_keys.sort_unstable();
+
+        // Page through the accounts, in pubkey order
+        let mut after = None;
+        let mut pages = vec![];
+        loop {
+            let request = create_test_request(
+                "getProgramAccounts",
+                Some(json!([
+                    new_program_id.to_string(),
+                    {"limit": 2, "after": after, "withContext": true},
+                ])),
+            );
+            let result: RpcResponse<Vec<RpcKeyedAccount>> =
+                parse_success_result(rpc.handle_request_sync(request));
+            assert_eq!(result.context.slot, 0);
+            if result.value.is_empty() {
+                break;
+            }
+            let page = result
+                .value
+                .into_iter()
+                .map(|keyed_account| keyed_account.pubkey)
+                .collect::<Vec<_>>();
+            after = page.last().cloned();
+            pages.push(page);
+        }
+        let expected_pages = new_program_account_keys
+            .chunks(2)
+            .map(|chunk| chunk.iter().map(|pubkey| pubkey.to_string()).collect())
+            .collect::<Vec<Vec<_>>>();
+        assert_eq!(pages, expected_pages);
+
+        // Test invalid pages
+        for (config, message) in [
+            (json!({"limit": 0}), "Invalid limit; max 10000"),
+            (json!({"limit": 10_001}), "Invalid limit; max 10000"),
+            (
+                json!({"after": new_program_account_keys[0].to_string()}),
+                "after requires a limit",
+            ),
+            (
+                json!({"limit": 1, "after": "bad"}),
+                "Invalid param: WrongSize",
+            ),
+        ] {
+            let request = create_test_request(
+                "getProgramAccounts",
+                Some(json!([new_program_id.to_string(), config])),
+            );
+            let response = parse_failure_response(rpc.handle_request_sync(request));
+            assert_eq!(
+                response,
+                (ErrorCode::InvalidParams.code(), message.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn test_rpc_get_program_accounts_memcmp_index() {
+        let program_id = Pubkey::new_unique();
+        let memcmp_index = MemcmpIndex {
+            program_id,
+            offset: 8,
+            length: 32,
+        };
+        let rpc = RpcHandler::start_with_config(JsonRpcConfig {
+            account_indexes: AccountSecondaryIndexes {
+                memcmp_indexes: HashSet::from([memcmp_index]),
+                ..AccountSecondaryIndexes::default()
+            },
+            ..JsonRpcConfig::default()
+        });
+        let bank = rpc.working_bank();
+
+        let authorities = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let new_account = |authority: &Pubkey| {
+            let mut data = vec![0; 40];
+            data[8..].copy_from_slice(authority.as_ref());
+            AccountSharedData::create(42, data, program_id, false, 0)
+        };
+        let account_keys = (0..3).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+        bank.store_account(&account_keys[0], &new_account(&authorities[0]));
+        bank.store_account(&account_keys[1], &new_account(&authorities[0]));
+        bank.store_account(&account_keys[2], &new_account(&authorities[1]));
+        // The account stays indexed under its former authority
+        bank.store_account(&account_keys[1], &new_account(&authorities[1]));
+
+        let get_program_accounts_by_authority = |authority: &Pubkey| {
+            let request = create_test_request(
+                "getProgramAccounts",
+                Some(json!([
+                    program_id.to_string(),
+                    {"filters": [{"memcmp": {"offset": 8, "bytes": authority.to_string()}}]},
+                ])),
+            );
+            let result: Vec<RpcKeyedAccount> =
+                parse_success_result(rpc.handle_request_sync(request));
+            let mut pubkeys = result
+                .into_iter()
+                .map(|keyed_account| keyed_account.pubkey)
+                .collect::<Vec<_>>();
+            pubkeys.sort_unstable();
+            pubkeys
+        };
+        let sorted_pubkeys = |pubkeys: &[Pubkey]| {
+            let mut pubkeys = pubkeys
+                .iter()
+                .map(|pubkey| pubkey.to_string())
+                .collect::<Vec<_>>();
+            pubkeys.sort_unstable();
+            pubkeys
+        };
+        assert_eq!(
+            get_program_accounts_by_authority(&authorities[0]),
+            sorted_pubkeys(&account_keys[..1])
+        );
+        assert_eq!(
+            get_program_accounts_by_authority(&authorities[1]),
+            sorted_pubkeys(&account_keys[1..])
+        );
+        assert!(get_program_accounts_by_authority(&Pubkey::new_unique()).is_empty());
+    }
+
     #[test]
     fn test_rpc_simulate_transaction() {
         let rpc = RpcHandler::start();
@@ -6942,6 +7361,29 @@ pub mod tests {
         assert_eq!(response, expected);
     }
 
+    #[test]
+    fn test_get_account_history() {
+        let rpc = RpcHandler::start();
+        let address = Pubkey::new_unique();
+
+        let expected = (
+            JSON_RPC_SERVER_ERROR_TRANSACTION_HISTORY_NOT_AVAILABLE,
+            String::from("Transaction history is not available from this node"),
+        );
+        for config in [json!({"limit": 10}), json!({"slot": 42, "limit": 1})] {
+            let request = create_test_request(
+                "getAccountHistory",
+                Some(json!([address.to_string(), config])),
+            );
+            let response = parse_failure_response(rpc.handle_request_sync(request));
+            assert_eq!(response, expected);
+        }
+
+        let request = create_test_request("getAccountHistory", Some(json!(["not-an-address"])));
+        let response = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(response.0, ErrorCode::InvalidParams.code());
+    }
+
     #[test]
     fn test_get_block_config() {
         let rpc = RpcHandler::start();
@@ -8303,6 +8745,75 @@ pub mod tests {
         .is_none());
     }
 
+    #[test]
+    fn test_get_memcmp_index_filter() {
+        let program_id = Pubkey::new_unique();
+        let memcmp_index = MemcmpIndex {
+            program_id,
+            offset: 8,
+            length: 32,
+        };
+        let memcmp_indexes = HashSet::from([memcmp_index]);
+        let authority = Pubkey::new_unique();
+
+        // Filtering on exactly the indexed bytes
+        assert_eq!(
+            get_memcmp_index_filter(
+                &program_id,
+                &[
+                    RpcFilterType::DataSize(40),
+                    RpcFilterType::Memcmp(Memcmp::new_raw_bytes(8, authority.to_bytes().to_vec())),
+                ],
+                &memcmp_indexes,
+            ),
+            Some((memcmp_index, authority))
+        );
+
+        // Filtering on more than the indexed bytes
+        let mut bytes = vec![7; 4];
+        bytes.extend_from_slice(&authority.to_bytes());
+        bytes.push(7);
+        assert_eq!(
+            get_memcmp_index_filter(
+                &program_id,
+                &[RpcFilterType::Memcmp(Memcmp::new_raw_bytes(4, bytes))],
+                &memcmp_indexes,
+            ),
+            Some((memcmp_index, authority))
+        );
+
+        // Filtering on part of the indexed bytes
+        assert!(get_memcmp_index_filter(
+            &program_id,
+            &[RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                8,
+                authority.to_bytes()[..31].to_vec()
+            ))],
+            &memcmp_indexes,
+        )
+        .is_none());
+        assert!(get_memcmp_index_filter(
+            &program_id,
+            &[RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                9,
+                authority.to_bytes().to_vec()
+            ))],
+            &memcmp_indexes,
+        )
+        .is_none());
+
+        // Wrong program id
+        assert!(get_memcmp_index_filter(
+            &Pubkey::new_unique(),
+            &[RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                8,
+                authority.to_bytes().to_vec()
+            ))],
+            &memcmp_indexes,
+        )
+        .is_none());
+    }
+
     #[test]
     fn test_rpc_single_gossip() {
         let exit = Arc::new(AtomicBool::new(false));
@@ -0,0 +1,603 @@
+//! The `rpc_grpc_service` module implements a gRPC service for the read-heavy RPC methods,
+//! sharing the request processor of the JSON RPC service. Account data is returned as raw bytes,
+//! which avoids the cost of encoding it. Subscriptions stream the notified accounts and slots as
+//! is, without going through their PubSub JSON notifications.
+
+use {
+    crate::{
+        rpc::{verify_filter, JsonRpcRequestProcessor},
+        rpc_subscription_tracker::{
+            AccountSubscriptionParams, SubscriptionControl, SubscriptionParams,
+        },
+        rpc_subscriptions::RawNotificationValue,
+    },
+    jsonrpc_core::{Error, ErrorCode},
+    solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig},
+    solana_rpc_client_api::{
+        config::RpcContextConfig,
+        filter::{Memcmp, RpcFilterType},
+        request::MAX_GET_PROGRAM_ACCOUNT_FILTERS,
+        response::SlotInfo,
+    },
+    solana_sdk::{
+        account::{AccountSharedData, ReadableAccount},
+        clock::Slot,
+        commitment_config::CommitmentConfig,
+        pubkey::Pubkey,
+        signature::Signature,
+    },
+    std::{
+        net::SocketAddr,
+        thread::{self, Builder, JoinHandle},
+    },
+    stream_cancel::{Trigger, Tripwire},
+    tokio::{
+        select,
+        sync::{broadcast::error::RecvError, mpsc},
+    },
+    tokio_stream::wrappers::ReceiverStream,
+    tonic::{transport::Server, Request, Response, Status},
+};
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+pub mod proto {
+    tonic::include_proto!("solana.rpc");
+}
+
+use proto::{
+    rpc_server::{Rpc, RpcServer},
+    AccountUpdate, CommitmentLevel, DataSlice, GetAccountInfoRequest, GetAccountInfoResponse,
+    GetBlockRequest, GetBlockResponse, GetMultipleAccountsRequest, GetMultipleAccountsResponse,
+    GetProgramAccountsRequest, GetProgramAccountsResponse, GetTransactionRequest,
+    GetTransactionResponse, MaybeAccount, SlotUpdate, SubscribeAccountRequest,
+    SubscribeSlotsRequest,
+};
+
+/// The number of updates buffered for each stream before the client is considered too slow
+const STREAM_CAPACITY: usize = 1_024;
+
+pub struct GrpcService {
+    thread_hdl: JoinHandle<()>,
+}
+
+impl GrpcService {
+    pub fn new(
+        grpc_addr: SocketAddr,
+        request_processor: JsonRpcRequestProcessor,
+        subscription_control: SubscriptionControl,
+    ) -> (Trigger, Self) {
+        info!("rpc_grpc bound to {:?}", grpc_addr);
+
+        let (trigger, tripwire) = Tripwire::new();
+        let thread_hdl = Builder::new()
+            .name("solRpcGrpc".to_string())
+            .spawn(move || {
+                let runtime = tokio::runtime::Builder::new_multi_thread()
+                    .thread_name("solRpcGrpcEl")
+                    .enable_all()
+                    .build()
+                    .expect("runtime creation failed");
+                let service = RpcServer::new(GrpcRpcImpl {
+                    request_processor,
+                    subscription_control,
+                });
+                let server = Server::builder().add_service(service).serve_with_shutdown(
+                    grpc_addr,
+                    async move {
+                        tripwire.await;
+                    },
+                );
+                if let Err(err) = runtime.block_on(server) {
+                    error!("grpc service failed: {}", err);
+                }
+            })
+            .expect("thread spawn failed");
+
+        (trigger, Self { thread_hdl })
+    }
+
+    pub fn join(self) -> thread::Result<()> {
+        self.thread_hdl.join()
+    }
+}
+
+struct GrpcRpcImpl {
+    request_processor: JsonRpcRequestProcessor,
+    subscription_control: SubscriptionControl,
+}
+
+#[tonic::async_trait]
+impl Rpc for GrpcRpcImpl {
+    async fn get_account_info(
+        &self,
+        request: Request<GetAccountInfoRequest>,
+    ) -> Result<Response<GetAccountInfoResponse>, Status> {
+        let request = request.into_inner();
+        let pubkey = parse_pubkey(&request.pubkey)?;
+        let config = context_config(request.commitment(), request.min_context_slot);
+        let response = self
+            .request_processor
+            .get_raw_accounts(&[pubkey], config)
+            .map_err(status_from_error)?;
+        let account = response.value.into_iter().next().flatten();
+        Ok(Response::new(GetAccountInfoResponse {
+            slot: response.context.slot,
+            account: account
+                .map(|account| account_to_proto(&pubkey, &account, request.data_slice.as_ref())),
+        }))
+    }
+
+    async fn get_multiple_accounts(
+        &self,
+        request: Request<GetMultipleAccountsRequest>,
+    ) -> Result<Response<GetMultipleAccountsResponse>, Status> {
+        let request = request.into_inner();
+        let pubkeys = request
+            .pubkeys
+            .iter()
+            .map(|pubkey| parse_pubkey(pubkey))
+            .collect::<Result<Vec<_>, _>>()?;
+        let config = context_config(request.commitment(), request.min_context_slot);
+        let response = self
+            .request_processor
+            .get_raw_accounts(&pubkeys, config)
+            .map_err(status_from_error)?;
+        let accounts = pubkeys
+            .iter()
+            .zip(response.value)
+            .map(|(pubkey, account)| MaybeAccount {
+                account: account
+                    .map(|account| account_to_proto(pubkey, &account, request.data_slice.as_ref())),
+            })
+            .collect();
+        Ok(Response::new(GetMultipleAccountsResponse {
+            slot: response.context.slot,
+            accounts,
+        }))
+    }
+
+    async fn get_program_accounts(
+        &self,
+        request: Request<GetProgramAccountsRequest>,
+    ) -> Result<Response<GetProgramAccountsResponse>, Status> {
+        let request = request.into_inner();
+        let program_id = parse_pubkey(&request.program_id)?;
+        let config = context_config(request.commitment(), request.min_context_slot);
+        if request.filters.len() > MAX_GET_PROGRAM_ACCOUNT_FILTERS {
+            return Err(Status::invalid_argument(format!(
+                "Too many filters provided; max {MAX_GET_PROGRAM_ACCOUNT_FILTERS}"
+            )));
+        }
+        let filters = request
+            .filters
+            .into_iter()
+            .map(filter_from_proto)
+            .collect::<Result<Vec<_>, _>>()?;
+        for filter in &filters {
+            verify_filter(filter).map_err(status_from_error)?;
+        }
+
+        // Scanning the accounts may take a while, so keep it off the runtime threads
+        let request_processor = self.request_processor.clone();
+        let response = tokio::task::spawn_blocking(move || {
+            request_processor.get_raw_program_accounts(&program_id, config, filters)
+        })
+        .await
+        .map_err(|err| Status::internal(err.to_string()))?
+        .map_err(status_from_error)?;
+        let accounts = response
+            .value
+            .iter()
+            .map(|(pubkey, account)| account_to_proto(pubkey, account, request.data_slice.as_ref()))
+            .collect();
+        Ok(Response::new(GetProgramAccountsResponse {
+            slot: response.context.slot,
+            accounts,
+        }))
+    }
+
+    async fn get_block(
+        &self,
+        request: Request<GetBlockRequest>,
+    ) -> Result<Response<GetBlockResponse>, Status> {
+        let request = request.into_inner();
+        let block = self
+            .request_processor
+            .get_confirmed_block(request.slot, commitment_config(request.commitment()))
+            .await
+            .map_err(status_from_error)?;
+        Ok(Response::new(GetBlockResponse {
+            block: block.map(Into::into),
+        }))
+    }
+
+    async fn get_transaction(
+        &self,
+        request: Request<GetTransactionRequest>,
+    ) -> Result<Response<GetTransactionResponse>, Status> {
+        let request = request.into_inner();
+        let signature = Signature::try_from(request.signature.as_slice())
+            .map_err(|_| Status::invalid_argument("Invalid param: invalid signature"))?;
+        let transaction = self
+            .request_processor
+            .get_confirmed_transaction(signature, commitment_config(request.commitment()))
+            .await
+            .map_err(status_from_error)?;
+        Ok(Response::new(match transaction {
+            Some(transaction) => GetTransactionResponse {
+                slot: transaction.slot,
+                transaction: Some(transaction.tx_with_meta.into()),
+                block_time: transaction.block_time.map(|timestamp| {
+                    solana_storage_proto::convert::generated::UnixTimestamp { timestamp }
+                }),
+            },
+            None => GetTransactionResponse::default(),
+        }))
+    }
+
+    type SubscribeAccountStream = ReceiverStream<Result<AccountUpdate, Status>>;
+
+    async fn subscribe_account(
+        &self,
+        request: Request<SubscribeAccountRequest>,
+    ) -> Result<Response<Self::SubscribeAccountStream>, Status> {
+        let request = request.into_inner();
+        let pubkey = parse_pubkey(&request.pubkey)?;
+        // The updates are streamed from the raw accounts, so keep the JSON notifications of the
+        // subscription, which are built regardless, empty
+        let params = SubscriptionParams::Account(AccountSubscriptionParams {
+            pubkey,
+            encoding: UiAccountEncoding::Base64,
+            data_slice: Some(UiDataSliceConfig {
+                offset: 0,
+                length: 0,
+            }),
+            commitment: commitment_config(request.commitment()),
+        });
+        let data_slice = request.data_slice;
+        let stream = subscribe(
+            &self.subscription_control,
+            params,
+            move |slot, value| match value {
+                RawNotificationValue::Account(account) => Some(AccountUpdate {
+                    slot,
+                    account: Some(account_to_proto(&pubkey, &account, data_slice.as_ref())),
+                }),
+                RawNotificationValue::Slot(_) => None,
+            },
+        )?;
+        Ok(Response::new(stream))
+    }
+
+    type SubscribeSlotsStream = ReceiverStream<Result<SlotUpdate, Status>>;
+
+    async fn subscribe_slots(
+        &self,
+        _request: Request<SubscribeSlotsRequest>,
+    ) -> Result<Response<Self::SubscribeSlotsStream>, Status> {
+        let stream = subscribe(
+            &self.subscription_control,
+            SubscriptionParams::Slot,
+            |_, value| match value {
+                RawNotificationValue::Slot(SlotInfo { slot, parent, root }) => {
+                    Some(SlotUpdate { slot, parent, root })
+                }
+                RawNotificationValue::Account(_) => None,
+            },
+        )?;
+        Ok(Response::new(stream))
+    }
+}
+
+/// Streams the raw notification values of a PubSub subscription, converted by `convert`, until
+/// the client goes away
+fn subscribe<U, F>(
+    subscription_control: &SubscriptionControl,
+    params: SubscriptionParams,
+    convert: F,
+) -> Result<ReceiverStream<Result<U, Status>>, Status>
+where
+    U: Send + 'static,
+    F: Fn(Slot, RawNotificationValue) -> Option<U> + Send + 'static,
+{
+    let mut broadcast_receiver = subscription_control.raw_broadcast_receiver();
+    let token = subscription_control
+        .subscribe(params)
+        .map_err(|err| Status::resource_exhausted(err.to_string()))?;
+    let (sender, receiver) = mpsc::channel(STREAM_CAPACITY);
+    tokio::spawn(async move {
+        loop {
+            let notification = select! {
+                result = broadcast_receiver.recv() => match result {
+                    Ok(notification) => notification,
+                    Err(RecvError::Lagged(_)) => {
+                        let status = Status::data_loss("client has lagged behind");
+                        let _ = sender.send(Err(status)).await;
+                        break;
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                _ = sender.closed() => break,
+            };
+            if notification.subscription_id != token.id() {
+                continue;
+            }
+            if let Some(update) = convert(notification.slot, notification.value) {
+                if sender.send(Ok(update)).await.is_err() {
+                    break;
+                }
+            }
+        }
+        drop(token);
+    });
+    Ok(ReceiverStream::new(receiver))
+}
+
+fn parse_pubkey(bytes: &[u8]) -> Result<Pubkey, Status> {
+    Pubkey::try_from(bytes).map_err(|_| Status::invalid_argument("Invalid param: invalid pubkey"))
+}
+
+fn commitment_config(commitment: CommitmentLevel) -> CommitmentConfig {
+    match commitment {
+        CommitmentLevel::Finalized => CommitmentConfig::finalized(),
+        CommitmentLevel::Confirmed => CommitmentConfig::confirmed(),
+        CommitmentLevel::Processed => CommitmentConfig::processed(),
+    }
+}
+
+fn context_config(commitment: CommitmentLevel, min_context_slot: Option<u64>) -> RpcContextConfig {
+    RpcContextConfig {
+        commitment: Some(commitment_config(commitment)),
+        min_context_slot,
+    }
+}
+
+fn filter_from_proto(filter: proto::Filter) -> Result<RpcFilterType, Status> {
+    use proto::filter::Filter;
+    match filter.filter {
+        Some(Filter::DataSize(data_size)) => Ok(RpcFilterType::DataSize(data_size)),
+        Some(Filter::Memcmp(proto::Memcmp { offset, bytes })) => {
+            let offset = usize::try_from(offset)
+                .map_err(|_| Status::invalid_argument("Invalid param: memcmp offset"))?;
+            Ok(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(offset, bytes)))
+        }
+        Some(Filter::TokenAccountState(true)) => Ok(RpcFilterType::TokenAccountState),
+        Some(Filter::TokenAccountState(false)) | None => {
+            Err(Status::invalid_argument("Invalid param: empty filter"))
+        }
+    }
+}
+
+fn account_to_proto(
+    pubkey: &Pubkey,
+    account: &AccountSharedData,
+    data_slice: Option<&DataSlice>,
+) -> proto::Account {
+    proto::Account {
+        pubkey: pubkey.to_bytes().to_vec(),
+        lamports: account.lamports(),
+        owner: account.owner().to_bytes().to_vec(),
+        executable: account.executable(),
+        rent_epoch: account.rent_epoch(),
+        data: slice_data(account.data(), data_slice).to_vec(),
+    }
+}
+
+fn slice_data<'a>(data: &'a [u8], data_slice: Option<&DataSlice>) -> &'a [u8] {
+    match data_slice {
+        Some(DataSlice { offset, length }) => {
+            let start = usize::try_from(*offset)
+                .unwrap_or(usize::MAX)
+                .min(data.len());
+            let end = start
+                .saturating_add(usize::try_from(*length).unwrap_or(usize::MAX))
+                .min(data.len());
+            &data[start..end]
+        }
+        None => data,
+    }
+}
+
+fn status_from_error(err: Error) -> Status {
+    match err.code {
+        ErrorCode::InvalidParams | ErrorCode::InvalidRequest => {
+            Status::invalid_argument(err.message)
+        }
+        ErrorCode::ServerError(_) => Status::failed_precondition(err.message),
+        _ => Status::internal(err.message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank, rpc_pubsub_service,
+            rpc_subscriptions::RpcSubscriptions,
+        },
+        proto::rpc_client::RpcClient,
+        solana_client::connection_cache::ConnectionCache,
+        solana_runtime::{
+            bank::Bank,
+            bank_forks::BankForks,
+            commitment::{BlockCommitmentCache, CommitmentSlots},
+            genesis_utils::{create_genesis_config, GenesisConfigInfo},
+        },
+        solana_sdk::{
+            signature::{Keypair, Signer},
+            system_program, system_transaction,
+        },
+        solana_streamer::socket::SocketAddrSpace,
+        std::{
+            net::{IpAddr, Ipv4Addr},
+            sync::{
+                atomic::{AtomicBool, AtomicU64},
+                Arc, RwLock,
+            },
+            time::Duration,
+        },
+        tokio::time::timeout,
+    };
+
+    #[test]
+    fn test_grpc_subscriptions() {
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(10_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let blockhash = bank.last_blockhash();
+        let bank_forks = BankForks::new_rw_arc(bank);
+        let bank0 = bank_forks.read().unwrap().get(0).unwrap();
+        let bank1 = Bank::new_from_parent(bank0.clone(), &Pubkey::default(), 1);
+        bank_forks.write().unwrap().insert(bank1);
+        let subscriptions = Arc::new(RpcSubscriptions::new_for_tests(
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(AtomicU64::default()),
+            Arc::new(AtomicU64::default()),
+            bank_forks.clone(),
+            Arc::new(RwLock::new(BlockCommitmentCache::new_for_tests_with_slots(
+                1, 1,
+            ))),
+            OptimisticallyConfirmedBank::locked_from_bank_forks_root(&bank_forks),
+        ));
+        let request_processor = JsonRpcRequestProcessor::new_from_bank(
+            bank0,
+            SocketAddrSpace::Unspecified,
+            Arc::new(ConnectionCache::new("connection_cache_test")),
+        );
+        let ip_addr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let grpc_addr = SocketAddr::new(
+            ip_addr,
+            solana_net_utils::find_available_port_in_range(ip_addr, (10000, 65535)).unwrap(),
+        );
+        let (trigger, grpc_service) = GrpcService::new(
+            grpc_addr,
+            request_processor,
+            subscriptions.control().clone(),
+        );
+
+        let alice = Keypair::new();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let mut client = loop {
+                match RpcClient::connect(format!("http://{grpc_addr}")).await {
+                    Ok(client) => break client,
+                    Err(_) => tokio::time::sleep(Duration::from_millis(10)).await,
+                }
+            };
+            let mut slots = client
+                .subscribe_slots(SubscribeSlotsRequest {})
+                .await
+                .unwrap()
+                .into_inner();
+            let mut accounts = client
+                .subscribe_account(SubscribeAccountRequest {
+                    pubkey: alice.pubkey().to_bytes().to_vec(),
+                    commitment: CommitmentLevel::Processed.into(),
+                    data_slice: Some(DataSlice {
+                        offset: 1,
+                        length: 2,
+                    }),
+                })
+                .await
+                .unwrap()
+                .into_inner();
+            // Wait for the subscriptions to be registered, which notifies slot 1
+            let (rpc, _receiver) = rpc_pubsub_service::test_connection(&subscriptions);
+            rpc.block_until_processed(&subscriptions);
+
+            let slot_update = timeout(Duration::from_secs(10), slots.message())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            assert_eq!(
+                slot_update,
+                SlotUpdate {
+                    slot: 1,
+                    parent: 0,
+                    root: 0,
+                }
+            );
+
+            let tx = system_transaction::create_account(
+                &mint_keypair,
+                &alice,
+                blockhash,
+                1,
+                4,
+                &system_program::id(),
+            );
+            bank_forks
+                .read()
+                .unwrap()
+                .get(1)
+                .unwrap()
+                .process_transaction(&tx)
+                .unwrap();
+            subscriptions.notify_subscribers(CommitmentSlots {
+                slot: 1,
+                ..CommitmentSlots::default()
+            });
+            let account_update = timeout(Duration::from_secs(10), accounts.message())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            assert_eq!(
+                account_update,
+                AccountUpdate {
+                    slot: 1,
+                    account: Some(proto::Account {
+                        pubkey: alice.pubkey().to_bytes().to_vec(),
+                        lamports: 1,
+                        owner: system_program::id().to_bytes().to_vec(),
+                        executable: false,
+                        rent_epoch: u64::MAX,
+                        data: vec![0, 0],
+                    }),
+                }
+            );
+        });
+
+        trigger.cancel();
+        grpc_service.join().unwrap();
+    }
+
+    #[test]
+    fn test_slice_data() {
+        let data = [1, 2, 3, 4];
+        let slice = |offset, length| slice_data(&data, Some(&DataSlice { offset, length }));
+        assert_eq!(slice_data(&data, None), &data);
+        assert_eq!(slice(1, 2), &[2, 3]);
+        assert_eq!(slice(2, 10), &[3, 4]);
+        assert_eq!(slice(4, 1), &[] as &[u8]);
+        assert_eq!(slice(u64::MAX, u64::MAX), &[] as &[u8]);
+    }
+
+    #[test]
+    fn test_filter_from_proto() {
+        use proto::filter::Filter;
+        let filter = |filter| filter_from_proto(proto::Filter { filter });
+        assert_eq!(
+            filter(Some(Filter::DataSize(165))).unwrap(),
+            RpcFilterType::DataSize(165)
+        );
+        assert_eq!(
+            filter(Some(Filter::Memcmp(proto::Memcmp {
+                offset: 32,
+                bytes: vec![1, 2],
+            })))
+            .unwrap(),
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(32, vec![1, 2]))
+        );
+        assert_eq!(
+            filter(Some(Filter::TokenAccountState(true))).unwrap(),
+            RpcFilterType::TokenAccountState
+        );
+        assert!(filter(None).is_err());
+    }
+}
@@ -4,12 +4,12 @@ use crate::{rpc_pubsub_service, rpc_subscriptions::RpcSubscriptions};
 use {
     crate::{
         rpc::check_is_at_least_confirmed,
-        rpc_pubsub_service::PubSubConfig,
+        rpc_pubsub_service::{PubSubConfig, ResumedSubscriptions},
         rpc_subscription_tracker::{
             AccountSubscriptionParams, BlockSubscriptionKind, BlockSubscriptionParams,
             LogsSubscriptionKind, LogsSubscriptionParams, ProgramSubscriptionParams,
             SignatureSubscriptionParams, SubscriptionControl, SubscriptionId, SubscriptionParams,
-            SubscriptionToken,
+            SubscriptionToken, TransactionSubscriptionParams,
         },
     },
     dashmap::DashMap,
@@ -19,18 +19,24 @@ use {
     solana_account_decoder::{UiAccount, UiAccountEncoding},
     solana_rpc_client_api::{
         config::{
-            RpcAccountInfoConfig, RpcBlockSubscribeConfig, RpcBlockSubscribeFilter,
-            RpcProgramAccountsConfig, RpcSignatureSubscribeConfig, RpcTransactionLogsConfig,
-            RpcTransactionLogsFilter,
+            RpcAccountInfoConfig, RpcAccountSubscribeConfig, RpcBlockSubscribeConfig,
+            RpcBlockSubscribeFilter, RpcProgramSubscribeConfig, RpcSignatureSubscribeConfig,
+            RpcSlotSubscribeConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter,
+            RpcTransactionSubscribeConfig, RpcTransactionSubscribeFilter,
         },
         response::{
             Response as RpcResponse, RpcBlockUpdate, RpcKeyedAccount, RpcLogsResponse,
-            RpcSignatureResult, RpcVersionInfo, RpcVote, SlotInfo, SlotUpdate,
+            RpcSignatureResult, RpcTransactionUpdate, RpcVersionInfo, RpcVote, SlotInfo,
+            SlotUpdate,
         },
     },
     solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature},
     solana_transaction_status::UiTransactionEncoding,
-    std::{str::FromStr, sync::Arc},
+    std::{
+        collections::BTreeSet,
+        str::FromStr,
+        sync::{Arc, Mutex},
+    },
 };
 
 // We have to keep both of the following traits to not break backwards compatibility.
@@ -61,7 +67,7 @@ pub trait RpcSolPubSub {
         meta: Self::Metadata,
         subscriber: Subscriber<RpcResponse<UiAccount>>,
         pubkey_str: String,
-        config: Option<RpcAccountInfoConfig>,
+        config: Option<RpcAccountSubscribeConfig>,
     );
 
     // Unsubscribe from account notification subscription.
@@ -88,7 +94,7 @@ pub trait RpcSolPubSub {
         meta: Self::Metadata,
         subscriber: Subscriber<RpcResponse<RpcKeyedAccount>>,
         pubkey_str: String,
-        config: Option<RpcProgramAccountsConfig>,
+        config: Option<RpcProgramSubscribeConfig>,
     );
 
     // Unsubscribe from account notification subscription.
@@ -154,7 +160,12 @@ pub trait RpcSolPubSub {
 
     // Get notification when slot is encountered
     #[pubsub(subscription = "slotNotification", subscribe, name = "slotSubscribe")]
-    fn slot_subscribe(&self, meta: Self::Metadata, subscriber: Subscriber<SlotInfo>);
+    fn slot_subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<SlotInfo>,
+        config: Option<RpcSlotSubscribeConfig>,
+    );
 
     // Unsubscribe from slot notification subscription.
     #[pubsub(
@@ -214,6 +225,32 @@ pub trait RpcSolPubSub {
         id: PubSubSubscriptionId,
     ) -> Result<bool>;
 
+    // Get notification for each transaction matching the filter
+    #[pubsub(
+        subscription = "transactionNotification",
+        subscribe,
+        name = "transactionSubscribe"
+    )]
+    fn transaction_subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<RpcResponse<RpcTransactionUpdate>>,
+        filter: RpcTransactionSubscribeFilter,
+        config: Option<RpcTransactionSubscribeConfig>,
+    );
+
+    // Unsubscribe from transaction notification subscription.
+    #[pubsub(
+        subscription = "transactionNotification",
+        unsubscribe,
+        name = "transactionUnsubscribe"
+    )]
+    fn transaction_unsubscribe(
+        &self,
+        meta: Option<Self::Metadata>,
+        id: PubSubSubscriptionId,
+    ) -> Result<bool>;
+
     // Get notification when vote is encountered
     #[pubsub(subscription = "voteNotification", subscribe, name = "voteSubscribe")]
     fn vote_subscribe(&self, meta: Self::Metadata, subscriber: Subscriber<RpcVote>);
@@ -261,7 +298,7 @@ mod internal {
         fn account_subscribe(
             &self,
             pubkey_str: String,
-            config: Option<RpcAccountInfoConfig>,
+            config: Option<RpcAccountSubscribeConfig>,
         ) -> Result<SubscriptionId>;
 
         // Unsubscribe from account notification subscription.
@@ -274,7 +311,7 @@ mod internal {
         fn program_subscribe(
             &self,
             pubkey_str: String,
-            config: Option<RpcProgramAccountsConfig>,
+            config: Option<RpcProgramSubscribeConfig>,
         ) -> Result<SubscriptionId>;
 
         // Unsubscribe from account notification subscription.
@@ -308,7 +345,7 @@ mod internal {
 
         // Get notification when slot is encountered
         #[rpc(name = "slotSubscribe")]
-        fn slot_subscribe(&self) -> Result<SubscriptionId>;
+        fn slot_subscribe(&self, config: Option<RpcSlotSubscribeConfig>) -> Result<SubscriptionId>;
 
         // Unsubscribe from slot notification subscription.
         #[rpc(name = "slotUnsubscribe")]
@@ -334,6 +371,18 @@ mod internal {
         #[rpc(name = "blockUnsubscribe")]
         fn block_unsubscribe(&self, id: SubscriptionId) -> Result<bool>;
 
+        // Get notification for each transaction matching the filter
+        #[rpc(name = "transactionSubscribe")]
+        fn transaction_subscribe(
+            &self,
+            filter: RpcTransactionSubscribeFilter,
+            config: Option<RpcTransactionSubscribeConfig>,
+        ) -> Result<SubscriptionId>;
+
+        // Unsubscribe from transaction notification subscription.
+        #[rpc(name = "transactionUnsubscribe")]
+        fn transaction_unsubscribe(&self, id: SubscriptionId) -> Result<bool>;
+
         // Get notification when vote is encountered
         #[rpc(name = "voteSubscribe")]
         fn vote_subscribe(&self) -> Result<SubscriptionId>;
@@ -360,6 +409,7 @@ pub struct RpcSolPubSubImpl {
     config: PubSubConfig,
     subscription_control: SubscriptionControl,
     current_subscriptions: Arc<DashMap<SubscriptionId, SubscriptionToken>>,
+    resumed_subscriptions: Arc<Mutex<ResumedSubscriptions>>,
 }
 
 impl RpcSolPubSubImpl {
@@ -372,10 +422,25 @@ impl RpcSolPubSubImpl {
             config,
             subscription_control,
             current_subscriptions,
+            resumed_subscriptions: Arc::default(),
         }
     }
 
+    /// The subscriptions resumed from a slot, with the notifications replayed
+    /// to them
+    pub fn resumed_subscriptions(&self) -> Arc<Mutex<ResumedSubscriptions>> {
+        Arc::clone(&self.resumed_subscriptions)
+    }
+
     fn subscribe(&self, params: SubscriptionParams) -> Result<SubscriptionId> {
+        self.subscribe_from_slot(params, None)
+    }
+
+    fn subscribe_from_slot(
+        &self,
+        params: SubscriptionParams,
+        from_slot: Option<Slot>,
+    ) -> Result<SubscriptionId> {
         let token = self
             .subscription_control
             .subscribe(params)
@@ -386,11 +451,34 @@ impl RpcSolPubSubImpl {
                 data: None,
             })?;
         let id = token.id();
-        self.current_subscriptions.insert(id, token);
+        // The subscription is registered before it is replayed, so that no live
+        // notification is missed in between.  The live notifications which were
+        // replayed as well are then dropped by their sequence number.
+        let was_subscribed = self.current_subscriptions.insert(id, token).is_some();
+        if let Some(from_slot) = from_slot {
+            let replayed = match self.subscription_control.replay(id, from_slot) {
+                Ok(replayed) => replayed,
+                Err(err) => {
+                    if !was_subscribed {
+                        self.current_subscriptions.remove(&id);
+                    }
+                    return Err(Error {
+                        code: ErrorCode::InvalidParams,
+                        message: format!("Invalid Request: {err}"),
+                        data: None,
+                    });
+                }
+            };
+            self.resumed_subscriptions
+                .lock()
+                .unwrap()
+                .resume(id, replayed);
+        }
         Ok(id)
     }
 
     fn unsubscribe(&self, id: SubscriptionId) -> Result<bool> {
+        self.resumed_subscriptions.lock().unwrap().remove(id);
         if self.current_subscriptions.remove(&id).is_some() {
             Ok(true)
         } else {
@@ -405,7 +493,7 @@ impl RpcSolPubSubImpl {
     #[cfg(test)]
     pub fn block_until_processed(&self, rpc_subscriptions: &Arc<RpcSubscriptions>) {
         let (rpc, mut receiver) = rpc_pubsub_service::test_connection(rpc_subscriptions);
-        rpc.slot_subscribe().unwrap();
+        rpc.slot_subscribe(None).unwrap();
         rpc_subscriptions.notify_slot(1, 0, 0);
         receiver.recv();
     }
@@ -419,17 +507,29 @@ fn param<T: FromStr>(param_str: &str, thing: &str) -> Result<T> {
     })
 }
 
+fn pubkey_set(pubkey_strs: Option<Vec<String>>, thing: &str) -> Result<BTreeSet<Pubkey>> {
+    pubkey_strs
+        .unwrap_or_default()
+        .iter()
+        .map(|pubkey_str| param::<Pubkey>(pubkey_str, thing))
+        .collect()
+}
+
 impl RpcSolPubSubInternal for RpcSolPubSubImpl {
     fn account_subscribe(
         &self,
         pubkey_str: String,
-        config: Option<RpcAccountInfoConfig>,
+        config: Option<RpcAccountSubscribeConfig>,
     ) -> Result<SubscriptionId> {
-        let RpcAccountInfoConfig {
-            encoding,
-            data_slice,
-            commitment,
-            min_context_slot: _, // ignored
+        let RpcAccountSubscribeConfig {
+            account_config:
+                RpcAccountInfoConfig {
+                    encoding,
+                    data_slice,
+                    commitment,
+                    min_context_slot: _, // ignored
+                },
+            from_slot,
         } = config.unwrap_or_default();
         let params = AccountSubscriptionParams {
             pubkey: param::<Pubkey>(&pubkey_str, "pubkey")?,
@@ -437,7 +537,7 @@ impl RpcSolPubSubInternal for RpcSolPubSubImpl {
             data_slice,
             encoding: encoding.unwrap_or(UiAccountEncoding::Binary),
         };
-        self.subscribe(SubscriptionParams::Account(params))
+        self.subscribe_from_slot(SubscriptionParams::Account(params), from_slot)
     }
 
     fn account_unsubscribe(&self, id: SubscriptionId) -> Result<bool> {
@@ -447,9 +547,12 @@ impl RpcSolPubSubInternal for RpcSolPubSubImpl {
     fn program_subscribe(
         &self,
         pubkey_str: String,
-        config: Option<RpcProgramAccountsConfig>,
+        config: Option<RpcProgramSubscribeConfig>,
     ) -> Result<SubscriptionId> {
-        let config = config.unwrap_or_default();
+        let RpcProgramSubscribeConfig {
+            program_config: config,
+            from_slot,
+        } = config.unwrap_or_default();
         let params = ProgramSubscriptionParams {
             pubkey: param::<Pubkey>(&pubkey_str, "pubkey")?,
             filters: config.filters.unwrap_or_default(),
@@ -461,7 +564,7 @@ impl RpcSolPubSubInternal for RpcSolPubSubImpl {
             commitment: config.account_config.commitment.unwrap_or_default(),
             with_context: config.with_context.unwrap_or_default(),
         };
-        self.subscribe(SubscriptionParams::Program(params))
+        self.subscribe_from_slot(SubscriptionParams::Program(params), from_slot)
     }
 
     fn program_unsubscribe(&self, id: SubscriptionId) -> Result<bool> {
@@ -473,6 +576,9 @@ impl RpcSolPubSubInternal for RpcSolPubSubImpl {
         filter: RpcTransactionLogsFilter,
         config: Option<RpcTransactionLogsConfig>,
     ) -> Result<SubscriptionId> {
+        let (commitment, from_slot) = config
+            .map(|config| (config.commitment, config.from_slot))
+            .unwrap_or_default();
         let params = LogsSubscriptionParams {
             kind: match filter {
                 RpcTransactionLogsFilter::All => LogsSubscriptionKind::All,
@@ -488,9 +594,9 @@ impl RpcSolPubSubInternal for RpcSolPubSubImpl {
                     LogsSubscriptionKind::Single(param::<Pubkey>(&keys[0], "mentions")?)
                 }
             },
-            commitment: config.and_then(|c| c.commitment).unwrap_or_default(),
+            commitment: commitment.unwrap_or_default(),
         };
-        self.subscribe(SubscriptionParams::Logs(params))
+        self.subscribe_from_slot(SubscriptionParams::Logs(params), from_slot)
     }
 
     fn logs_unsubscribe(&self, id: SubscriptionId) -> Result<bool> {
@@ -515,8 +621,9 @@ impl RpcSolPubSubInternal for RpcSolPubSubImpl {
         self.unsubscribe(id)
     }
 
-    fn slot_subscribe(&self) -> Result<SubscriptionId> {
-        self.subscribe(SubscriptionParams::Slot)
+    fn slot_subscribe(&self, config: Option<RpcSlotSubscribeConfig>) -> Result<SubscriptionId> {
+        let from_slot = config.and_then(|config| config.from_slot);
+        self.subscribe_from_slot(SubscriptionParams::Slot, from_slot)
     }
 
     fn slot_unsubscribe(&self, id: SubscriptionId) -> Result<bool> {
@@ -568,6 +675,35 @@ impl RpcSolPubSubInternal for RpcSolPubSubImpl {
         self.unsubscribe(id)
     }
 
+    fn transaction_subscribe(
+        &self,
+        filter: RpcTransactionSubscribeFilter,
+        config: Option<RpcTransactionSubscribeConfig>,
+    ) -> Result<SubscriptionId> {
+        if !self.config.enable_transaction_subscription {
+            return Err(Error::new(jsonrpc_core::ErrorCode::MethodNotFound));
+        }
+        let config = config.unwrap_or_default();
+        let params = TransactionSubscriptionParams {
+            commitment: config.commitment.unwrap_or_default(),
+            encoding: config.encoding.unwrap_or(UiTransactionEncoding::Base64),
+            max_supported_transaction_version: config.max_supported_transaction_version,
+            account_include: pubkey_set(filter.account_include, "account_include")?,
+            account_exclude: pubkey_set(filter.account_exclude, "account_exclude")?,
+            account_required: pubkey_set(filter.account_required, "account_required")?,
+            vote: filter.vote.unwrap_or(false),
+            failed: filter.failed.unwrap_or(true),
+        };
+        self.subscribe(SubscriptionParams::Transaction(params))
+    }
+
+    fn transaction_unsubscribe(&self, id: SubscriptionId) -> Result<bool> {
+        if !self.config.enable_transaction_subscription {
+            return Err(Error::new(jsonrpc_core::ErrorCode::MethodNotFound));
+        }
+        self.unsubscribe(id)
+    }
+
     fn vote_subscribe(&self) -> Result<SubscriptionId> {
         if !self.config.enable_vote_subscription {
             return Err(Error::new(jsonrpc_core::ErrorCode::MethodNotFound));
@@ -894,11 +1030,14 @@ mod tests {
 
         rpc.account_subscribe(
             stake_account.pubkey().to_string(),
-            Some(RpcAccountInfoConfig {
-                commitment: Some(CommitmentConfig::processed()),
-                encoding: Some(encoding),
-                data_slice: None,
-                min_context_slot: None,
+            Some(RpcAccountSubscribeConfig {
+                account_config: RpcAccountInfoConfig {
+                    commitment: Some(CommitmentConfig::processed()),
+                    encoding: Some(encoding),
+                    data_slice: None,
+                    min_context_slot: None,
+                },
+                from_slot: None,
             }),
         )
         .unwrap();
@@ -1020,11 +1159,14 @@ mod tests {
 
         rpc.account_subscribe(
             nonce_account.pubkey().to_string(),
-            Some(RpcAccountInfoConfig {
-                commitment: Some(CommitmentConfig::processed()),
-                encoding: Some(UiAccountEncoding::JsonParsed),
-                data_slice: None,
-                min_context_slot: None,
+            Some(RpcAccountSubscribeConfig {
+                account_config: RpcAccountInfoConfig {
+                    commitment: Some(CommitmentConfig::processed()),
+                    encoding: Some(UiAccountEncoding::JsonParsed),
+                    data_slice: None,
+                    min_context_slot: None,
+                },
+                from_slot: None,
             }),
         )
         .unwrap();
@@ -1155,11 +1297,14 @@ mod tests {
 
         rpc.account_subscribe(
             bob.pubkey().to_string(),
-            Some(RpcAccountInfoConfig {
-                commitment: Some(CommitmentConfig::finalized()),
-                encoding: None,
-                data_slice: None,
-                min_context_slot: None,
+            Some(RpcAccountSubscribeConfig {
+                account_config: RpcAccountInfoConfig {
+                    commitment: Some(CommitmentConfig::finalized()),
+                    encoding: None,
+                    data_slice: None,
+                    min_context_slot: None,
+                },
+                from_slot: None,
             }),
         )
         .unwrap();
@@ -1210,11 +1355,14 @@ mod tests {
 
         rpc.account_subscribe(
             bob.pubkey().to_string(),
-            Some(RpcAccountInfoConfig {
-                commitment: Some(CommitmentConfig::finalized()),
-                encoding: None,
-                data_slice: None,
-                min_context_slot: None,
+            Some(RpcAccountSubscribeConfig {
+                account_config: RpcAccountInfoConfig {
+                    commitment: Some(CommitmentConfig::finalized()),
+                    encoding: None,
+                    data_slice: None,
+                    min_context_slot: None,
+                },
+                from_slot: None,
             }),
         )
         .unwrap();
@@ -1279,7 +1427,7 @@ mod tests {
             bank_forks,
         ));
         let (rpc, mut receiver) = rpc_pubsub_service::test_connection(&rpc_subscriptions);
-        rpc.slot_subscribe().unwrap();
+        rpc.slot_subscribe(None).unwrap();
 
         rpc_subscriptions.notify_slot(0, 0, 0);
 
@@ -1312,7 +1460,7 @@ mod tests {
             bank_forks,
         ));
         let (rpc, mut receiver) = rpc_pubsub_service::test_connection(&rpc_subscriptions);
-        let sub_id = rpc.slot_subscribe().unwrap();
+        let sub_id = rpc.slot_subscribe(None).unwrap();
 
         rpc_subscriptions.notify_slot(0, 0, 0);
         let response = receiver.recv();
@@ -6,7 +6,7 @@ use {
         rpc_subscription_tracker::{
             SubscriptionControl, SubscriptionId, SubscriptionParams, SubscriptionToken,
         },
-        rpc_subscriptions::{RpcNotification, RpcSubscriptions},
+        rpc_subscriptions::{ReplayedNotifications, RpcNotification, RpcSubscriptions},
     },
     dashmap::{mapref::entry::Entry, DashMap},
     jsonrpc_core::IoHandler,
@@ -14,14 +14,16 @@ use {
     solana_metrics::TokenCounter,
     solana_sdk::timing::AtomicInterval,
     std::{
-        io,
+        collections::HashMap,
+        io, mem,
         net::SocketAddr,
         str,
         sync::{
             atomic::{AtomicU64, AtomicUsize, Ordering},
-            Arc,
+            Arc, Mutex,
         },
         thread::{self, Builder, JoinHandle},
+        time::Duration,
     },
     stream_cancel::{Trigger, Tripwire},
     thiserror::Error,
@@ -34,16 +36,25 @@ pub const DEFAULT_QUEUE_CAPACITY_ITEMS: usize = 10_000_000;
 pub const DEFAULT_TEST_QUEUE_CAPACITY_ITEMS: usize = 100;
 pub const DEFAULT_QUEUE_CAPACITY_BYTES: usize = 256 * 1024 * 1024;
 pub const DEFAULT_WORKER_THREADS: usize = 1;
+pub const DEFAULT_REPLAY_CAPACITY_BYTES: usize = 64 * 1024 * 1024;
+pub const DEFAULT_REPLAY_LINGER: Duration = Duration::from_secs(60);
 
 #[derive(Debug, Clone)]
 pub struct PubSubConfig {
     pub enable_block_subscription: bool,
     pub enable_vote_subscription: bool,
+    pub enable_transaction_subscription: bool,
     pub max_active_subscriptions: usize,
     pub queue_capacity_items: usize,
     pub queue_capacity_bytes: usize,
     pub worker_threads: usize,
     pub notification_threads: Option<usize>,
+    /// The maximum number of notifications kept for replay, replay is disabled if 0
+    pub replay_capacity_items: usize,
+    pub replay_capacity_bytes: usize,
+    /// How long the subscriptions of a closed connection are kept alive, so
+    /// that they can be resumed
+    pub replay_linger: Duration,
 }
 
 impl Default for PubSubConfig {
@@ -51,11 +62,15 @@ impl Default for PubSubConfig {
         Self {
             enable_block_subscription: false,
             enable_vote_subscription: false,
+            enable_transaction_subscription: false,
             max_active_subscriptions: MAX_ACTIVE_SUBSCRIPTIONS,
             queue_capacity_items: DEFAULT_QUEUE_CAPACITY_ITEMS,
             queue_capacity_bytes: DEFAULT_QUEUE_CAPACITY_BYTES,
             worker_threads: DEFAULT_WORKER_THREADS,
             notification_threads: None,
+            replay_capacity_items: 0,
+            replay_capacity_bytes: DEFAULT_REPLAY_CAPACITY_BYTES,
+            replay_linger: DEFAULT_REPLAY_LINGER,
         }
     }
 }
@@ -65,11 +80,15 @@ impl PubSubConfig {
         Self {
             enable_block_subscription: false,
             enable_vote_subscription: false,
+            enable_transaction_subscription: false,
             max_active_subscriptions: MAX_ACTIVE_SUBSCRIPTIONS,
             queue_capacity_items: DEFAULT_TEST_QUEUE_CAPACITY_ITEMS,
             queue_capacity_bytes: DEFAULT_QUEUE_CAPACITY_BYTES,
             worker_threads: DEFAULT_WORKER_THREADS,
             notification_threads: Some(2),
+            replay_capacity_items: 0,
+            replay_capacity_bytes: DEFAULT_REPLAY_CAPACITY_BYTES,
+            replay_linger: DEFAULT_REPLAY_LINGER,
         }
     }
 }
@@ -132,6 +151,7 @@ struct SentNotificationStats {
     num_root: AtomicUsize,
     num_vote: AtomicUsize,
     num_block: AtomicUsize,
+    num_transaction: AtomicUsize,
     total_creation_to_queue_time_us: AtomicU64,
     last_report: AtomicInterval,
 }
@@ -186,6 +206,11 @@ impl SentNotificationStats {
                     self.num_block.swap(0, Ordering::Relaxed) as i64,
                     i64
                 ),
+                (
+                    "num_transaction",
+                    self.num_transaction.swap(0, Ordering::Relaxed) as i64,
+                    i64
+                ),
                 (
                     "total_creation_to_queue_time_us",
                     self.total_creation_to_queue_time_us
@@ -199,6 +224,7 @@ impl SentNotificationStats {
 
 struct BroadcastHandler {
     current_subscriptions: Arc<DashMap<SubscriptionId, SubscriptionToken>>,
+    resumed_subscriptions: Arc<Mutex<ResumedSubscriptions>>,
     sent_stats: Arc<SentNotificationStats>,
 }
 
@@ -235,6 +261,9 @@ fn increment_sent_notification_stats(
         SubscriptionParams::Block(_) => {
             stats.num_block.fetch_add(1, Ordering::Relaxed);
         }
+        SubscriptionParams::Transaction(_) => {
+            stats.num_transaction.fetch_add(1, Ordering::Relaxed);
+        }
     }
     stats.total_creation_to_queue_time_us.fetch_add(
         notification.created_at.elapsed().as_micros() as u64,
@@ -244,16 +273,65 @@ fn increment_sent_notification_stats(
     stats.maybe_report();
 }
 
+/// The subscriptions resumed from a slot on a connection
+#[derive(Default)]
+pub struct ResumedSubscriptions {
+    /// The replayed notifications, which must be sent to the client after the
+    /// response to the subscription request
+    pending: Vec<Arc<String>>,
+    next_sequences: HashMap<SubscriptionId, u64>,
+}
+
+impl ResumedSubscriptions {
+    pub(crate) fn resume(&mut self, id: SubscriptionId, replayed: ReplayedNotifications) {
+        self.pending.extend(replayed.notifications);
+        self.next_sequences.insert(id, replayed.next_sequence);
+    }
+
+    pub(crate) fn remove(&mut self, id: SubscriptionId) {
+        self.next_sequences.remove(&id);
+    }
+
+    fn take_pending(&mut self) -> Vec<Arc<String>> {
+        mem::take(&mut self.pending)
+    }
+
+    /// Whether the live notification was already replayed, or predates the
+    /// replay
+    fn is_replayed(&self, notification: &RpcNotification) -> bool {
+        match (
+            notification.sequence,
+            self.next_sequences.get(&notification.subscription_id),
+        ) {
+            (Some(sequence), Some(next_sequence)) => sequence < *next_sequence,
+            _ => false,
+        }
+    }
+}
+
 impl BroadcastHandler {
-    fn new(current_subscriptions: Arc<DashMap<SubscriptionId, SubscriptionToken>>) -> Self {
+    fn new(
+        current_subscriptions: Arc<DashMap<SubscriptionId, SubscriptionToken>>,
+        resumed_subscriptions: Arc<Mutex<ResumedSubscriptions>>,
+    ) -> Self {
         let sent_stats = Arc::new(SentNotificationStats::default());
         Self {
             current_subscriptions,
+            resumed_subscriptions,
             sent_stats,
         }
     }
 
     fn handle(&self, notification: RpcNotification) -> Result<Option<Arc<String>>, Error> {
+        if notification.sequence.is_some()
+            && self
+                .resumed_subscriptions
+                .lock()
+                .unwrap()
+                .is_replayed(&notification)
+        {
+            return Ok(None);
+        }
         if let Entry::Occupied(entry) = self
             .current_subscriptions
             .entry(notification.subscription_id)
@@ -282,6 +360,7 @@ impl BroadcastHandler {
 pub struct TestBroadcastReceiver {
     handler: BroadcastHandler,
     inner: tokio::sync::broadcast::Receiver<RpcNotification>,
+    resumed_subscriptions: Arc<Mutex<ResumedSubscriptions>>,
 }
 
 #[cfg(test)]
@@ -298,6 +377,13 @@ impl TestBroadcastReceiver {
 
         let started = std::time::Instant::now();
 
+        {
+            let mut resumed_subscriptions = self.resumed_subscriptions.lock().unwrap();
+            if !resumed_subscriptions.pending.is_empty() {
+                return Ok(resumed_subscriptions.pending.remove(0).to_string());
+            }
+        }
+
         loop {
             match self.inner.try_recv() {
                 Ok(notification) => {
@@ -331,16 +417,20 @@ pub fn test_connection(
         PubSubConfig {
             enable_block_subscription: true,
             enable_vote_subscription: true,
+            enable_transaction_subscription: true,
             queue_capacity_items: 100,
             ..PubSubConfig::default()
         },
         subscriptions.control().clone(),
         Arc::clone(&current_subscriptions),
     );
-    let broadcast_handler = BroadcastHandler::new(current_subscriptions);
+    let resumed_subscriptions = rpc_impl.resumed_subscriptions();
+    let broadcast_handler =
+        BroadcastHandler::new(current_subscriptions, Arc::clone(&resumed_subscriptions));
     let receiver = TestBroadcastReceiver {
         inner: subscriptions.control().broadcast_receiver(),
         handler: broadcast_handler,
+        resumed_subscriptions,
     };
     (rpc_impl, receiver)
 }
@@ -361,6 +451,7 @@ async fn handle_connection(
     socket: TcpStream,
     subscription_control: SubscriptionControl,
     config: PubSubConfig,
+    current_subscriptions: Arc<DashMap<SubscriptionId, SubscriptionToken>>,
     mut tripwire: Tripwire,
 ) -> Result<(), Error> {
     let mut server = Server::new(socket.compat());
@@ -374,7 +465,6 @@ async fn handle_connection(
 
     let mut broadcast_receiver = subscription_control.broadcast_receiver();
     let mut data = Vec::new();
-    let current_subscriptions = Arc::new(DashMap::new());
 
     let mut json_rpc_handler = IoHandler::new();
     let rpc_impl = RpcSolPubSubImpl::new(
@@ -382,8 +472,10 @@ async fn handle_connection(
         subscription_control,
         Arc::clone(&current_subscriptions),
     );
+    let resumed_subscriptions = rpc_impl.resumed_subscriptions();
     json_rpc_handler.extend_with(rpc_impl.to_delegate());
-    let broadcast_handler = BroadcastHandler::new(current_subscriptions);
+    let broadcast_handler =
+        BroadcastHandler::new(current_subscriptions, Arc::clone(&resumed_subscriptions));
     loop {
         // Extra block for dropping `receive_future`.
         {
@@ -422,6 +514,10 @@ async fn handle_connection(
         if let Some(response) = json_rpc_handler.handle_request(data_str).await {
             sender.send_text(&response).await?;
         }
+        let notifications = resumed_subscriptions.lock().unwrap().take_pending();
+        for json in notifications {
+            sender.send_text(&*json).await?;
+        }
         data.clear();
     }
 
@@ -446,14 +542,31 @@ async fn listen(
                     let tripwire = tripwire.clone();
                     let counter_token = counter.create_token();
                     tokio::spawn(async move {
+                        let current_subscriptions = Arc::new(DashMap::new());
+                        let replay_linger = (config.replay_capacity_items > 0)
+                            .then_some(config.replay_linger);
                         let handle = handle_connection(
-                            socket, subscription_control, config, tripwire
+                            socket,
+                            subscription_control,
+                            config,
+                            Arc::clone(&current_subscriptions),
+                            tripwire.clone(),
                         );
                         match handle.await {
                             Ok(()) => debug!("connection closed ({:?})", addr),
                             Err(err) => warn!("connection handler error ({:?}): {}", addr, err),
                         }
                         drop(counter_token); // Force moving token into the task.
+                        // Keep the subscriptions alive so that they can be resumed
+                        if let Some(replay_linger) = replay_linger {
+                            if !current_subscriptions.is_empty() {
+                                select! {
+                                    _ = tokio::time::sleep(replay_linger) => (),
+                                    _ = tripwire => (),
+                                }
+                            }
+                        }
+                        drop(current_subscriptions);
                     });
                 }
                 Err(e) => error!("couldn't accept connection: {:?}", e),
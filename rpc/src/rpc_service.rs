@@ -59,10 +59,7 @@ const LARGEST_ACCOUNTS_CACHE_DURATION: u64 = 60 * 60 * 2;
 
 pub struct JsonRpcService {
     thread_hdl: JoinHandle<()>,
-
-    #[cfg(test)]
-    pub request_processor: JsonRpcRequestProcessor, // Used only by test_rpc_new()...
-
+    request_processor: JsonRpcRequestProcessor,
     close_handle: Option<CloseHandle>,
 }
 
@@ -406,6 +403,7 @@ impl JsonRpcService {
                 ref bigtable_instance_name,
                 ref bigtable_app_profile_id,
                 timeout,
+                ref local_storage_path,
             }) = config.rpc_bigtable_config
             {
                 let bigtable_config = solana_storage_bigtable::LedgerStorageConfig {
@@ -414,6 +412,7 @@ impl JsonRpcService {
                     credential_type: CredentialType::Filepath(None),
                     instance_name: bigtable_instance_name.clone(),
                     app_profile_id: bigtable_app_profile_id.clone(),
+                    local_storage_path: local_storage_path.clone(),
                 };
                 runtime
                     .block_on(solana_storage_bigtable::LedgerStorage::new_with_config(
@@ -487,8 +486,7 @@ impl JsonRpcService {
             exit,
         ));
 
-        #[cfg(test)]
-        let test_request_processor = request_processor.clone();
+        let service_request_processor = request_processor.clone();
 
         let ledger_path = ledger_path.to_path_buf();
 
@@ -561,12 +559,16 @@ impl JsonRpcService {
             }));
         Ok(Self {
             thread_hdl,
-            #[cfg(test)]
-            request_processor: test_request_processor,
+            request_processor: service_request_processor,
             close_handle: Some(close_handle),
         })
     }
 
+    /// The request processor shared by the requests, for serving them over other protocols
+    pub fn request_processor(&self) -> &JsonRpcRequestProcessor {
+        &self.request_processor
+    }
+
     pub fn exit(&mut self) {
         if let Some(c) = self.close_handle.take() {
             c.close()
@@ -659,7 +661,7 @@ mod tests {
         assert_eq!(
             10_000,
             rpc_service
-                .request_processor
+                .request_processor()
                 .get_balance(&mint_keypair.pubkey(), RpcContextConfig::default())
                 .unwrap()
                 .value
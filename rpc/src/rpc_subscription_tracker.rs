@@ -1,5 +1,8 @@
 use {
-    crate::rpc_subscriptions::{NotificationEntry, RpcNotification, TimestampedNotificationEntry},
+    crate::rpc_subscriptions::{
+        NotificationEntry, RawNotification, ReplayBuffer, ReplayedNotifications, RpcNotification,
+        TimestampedNotificationEntry,
+    },
     dashmap::{mapref::entry::Entry as DashEntry, DashMap},
     solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig},
     solana_metrics::{CounterToken, TokenCounter},
@@ -13,7 +16,10 @@ use {
     },
     solana_transaction_status::{TransactionDetails, UiTransactionEncoding},
     std::{
-        collections::hash_map::{Entry, HashMap},
+        collections::{
+            hash_map::{Entry, HashMap},
+            BTreeSet,
+        },
         fmt,
         sync::{
             atomic::{AtomicU64, Ordering},
@@ -46,6 +52,7 @@ pub enum SubscriptionParams {
     Logs(LogsSubscriptionParams),
     Program(ProgramSubscriptionParams),
     Signature(SignatureSubscriptionParams),
+    Transaction(TransactionSubscriptionParams),
     Slot,
     SlotsUpdates,
     Root,
@@ -62,18 +69,20 @@ impl SubscriptionParams {
             SubscriptionParams::Slot => "slotNotification",
             SubscriptionParams::SlotsUpdates => "slotsUpdatesNotification",
             SubscriptionParams::Block(_) => "blockNotification",
+            SubscriptionParams::Transaction(_) => "transactionNotification",
             SubscriptionParams::Root => "rootNotification",
             SubscriptionParams::Vote => "voteNotification",
         }
     }
 
-    fn commitment(&self) -> Option<CommitmentConfig> {
+    pub(crate) fn commitment(&self) -> Option<CommitmentConfig> {
         match self {
             SubscriptionParams::Account(params) => Some(params.commitment),
             SubscriptionParams::Logs(params) => Some(params.commitment),
             SubscriptionParams::Program(params) => Some(params.commitment),
             SubscriptionParams::Signature(params) => Some(params.commitment),
             SubscriptionParams::Block(params) => Some(params.commitment),
+            SubscriptionParams::Transaction(params) => Some(params.commitment),
             SubscriptionParams::Slot
             | SubscriptionParams::SlotsUpdates
             | SubscriptionParams::Root
@@ -88,6 +97,7 @@ impl SubscriptionParams {
             SubscriptionParams::Logs(params) => &params.commitment,
             SubscriptionParams::Program(params) => &params.commitment,
             SubscriptionParams::Signature(params) => &params.commitment,
+            SubscriptionParams::Transaction(params) => &params.commitment,
             SubscriptionParams::Root
             | SubscriptionParams::Slot
             | SubscriptionParams::SlotsUpdates
@@ -103,6 +113,7 @@ impl SubscriptionParams {
             SubscriptionParams::Logs(params) => &params.commitment,
             SubscriptionParams::Program(params) => &params.commitment,
             SubscriptionParams::Signature(params) => &params.commitment,
+            SubscriptionParams::Transaction(params) => &params.commitment,
             SubscriptionParams::Root
             | SubscriptionParams::Slot
             | SubscriptionParams::SlotsUpdates
@@ -111,6 +122,18 @@ impl SubscriptionParams {
         commitment.is_confirmed()
     }
 
+    /// Whether the notifications of this subscription are kept for the clients
+    /// resuming it from a slot
+    pub(crate) fn is_replayable(&self) -> bool {
+        matches!(
+            self,
+            SubscriptionParams::Account(_)
+                | SubscriptionParams::Logs(_)
+                | SubscriptionParams::Program(_)
+                | SubscriptionParams::Slot
+        )
+    }
+
     fn is_node_progress_watcher(&self) -> bool {
         matches!(
             self,
@@ -176,6 +199,18 @@ pub struct SignatureSubscriptionParams {
     pub enable_received_notification: bool,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TransactionSubscriptionParams {
+    pub commitment: CommitmentConfig,
+    pub encoding: UiTransactionEncoding,
+    pub max_supported_transaction_version: Option<u8>,
+    pub account_include: BTreeSet<Pubkey>,
+    pub account_exclude: BTreeSet<Pubkey>,
+    pub account_required: BTreeSet<Pubkey>,
+    pub vote: bool,
+    pub failed: bool,
+}
+
 #[derive(Clone)]
 pub struct SubscriptionControl(Arc<SubscriptionControlInner>);
 pub struct WeakSubscriptionTokenRef(Weak<SubscriptionTokenInner>, SubscriptionId);
@@ -186,6 +221,8 @@ struct SubscriptionControlInner {
     max_active_subscriptions: usize,
     sender: crossbeam_channel::Sender<TimestampedNotificationEntry>,
     broadcast_sender: broadcast::Sender<RpcNotification>,
+    raw_broadcast_sender: broadcast::Sender<RawNotification>,
+    replay_buffer: Option<Arc<ReplayBuffer>>,
     counter: TokenCounter,
 }
 
@@ -194,6 +231,8 @@ impl SubscriptionControl {
         max_active_subscriptions: usize,
         sender: crossbeam_channel::Sender<TimestampedNotificationEntry>,
         broadcast_sender: broadcast::Sender<RpcNotification>,
+        raw_broadcast_sender: broadcast::Sender<RawNotification>,
+        replay_buffer: Option<Arc<ReplayBuffer>>,
     ) -> Self {
         Self(Arc::new(SubscriptionControlInner {
             subscriptions: DashMap::new(),
@@ -201,6 +240,8 @@ impl SubscriptionControl {
             max_active_subscriptions,
             sender,
             broadcast_sender,
+            raw_broadcast_sender,
+            replay_buffer,
             counter: TokenCounter::new("rpc_pubsub_total_subscriptions"),
         }))
     }
@@ -209,6 +250,11 @@ impl SubscriptionControl {
         self.0.broadcast_sender.subscribe()
     }
 
+    /// Receives the notification values as is, for the gRPC subscribers
+    pub fn raw_broadcast_receiver(&self) -> broadcast::Receiver<RawNotification> {
+        self.0.raw_broadcast_sender.subscribe()
+    }
+
     pub fn subscribe(&self, params: SubscriptionParams) -> Result<SubscriptionToken, Error> {
         debug!(
             "Total existing subscriptions: {}",
@@ -266,6 +312,19 @@ impl SubscriptionControl {
         self.0.subscriptions.len()
     }
 
+    /// Returns the notifications of the subscription `id` for `from_slot` and
+    /// later slots, if none of them is missing
+    pub fn replay(
+        &self,
+        id: SubscriptionId,
+        from_slot: Slot,
+    ) -> Result<ReplayedNotifications, Error> {
+        let replay_buffer = self.0.replay_buffer.as_ref().ok_or(Error::ReplayDisabled)?;
+        replay_buffer
+            .replay(id, from_slot)
+            .ok_or(Error::ReplayUnavailable(from_slot))
+    }
+
     #[cfg(test)]
     pub fn assert_subscribed(&self, params: &SubscriptionParams) {
         assert!(self.0.subscriptions.contains_key(params));
@@ -345,6 +404,12 @@ impl SubscriptionInfo {
 pub enum Error {
     #[error("node subscription limit reached")]
     TooManySubscriptions,
+
+    #[error("subscription replay is disabled")]
+    ReplayDisabled,
+
+    #[error("notifications since slot {0} are not available")]
+    ReplayUnavailable(Slot),
 }
 
 struct LogsSubscriptionsIndex {
@@ -607,11 +672,14 @@ mod tests {
         fn new() -> Self {
             let (sender, receiver) = crossbeam_channel::unbounded();
             let (broadcast_sender, _broadcast_receiver) = broadcast::channel(42);
+            let (raw_broadcast_sender, _raw_broadcast_receiver) = broadcast::channel(42);
 
             let control = SubscriptionControl::new(
                 PubSubConfig::default().max_active_subscriptions,
                 sender,
                 broadcast_sender,
+                raw_broadcast_sender,
+                None,
             );
             Self { control, receiver }
         }
@@ -9,7 +9,7 @@ use {
             AccountSubscriptionParams, BlockSubscriptionKind, BlockSubscriptionParams,
             LogsSubscriptionKind, LogsSubscriptionParams, ProgramSubscriptionParams,
             SignatureSubscriptionParams, SubscriptionControl, SubscriptionId, SubscriptionInfo,
-            SubscriptionParams, SubscriptionsTracker,
+            SubscriptionParams, SubscriptionsTracker, TransactionSubscriptionParams,
         },
     },
     crossbeam_channel::{Receiver, RecvTimeoutError, SendError, Sender},
@@ -23,7 +23,7 @@ use {
     solana_rpc_client_api::response::{
         ProcessedSignatureResult, ReceivedSignatureResult, Response as RpcResponse, RpcBlockUpdate,
         RpcBlockUpdateError, RpcKeyedAccount, RpcLogsResponse, RpcResponseContext,
-        RpcSignatureResult, RpcVote, SlotInfo, SlotUpdate,
+        RpcSignatureResult, RpcTransactionUpdate, RpcVote, SlotInfo, SlotUpdate,
     },
     solana_runtime::{
         bank::{Bank, TransactionLogInfo},
@@ -40,6 +40,7 @@ use {
     },
     solana_transaction_status::{
         BlockEncodingOptions, ConfirmedBlock, EncodeError, VersionedConfirmedBlock,
+        VersionedTransactionWithStatusMeta,
     },
     solana_vote::vote_transaction::VoteTransaction,
     std::{
@@ -59,6 +60,9 @@ use {
 
 const RECEIVE_DELAY_MILLIS: u64 = 100;
 
+/// The number of raw notifications queued for the gRPC subscribers
+const RAW_QUEUE_CAPACITY_ITEMS: usize = 65_536;
+
 fn get_transaction_logs(
     bank: &Bank,
     params: &LogsSubscriptionParams,
@@ -155,14 +159,15 @@ where
         let (filter_results, result_slot) =
             filter_results(results, params, *w_last_notified_slot, bank);
         for result in filter_results {
-            notifier.notify(
-                RpcResponse::from(RpcNotificationResponse {
-                    context: RpcNotificationContext { slot },
-                    value: result,
-                }),
-                subscription,
-                is_final,
-            );
+            let notification = RpcResponse::from(RpcNotificationResponse {
+                context: RpcNotificationContext { slot },
+                value: result,
+            });
+            if is_final {
+                notifier.notify(notification, subscription, is_final);
+            } else {
+                notifier.notify_replayable(notification, subscription, slot);
+            }
             *w_last_notified_slot = result_slot;
             notified = true;
         }
@@ -177,6 +182,25 @@ pub struct RpcNotification {
     pub is_final: bool,
     pub json: Weak<String>,
     pub created_at: Instant,
+    /// The sequence number of the notifications kept for replay, which tells
+    /// the live notifications of a resumed subscription from the replayed ones
+    pub sequence: Option<u64>,
+}
+
+/// A notification value as is, for the gRPC subscribers which do not need it
+/// encoded to JSON
+#[derive(Debug, Clone)]
+pub enum RawNotificationValue {
+    /// The account, or the default account if it was deleted
+    Account(AccountSharedData),
+    Slot(SlotInfo),
+}
+
+#[derive(Debug, Clone)]
+pub struct RawNotification {
+    pub subscription_id: SubscriptionId,
+    pub slot: Slot,
+    pub value: RawNotificationValue,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -261,9 +285,104 @@ impl RecentItems {
     }
 }
 
+/// Keeps the recent notifications of the replayable subscriptions, so that a
+/// client reconnecting while its subscriptions linger can resume them from a
+/// slot
+pub struct ReplayBuffer {
+    max_len: usize,
+    max_total_bytes: usize,
+    inner: Mutex<ReplayBufferInner>,
+}
+
+/// The notifications replayed to a resumed subscription
+pub struct ReplayedNotifications {
+    pub notifications: Vec<Arc<String>>,
+    /// The live notifications of the subscription with a lower sequence number
+    /// were either replayed or are older than the replay
+    pub next_sequence: u64,
+}
+
+#[derive(Default)]
+struct ReplayBufferInner {
+    queue: VecDeque<(SubscriptionId, Slot, u64, Arc<String>)>,
+    total_bytes: usize,
+    next_sequence: u64,
+    // The first slot from which the notifications of each subscription are all kept
+    first_slots: HashMap<SubscriptionId, Slot>,
+}
+
+impl ReplayBuffer {
+    pub fn new(max_len: usize, max_total_bytes: usize) -> Self {
+        Self {
+            max_len,
+            max_total_bytes,
+            inner: Mutex::default(),
+        }
+    }
+
+    fn subscribe(&self, id: SubscriptionId, first_slot: Slot) {
+        self.inner
+            .lock()
+            .unwrap()
+            .first_slots
+            .insert(id, first_slot);
+    }
+
+    fn unsubscribe(&self, id: SubscriptionId) {
+        // The notifications of the subscription are dropped as the queue moves on
+        self.inner.lock().unwrap().first_slots.remove(&id);
+    }
+
+    /// Keeps the notification if the subscription is replayable, returning
+    /// its sequence number
+    fn push(&self, id: SubscriptionId, slot: Slot, item: Arc<String>) -> Option<u64> {
+        let mut inner = self.inner.lock().unwrap();
+        if !inner.first_slots.contains_key(&id) {
+            return None;
+        }
+        // Sequence numbers are assigned under the lock, so that a replay holds
+        // all the notifications of the subscription below its `next_sequence`
+        let sequence = inner.next_sequence;
+        inner.next_sequence = sequence.checked_add(1).expect("sequence overflow");
+        inner.total_bytes = inner
+            .total_bytes
+            .checked_add(item.len())
+            .expect("total bytes overflow");
+        inner.queue.push_back((id, slot, sequence, item));
+
+        while inner.total_bytes > self.max_total_bytes || inner.queue.len() > self.max_len {
+            let (id, slot, _, item) = inner.queue.pop_front().expect("can't be empty");
+            inner.total_bytes = inner
+                .total_bytes
+                .checked_sub(item.len())
+                .expect("total bytes underflow");
+            if let Some(first_slot) = inner.first_slots.get_mut(&id) {
+                *first_slot = (*first_slot).max(slot.saturating_add(1));
+            }
+        }
+        Some(sequence)
+    }
+
+    fn replay(&self, id: SubscriptionId, from_slot: Slot) -> Option<ReplayedNotifications> {
+        let inner = self.inner.lock().unwrap();
+        let first_slot = *inner.first_slots.get(&id)?;
+        (first_slot <= from_slot).then(|| ReplayedNotifications {
+            notifications: inner
+                .queue
+                .iter()
+                .filter(|(item_id, slot, _, _)| *item_id == id && *slot >= from_slot)
+                .map(|(_, _, _, item)| Arc::clone(item))
+                .collect(),
+            next_sequence: inner.next_sequence,
+        })
+    }
+}
+
 struct RpcNotifier {
     sender: broadcast::Sender<RpcNotification>,
+    raw_sender: broadcast::Sender<RawNotification>,
     recent_items: Mutex<RecentItems>,
+    replay_buffer: Option<Arc<ReplayBuffer>>,
 }
 
 thread_local! {
@@ -288,7 +407,41 @@ impl RpcNotifier {
     where
         T: serde::Serialize,
     {
-        let buf_arc = RPC_NOTIFIER_BUF.with(|buf| {
+        let buf_arc = Self::serialize(value, subscription);
+        self.send(buf_arc, subscription, is_final, None);
+    }
+
+    /// Like `notify`, also keeping the notification for `slot` in the replay buffer
+    fn notify_replayable<T>(&self, value: T, subscription: &SubscriptionInfo, slot: Slot)
+    where
+        T: serde::Serialize,
+    {
+        let buf_arc = Self::serialize(value, subscription);
+        let sequence = self.replay_buffer.as_ref().and_then(|replay_buffer| {
+            replay_buffer.push(subscription.id(), slot, Arc::clone(&buf_arc))
+        });
+        self.send(buf_arc, subscription, false, sequence);
+    }
+
+    fn has_raw_receivers(&self) -> bool {
+        self.raw_sender.receiver_count() > 0
+    }
+
+    /// Sends the value of a notification as is to the gRPC subscribers
+    fn notify_raw(&self, subscription: &SubscriptionInfo, slot: Slot, value: RawNotificationValue) {
+        // This fails if there is no gRPC subscriber
+        let _ = self.raw_sender.send(RawNotification {
+            subscription_id: subscription.id(),
+            slot,
+            value,
+        });
+    }
+
+    fn serialize<T>(value: T, subscription: &SubscriptionInfo) -> Arc<String>
+    where
+        T: serde::Serialize,
+    {
+        RPC_NOTIFIER_BUF.with(|buf| {
             let mut buf = buf.borrow_mut();
             buf.clear();
             let notification = Notification {
@@ -303,13 +456,22 @@ impl RpcNotifier {
                 .expect("serialization never fails");
             let buf_str = str::from_utf8(&buf).expect("json is always utf-8");
             Arc::new(String::from(buf_str))
-        });
+        })
+    }
 
+    fn send(
+        &self,
+        buf_arc: Arc<String>,
+        subscription: &SubscriptionInfo,
+        is_final: bool,
+        sequence: Option<u64>,
+    ) {
         let notification = RpcNotification {
             subscription_id: subscription.id(),
             json: Arc::downgrade(&buf_arc),
             is_final,
             created_at: Instant::now(),
+            sequence,
         };
         // There is an unlikely case where this can fail: if the last subscription is closed
         // just as the notifier generates a notification for it.
@@ -367,6 +529,57 @@ fn filter_block_result_txs(
     }))
 }
 
+fn is_vote_transaction(tx_with_meta: &VersionedTransactionWithStatusMeta) -> bool {
+    let message = &tx_with_meta.transaction.message;
+    message.instructions().iter().any(|instruction| {
+        instruction.program_id(message.static_account_keys()) == &solana_sdk::vote::program::id()
+    })
+}
+
+fn filter_transaction_results(
+    block: VersionedConfirmedBlock,
+    slot: Slot,
+    params: &TransactionSubscriptionParams,
+) -> Vec<RpcTransactionUpdate> {
+    let block_time = block.block_time;
+    block
+        .transactions
+        .into_iter()
+        .filter(|tx_with_meta| {
+            if !params.vote && is_vote_transaction(tx_with_meta) {
+                return false;
+            }
+            if !params.failed && tx_with_meta.meta.status.is_err() {
+                return false;
+            }
+            let account_keys = tx_with_meta.account_keys();
+            let mentions = |pubkey: &Pubkey| account_keys.iter().any(|key| key == pubkey);
+            (params.account_include.is_empty() || params.account_include.iter().any(mentions))
+                && !params.account_exclude.iter().any(mentions)
+                && params.account_required.iter().all(mentions)
+        })
+        .filter_map(|tx_with_meta| {
+            let signature = tx_with_meta.transaction.signatures[0].to_string();
+            match tx_with_meta.encode(
+                params.encoding,
+                params.max_supported_transaction_version,
+                true,
+            ) {
+                Ok(transaction) => Some(RpcTransactionUpdate {
+                    signature,
+                    slot,
+                    transaction,
+                    block_time,
+                }),
+                Err(EncodeError::UnsupportedTransactionVersion(version)) => {
+                    debug!("skipping transaction {signature} with unsupported version {version}");
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
 fn filter_account_result(
     result: Option<(AccountSharedData, Slot)>,
     params: &AccountSubscriptionParams,
@@ -475,6 +688,27 @@ fn initial_last_notified_slot(
     }
 }
 
+/// Returns the first slot for which all the notifications of a new subscription
+/// are sent
+fn first_replayable_slot(
+    params: &SubscriptionParams,
+    bank_forks: &RwLock<BankForks>,
+    block_commitment_cache: &RwLock<BlockCommitmentCache>,
+    optimistically_confirmed_bank: &RwLock<OptimisticallyConfirmedBank>,
+) -> Slot {
+    let slot = match params.commitment() {
+        Some(commitment) if commitment.is_finalized() => block_commitment_cache
+            .read()
+            .unwrap()
+            .highest_super_majority_root(),
+        Some(commitment) if commitment.is_confirmed() => {
+            optimistically_confirmed_bank.read().unwrap().bank.slot()
+        }
+        _ => bank_forks.read().unwrap().highest_slot(),
+    };
+    slot.saturating_add(1)
+}
+
 #[derive(Default)]
 struct PubsubNotificationStats {
     since: Option<Instant>,
@@ -623,13 +857,22 @@ impl RpcSubscriptions {
         let subscriptions = SubscriptionsTracker::new(bank_forks.clone());
 
         let (broadcast_sender, _) = broadcast::channel(config.queue_capacity_items);
+        let (raw_broadcast_sender, _) = broadcast::channel(RAW_QUEUE_CAPACITY_ITEMS);
 
+        let replay_buffer = (config.replay_capacity_items > 0).then(|| {
+            Arc::new(ReplayBuffer::new(
+                config.replay_capacity_items,
+                config.replay_capacity_bytes,
+            ))
+        });
         let notifier = RpcNotifier {
             sender: broadcast_sender.clone(),
+            raw_sender: raw_broadcast_sender.clone(),
             recent_items: Mutex::new(RecentItems::new(
                 config.queue_capacity_items,
                 config.queue_capacity_bytes,
             )),
+            replay_buffer: replay_buffer.clone(),
         };
         let notification_threads = config.notification_threads.unwrap_or_else(get_thread_count);
         let t_cleanup = if notification_threads == 0 {
@@ -671,6 +914,8 @@ impl RpcSubscriptions {
             config.max_active_subscriptions,
             notification_sender.clone(),
             broadcast_sender,
+            raw_broadcast_sender,
+            replay_buffer,
         );
 
         Self {
@@ -793,6 +1038,19 @@ impl RpcSubscriptions {
                     let TimestampedNotificationEntry { entry, queued_at } = notification_entry;
                     match entry {
                         NotificationEntry::Subscribed(params, id) => {
+                            if let Some(replay_buffer) = &notifier.replay_buffer {
+                                if params.is_replayable() {
+                                    replay_buffer.subscribe(
+                                        id,
+                                        first_replayable_slot(
+                                            &params,
+                                            &bank_forks,
+                                            &block_commitment_cache,
+                                            &optimistically_confirmed_bank,
+                                        ),
+                                    );
+                                }
+                            }
                             subscriptions.subscribe(params.clone(), id, || {
                                 initial_last_notified_slot(
                                     &params,
@@ -804,6 +1062,9 @@ impl RpcSubscriptions {
                             });
                         }
                         NotificationEntry::Unsubscribed(params, id) => {
+                            if let Some(replay_buffer) = &notifier.replay_buffer {
+                                replay_buffer.unsubscribe(id);
+                            }
                             subscriptions.unsubscribe(params, id);
                         }
                         NotificationEntry::Slot(slot_info) => {
@@ -813,7 +1074,12 @@ impl RpcSubscriptions {
                             {
                                 debug!("slot notify: {:?}", slot_info);
                                 inc_new_counter_info!("rpc-subscription-notify-slot", 1);
-                                notifier.notify(slot_info, sub, false);
+                                notifier.notify_replayable(slot_info, sub, slot_info.slot);
+                                notifier.notify_raw(
+                                    sub,
+                                    slot_info.slot,
+                                    RawNotificationValue::Slot(slot_info),
+                                );
                             }
                         }
                         NotificationEntry::SlotUpdate(slot_update) => {
@@ -956,6 +1222,9 @@ impl RpcSubscriptions {
         let num_signatures_found = AtomicUsize::new(0);
         let num_signatures_notified = AtomicUsize::new(0);
 
+        let num_transactions_found = AtomicUsize::new(0);
+        let num_transactions_notified = AtomicUsize::new(0);
+
         let subscriptions = subscriptions.into_par_iter();
         subscriptions.for_each(|(_id, subscription)| {
             let slot = if let Some(commitment) = subscription.commitment() {
@@ -974,12 +1243,24 @@ impl RpcSubscriptions {
                 SubscriptionParams::Account(params) => {
                     num_accounts_found.fetch_add(1, Ordering::Relaxed);
                     if let Some(slot) = slot {
+                        let raw_account = RefCell::new(None);
                         let notified = check_commitment_and_notify(
                             params,
                             subscription,
                             bank_forks,
                             slot,
-                            |bank, params| bank.get_account_modified_slot(&params.pubkey),
+                            |bank, params| {
+                                let result = bank.get_account_modified_slot(&params.pubkey);
+                                if notifier.has_raw_receivers() {
+                                    *raw_account.borrow_mut() = Some(
+                                        result
+                                            .as_ref()
+                                            .map(|(account, _)| account.clone())
+                                            .unwrap_or_default(),
+                                    );
+                                }
+                                result
+                            },
                             filter_account_result,
                             notifier,
                             false,
@@ -987,6 +1268,13 @@ impl RpcSubscriptions {
 
                         if notified {
                             num_accounts_notified.fetch_add(1, Ordering::Relaxed);
+                            if let Some(account) = raw_account.into_inner() {
+                                notifier.notify_raw(
+                                    subscription,
+                                    slot,
+                                    RawNotificationValue::Account(account),
+                                );
+                            }
                         }
                     }
                 }
@@ -1138,6 +1426,54 @@ impl RpcSubscriptions {
                         }
                     }
                 }
+                SubscriptionParams::Transaction(params) => {
+                    num_transactions_found.fetch_add(1, Ordering::Relaxed);
+                    if let Some(slot) = slot {
+                        let bank = bank_forks.read().unwrap().get(slot);
+                        if let Some(bank) = bank {
+                            // Like block subscriptions, catch up on the ancestors of `slot`
+                            // that were not notified yet
+                            let mut w_last_unnotified_slot =
+                                subscription.last_notified_slot.write().unwrap();
+                            if *w_last_unnotified_slot == 0 {
+                                *w_last_unnotified_slot = slot;
+                            }
+                            let mut slots_to_notify: Vec<_> =
+                                (*w_last_unnotified_slot..slot).collect();
+                            let ancestors = bank.proper_ancestors_set();
+                            slots_to_notify.retain(|slot| ancestors.contains(slot));
+                            slots_to_notify.push(slot);
+                            for s in slots_to_notify {
+                                if s > max_complete_transaction_status_slot.load(Ordering::SeqCst) {
+                                    break;
+                                }
+
+                                let block = match blockstore.get_complete_block(s, false) {
+                                    Ok(block) => block,
+                                    Err(e) => {
+                                        // Retry from `s` on the next notification trigger
+                                        error!("get_complete_block error: {}", e);
+                                        break;
+                                    }
+                                };
+                                for transaction_update in
+                                    filter_transaction_results(block, s, params)
+                                {
+                                    notifier.notify(
+                                        RpcResponse::from(RpcNotificationResponse {
+                                            context: RpcNotificationContext { slot: s },
+                                            value: transaction_update,
+                                        }),
+                                        subscription,
+                                        false,
+                                    );
+                                    num_transactions_notified.fetch_add(1, Ordering::Relaxed);
+                                }
+                                *w_last_unnotified_slot = s + 1;
+                            }
+                        }
+                    }
+                }
                 _ => error!("wrong subscription type in alps map"),
             }
         });
@@ -1147,11 +1483,13 @@ impl RpcSubscriptions {
         let total_notified = num_accounts_notified.load(Ordering::Relaxed)
             + num_logs_notified.load(Ordering::Relaxed)
             + num_programs_notified.load(Ordering::Relaxed)
-            + num_signatures_notified.load(Ordering::Relaxed);
+            + num_signatures_notified.load(Ordering::Relaxed)
+            + num_transactions_notified.load(Ordering::Relaxed);
         let total_ms = total_time.as_ms();
         if total_notified > 0 || total_ms > 10 {
             debug!(
-                "notified({}): accounts: {} / {} logs: {} / {} programs: {} / {} signatures: {} / {}",
+                "notified({}): accounts: {} / {} logs: {} / {} programs: {} / {} signatures: {} / {} \
+                 transactions: {} / {}",
                 source,
                 num_accounts_found.load(Ordering::Relaxed),
                 num_accounts_notified.load(Ordering::Relaxed),
@@ -1161,6 +1499,8 @@ impl RpcSubscriptions {
                 num_programs_notified.load(Ordering::Relaxed),
                 num_signatures_found.load(Ordering::Relaxed),
                 num_signatures_notified.load(Ordering::Relaxed),
+                num_transactions_found.load(Ordering::Relaxed),
+                num_transactions_notified.load(Ordering::Relaxed),
             );
             inc_new_counter_info!("rpc-subscription-notify-bank-or-gossip", total_notified);
             datapoint_info!(
@@ -1206,6 +1546,16 @@ impl RpcSubscriptions {
                     num_signatures_notified.load(Ordering::Relaxed),
                     i64
                 ),
+                (
+                    "num_transaction_subscriptions",
+                    num_transactions_found.load(Ordering::Relaxed),
+                    i64
+                ),
+                (
+                    "num_transactions_notified",
+                    num_transactions_notified.load(Ordering::Relaxed),
+                    i64
+                ),
                 ("notifications_time", total_time.as_us() as i64, i64),
             );
             inc_new_counter_info!(
@@ -1224,6 +1574,10 @@ impl RpcSubscriptions {
                 "rpc-subscription-counter-num_signatures_notified",
                 num_signatures_notified.load(Ordering::Relaxed)
             );
+            inc_new_counter_info!(
+                "rpc-subscription-counter-num_transactions_notified",
+                num_transactions_notified.load(Ordering::Relaxed)
+            );
         }
     }
 
@@ -1261,9 +1615,10 @@ pub(crate) mod tests {
         serial_test::serial,
         solana_ledger::get_tmp_ledger_path_auto_delete,
         solana_rpc_client_api::config::{
-            RpcAccountInfoConfig, RpcBlockSubscribeConfig, RpcBlockSubscribeFilter,
-            RpcProgramAccountsConfig, RpcSignatureSubscribeConfig, RpcTransactionLogsConfig,
-            RpcTransactionLogsFilter,
+            RpcAccountInfoConfig, RpcAccountSubscribeConfig, RpcBlockSubscribeConfig,
+            RpcBlockSubscribeFilter, RpcProgramAccountsConfig, RpcProgramSubscribeConfig,
+            RpcSignatureSubscribeConfig, RpcSlotSubscribeConfig, RpcTransactionLogsConfig,
+            RpcTransactionLogsFilter, RpcTransactionSubscribeConfig, RpcTransactionSubscribeFilter,
         },
         solana_runtime::{
             commitment::BlockCommitment,
@@ -1279,7 +1634,7 @@ pub(crate) mod tests {
         },
         solana_transaction_status::{TransactionDetails, UiTransactionEncoding},
         std::{
-            collections::HashSet,
+            collections::{BTreeSet, HashSet},
             sync::atomic::{AtomicU64, Ordering::Relaxed},
         },
     };
@@ -1409,11 +1764,14 @@ pub(crate) mod tests {
             let sub_id = rpc
                 .account_subscribe(
                     pubkey.to_string(),
-                    Some(RpcAccountInfoConfig {
-                        commitment: Some(CommitmentConfig::processed()),
-                        encoding: None,
-                        data_slice: None,
-                        min_context_slot: None,
+                    Some(RpcAccountSubscribeConfig {
+                        account_config: RpcAccountInfoConfig {
+                            commitment: Some(CommitmentConfig::processed()),
+                            encoding: None,
+                            data_slice: None,
+                            min_context_slot: None,
+                        },
+                        from_slot: None,
                     }),
                 )
                 .unwrap();
@@ -1698,6 +2056,151 @@ pub(crate) mod tests {
             .assert_unsubscribed(&SubscriptionParams::Block(params));
     }
 
+    #[test]
+    #[serial]
+    fn test_check_confirmed_transaction_subscribe() {
+        let exit = Arc::new(AtomicBool::new(false));
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(10_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let bank_forks = BankForks::new_rw_arc(bank);
+        let optimistically_confirmed_bank =
+            OptimisticallyConfirmedBank::locked_from_bank_forks_root(&bank_forks);
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        let blockstore = Arc::new(blockstore);
+        let max_complete_transaction_status_slot = Arc::new(AtomicU64::default());
+        let max_complete_rewards_slot = Arc::new(AtomicU64::default());
+        let subscriptions = Arc::new(RpcSubscriptions::new_for_tests_with_blockstore(
+            exit,
+            max_complete_transaction_status_slot,
+            max_complete_rewards_slot,
+            blockstore.clone(),
+            bank_forks.clone(),
+            Arc::new(RwLock::new(BlockCommitmentCache::new_for_tests())),
+            optimistically_confirmed_bank,
+        ));
+        let (rpc, mut receiver) = rpc_pubsub_service::test_connection(&subscriptions);
+        let keypair1 = Keypair::new();
+        let keypair2 = Keypair::new();
+        let keypair3 = Keypair::new();
+        let filter = RpcTransactionSubscribeFilter {
+            account_include: Some(vec![
+                keypair1.pubkey().to_string(),
+                keypair2.pubkey().to_string(),
+            ]),
+            account_required: Some(vec![keypair3.pubkey().to_string()]),
+            ..RpcTransactionSubscribeFilter::default()
+        };
+        let config = RpcTransactionSubscribeConfig {
+            commitment: Some(CommitmentConfig::confirmed()),
+            encoding: Some(UiTransactionEncoding::Json),
+            max_supported_transaction_version: None,
+        };
+        let params = TransactionSubscriptionParams {
+            commitment: CommitmentConfig::confirmed(),
+            encoding: UiTransactionEncoding::Json,
+            max_supported_transaction_version: None,
+            account_include: [keypair1.pubkey(), keypair2.pubkey()].into(),
+            account_exclude: BTreeSet::new(),
+            account_required: [keypair3.pubkey()].into(),
+            vote: false,
+            failed: true,
+        };
+        let sub_id = rpc.transaction_subscribe(filter, Some(config)).unwrap();
+
+        subscriptions
+            .control
+            .assert_subscribed(&SubscriptionParams::Transaction(params.clone()));
+
+        let bank = bank_forks.read().unwrap().working_bank();
+        let max_complete_transaction_status_slot = Arc::new(AtomicU64::new(blockstore.max_root()));
+        bank.transfer(rent_exempt_amount, &mint_keypair, &keypair2.pubkey())
+            .unwrap();
+        let (entries, signatures) = create_test_transaction_entries(
+            vec![&mint_keypair, &keypair1, &keypair2, &keypair3],
+            bank.clone(),
+        );
+        populate_blockstore_for_tests(
+            entries,
+            bank,
+            blockstore.clone(),
+            max_complete_transaction_status_slot,
+        );
+
+        let slot = 0;
+        subscriptions.notify_gossip_subscribers(slot);
+        let actual_resp = receiver.recv();
+        let actual_resp = serde_json::from_str::<serde_json::Value>(&actual_resp).unwrap();
+
+        // Only the failed transfer from keypair2 to keypair3 matches the filter
+        let block = blockstore.get_complete_block(slot, false).unwrap();
+        let block_time = block.block_time;
+        let tx_with_meta = block
+            .transactions
+            .into_iter()
+            .find(|tx_with_meta| tx_with_meta.transaction.signatures[0] == signatures[1])
+            .unwrap();
+        assert!(tx_with_meta.meta.status.is_err());
+        let expected_resp = RpcTransactionUpdate {
+            signature: signatures[1].to_string(),
+            slot,
+            transaction: tx_with_meta.encode(params.encoding, None, true).unwrap(),
+            block_time,
+        };
+        let expected_resp = json!({
+           "jsonrpc": "2.0",
+           "method": "transactionNotification",
+           "params": {
+               "result": {
+                   "context": { "slot": slot },
+                   "value": expected_resp,
+               },
+               "subscription": 0,
+           }
+        });
+        assert_eq!(expected_resp, actual_resp);
+        let should_err = receiver.recv_timeout(Duration::from_millis(300));
+        assert!(should_err.is_err());
+
+        // Excluding an account or failed transactions filters out the transaction
+        let block = blockstore.get_complete_block(slot, false).unwrap();
+        let excluded = TransactionSubscriptionParams {
+            account_exclude: [keypair2.pubkey()].into(),
+            ..params.clone()
+        };
+        assert!(filter_transaction_results(block.clone(), slot, &excluded).is_empty());
+        let successful = TransactionSubscriptionParams {
+            failed: false,
+            ..params.clone()
+        };
+        assert!(filter_transaction_results(block.clone(), slot, &successful).is_empty());
+        let all = TransactionSubscriptionParams {
+            account_include: BTreeSet::new(),
+            account_required: BTreeSet::new(),
+            ..params.clone()
+        };
+        assert_eq!(
+            filter_transaction_results(block, slot, &all)
+                .into_iter()
+                .map(|update| update.signature)
+                .collect::<Vec<_>>(),
+            signatures
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+        );
+
+        rpc.transaction_unsubscribe(sub_id).unwrap();
+        subscriptions
+            .control
+            .assert_unsubscribed(&SubscriptionParams::Transaction(params));
+    }
+
     #[test]
     #[serial]
     fn test_check_finalized_block_subscribe() {
@@ -1862,12 +2365,15 @@ pub(crate) mod tests {
         let sub_id = rpc
             .program_subscribe(
                 stake::program::id().to_string(),
-                Some(RpcProgramAccountsConfig {
-                    account_config: RpcAccountInfoConfig {
-                        commitment: Some(CommitmentConfig::processed()),
-                        ..RpcAccountInfoConfig::default()
+                Some(RpcProgramSubscribeConfig {
+                    program_config: RpcProgramAccountsConfig {
+                        account_config: RpcAccountInfoConfig {
+                            commitment: Some(CommitmentConfig::processed()),
+                            ..RpcAccountInfoConfig::default()
+                        },
+                        ..RpcProgramAccountsConfig::default()
                     },
-                    ..RpcProgramAccountsConfig::default()
+                    from_slot: None,
                 }),
             )
             .unwrap();
@@ -2016,12 +2522,15 @@ pub(crate) mod tests {
         let sub_id = rpc
             .program_subscribe(
                 stake::program::id().to_string(),
-                Some(RpcProgramAccountsConfig {
-                    account_config: RpcAccountInfoConfig {
-                        commitment: Some(CommitmentConfig::confirmed()),
-                        ..RpcAccountInfoConfig::default()
+                Some(RpcProgramSubscribeConfig {
+                    program_config: RpcProgramAccountsConfig {
+                        account_config: RpcAccountInfoConfig {
+                            commitment: Some(CommitmentConfig::confirmed()),
+                            ..RpcAccountInfoConfig::default()
+                        },
+                        ..RpcProgramAccountsConfig::default()
                     },
-                    ..RpcProgramAccountsConfig::default()
+                    from_slot: None,
                 }),
             )
             .unwrap();
@@ -2191,12 +2700,15 @@ pub(crate) mod tests {
         let (rpc, mut receiver) = rpc_pubsub_service::test_connection(&subscriptions);
         rpc.program_subscribe(
             stake::program::id().to_string(),
-            Some(RpcProgramAccountsConfig {
-                account_config: RpcAccountInfoConfig {
-                    commitment: Some(CommitmentConfig::confirmed()),
-                    ..RpcAccountInfoConfig::default()
+            Some(RpcProgramSubscribeConfig {
+                program_config: RpcProgramAccountsConfig {
+                    account_config: RpcAccountInfoConfig {
+                        commitment: Some(CommitmentConfig::confirmed()),
+                        ..RpcAccountInfoConfig::default()
+                    },
+                    ..RpcProgramAccountsConfig::default()
                 },
-                ..RpcProgramAccountsConfig::default()
+                from_slot: None,
             }),
         )
         .unwrap();
@@ -2308,12 +2820,15 @@ pub(crate) mod tests {
         let sub_id = rpc
             .program_subscribe(
                 stake::program::id().to_string(),
-                Some(RpcProgramAccountsConfig {
-                    account_config: RpcAccountInfoConfig {
-                        commitment: Some(CommitmentConfig::confirmed()),
-                        ..RpcAccountInfoConfig::default()
+                Some(RpcProgramSubscribeConfig {
+                    program_config: RpcProgramAccountsConfig {
+                        account_config: RpcAccountInfoConfig {
+                            commitment: Some(CommitmentConfig::confirmed()),
+                            ..RpcAccountInfoConfig::default()
+                        },
+                        ..RpcProgramAccountsConfig::default()
                     },
-                    ..RpcProgramAccountsConfig::default()
+                    from_slot: None,
                 }),
             )
             .unwrap();
@@ -2676,7 +3191,7 @@ pub(crate) mod tests {
             optimistically_confirmed_bank,
         ));
         let (rpc, mut receiver) = rpc_pubsub_service::test_connection(&subscriptions);
-        let sub_id = rpc.slot_subscribe().unwrap();
+        let sub_id = rpc.slot_subscribe(None).unwrap();
 
         subscriptions
             .control
@@ -2703,6 +3218,70 @@ pub(crate) mod tests {
             .assert_unsubscribed(&SubscriptionParams::Slot);
     }
 
+    #[test]
+    #[serial]
+    fn test_resume_slot_subscribe() {
+        let exit = Arc::new(AtomicBool::new(false));
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let bank_forks = BankForks::new_rw_arc(bank);
+        let optimistically_confirmed_bank =
+            OptimisticallyConfirmedBank::locked_from_bank_forks_root(&bank_forks);
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
+        let subscriptions = Arc::new(RpcSubscriptions::new_with_config(
+            exit,
+            Arc::new(AtomicU64::default()),
+            Arc::new(AtomicU64::default()),
+            blockstore,
+            bank_forks,
+            Arc::new(RwLock::new(BlockCommitmentCache::new_for_tests())),
+            optimistically_confirmed_bank,
+            &PubSubConfig {
+                replay_capacity_items: 2,
+                ..PubSubConfig::default_for_tests()
+            },
+            None,
+        ));
+        let slot_notification = |slot: Slot| {
+            let slot_info = serde_json::to_string(&SlotInfo {
+                parent: slot - 1,
+                slot,
+                root: 0,
+            })
+            .unwrap();
+            format!(
+                r#"{{"jsonrpc":"2.0","method":"slotNotification","params":{{"result":{slot_info},"subscription":0}}}}"#
+            )
+        };
+
+        // The first connection keeps the subscription alive
+        let (rpc, mut receiver) = rpc_pubsub_service::test_connection(&subscriptions);
+        let sub_id = rpc.slot_subscribe(None).unwrap();
+        for slot in 1..=3 {
+            subscriptions.notify_slot(slot, slot - 1, 0);
+            assert_eq!(receiver.recv(), slot_notification(slot));
+        }
+
+        let (rpc2, mut receiver2) = rpc_pubsub_service::test_connection(&subscriptions);
+        let config = |from_slot| {
+            Some(RpcSlotSubscribeConfig {
+                from_slot: Some(from_slot),
+            })
+        };
+        // The notification for slot 1 was evicted
+        assert!(rpc2.slot_subscribe(config(1)).is_err());
+        assert_eq!(rpc2.slot_subscribe(config(2)).unwrap(), sub_id);
+        // The live notifications for slots 1 to 3, which the second connection
+        // also received, are not sent twice
+        assert_eq!(receiver2.recv(), slot_notification(2));
+        assert_eq!(receiver2.recv(), slot_notification(3));
+
+        subscriptions.notify_slot(4, 3, 0);
+        assert_eq!(receiver2.recv(), slot_notification(4));
+        assert!(receiver2.recv_timeout(Duration::from_millis(100)).is_err());
+    }
+
     #[test]
     #[serial]
     fn test_check_root_subscribe() {
@@ -2790,11 +3369,14 @@ pub(crate) mod tests {
         let sub_id0 = rpc0
             .account_subscribe(
                 alice.pubkey().to_string(),
-                Some(RpcAccountInfoConfig {
-                    commitment: Some(CommitmentConfig::confirmed()),
-                    encoding: None,
-                    data_slice: None,
-                    min_context_slot: None,
+                Some(RpcAccountSubscribeConfig {
+                    account_config: RpcAccountInfoConfig {
+                        commitment: Some(CommitmentConfig::confirmed()),
+                        encoding: None,
+                        data_slice: None,
+                        min_context_slot: None,
+                    },
+                    from_slot: None,
                 }),
             )
             .unwrap();
@@ -2886,11 +3468,14 @@ pub(crate) mod tests {
         let sub_id1 = rpc1
             .account_subscribe(
                 alice.pubkey().to_string(),
-                Some(RpcAccountInfoConfig {
-                    commitment: Some(CommitmentConfig::confirmed()),
-                    encoding: None,
-                    data_slice: None,
-                    min_context_slot: None,
+                Some(RpcAccountSubscribeConfig {
+                    account_config: RpcAccountInfoConfig {
+                        commitment: Some(CommitmentConfig::confirmed()),
+                        encoding: None,
+                        data_slice: None,
+                        min_context_slot: None,
+                    },
+                    from_slot: None,
                 }),
             )
             .unwrap();
@@ -2992,6 +3577,7 @@ pub(crate) mod tests {
 
         let sub_config = RpcTransactionLogsConfig {
             commitment: Some(CommitmentConfig::processed()),
+            from_slot: None,
         };
 
         let (rpc_all, mut receiver_all) = rpc_pubsub_service::test_connection(&subscriptions);
@@ -3091,7 +3677,7 @@ pub(crate) mod tests {
         assert_eq!(subscriptions.total(), 4);
 
         let (rpc5, _receiver5) = rpc_pubsub_service::test_connection(&subscriptions);
-        let sub_id5 = rpc5.slot_subscribe().unwrap();
+        let sub_id5 = rpc5.slot_subscribe(None).unwrap();
 
         assert_eq!(subscriptions.total(), 5);
 
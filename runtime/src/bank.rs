@@ -96,7 +96,8 @@ use {
         storable_accounts::StorableAccounts,
         transaction_error_metrics::TransactionErrorMetrics,
         transaction_results::{
-            inner_instructions_list_from_instruction_trace, DurableNonceFee,
+            executed_instructions_from_instruction_trace,
+            inner_instructions_list_from_instruction_trace, DurableNonceFee, ExecutedInstruction,
             TransactionCheckResult, TransactionExecutionDetails, TransactionExecutionResult,
             TransactionResults,
         },
@@ -338,6 +339,8 @@ pub struct TransactionSimulationResult {
     pub post_simulation_accounts: Vec<TransactionAccount>,
     pub units_consumed: u64,
     pub return_data: Option<TransactionReturnData>,
+    /// Set by `simulate_transaction_unchecked_with_instruction_trace()`
+    pub executed_instructions: Option<Vec<ExecutedInstruction>>,
 }
 pub struct TransactionBalancesSet {
     pub pre_balances: TransactionBalances,
@@ -4328,6 +4331,24 @@ impl Bank {
     pub fn simulate_transaction_unchecked(
         &self,
         transaction: SanitizedTransaction,
+    ) -> TransactionSimulationResult {
+        self.do_simulate_transaction_unchecked(transaction, false)
+    }
+
+    /// Like `simulate_transaction_unchecked()`, but also returns every executed
+    /// instruction, including cross-program invocations, with the compute
+    /// units it consumed
+    pub fn simulate_transaction_unchecked_with_instruction_trace(
+        &self,
+        transaction: SanitizedTransaction,
+    ) -> TransactionSimulationResult {
+        self.do_simulate_transaction_unchecked(transaction, true)
+    }
+
+    fn do_simulate_transaction_unchecked(
+        &self,
+        transaction: SanitizedTransaction,
+        enable_instruction_trace_recording: bool,
     ) -> TransactionSimulationResult {
         let account_keys = transaction.message().account_keys();
         let number_of_accounts = account_keys.len();
@@ -4348,6 +4369,7 @@ impl Bank {
             false,
             true,
             true,
+            enable_instruction_trace_recording,
             &mut timings,
             Some(&account_overrides),
             None,
@@ -4380,11 +4402,13 @@ impl Bank {
 
         let execution_result = execution_results.pop().unwrap();
         let flattened_result = execution_result.flattened_result();
-        let (logs, return_data) = match execution_result {
-            TransactionExecutionResult::Executed { details, .. } => {
-                (details.log_messages, details.return_data)
-            }
-            TransactionExecutionResult::NotExecuted(_) => (None, None),
+        let (logs, return_data, executed_instructions) = match execution_result {
+            TransactionExecutionResult::Executed { details, .. } => (
+                details.log_messages,
+                details.return_data,
+                details.executed_instructions,
+            ),
+            TransactionExecutionResult::NotExecuted(_) => (None, None, None),
         };
         let logs = logs.unwrap_or_default();
 
@@ -4394,6 +4418,7 @@ impl Bank {
             post_simulation_accounts,
             units_consumed,
             return_data,
+            executed_instructions,
         }
     }
 
@@ -4820,6 +4845,7 @@ impl Bank {
         enable_cpi_recording: bool,
         enable_log_recording: bool,
         enable_return_data_recording: bool,
+        enable_instruction_trace_recording: bool,
         timings: &mut ExecuteTimings,
         error_counters: &mut TransactionErrorMetrics,
         log_messages_bytes_limit: Option<usize>,
@@ -4857,13 +4883,20 @@ impl Bank {
         let pre_account_state_info =
             self.get_transaction_account_state_info(&transaction_context, tx.message());
 
-        let log_collector = if enable_log_recording {
-            match log_messages_bytes_limit {
-                None => Some(LogCollector::new_ref()),
-                Some(log_messages_bytes_limit) => Some(LogCollector::new_ref_with_limit(Some(
-                    log_messages_bytes_limit,
-                ))),
+        // The compute units of the instruction trace are recorded by the log collector
+        let log_collector = if enable_log_recording || enable_instruction_trace_recording {
+            let log_collector = match log_messages_bytes_limit {
+                None => LogCollector::new_ref(),
+                Some(log_messages_bytes_limit) => {
+                    LogCollector::new_ref_with_limit(Some(log_messages_bytes_limit))
+                }
+            };
+            if enable_instruction_trace_recording {
+                log_collector
+                    .borrow_mut()
+                    .enable_instruction_compute_units_recording();
             }
+            Some(log_collector)
         } else {
             None
         };
@@ -4926,13 +4959,30 @@ impl Bank {
                 err
             });
 
-        let log_messages: Option<TransactionLogMessages> =
-            log_collector.and_then(|log_collector| {
+        let (log_messages, instruction_compute_units) = log_collector
+            .and_then(|log_collector| {
                 Rc::try_unwrap(log_collector)
-                    .map(|log_collector| log_collector.into_inner().into_messages())
+                    .map(|log_collector| {
+                        log_collector
+                            .into_inner()
+                            .into_messages_and_instruction_compute_units()
+                    })
                     .ok()
+            })
+            .map_or((None, None), |(log_messages, instruction_compute_units)| {
+                (
+                    enable_log_recording.then_some(log_messages),
+                    instruction_compute_units,
+                )
             });
 
+        let executed_instructions = instruction_compute_units.map(|instruction_compute_units| {
+            executed_instructions_from_instruction_trace(
+                &transaction_context,
+                &instruction_compute_units,
+            )
+        });
+
         let inner_instructions = if enable_cpi_recording {
             Some(inner_instructions_list_from_instruction_trace(
                 &transaction_context,
@@ -4976,6 +5026,7 @@ impl Bank {
                 status,
                 log_messages,
                 inner_instructions,
+                executed_instructions,
                 durable_nonce_fee,
                 return_data,
                 executed_units,
@@ -5112,6 +5163,7 @@ impl Bank {
         enable_cpi_recording: bool,
         enable_log_recording: bool,
         enable_return_data_recording: bool,
+        enable_instruction_trace_recording: bool,
         timings: &mut ExecuteTimings,
         account_overrides: Option<&AccountOverrides>,
         log_messages_bytes_limit: Option<usize>,
@@ -5240,6 +5292,7 @@ impl Bank {
                         enable_cpi_recording,
                         enable_log_recording,
                         enable_return_data_recording,
+                        enable_instruction_trace_recording,
                         timings,
                         &mut error_counters,
                         log_messages_bytes_limit,
@@ -6272,6 +6325,7 @@ impl Bank {
             enable_cpi_recording,
             enable_log_recording,
             enable_return_data_recording,
+            false,
             timings,
             None,
             log_messages_bytes_limit,
@@ -6735,6 +6789,29 @@ impl Bank {
         )
     }
 
+    /// Returns, in pubkey order, the first `limit` accounts after `after` which pass `filter`,
+    /// through the secondary index of `index_key` if the key is indexed
+    pub fn get_filtered_accounts_page<F: Fn(&AccountSharedData) -> bool>(
+        &self,
+        index_key: Option<&IndexKey>,
+        after: Option<&Pubkey>,
+        limit: usize,
+        filter: F,
+        config: &ScanConfig,
+        byte_limit_for_scan: Option<usize>,
+    ) -> ScanResult<Vec<TransactionAccount>> {
+        self.rc.accounts.load_page_with_filter(
+            &self.ancestors,
+            self.bank_id,
+            index_key,
+            after,
+            limit,
+            filter,
+            config,
+            byte_limit_for_scan,
+        )
+    }
+
     pub fn account_indexes_include_key(&self, key: &Pubkey) -> bool {
         self.rc.accounts.account_indexes_include_key(key)
     }
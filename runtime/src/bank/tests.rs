@@ -212,6 +212,7 @@ fn new_execution_result(
             status,
             log_messages: None,
             inner_instructions: None,
+            executed_instructions: None,
             durable_nonce_fee: nonce.map(DurableNonceFee::from),
             return_data: None,
             executed_units: 0,
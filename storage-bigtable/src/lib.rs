@@ -1,7 +1,6 @@
 #![allow(clippy::arithmetic_side_effects)]
 
 use {
-    crate::bigtable::RowKey,
     log::*,
     serde::{Deserialize, Serialize},
     solana_metrics::datapoint_info,
@@ -17,15 +16,16 @@ use {
     },
     solana_storage_proto::convert::{entries, generated, tx_by_addr},
     solana_transaction_status::{
-        extract_and_fmt_memos, ConfirmedBlock, ConfirmedTransactionStatusWithSignature,
-        ConfirmedTransactionWithStatusMeta, EntrySummary, Reward, TransactionByAddrInfo,
-        TransactionConfirmationStatus, TransactionStatus, TransactionStatusMeta,
-        TransactionWithStatusMeta, VersionedConfirmedBlock, VersionedConfirmedBlockWithEntries,
-        VersionedTransactionWithStatusMeta,
+        extract_and_fmt_memos, AccountBalanceChange, ConfirmedBlock,
+        ConfirmedTransactionStatusWithSignature, ConfirmedTransactionWithStatusMeta, EntrySummary,
+        Reward, TransactionByAddrInfo, TransactionConfirmationStatus, TransactionStatus,
+        TransactionStatusMeta, TransactionWithStatusMeta, VersionedConfirmedBlock,
+        VersionedConfirmedBlockWithEntries, VersionedTransactionWithStatusMeta,
     },
     std::{
         collections::{HashMap, HashSet},
         convert::TryInto,
+        path::{Path, PathBuf},
         sync::{
             atomic::{AtomicUsize, Ordering},
             Arc,
@@ -43,10 +43,18 @@ extern crate solana_metrics;
 extern crate serde_derive;
 
 mod access_token;
+mod backend;
 mod bigtable;
 mod compression;
+mod local_storage;
 mod root_ca_certificate;
 
+pub use {
+    backend::LedgerStorageBackend,
+    bigtable::{Error as BackendError, RowData, RowKey},
+    local_storage::LocalStorage,
+};
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("BigTable: {0}")]
@@ -395,6 +403,8 @@ pub struct LedgerStorageConfig {
     pub credential_type: CredentialType,
     pub instance_name: String,
     pub app_profile_id: String,
+    /// Store the ledger in this local RocksDB directory instead of BigTable
+    pub local_storage_path: Option<PathBuf>,
 }
 
 impl Default for LedgerStorageConfig {
@@ -405,6 +415,7 @@ impl Default for LedgerStorageConfig {
             credential_type: CredentialType::Filepath(None),
             instance_name: DEFAULT_INSTANCE_NAME.to_string(),
             app_profile_id: DEFAULT_APP_PROFILE_ID.to_string(),
+            local_storage_path: None,
         }
     }
 }
@@ -439,7 +450,7 @@ impl LedgerStorageStats {
 
 #[derive(Clone)]
 pub struct LedgerStorage {
-    connection: bigtable::BigTableConnection,
+    backend: Arc<dyn LedgerStorageBackend>,
     stats: Arc<LedgerStorageStats>,
 }
 
@@ -464,27 +475,37 @@ impl LedgerStorage {
         endpoint: &str,
         timeout: Option<Duration>,
     ) -> Result<Self> {
+        let connection = bigtable::BigTableConnection::new_for_emulator(
+            instance_name,
+            app_profile_id,
+            endpoint,
+            timeout,
+        )?;
+        Ok(Self::new_with_backend(Arc::new(connection)))
+    }
+
+    pub fn new_for_local_storage(path: &Path, read_only: bool) -> Result<Self> {
+        let local_storage = LocalStorage::open(path, read_only)?;
+        Ok(Self::new_with_backend(Arc::new(local_storage)))
+    }
+
+    pub fn new_with_backend(backend: Arc<dyn LedgerStorageBackend>) -> Self {
         let stats = Arc::new(LedgerStorageStats::default());
-        Ok(Self {
-            connection: bigtable::BigTableConnection::new_for_emulator(
-                instance_name,
-                app_profile_id,
-                endpoint,
-                timeout,
-            )?,
-            stats,
-        })
+        Self { backend, stats }
     }
 
     pub async fn new_with_config(config: LedgerStorageConfig) -> Result<Self> {
-        let stats = Arc::new(LedgerStorageStats::default());
         let LedgerStorageConfig {
             read_only,
             timeout,
             instance_name,
             app_profile_id,
             credential_type,
+            local_storage_path,
         } = config;
+        if let Some(local_storage_path) = local_storage_path {
+            return Self::new_for_local_storage(&local_storage_path, read_only);
+        }
         let connection = bigtable::BigTableConnection::new(
             instance_name.as_str(),
             app_profile_id.as_str(),
@@ -493,7 +514,7 @@ impl LedgerStorage {
             credential_type,
         )
         .await?;
-        Ok(Self { stats, connection })
+        Ok(Self::new_with_backend(Arc::new(connection)))
     }
 
     pub async fn new_with_stringified_credential(credential: String) -> Result<Self> {
@@ -508,8 +529,8 @@ impl LedgerStorage {
     pub async fn get_first_available_block(&self) -> Result<Option<Slot>> {
         trace!("LedgerStorage::get_first_available_block request received");
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-        let blocks = bigtable.get_row_keys("blocks", None, None, 1).await?;
+        let backend = self.backend.as_ref();
+        let blocks = backend.get_row_keys("blocks", None, None, 1).await?;
         if blocks.is_empty() {
             return Ok(None);
         }
@@ -527,8 +548,8 @@ impl LedgerStorage {
             limit
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-        let blocks = bigtable
+        let backend = self.backend.as_ref();
+        let blocks = backend
             .get_row_keys(
                 "blocks",
                 Some(slot_to_blocks_key(start_slot)),
@@ -549,9 +570,9 @@ impl LedgerStorage {
             slots
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
+        let backend = self.backend.as_ref();
         let row_keys = slots.iter().copied().map(slot_to_blocks_key);
-        let data = bigtable
+        let data = backend
             .get_protobuf_or_bincode_cells("blocks", row_keys)
             .await?
             .filter_map(
@@ -576,8 +597,8 @@ impl LedgerStorage {
             slot
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-        let block_cell_data = bigtable
+        let backend = self.backend.as_ref();
+        let block_cell_data = backend
             .get_protobuf_or_bincode_cell::<StoredConfirmedBlock, generated::ConfirmedBlock>(
                 "blocks",
                 slot_to_blocks_key(slot),
@@ -602,9 +623,9 @@ impl LedgerStorage {
             slot
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
+        let backend = self.backend.as_ref();
 
-        let block_exists = bigtable
+        let block_exists = backend
             .row_key_exists("blocks", slot_to_blocks_key(slot))
             .await?;
 
@@ -618,8 +639,8 @@ impl LedgerStorage {
             slot
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-        let entry_cell_data = bigtable
+        let backend = self.backend.as_ref();
+        let entry_cell_data = backend
             .get_protobuf_cell::<entries::Entries>("entries", slot_to_entries_key(slot))
             .await
             .map_err(|err| match err {
@@ -636,8 +657,8 @@ impl LedgerStorage {
             signature
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-        let transaction_info = bigtable
+        let backend = self.backend.as_ref();
+        let transaction_info = backend
             .get_bincode_cell::<TransactionInfo>("tx", signature.to_string())
             .await
             .map_err(|err| match err {
@@ -657,11 +678,11 @@ impl LedgerStorage {
             signatures
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
+        let backend = self.backend.as_ref();
 
         // Fetch transactions info
         let keys = signatures.iter().map(|s| s.to_string()).collect::<Vec<_>>();
-        let cells = bigtable
+        let cells = backend
             .get_bincode_cells::<TransactionInfo>("tx", &keys)
             .await?;
 
@@ -719,10 +740,10 @@ impl LedgerStorage {
             signature
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
+        let backend = self.backend.as_ref();
 
         // Figure out which block the transaction is located in
-        let TransactionInfo { slot, index, .. } = bigtable
+        let TransactionInfo { slot, index, .. } = backend
             .get_bincode_cell("tx", signature.to_string())
             .await
             .map_err(|err| match err {
@@ -779,26 +800,48 @@ impl LedgerStorage {
             address
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-        let address_prefix = format!("{address}/");
 
         // Figure out where to start listing from based on `before_signature`
-        let (first_slot, before_transaction_index) = match before_signature {
+        let before = match before_signature {
             None => (Slot::MAX, 0),
-            Some(before_signature) => {
-                let TransactionInfo { slot, index, .. } = bigtable
-                    .get_bincode_cell("tx", before_signature.to_string())
-                    .await?;
-
-                (slot, index)
-            }
+            Some(before_signature) => self.get_transaction_position(before_signature).await?,
         };
+        self.get_confirmed_signatures_for_address_before(address, before, until_signature, limit)
+            .await
+    }
+
+    /// Returns the slot and the index in its block of the confirmed transaction
+    async fn get_transaction_position(&self, signature: &Signature) -> Result<(Slot, u32)> {
+        let TransactionInfo { slot, index, .. } = self
+            .backend
+            .as_ref()
+            .get_bincode_cell("tx", signature.to_string())
+            .await?;
+        Ok((slot, index))
+    }
+
+    /// Like `get_confirmed_signatures_for_address()`, starting with the first signature before
+    /// the `(slot, transaction index)` position
+    async fn get_confirmed_signatures_for_address_before(
+        &self,
+        address: &Pubkey,
+        (first_slot, before_transaction_index): (Slot, u32),
+        until_signature: Option<&Signature>,
+        limit: usize,
+    ) -> Result<
+        Vec<(
+            ConfirmedTransactionStatusWithSignature,
+            u32, /*slot index*/
+        )>,
+    > {
+        let backend = self.backend.as_ref();
+        let address_prefix = format!("{address}/");
 
         // Figure out where to end listing from based on `until_signature`
         let (last_slot, until_transaction_index) = match until_signature {
             None => (0, u32::MAX),
             Some(until_signature) => {
-                let TransactionInfo { slot, index, .. } = bigtable
+                let TransactionInfo { slot, index, .. } = backend
                     .get_bincode_cell("tx", until_signature.to_string())
                     .await?;
 
@@ -808,7 +851,7 @@ impl LedgerStorage {
 
         let mut infos = vec![];
 
-        let starting_slot_tx_len = bigtable
+        let starting_slot_tx_len = backend
             .get_protobuf_or_bincode_cell::<Vec<LegacyTransactionByAddrInfo>, tx_by_addr::TransactionByAddr>(
                 "tx-by-addr",
                 format!("{}{}", address_prefix, slot_to_tx_by_addr_key(first_slot)),
@@ -824,7 +867,7 @@ impl LedgerStorage {
 
         // Return the next tx-by-addr data of amount `limit` plus extra to account for the largest
         // number that might be flitered out
-        let tx_by_addr_data = bigtable
+        let tx_by_addr_data = backend
             .get_row_data(
                 "tx-by-addr",
                 Some(format!(
@@ -897,6 +940,69 @@ impl LedgerStorage {
         Ok(infos)
     }
 
+    /// Get the balance changes of the provided address, in descending ledger order, from the
+    /// metadata of its confirmed transactions
+    ///
+    /// address: address to search for
+    /// before_signature: start with the first transaction older than this one
+    /// until_signature: end with the last transaction more recent than this one
+    /// before_slot: start with the last transaction of this slot or an earlier one, so that the
+    ///   post-balance of the first change is the balance of the address at this slot
+    /// limit: stop after this many transactions
+    pub async fn get_account_history(
+        &self,
+        address: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        before_slot: Option<Slot>,
+        limit: usize,
+    ) -> Result<Vec<AccountBalanceChange>> {
+        trace!(
+            "LedgerStorage::get_account_history request received: {:?}",
+            address
+        );
+        self.stats.increment_num_queries();
+
+        // Start from the earliest of `before_signature` and the end of `before_slot`
+        let mut before = (Slot::MAX, 0);
+        if let Some(before_signature) = before_signature {
+            before = self.get_transaction_position(before_signature).await?;
+        }
+        if let Some(before_slot) = before_slot {
+            before = before.min((before_slot, u32::MAX));
+        }
+        let signatures = self
+            .get_confirmed_signatures_for_address_before(address, before, until_signature, limit)
+            .await?
+            .into_iter()
+            .map(|(info, _)| info.signature)
+            .collect::<Vec<_>>();
+        if signatures.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut transactions = self
+            .get_confirmed_transactions(&signatures)
+            .await?
+            .into_iter()
+            .map(|transaction| {
+                (
+                    *transaction.tx_with_meta.transaction_signature(),
+                    transaction,
+                )
+            })
+            .collect::<HashMap<_, _>>();
+
+        // Transactions missing their metadata have no balances
+        Ok(signatures
+            .iter()
+            .filter_map(|signature| {
+                transactions
+                    .remove(signature)?
+                    .account_balance_change(address)
+            })
+            .collect())
+    }
+
     /// Upload a new confirmed block and associated meta data.
     pub async fn upload_confirmed_block(
         &self,
@@ -992,28 +1098,31 @@ impl LedgerStorage {
         let mut tasks = vec![];
 
         if !tx_cells.is_empty() {
-            let conn = self.connection.clone();
+            let backend = self.backend.clone();
             tasks.push(tokio::spawn(async move {
-                conn.put_bincode_cells_with_retry::<TransactionInfo>("tx", &tx_cells)
+                backend
+                    .put_bincode_cells_with_retry::<TransactionInfo>("tx", &tx_cells)
                     .await
             }));
         }
 
         if !tx_by_addr_cells.is_empty() {
-            let conn = self.connection.clone();
+            let backend = self.backend.clone();
             tasks.push(tokio::spawn(async move {
-                conn.put_protobuf_cells_with_retry::<tx_by_addr::TransactionByAddr>(
-                    "tx-by-addr",
-                    &tx_by_addr_cells,
-                )
-                .await
+                backend
+                    .put_protobuf_cells_with_retry::<tx_by_addr::TransactionByAddr>(
+                        "tx-by-addr",
+                        &tx_by_addr_cells,
+                    )
+                    .await
             }));
         }
 
         if num_entries > 0 {
-            let conn = self.connection.clone();
+            let backend = self.backend.clone();
             tasks.push(tokio::spawn(async move {
-                conn.put_protobuf_cells_with_retry::<entries::Entries>("entries", &[entry_cell])
+                backend
+                    .put_protobuf_cells_with_retry::<entries::Entries>("entries", &[entry_cell])
                     .await
             }));
         }
@@ -1051,7 +1160,7 @@ impl LedgerStorage {
         // `get_confirmed_block()` and `get_confirmed_blocks()`
         let blocks_cells = [(slot_to_blocks_key(slot), confirmed_block.into())];
         bytes_written += self
-            .connection
+            .backend
             .put_protobuf_cells_with_retry::<generated::ConfirmedBlock>("blocks", &blocks_cells)
             .await?;
         datapoint_info!(
@@ -1115,7 +1224,7 @@ impl LedgerStorage {
         let tx_deletion_rows = if !expected_tx_infos.is_empty() {
             let signatures = expected_tx_infos.keys().cloned().collect::<Vec<_>>();
             let fetched_tx_infos: HashMap<String, std::result::Result<UploadedTransaction, _>> =
-                self.connection
+                self.backend
                     .get_bincode_cells_with_retry::<TransactionInfo>("tx", &signatures)
                     .await?
                     .into_iter()
@@ -1153,32 +1262,31 @@ impl LedgerStorage {
         };
 
         let entries_exist = self
-            .connection
-            .client()
+            .backend
             .row_key_exists("entries", slot_to_entries_key(slot))
             .await
             .is_ok_and(|x| x);
 
         if !dry_run {
             if !address_slot_rows.is_empty() {
-                self.connection
+                self.backend
                     .delete_rows_with_retry("tx-by-addr", &address_slot_rows)
                     .await?;
             }
 
             if !tx_deletion_rows.is_empty() {
-                self.connection
+                self.backend
                     .delete_rows_with_retry("tx", &tx_deletion_rows)
                     .await?;
             }
 
             if entries_exist {
-                self.connection
+                self.backend
                     .delete_rows_with_retry("entries", &[slot_to_entries_key(slot)])
                     .await?;
             }
 
-            self.connection
+            self.backend
                 .delete_rows_with_retry("blocks", &[slot_to_blocks_key(slot)])
                 .await?;
         }
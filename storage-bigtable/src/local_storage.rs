@@ -0,0 +1,361 @@
+// A self-hosted alternative to BigTable, storing the ledger tables in a local RocksDB directory
+
+use {
+    crate::{
+        backend::LedgerStorageBackend,
+        bigtable::{Error, Result, RowData, RowKey},
+    },
+    async_trait::async_trait,
+    rocksdb::{ColumnFamily, Direction, IteratorMode, Options, WriteBatch, DB},
+    std::{collections::BTreeSet, io, path::Path, sync::Arc},
+    tempfile::TempDir,
+};
+
+/// The tables created by `init-bigtable.sh`, each stored in its own column family
+const TABLES: &[&str] = &["blocks", "entries", "tx", "tx-by-addr"];
+
+/// Stores each table row under its row key, with its cells serialized with bincode.
+///
+/// Unlike BigTable, which only updates the cells being written, writing a row replaces all of its
+/// cells.
+#[derive(Clone)]
+pub struct LocalStorage {
+    db: Arc<DB>,
+    // Declared after `db` so the directory is removed once the secondary instance is closed
+    secondary_dir: Option<Arc<TempDir>>,
+}
+
+impl LocalStorage {
+    /// Open the RocksDB directory at `path`, creating it unless `read_only` is set.
+    ///
+    /// A read-only instance is opened as a RocksDB secondary instance, which catches up with the
+    /// rows written by the primary instance before each read. Each secondary instance keeps its
+    /// own files in a temporary directory, so several processes can read the same `path`.
+    pub fn open(path: &Path, read_only: bool) -> Result<Self> {
+        let mut options = Options::default();
+        let (db, secondary_dir) = if read_only {
+            // Required for a secondary instance,
+            // see https://github.com/facebook/rocksdb/wiki/Secondary-instance
+            options.set_max_open_files(-1);
+            let secondary_dir = tempfile::Builder::new()
+                .prefix("solana-secondary-")
+                .tempdir()?;
+            let db = DB::open_cf_as_secondary(&options, path, secondary_dir.path(), TABLES)?;
+            (db, Some(Arc::new(secondary_dir)))
+        } else {
+            options.create_if_missing(true);
+            options.create_missing_column_families(true);
+            (DB::open_cf(&options, path, TABLES)?, None)
+        };
+        Ok(Self {
+            db: Arc::new(db),
+            secondary_dir,
+        })
+    }
+
+    /// Run `f` on a blocking thread, since RocksDB reads and writes would stall the async runtime
+    async fn run_blocking<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Self) -> Result<T> + Send + 'static,
+    {
+        let storage = self.clone();
+        tokio::task::spawn_blocking(move || f(&storage))
+            .await
+            .map_err(io::Error::from)?
+    }
+
+    fn catch_up_with_primary(&self) -> Result<()> {
+        if self.secondary_dir.is_some() {
+            self.db.try_catch_up_with_primary()?;
+        }
+        Ok(())
+    }
+
+    fn table(&self, table_name: &str) -> Result<&ColumnFamily> {
+        self.db
+            .cf_handle(table_name)
+            .ok_or_else(|| Error::ObjectNotFound(format!("table {table_name}")))
+    }
+
+    fn deserialize_row_data(table_name: &str, row_key: &str, value: &[u8]) -> Result<RowData> {
+        bincode::deserialize(value)
+            .map_err(|_| Error::ObjectCorrupt(format!("{table_name}/{row_key}")))
+    }
+
+    /// Returns the rows of `table_name` from `start_at` to `end_at` included, in lexical order
+    fn scan(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<(RowKey, Box<[u8]>)>> {
+        if rows_limit <= 0 {
+            return Ok(vec![]);
+        }
+        self.catch_up_with_primary()?;
+        let table = self.table(table_name)?;
+        let mode = match &start_at {
+            Some(start_at) => IteratorMode::From(start_at.as_bytes(), Direction::Forward),
+            None => IteratorMode::Start,
+        };
+
+        let mut rows = vec![];
+        for item in self.db.iterator_cf(table, mode) {
+            let (row_key, value) = item?;
+            let row_key = String::from_utf8(row_key.into_vec())
+                .map_err(|_| Error::ObjectCorrupt(format!("{table_name} row key")))?;
+            if end_at.as_ref().is_some_and(|end_at| row_key > *end_at) {
+                break;
+            }
+            rows.push((row_key, value));
+            if rows.len() as i64 >= rows_limit {
+                break;
+            }
+        }
+        Ok(rows)
+    }
+}
+
+#[async_trait]
+impl LedgerStorageBackend for LocalStorage {
+    async fn get_row_keys(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<RowKey>> {
+        let table_name = table_name.to_string();
+        self.run_blocking(move |storage| {
+            Ok(storage
+                .scan(&table_name, start_at, end_at, rows_limit)?
+                .into_iter()
+                .map(|(row_key, _)| row_key)
+                .collect())
+        })
+        .await
+    }
+
+    async fn row_key_exists(&self, table_name: &str, row_key: RowKey) -> Result<bool> {
+        let table_name = table_name.to_string();
+        self.run_blocking(move |storage| {
+            storage.catch_up_with_primary()?;
+            let table = storage.table(&table_name)?;
+            Ok(storage.db.get_pinned_cf(table, row_key)?.is_some())
+        })
+        .await
+    }
+
+    async fn get_row_data(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        let table_name = table_name.to_string();
+        self.run_blocking(move |storage| {
+            storage
+                .scan(&table_name, start_at, end_at, rows_limit)?
+                .into_iter()
+                .map(|(row_key, value)| {
+                    let row_data = Self::deserialize_row_data(&table_name, &row_key, &value)?;
+                    Ok((row_key, row_data))
+                })
+                .collect()
+        })
+        .await
+    }
+
+    async fn get_multi_row_data(
+        &self,
+        table_name: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        let table_name = table_name.to_string();
+        // Like BigTable, return each existing row once, in lexical order
+        let row_keys = row_keys.iter().cloned().collect::<BTreeSet<_>>();
+        self.run_blocking(move |storage| {
+            storage.catch_up_with_primary()?;
+            let table = storage.table(&table_name)?;
+            let values = storage
+                .db
+                .multi_get_cf(row_keys.iter().map(|row_key| (table, row_key.as_bytes())));
+
+            let mut rows = vec![];
+            for (row_key, value) in row_keys.into_iter().zip(values) {
+                if let Some(value) = value? {
+                    let row_data = Self::deserialize_row_data(&table_name, &row_key, &value)?;
+                    rows.push((row_key, row_data));
+                }
+            }
+            Ok(rows)
+        })
+        .await
+    }
+
+    async fn get_single_row_data(&self, table_name: &str, row_key: RowKey) -> Result<RowData> {
+        let table_name = table_name.to_string();
+        self.run_blocking(move |storage| {
+            storage.catch_up_with_primary()?;
+            let table = storage.table(&table_name)?;
+            let value = storage
+                .db
+                .get_pinned_cf(table, &row_key)?
+                .ok_or(Error::RowNotFound)?;
+            Self::deserialize_row_data(&table_name, &row_key, &value)
+        })
+        .await
+    }
+
+    async fn put_row_data(&self, table_name: &str, row_data: &[(&RowKey, RowData)]) -> Result<()> {
+        let table_name = table_name.to_string();
+        let values = row_data
+            .iter()
+            .map(|(row_key, row_data)| {
+                let value = bincode::serialize(row_data).map_err(|_| Error::RowWriteFailed)?;
+                Ok((row_key.as_bytes().to_vec(), value))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.run_blocking(move |storage| {
+            let table = storage.table(&table_name)?;
+            let mut batch = WriteBatch::default();
+            for (row_key, value) in values {
+                batch.put_cf(table, row_key, value);
+            }
+            Ok(storage.db.write(batch)?)
+        })
+        .await
+    }
+
+    async fn delete_rows(&self, table_name: &str, row_keys: &[RowKey]) -> Result<()> {
+        let table_name = table_name.to_string();
+        let row_keys = row_keys.to_vec();
+        self.run_blocking(move |storage| {
+            let table = storage.table(&table_name)?;
+            let mut batch = WriteBatch::default();
+            for row_key in row_keys {
+                batch.delete_cf(table, row_key.as_bytes());
+            }
+            Ok(storage.db.write(batch)?)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_local_storage() {
+        let ledger_path = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::open(ledger_path.path(), false).unwrap();
+        let row_keys = ["a", "b", "c", "d"].map(String::from);
+        let row_data = row_keys
+            .iter()
+            .map(|row_key| {
+                (
+                    row_key,
+                    vec![("bin".to_string(), row_key.as_bytes().to_vec())],
+                )
+            })
+            .collect::<Vec<_>>();
+        storage.put_row_data("tx", &row_data).await.unwrap();
+
+        assert_eq!(
+            storage
+                .get_row_keys("tx", Some("b".to_string()), Some("c".to_string()), 10)
+                .await
+                .unwrap(),
+            vec!["b".to_string(), "c".to_string()]
+        );
+        assert_eq!(
+            storage.get_row_keys("tx", None, None, 3).await.unwrap(),
+            row_keys[..3].to_vec()
+        );
+        assert!(storage
+            .get_row_keys("tx", None, None, 0)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(storage
+            .get_row_keys("blocks", None, None, 1)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            storage
+                .get_row_data("tx", Some("bb".to_string()), None, 1)
+                .await
+                .unwrap(),
+            vec![(row_keys[2].clone(), row_data[2].1.clone())]
+        );
+        assert_eq!(
+            storage
+                .get_multi_row_data("tx", &["d".to_string(), "e".to_string(), "a".to_string()])
+                .await
+                .unwrap(),
+            vec![
+                (row_keys[0].clone(), row_data[0].1.clone()),
+                (row_keys[3].clone(), row_data[3].1.clone()),
+            ]
+        );
+
+        storage.delete_rows("tx", &["a".to_string()]).await.unwrap();
+        assert!(!storage.row_key_exists("tx", "a".to_string()).await.unwrap());
+        assert!(matches!(
+            storage.get_single_row_data("tx", "a".to_string()).await,
+            Err(Error::RowNotFound)
+        ));
+        assert_eq!(
+            storage
+                .get_single_row_data("tx", "b".to_string())
+                .await
+                .unwrap(),
+            row_data[1].1
+        );
+        assert!(matches!(
+            storage
+                .get_single_row_data("accounts", "b".to_string())
+                .await,
+            Err(Error::ObjectNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_local_storage_read_only_follows_writer() {
+        let ledger_path = tempfile::tempdir().unwrap();
+        let writer = LocalStorage::open(ledger_path.path(), false).unwrap();
+        let reader = LocalStorage::open(ledger_path.path(), true).unwrap();
+        assert!(!reader.row_key_exists("tx", "a".to_string()).await.unwrap());
+
+        let row_key = "a".to_string();
+        let row_data = vec![("bin".to_string(), vec![1, 2, 3])];
+        writer
+            .put_row_data("tx", &[(&row_key, row_data.clone())])
+            .await
+            .unwrap();
+        assert!(reader.row_key_exists("tx", row_key.clone()).await.unwrap());
+        assert_eq!(
+            reader
+                .get_single_row_data("tx", row_key.clone())
+                .await
+                .unwrap(),
+            row_data
+        );
+        assert_eq!(
+            reader.get_row_keys("tx", None, None, 10).await.unwrap(),
+            vec![row_key.clone()]
+        );
+
+        writer.delete_rows("tx", &[row_key.clone()]).await.unwrap();
+        assert!(reader
+            .get_multi_row_data("tx", &[row_key])
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
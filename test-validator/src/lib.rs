@@ -33,13 +33,18 @@ use {
     solana_rpc::{rpc::JsonRpcConfig, rpc_pubsub_service::PubSubConfig},
     solana_rpc_client::{nonblocking, rpc_client::RpcClient},
     solana_runtime::{
-        bank_forks::BankForks, genesis_utils::create_genesis_config_with_leader_ex,
-        runtime_config::RuntimeConfig, snapshot_config::SnapshotConfig,
+        bank_forks::BankForks,
+        genesis_utils::create_genesis_config_with_leader_ex,
+        runtime_config::RuntimeConfig,
+        snapshot_archive_info::{FullSnapshotArchiveInfo, IncrementalSnapshotArchiveInfo},
+        snapshot_bank_utils,
+        snapshot_config::SnapshotConfig,
+        snapshot_utils,
     },
     solana_sdk::{
-        account::{Account, AccountSharedData},
-        bpf_loader_upgradeable::UpgradeableLoaderState,
-        clock::{Slot, DEFAULT_MS_PER_SLOT},
+        account::{Account, AccountSharedData, ReadableAccount},
+        bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+        clock::{Clock, Slot, UnixTimestamp, DEFAULT_MS_PER_SLOT},
         commitment_config::CommitmentConfig,
         epoch_schedule::EpochSchedule,
         exit::Exit,
@@ -94,6 +99,20 @@ pub struct UpgradeableProgramInfo {
     pub program_path: PathBuf,
 }
 
+/// The accounts to clone from snapshot archives, see
+/// `TestValidatorGenesis::clone_accounts_from_snapshot_archives()`
+#[derive(Clone, Debug, Default)]
+pub struct SnapshotAccountsSelection {
+    /// Accounts to clone, which must exist
+    pub addresses: HashSet<Pubkey>,
+    /// Accounts to clone if they exist
+    pub maybe_addresses: HashSet<Pubkey>,
+    /// Programs whose accounts are all cloned
+    pub owners: HashSet<Pubkey>,
+    /// Upgradeable programs to clone, along with their program data accounts
+    pub upgradeable_programs: HashSet<Pubkey>,
+}
+
 #[derive(Debug)]
 pub struct TestValidatorNodeConfig {
     gossip_addr: SocketAddr,
@@ -365,6 +384,140 @@ impl TestValidatorGenesis {
         Ok(self)
     }
 
+    /// Clone accounts from a local full snapshot archive, and optionally an incremental snapshot
+    /// archive on top of it, instead of a cluster
+    ///
+    /// The archives are unpacked to a temporary directory, which needs about as much space as a
+    /// validator's accounts directory.
+    pub fn clone_accounts_from_snapshot_archives(
+        &mut self,
+        full_snapshot_archive: &Path,
+        incremental_snapshot_archive: Option<&Path>,
+        selection: &SnapshotAccountsSelection,
+    ) -> Result<&mut Self, String> {
+        let full_snapshot_archive_info = FullSnapshotArchiveInfo::new_from_path(
+            full_snapshot_archive.to_path_buf(),
+        )
+        .map_err(|err| {
+            format!(
+                "Invalid full snapshot archive {}: {err}",
+                full_snapshot_archive.display()
+            )
+        })?;
+        let incremental_snapshot_archive_info = incremental_snapshot_archive
+            .map(|incremental_snapshot_archive| {
+                IncrementalSnapshotArchiveInfo::new_from_path(
+                    incremental_snapshot_archive.to_path_buf(),
+                )
+                .map_err(|err| {
+                    format!(
+                        "Invalid incremental snapshot archive {}: {err}",
+                        incremental_snapshot_archive.display()
+                    )
+                })
+            })
+            .transpose()?;
+
+        let unpack_dir = tempfile::tempdir()
+            .map_err(|err| format!("Failed to create a temporary directory: {err}"))?;
+        let bank_snapshots_dir = unpack_dir.path().join("snapshot");
+        let account_path = unpack_dir.path().join("accounts");
+        for dir in [&bank_snapshots_dir, &account_path] {
+            fs::create_dir(dir)
+                .map_err(|err| format!("Failed to create {}: {err}", dir.display()))?;
+        }
+        info!("Unpacking {}...", full_snapshot_archive.display());
+        let (full_snapshot, incremental_snapshot, _next_append_vec_id) =
+            snapshot_utils::verify_and_unarchive_snapshots(
+                &bank_snapshots_dir,
+                &full_snapshot_archive_info,
+                incremental_snapshot_archive_info.as_ref(),
+                &[account_path],
+            )
+            .map_err(|err| format!("Failed to unpack the snapshot archives: {err}"))?;
+
+        let programdata_addresses: HashMap<Pubkey, Pubkey> = selection
+            .upgradeable_programs
+            .iter()
+            .map(|program_id| {
+                let (programdata_address, _) = Pubkey::find_program_address(
+                    &[program_id.as_ref()],
+                    &bpf_loader_upgradeable::id(),
+                );
+                (programdata_address, *program_id)
+            })
+            .collect();
+        let is_selected = |address: &Pubkey, owner: &Pubkey| {
+            selection.addresses.contains(address)
+                || selection.maybe_addresses.contains(address)
+                || selection.upgradeable_programs.contains(address)
+                || programdata_addresses.contains_key(address)
+                || selection.owners.contains(owner)
+        };
+
+        // An account may be stored in several slots, the latest of which has its current state
+        let mut accounts: HashMap<Pubkey, (Slot, AccountSharedData)> = HashMap::new();
+        for storage in std::iter::once(&full_snapshot.storage).chain(
+            incremental_snapshot
+                .as_ref()
+                .map(|snapshot| &snapshot.storage),
+        ) {
+            for entry in storage.iter() {
+                let slot = *entry.key();
+                for account in entry.value().storage.accounts.account_iter() {
+                    if !is_selected(account.pubkey(), account.owner()) {
+                        continue;
+                    }
+                    if accounts
+                        .get(account.pubkey())
+                        .map_or(true, |(latest_slot, _)| *latest_slot <= slot)
+                    {
+                        accounts
+                            .insert(*account.pubkey(), (slot, account.to_account_shared_data()));
+                    }
+                }
+            }
+        }
+        // Zero lamport accounts have been deleted
+        accounts.retain(|_, (_, account)| account.lamports() > 0);
+
+        for address in selection
+            .addresses
+            .iter()
+            .chain(&selection.upgradeable_programs)
+            .chain(programdata_addresses.keys())
+        {
+            if !accounts.contains_key(address) {
+                return Err(format!("Failed to find {address} in the snapshot"));
+            }
+        }
+        for address in &selection.maybe_addresses {
+            if !accounts.contains_key(address) {
+                warn!("Could not find {}, skipping.", address);
+            }
+        }
+        for (programdata_address, program_id) in &programdata_addresses {
+            let (_, account) = &accounts[program_id];
+            if !matches!(
+                account.deserialize_data(),
+                Ok(UpgradeableLoaderState::Program {
+                    programdata_address: address,
+                }) if address == *programdata_address
+            ) {
+                return Err(format!(
+                    "Failed to read upgradeable program account {program_id}",
+                ));
+            }
+        }
+
+        self.add_accounts(
+            accounts
+                .into_iter()
+                .map(|(address, (_, account))| (address, account)),
+        );
+        Ok(self)
+    }
+
     pub fn add_accounts_from_json_files(
         &mut self,
         accounts: &[AccountInfo],
@@ -620,6 +773,21 @@ pub struct TestValidator {
     gossip: SocketAddr,
     validator: Option<Validator>,
     vote_account_address: Pubkey,
+    restart_config: TestValidatorRestartConfig,
+}
+
+/// What is needed to restart the validator on its ledger, see `TestValidator::warp_to_slot()`
+struct TestValidatorRestartConfig {
+    validator_config: ValidatorConfig,
+    port_range: PortRange,
+    bind_ip_addr: IpAddr,
+    validator_exit: Arc<RwLock<Exit>>,
+    authorized_voter_keypairs: Arc<RwLock<Vec<Arc<Keypair>>>>,
+    start_progress: Arc<RwLock<ValidatorStartProgress>>,
+    socket_addr_space: SocketAddrSpace,
+    tpu_enable_udp: bool,
+    admin_rpc_service_post_init: Arc<RwLock<Option<AdminRpcRequestMetadataPostInit>>>,
+    rpc_to_plugin_manager_receiver: Option<Receiver<GeyserPluginManagerRequest>>,
 }
 
 impl TestValidator {
@@ -881,17 +1049,13 @@ impl TestValidator {
                 .unwrap(),
         )?;
 
-        let mut node = Node::new_single_bind(
+        let node = Self::new_node(
             &validator_identity.pubkey(),
             &config.node_config.gossip_addr,
             config.node_config.port_range,
             config.node_config.bind_ip_addr,
+            config.rpc_ports,
         );
-        if let Some((rpc, rpc_pubsub)) = config.rpc_ports {
-            let addr = node.info.gossip().unwrap().ip();
-            node.info.set_rpc((addr, rpc)).unwrap();
-            node.info.set_rpc_pubsub((addr, rpc_pubsub)).unwrap();
-        }
 
         let vote_account_address = validator_vote_account.pubkey();
         let rpc_url = format!("http://{}", node.info.rpc().unwrap());
@@ -959,7 +1123,6 @@ impl TestValidator {
             },
             enforce_ulimit_nofile: false,
             warp_slot: config.warp_slot,
-            validator_exit: config.validator_exit.clone(),
             max_ledger_shreds: config.max_ledger_shreds,
             no_wait_for_vote_to_start_leader: true,
             staked_nodes_overrides: config.staked_nodes_overrides.clone(),
@@ -972,29 +1135,26 @@ impl TestValidator {
             validator_config.tower_storage = tower_storage.clone();
         }
 
-        let validator = Some(Validator::new(
+        let mut restart_config = TestValidatorRestartConfig {
+            validator_config,
+            port_range: config.node_config.port_range,
+            bind_ip_addr: config.node_config.bind_ip_addr,
+            validator_exit: config.validator_exit.clone(),
+            authorized_voter_keypairs: config.authorized_voter_keypairs.clone(),
+            start_progress: config.start_progress.clone(),
+            socket_addr_space,
+            tpu_enable_udp: config.tpu_enable_udp,
+            admin_rpc_service_post_init: config.admin_rpc_service_post_init.clone(),
+            rpc_to_plugin_manager_receiver,
+        };
+        let validator = Some(Self::start_validator(
             node,
-            Arc::new(validator_identity),
+            validator_identity,
             &ledger_path,
             &vote_account_address,
-            config.authorized_voter_keypairs.clone(),
-            vec![],
-            &validator_config,
-            true, // should_check_duplicate_instance
-            rpc_to_plugin_manager_receiver,
-            config.start_progress.clone(),
-            socket_addr_space,
-            DEFAULT_TPU_USE_QUIC,
-            DEFAULT_TPU_CONNECTION_POOL_SIZE,
-            config.tpu_enable_udp,
-            config.admin_rpc_service_post_init.clone(),
+            &mut restart_config,
         )?);
 
-        // Needed to avoid panics in `solana-responder-gossip` in tests that create a number of
-        // test validators concurrently...
-        discover_cluster(&gossip, 1, socket_addr_space)
-            .map_err(|err| format!("TestValidator startup failed: {err:?}"))?;
-
         let test_validator = TestValidator {
             ledger_path,
             preserve_ledger,
@@ -1004,10 +1164,217 @@ impl TestValidator {
             gossip,
             validator,
             vote_account_address,
+            restart_config,
         };
         Ok(test_validator)
     }
 
+    fn new_node(
+        validator_identity: &Pubkey,
+        gossip_addr: &SocketAddr,
+        port_range: PortRange,
+        bind_ip_addr: IpAddr,
+        rpc_ports: Option<(u16, u16)>,
+    ) -> Node {
+        let mut node =
+            Node::new_single_bind(validator_identity, gossip_addr, port_range, bind_ip_addr);
+        if let Some((rpc, rpc_pubsub)) = rpc_ports {
+            let addr = node.info.gossip().unwrap().ip();
+            node.info.set_rpc((addr, rpc)).unwrap();
+            node.info.set_rpc_pubsub((addr, rpc_pubsub)).unwrap();
+        }
+        node
+    }
+
+    fn start_validator(
+        node: Node,
+        validator_identity: Keypair,
+        ledger_path: &Path,
+        vote_account_address: &Pubkey,
+        restart_config: &mut TestValidatorRestartConfig,
+    ) -> Result<Validator, Box<dyn std::error::Error>> {
+        let gossip = node.info.gossip().unwrap();
+
+        // Each run of the validator gets its own `Exit`, so that closing it for a restart does not
+        // exit the test validator
+        let validator_exit = Arc::new(RwLock::new(Exit::default()));
+        {
+            let validator_exit = validator_exit.clone();
+            restart_config
+                .validator_exit
+                .write()
+                .unwrap()
+                .register_exit(Box::new(move || validator_exit.write().unwrap().exit()));
+        }
+        restart_config.validator_config.validator_exit = validator_exit;
+
+        let validator = Validator::new(
+            node,
+            Arc::new(validator_identity),
+            ledger_path,
+            vote_account_address,
+            restart_config.authorized_voter_keypairs.clone(),
+            vec![],
+            &restart_config.validator_config,
+            true, // should_check_duplicate_instance
+            restart_config.rpc_to_plugin_manager_receiver.clone(),
+            restart_config.start_progress.clone(),
+            restart_config.socket_addr_space,
+            DEFAULT_TPU_USE_QUIC,
+            DEFAULT_TPU_CONNECTION_POOL_SIZE,
+            restart_config.tpu_enable_udp,
+            restart_config.admin_rpc_service_post_init.clone(),
+        )?;
+
+        // Needed to avoid panics in `solana-responder-gossip` in tests that create a number of
+        // test validators concurrently...
+        discover_cluster(&gossip, 1, restart_config.socket_addr_space)
+            .map_err(|err| format!("TestValidator startup failed: {err:?}"))?;
+
+        Ok(validator)
+    }
+
+    /// Restart the validator on its ledger, warped to `warp_slot`
+    ///
+    /// The warped bank is a child of the root bank, so transactions which are not finalized yet
+    /// are discarded.  Warping to a later epoch triggers the epoch boundary processing, like
+    /// rewards and stake activation, once.
+    ///
+    /// RPC and PubSub connections are closed by the restart, while their addresses stay the same.
+    /// The TPU address may change.
+    ///
+    /// If the warped validator fails to start, the validator is restarted on the root bank
+    /// without the warp and the error is returned.  The process exits if that fails as well.
+    pub fn warp_to_slot(&mut self, warp_slot: Slot) -> Result<(), Box<dyn std::error::Error>> {
+        let root_bank = {
+            let bank_forks = self.bank_forks();
+            let bank_forks = bank_forks.read().unwrap();
+            let working_slot = bank_forks.working_bank().slot();
+            if warp_slot <= working_slot {
+                return Err(format!(
+                    "warp slot ({warp_slot}) must be greater than the working bank slot \
+                     ({working_slot})"
+                )
+                .into());
+            }
+            bank_forks.root_bank()
+        };
+        // The validator starts from the latest snapshot, so snapshot the root bank to carry over
+        // the transactions processed since the last snapshot.  This is done before the validator
+        // is closed, so that it keeps running if the snapshot fails
+        let snapshot_config = &self.restart_config.validator_config.snapshot_config;
+        snapshot_bank_utils::bank_to_full_snapshot_archive(
+            &self.ledger_path,
+            &root_bank,
+            None,
+            &snapshot_config.full_snapshot_archives_dir,
+            &snapshot_config.incremental_snapshot_archives_dir,
+            snapshot_config.archive_format,
+            snapshot_config.maximum_full_snapshot_archives_to_retain,
+            snapshot_config.maximum_incremental_snapshot_archives_to_retain,
+        )?;
+        drop(root_bank);
+
+        info!("Restarting the test validator warped to slot {warp_slot}");
+        if let Some(validator) = self.validator.take() {
+            validator.close();
+        }
+        if let Err(err) = self.restart_validator(Some(warp_slot)) {
+            error!("Failed to restart the test validator warped to slot {warp_slot}: {err}");
+            // Bring the validator back up from the snapshot of the root bank
+            if let Err(err) = self.restart_validator(None) {
+                error!("Failed to restart the test validator: {err}");
+                std::process::exit(1);
+            }
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    fn restart_validator(
+        &mut self,
+        warp_slot: Option<Slot>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let validator_identity = read_keypair_file(
+            self.ledger_path
+                .join("validator-keypair.json")
+                .to_str()
+                .unwrap(),
+        )?;
+        let (rpc_addr, rpc_pubsub_addr) = self.restart_config.validator_config.rpc_addrs.unwrap();
+        let node = Self::new_node(
+            &validator_identity.pubkey(),
+            &self.gossip,
+            self.restart_config.port_range,
+            self.restart_config.bind_ip_addr,
+            Some((rpc_addr.port(), rpc_pubsub_addr.port())),
+        );
+        self.tpu = node.info.tpu(Protocol::UDP).unwrap();
+
+        self.restart_config.validator_config.warp_slot = warp_slot;
+        self.validator = Some(Self::start_validator(
+            node,
+            validator_identity,
+            &self.ledger_path,
+            &self.vote_account_address,
+            &mut self.restart_config,
+        )?);
+        Ok(())
+    }
+
+    /// Restart the validator on its ledger, warped forward by `slots` slots from the working bank
+    ///
+    /// Returns the new working slot.  See `warp_to_slot()`.
+    pub fn warp_slots(&mut self, slots: u64) -> Result<Slot, Box<dyn std::error::Error>> {
+        let working_slot = self.bank_forks().read().unwrap().working_bank().slot();
+        let warp_slot = working_slot.saturating_add(slots);
+        self.warp_to_slot(warp_slot)?;
+        Ok(warp_slot)
+    }
+
+    /// Restart the validator on its ledger, warped to the first slot of the next epoch
+    ///
+    /// Returns the new working slot.  See `warp_to_slot()`.
+    pub fn warp_to_next_epoch(&mut self) -> Result<Slot, Box<dyn std::error::Error>> {
+        let warp_slot = {
+            let working_bank = self.bank_forks().read().unwrap().working_bank();
+            working_bank
+                .epoch_schedule()
+                .get_first_slot_in_epoch(working_bank.epoch() + 1)
+        };
+        self.warp_to_slot(warp_slot)?;
+        Ok(warp_slot)
+    }
+
+    /// Override `Clock::unix_timestamp` of the working bank
+    ///
+    /// The clock of the following banks never goes below the timestamp of their parent, so it
+    /// stays at `unix_timestamp` until the vote timestamp estimates catch up with it.
+    /// Overriding the timestamp with an earlier one only lasts for the current working bank.
+    pub fn set_clock_unix_timestamp(
+        &self,
+        unix_timestamp: UnixTimestamp,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        const MAX_ATTEMPTS: u64 = 10;
+        let bank_forks = self.bank_forks();
+        // The working bank is briefly frozen between slots
+        for _ in 0..MAX_ATTEMPTS {
+            let working_bank = bank_forks.read().unwrap().working_bank();
+            {
+                let _freeze_lock = working_bank.freeze_lock();
+                if !working_bank.is_frozen() {
+                    working_bank.set_sysvar_for_tests(&Clock {
+                        unix_timestamp,
+                        ..working_bank.clock()
+                    });
+                    return Ok(());
+                }
+            }
+            std::thread::sleep(Duration::from_millis(DEFAULT_MS_PER_SLOT / MAX_ATTEMPTS));
+        }
+        Err("No unfrozen working bank to update the clock of".into())
+    }
+
     /// This is a hack to delay until the fees are non-zero for test consistency
     /// (fees from genesis are zero until the first block with a transaction in it is completed
     ///  due to a bug in the Bank)
@@ -1146,7 +1513,12 @@ impl Drop for TestValidator {
 
 #[cfg(test)]
 mod test {
-    use super::*;
+    use {
+        super::*,
+        solana_runtime::{bank::Bank, genesis_utils::create_genesis_config},
+        solana_sdk::sysvar,
+        std::num::NonZeroUsize,
+    };
 
     #[test]
     fn get_health() {
@@ -1164,6 +1536,109 @@ mod test {
         rpc_client.get_health().await.expect("health");
     }
 
+    #[test]
+    fn warp_and_set_clock() {
+        let (mut test_validator, payer) = TestValidatorGenesis::default().start();
+        let rpc_client = test_validator.get_rpc_client();
+        let balance = rpc_client.get_balance(&payer.pubkey()).unwrap();
+        let epoch = rpc_client.get_epoch_info().unwrap().epoch;
+
+        let warp_slot = test_validator.warp_to_next_epoch().unwrap();
+        let epoch_info = rpc_client.get_epoch_info().unwrap();
+        assert_eq!(epoch_info.epoch, epoch + 1);
+        assert!(epoch_info.absolute_slot >= warp_slot);
+        assert_eq!(rpc_client.get_balance(&payer.pubkey()).unwrap(), balance);
+
+        let warp_slot = test_validator.warp_slots(100).unwrap();
+        assert!(rpc_client.get_slot().unwrap() >= warp_slot);
+        assert!(test_validator.warp_to_slot(warp_slot).is_err());
+
+        let get_clock = || -> Clock {
+            bincode::deserialize(&rpc_client.get_account(&sysvar::clock::id()).unwrap().data)
+                .unwrap()
+        };
+        let unix_timestamp = get_clock().unix_timestamp + 1_000_000;
+        test_validator
+            .set_clock_unix_timestamp(unix_timestamp)
+            .unwrap();
+        assert!(get_clock().unix_timestamp >= unix_timestamp);
+    }
+
+    #[test]
+    fn clone_accounts_from_snapshot_archives() {
+        let genesis_config = create_genesis_config(sol_to_lamports(1.)).genesis_config;
+        let bank = Bank::new_for_tests(&genesis_config);
+        let owner = Pubkey::new_unique();
+        let address = Pubkey::new_unique();
+        let owned_address = Pubkey::new_unique();
+        let deleted_address = Pubkey::new_unique();
+        bank.store_account(
+            &address,
+            &AccountSharedData::new(1, 0, &Pubkey::new_unique()),
+        );
+        bank.store_account(&owned_address, &AccountSharedData::new(2, 0, &owner));
+        bank.store_account(&deleted_address, &AccountSharedData::new(0, 0, &owner));
+        bank.store_account(&Pubkey::new_unique(), &AccountSharedData::new(3, 0, &owner));
+        bank.fill_bank_with_ticks_for_tests();
+
+        let snapshot_archives_dir = tempfile::tempdir().unwrap();
+        let full_snapshot_archive_info = snapshot_bank_utils::bank_to_full_snapshot_archive(
+            snapshot_archives_dir.path(),
+            &bank,
+            None,
+            snapshot_archives_dir.path(),
+            snapshot_archives_dir.path(),
+            snapshot_utils::ArchiveFormat::TarZstd,
+            NonZeroUsize::new(1).unwrap(),
+            NonZeroUsize::new(1).unwrap(),
+        )
+        .unwrap();
+
+        let mut genesis = TestValidatorGenesis::default();
+        let selection = SnapshotAccountsSelection {
+            addresses: HashSet::from([address]),
+            maybe_addresses: HashSet::from([deleted_address]),
+            owners: HashSet::from([Pubkey::new_unique()]),
+            ..SnapshotAccountsSelection::default()
+        };
+        genesis
+            .clone_accounts_from_snapshot_archives(
+                full_snapshot_archive_info.path(),
+                None,
+                &selection,
+            )
+            .unwrap();
+        assert_eq!(genesis.accounts.len(), 1);
+        assert_eq!(genesis.accounts[&address].lamports(), 1);
+
+        let selection = SnapshotAccountsSelection {
+            owners: HashSet::from([owner]),
+            ..SnapshotAccountsSelection::default()
+        };
+        genesis
+            .clone_accounts_from_snapshot_archives(
+                full_snapshot_archive_info.path(),
+                None,
+                &selection,
+            )
+            .unwrap();
+        assert_eq!(genesis.accounts.len(), 3);
+        assert_eq!(genesis.accounts[&owned_address].lamports(), 2);
+        assert!(!genesis.accounts.contains_key(&deleted_address));
+
+        let selection = SnapshotAccountsSelection {
+            upgradeable_programs: HashSet::from([Pubkey::new_unique()]),
+            ..SnapshotAccountsSelection::default()
+        };
+        assert!(genesis
+            .clone_accounts_from_snapshot_archives(
+                full_snapshot_archive_info.path(),
+                None,
+                &selection,
+            )
+            .is_err());
+    }
+
     #[tokio::test]
     #[should_panic]
     async fn document_tokio_panic() {
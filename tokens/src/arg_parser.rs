@@ -1,15 +1,21 @@
 use {
     crate::args::{
-        Args, BalancesArgs, Command, DistributeTokensArgs, SenderStakeArgs, SplTokenArgs,
-        StakeArgs, TransactionLogArgs,
+        Args, BalancesArgs, Command, ComputeUnitPrice, DistributeTokensArgs, SenderStakeArgs,
+        SplTokenArgs, StakeArgs, SubmitTransactionsArgs, TransactionLogArgs,
     },
     clap::{
         crate_description, crate_name, value_t, value_t_or_exit, App, Arg, ArgMatches, SubCommand,
     },
     solana_clap_utils::{
+        compute_unit_price::{compute_unit_price_arg, COMPUTE_UNIT_PRICE_ARG},
         input_parsers::{pubkey_of_signer, value_of},
-        input_validators::{is_amount, is_url_or_moniker, is_valid_pubkey, is_valid_signer},
-        keypair::{pubkey_from_path, signer_from_path},
+        input_validators::{
+            is_amount, is_parsable, is_url_or_moniker, is_valid_pubkey, is_valid_signer,
+            is_within_range,
+        },
+        keypair::{
+            pubkey_from_path, signer_from_path, signer_from_path_with_config, SignerFromPathConfig,
+        },
     },
     solana_cli_config::CONFIG_FILE,
     solana_remote_wallet::remote_wallet::maybe_wallet_manager,
@@ -109,6 +115,68 @@ where
                         .value_name("KEYPAIR")
                         .validator(is_valid_signer)
                         .help("Fee payer"),
+                )
+                .arg(
+                    Arg::with_name("nonce_pool_size")
+                        .long("nonce-pool-size")
+                        .takes_value(true)
+                        .value_name("NUMBER")
+                        .validator(|value| is_within_range(value, 1usize..))
+                        .help(
+                            "Sign transactions with durable nonces instead of recent blockhashes, \
+                            using this many nonce accounts derived from the fee payer. \
+                            Transactions are sent in batches of this size.",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("prepare_sign_only")
+                        .long("prepare-sign-only")
+                        .conflicts_with_all(&["dry_run", "nonce_pool_size"])
+                        .help(
+                            "Create a nonce account for each allocation left to distribute, and \
+                            write the transactions using them to the database unsigned, for \
+                            --sign-only to sign offline.",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("sign_only")
+                        .long("sign-only")
+                        .conflicts_with_all(&[
+                            "dry_run",
+                            "nonce_pool_size",
+                            "prepare_sign_only",
+                            COMPUTE_UNIT_PRICE_ARG.name,
+                            "dynamic_compute_unit_price",
+                        ])
+                        .help(
+                            "Sign the transactions written by --prepare-sign-only without \
+                            connecting to the cluster. Send them with the submit-transactions \
+                            command.",
+                        ),
+                )
+                .arg(compute_unit_price_arg().validator(is_parsable::<u64>))
+                .arg(
+                    Arg::with_name("dynamic_compute_unit_price")
+                        .long("dynamic-compute-unit-price")
+                        .conflicts_with(COMPUTE_UNIT_PRICE_ARG.name)
+                        .requires("max_compute_unit_price")
+                        .help(
+                            "Set the compute unit price of each transaction to the median of the \
+                            recent prioritization fees paid for the accounts it writes, up to \
+                            --max-compute-unit-price",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("max_compute_unit_price")
+                        .long("max-compute-unit-price")
+                        .takes_value(true)
+                        .value_name("COMPUTE-UNIT-PRICE")
+                        .validator(is_parsable::<u64>)
+                        .requires("dynamic_compute_unit_price")
+                        .help(
+                            "Maximum compute unit price of --dynamic-compute-unit-price, which \
+                            the fee payer balance is checked at",
+                        ),
                 ),
         )
         .subcommand(
@@ -180,6 +248,68 @@ where
                         .value_name("KEYPAIR")
                         .validator(is_valid_signer)
                         .help("Fee payer"),
+                )
+                .arg(
+                    Arg::with_name("nonce_pool_size")
+                        .long("nonce-pool-size")
+                        .takes_value(true)
+                        .value_name("NUMBER")
+                        .validator(|value| is_within_range(value, 1usize..))
+                        .help(
+                            "Sign transactions with durable nonces instead of recent blockhashes, \
+                            using this many nonce accounts derived from the fee payer. \
+                            Transactions are sent in batches of this size.",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("prepare_sign_only")
+                        .long("prepare-sign-only")
+                        .conflicts_with_all(&["dry_run", "nonce_pool_size"])
+                        .help(
+                            "Create a nonce account for each allocation left to distribute, and \
+                            write the transactions using them to the database unsigned, for \
+                            --sign-only to sign offline.",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("sign_only")
+                        .long("sign-only")
+                        .conflicts_with_all(&[
+                            "dry_run",
+                            "nonce_pool_size",
+                            "prepare_sign_only",
+                            COMPUTE_UNIT_PRICE_ARG.name,
+                            "dynamic_compute_unit_price",
+                        ])
+                        .help(
+                            "Sign the transactions written by --prepare-sign-only without \
+                            connecting to the cluster. Send them with the submit-transactions \
+                            command.",
+                        ),
+                )
+                .arg(compute_unit_price_arg().validator(is_parsable::<u64>))
+                .arg(
+                    Arg::with_name("dynamic_compute_unit_price")
+                        .long("dynamic-compute-unit-price")
+                        .conflicts_with(COMPUTE_UNIT_PRICE_ARG.name)
+                        .requires("max_compute_unit_price")
+                        .help(
+                            "Set the compute unit price of each transaction to the median of the \
+                            recent prioritization fees paid for the accounts it writes, up to \
+                            --max-compute-unit-price",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("max_compute_unit_price")
+                        .long("max-compute-unit-price")
+                        .takes_value(true)
+                        .value_name("COMPUTE-UNIT-PRICE")
+                        .validator(is_parsable::<u64>)
+                        .requires("dynamic_compute_unit_price")
+                        .help(
+                            "Maximum compute unit price of --dynamic-compute-unit-price, which \
+                            the fee payer balance is checked at",
+                        ),
                 ),
         )
         .subcommand(
@@ -278,6 +408,68 @@ where
                         .value_name("KEYPAIR")
                         .validator(is_valid_signer)
                         .help("Fee payer"),
+                )
+                .arg(
+                    Arg::with_name("nonce_pool_size")
+                        .long("nonce-pool-size")
+                        .takes_value(true)
+                        .value_name("NUMBER")
+                        .validator(|value| is_within_range(value, 1usize..))
+                        .help(
+                            "Sign transactions with durable nonces instead of recent blockhashes, \
+                            using this many nonce accounts derived from the fee payer. \
+                            Transactions are sent in batches of this size.",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("prepare_sign_only")
+                        .long("prepare-sign-only")
+                        .conflicts_with_all(&["dry_run", "nonce_pool_size"])
+                        .help(
+                            "Create a nonce account for each allocation left to distribute, and \
+                            write the transactions using them to the database unsigned, for \
+                            --sign-only to sign offline.",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("sign_only")
+                        .long("sign-only")
+                        .conflicts_with_all(&[
+                            "dry_run",
+                            "nonce_pool_size",
+                            "prepare_sign_only",
+                            COMPUTE_UNIT_PRICE_ARG.name,
+                            "dynamic_compute_unit_price",
+                        ])
+                        .help(
+                            "Sign the transactions written by --prepare-sign-only without \
+                            connecting to the cluster. Send them with the submit-transactions \
+                            command.",
+                        ),
+                )
+                .arg(compute_unit_price_arg().validator(is_parsable::<u64>))
+                .arg(
+                    Arg::with_name("dynamic_compute_unit_price")
+                        .long("dynamic-compute-unit-price")
+                        .conflicts_with(COMPUTE_UNIT_PRICE_ARG.name)
+                        .requires("max_compute_unit_price")
+                        .help(
+                            "Set the compute unit price of each transaction to the median of the \
+                            recent prioritization fees paid for the accounts it writes, up to \
+                            --max-compute-unit-price",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("max_compute_unit_price")
+                        .long("max-compute-unit-price")
+                        .takes_value(true)
+                        .value_name("COMPUTE-UNIT-PRICE")
+                        .validator(is_parsable::<u64>)
+                        .requires("dynamic_compute_unit_price")
+                        .help(
+                            "Maximum compute unit price of --dynamic-compute-unit-price, which \
+                            the fee payer balance is checked at",
+                        ),
                 ),
         )
         .subcommand(
@@ -350,6 +542,68 @@ where
                         .value_name("KEYPAIR")
                         .validator(is_valid_signer)
                         .help("Fee payer"),
+                )
+                .arg(
+                    Arg::with_name("nonce_pool_size")
+                        .long("nonce-pool-size")
+                        .takes_value(true)
+                        .value_name("NUMBER")
+                        .validator(|value| is_within_range(value, 1usize..))
+                        .help(
+                            "Sign transactions with durable nonces instead of recent blockhashes, \
+                            using this many nonce accounts derived from the fee payer. \
+                            Transactions are sent in batches of this size.",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("prepare_sign_only")
+                        .long("prepare-sign-only")
+                        .conflicts_with_all(&["dry_run", "nonce_pool_size"])
+                        .help(
+                            "Create a nonce account for each allocation left to distribute, and \
+                            write the transactions using them to the database unsigned, for \
+                            --sign-only to sign offline.",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("sign_only")
+                        .long("sign-only")
+                        .conflicts_with_all(&[
+                            "dry_run",
+                            "nonce_pool_size",
+                            "prepare_sign_only",
+                            COMPUTE_UNIT_PRICE_ARG.name,
+                            "dynamic_compute_unit_price",
+                        ])
+                        .help(
+                            "Sign the transactions written by --prepare-sign-only without \
+                            connecting to the cluster. Send them with the submit-transactions \
+                            command.",
+                        ),
+                )
+                .arg(compute_unit_price_arg().validator(is_parsable::<u64>))
+                .arg(
+                    Arg::with_name("dynamic_compute_unit_price")
+                        .long("dynamic-compute-unit-price")
+                        .conflicts_with(COMPUTE_UNIT_PRICE_ARG.name)
+                        .requires("max_compute_unit_price")
+                        .help(
+                            "Set the compute unit price of each transaction to the median of the \
+                            recent prioritization fees paid for the accounts it writes, up to \
+                            --max-compute-unit-price",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("max_compute_unit_price")
+                        .long("max-compute-unit-price")
+                        .takes_value(true)
+                        .value_name("COMPUTE-UNIT-PRICE")
+                        .validator(is_parsable::<u64>)
+                        .requires("dynamic_compute_unit_price")
+                        .help(
+                            "Maximum compute unit price of --dynamic-compute-unit-price, which \
+                            the fee payer balance is checked at",
+                        ),
                 ),
         )
         .subcommand(
@@ -405,21 +659,56 @@ where
                         .help("Output file"),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("submit-transactions")
+                .about("Send the transactions signed with --sign-only and finalize them")
+                .arg(
+                    Arg::with_name("db_path")
+                        .long("db-path")
+                        .required(true)
+                        .takes_value(true)
+                        .value_name("FILE")
+                        .help("Location of the database of the signed transactions"),
+                )
+                .arg(
+                    Arg::with_name("output_path")
+                        .long("output-path")
+                        .short("o")
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .help("Write the transaction log to this file"),
+                ),
+        )
         .get_matches_from(args)
 }
 
+fn parse_compute_unit_price(matches: &ArgMatches<'_>) -> Option<ComputeUnitPrice> {
+    if matches.is_present("dynamic_compute_unit_price") {
+        Some(ComputeUnitPrice::Dynamic {
+            max_compute_unit_price: value_t_or_exit!(matches, "max_compute_unit_price", u64),
+        })
+    } else {
+        value_of(matches, COMPUTE_UNIT_PRICE_ARG.name).map(ComputeUnitPrice::Fixed)
+    }
+}
+
 fn parse_distribute_tokens_args(
     matches: &ArgMatches<'_>,
 ) -> Result<DistributeTokensArgs, Box<dyn Error>> {
     let mut wallet_manager = maybe_wallet_manager()?;
     let signer_matches = ArgMatches::default(); // No default signer
+                                                // Preparing to sign offline only needs the addresses of the signers other than the fee payer
+    let signer_config = SignerFromPathConfig {
+        allow_null_signer: matches.is_present("prepare_sign_only"),
+    };
 
     let sender_keypair_str = value_t_or_exit!(matches, "sender_keypair", String);
-    let sender_keypair = signer_from_path(
+    let sender_keypair = signer_from_path_with_config(
         &signer_matches,
         &sender_keypair_str,
         "sender",
         &mut wallet_manager,
+        &signer_config,
     )?;
 
     let fee_payer_str = value_t_or_exit!(matches, "fee_payer", String);
@@ -440,6 +729,10 @@ fn parse_distribute_tokens_args(
         stake_args: None,
         spl_token_args: None,
         transfer_amount: value_of(matches, "transfer_amount").map(sol_to_lamports),
+        nonce_pool_size: value_of(matches, "nonce_pool_size"),
+        prepare_sign_only: matches.is_present("prepare_sign_only"),
+        sign_only: matches.is_present("sign_only"),
+        compute_unit_price: parse_compute_unit_price(matches),
     })
 }
 
@@ -448,13 +741,18 @@ fn parse_create_stake_args(
 ) -> Result<DistributeTokensArgs, Box<dyn Error>> {
     let mut wallet_manager = maybe_wallet_manager()?;
     let signer_matches = ArgMatches::default(); // No default signer
+                                                // Preparing to sign offline only needs the addresses of the signers other than the fee payer
+    let signer_config = SignerFromPathConfig {
+        allow_null_signer: matches.is_present("prepare_sign_only"),
+    };
 
     let sender_keypair_str = value_t_or_exit!(matches, "sender_keypair", String);
-    let sender_keypair = signer_from_path(
+    let sender_keypair = signer_from_path_with_config(
         &signer_matches,
         &sender_keypair_str,
         "sender",
         &mut wallet_manager,
+        &signer_config,
     )?;
 
     let fee_payer_str = value_t_or_exit!(matches, "fee_payer", String);
@@ -492,6 +790,10 @@ fn parse_create_stake_args(
         stake_args: Some(stake_args),
         spl_token_args: None,
         transfer_amount: None,
+        nonce_pool_size: value_of(matches, "nonce_pool_size"),
+        prepare_sign_only: matches.is_present("prepare_sign_only"),
+        sign_only: matches.is_present("sign_only"),
+        compute_unit_price: parse_compute_unit_price(matches),
     })
 }
 
@@ -500,13 +802,18 @@ fn parse_distribute_stake_args(
 ) -> Result<DistributeTokensArgs, Box<dyn Error>> {
     let mut wallet_manager = maybe_wallet_manager()?;
     let signer_matches = ArgMatches::default(); // No default signer
+                                                // Preparing to sign offline only needs the addresses of the signers other than the fee payer
+    let signer_config = SignerFromPathConfig {
+        allow_null_signer: matches.is_present("prepare_sign_only"),
+    };
 
     let sender_keypair_str = value_t_or_exit!(matches, "sender_keypair", String);
-    let sender_keypair = signer_from_path(
+    let sender_keypair = signer_from_path_with_config(
         &signer_matches,
         &sender_keypair_str,
         "sender",
         &mut wallet_manager,
+        &signer_config,
     )?;
 
     let fee_payer_str = value_t_or_exit!(matches, "fee_payer", String);
@@ -526,29 +833,32 @@ fn parse_distribute_stake_args(
     )?;
 
     let stake_authority_str = value_t_or_exit!(matches, "stake_authority", String);
-    let stake_authority = signer_from_path(
+    let stake_authority = signer_from_path_with_config(
         &signer_matches,
         &stake_authority_str,
         "stake authority",
         &mut wallet_manager,
+        &signer_config,
     )?;
 
     let withdraw_authority_str = value_t_or_exit!(matches, "withdraw_authority", String);
-    let withdraw_authority = signer_from_path(
+    let withdraw_authority = signer_from_path_with_config(
         &signer_matches,
         &withdraw_authority_str,
         "withdraw authority",
         &mut wallet_manager,
+        &signer_config,
     )?;
 
     let lockup_authority_str = value_t!(matches, "lockup_authority", String).ok();
     let lockup_authority = lockup_authority_str
         .map(|path| {
-            signer_from_path(
+            signer_from_path_with_config(
                 &signer_matches,
                 &path,
                 "lockup authority",
                 &mut wallet_manager,
+                &signer_config,
             )
         })
         .transpose()?;
@@ -576,6 +886,10 @@ fn parse_distribute_stake_args(
         stake_args: Some(stake_args),
         spl_token_args: None,
         transfer_amount: None,
+        nonce_pool_size: value_of(matches, "nonce_pool_size"),
+        prepare_sign_only: matches.is_present("prepare_sign_only"),
+        sign_only: matches.is_present("sign_only"),
+        compute_unit_price: parse_compute_unit_price(matches),
     })
 }
 
@@ -584,13 +898,18 @@ fn parse_distribute_spl_tokens_args(
 ) -> Result<DistributeTokensArgs, Box<dyn Error>> {
     let mut wallet_manager = maybe_wallet_manager()?;
     let signer_matches = ArgMatches::default(); // No default signer
+                                                // Preparing to sign offline only needs the addresses of the signers other than the fee payer
+    let signer_config = SignerFromPathConfig {
+        allow_null_signer: matches.is_present("prepare_sign_only"),
+    };
 
     let token_owner_str = value_t_or_exit!(matches, "token_owner", String);
-    let token_owner = signer_from_path(
+    let token_owner = signer_from_path_with_config(
         &signer_matches,
         &token_owner_str,
         "owner",
         &mut wallet_manager,
+        &signer_config,
     )?;
 
     let fee_payer_str = value_t_or_exit!(matches, "fee_payer", String);
@@ -622,6 +941,10 @@ fn parse_distribute_spl_tokens_args(
             ..SplTokenArgs::default()
         }),
         transfer_amount: value_of(matches, "transfer_amount"),
+        nonce_pool_size: value_of(matches, "nonce_pool_size"),
+        prepare_sign_only: matches.is_present("prepare_sign_only"),
+        sign_only: matches.is_present("sign_only"),
+        compute_unit_price: parse_compute_unit_price(matches),
     })
 }
 
@@ -645,6 +968,13 @@ fn parse_transaction_log_args(matches: &ArgMatches<'_>) -> TransactionLogArgs {
     }
 }
 
+fn parse_submit_transactions_args(matches: &ArgMatches<'_>) -> SubmitTransactionsArgs {
+    SubmitTransactionsArgs {
+        transaction_db: value_t_or_exit!(matches, "db_path", String),
+        output_path: matches.value_of("output_path").map(|path| path.to_string()),
+    }
+}
+
 pub fn parse_args<I, T>(args: I) -> Result<Args, Box<dyn Error>>
 where
     I: IntoIterator<Item = T>,
@@ -672,6 +1002,9 @@ where
         ("transaction-log", Some(matches)) => {
             Command::TransactionLog(parse_transaction_log_args(matches))
         }
+        ("submit-transactions", Some(matches)) => {
+            Command::SubmitTransactions(parse_submit_transactions_args(matches))
+        }
         _ => {
             eprintln!("{}", matches.usage());
             exit(1);
@@ -24,6 +24,18 @@ pub struct DistributeTokensArgs {
     pub stake_args: Option<StakeArgs>,
     pub spl_token_args: Option<SplTokenArgs>,
     pub transfer_amount: Option<u64>,
+    pub nonce_pool_size: Option<usize>,
+    pub prepare_sign_only: bool,
+    pub sign_only: bool,
+    pub compute_unit_price: Option<ComputeUnitPrice>,
+}
+
+pub enum ComputeUnitPrice {
+    /// Price in micro-lamports per compute unit
+    Fixed(u64),
+    /// Price following the recent prioritization fees, up to a maximum, in micro-lamports per
+    /// compute unit
+    Dynamic { max_compute_unit_price: u64 },
 }
 
 #[derive(Default)]
@@ -38,6 +50,11 @@ pub struct BalancesArgs {
     pub spl_token_args: Option<SplTokenArgs>,
 }
 
+pub struct SubmitTransactionsArgs {
+    pub transaction_db: String,
+    pub output_path: Option<String>,
+}
+
 pub struct TransactionLogArgs {
     pub transaction_db: String,
     pub output_path: String,
@@ -48,6 +65,7 @@ pub enum Command {
     DistributeTokens(DistributeTokensArgs),
     Balances(BalancesArgs),
     TransactionLog(TransactionLogArgs),
+    SubmitTransactions(SubmitTransactionsArgs),
 }
 
 pub struct Args {
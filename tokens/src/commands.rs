@@ -1,9 +1,12 @@
 use {
     crate::{
         args::{
-            BalancesArgs, DistributeTokensArgs, SenderStakeArgs, StakeArgs, TransactionLogArgs,
+            BalancesArgs, DistributeTokensArgs, SenderStakeArgs, StakeArgs, SubmitTransactionsArgs,
+            TransactionLogArgs,
         },
-        db::{self, TransactionInfo},
+        compute_budget::{self, WithComputeUnitPrice},
+        db::{self, TransactionInfo, UnsignedTransactionInfo},
+        nonce,
         spl_token::*,
         token_display::Token,
     },
@@ -34,7 +37,7 @@ use {
             state::{Authorized, Lockup, StakeAuthorize, StakeStateV2},
         },
         system_instruction,
-        transaction::Transaction,
+        transaction::{Transaction, TransactionError},
     },
     solana_transaction_status::TransactionStatus,
     spl_associated_token_account::get_associated_token_address,
@@ -136,6 +139,14 @@ pub enum Error {
     InsufficientFunds(FundingSources, String),
     #[error("Program error")]
     ProgramError(#[from] ProgramError),
+    #[error("Nonce error: {0}")]
+    NonceError(#[from] solana_rpc_client_nonce_utils::Error),
+    #[error("Nonce account {0} not found")]
+    MissingNonceAccount(Pubkey),
+    #[error("The allocations left to distribute weren't prepared with --prepare-sign-only")]
+    UnpreparedAllocations,
+    #[error("Transaction simulation failed: {0}")]
+    SimulationError(TransactionError),
     #[error("Exit signal received")]
     ExitSignal,
 }
@@ -349,6 +360,11 @@ fn build_messages(
         }
     }
 
+    let fee_payer_pubkey = args.fee_payer.pubkey();
+    let nonce_accounts = nonce::nonce_pool_size(args, allocations.len())
+        .map(|pool_size| nonce::nonce_account_addresses(&fee_payer_pubkey, pool_size))
+        .unwrap_or_default();
+
     for (i, allocation) in allocations.iter().enumerate() {
         if exit.load(Ordering::SeqCst) {
             db.dump()?;
@@ -384,19 +400,53 @@ fn build_messages(
             args,
             lockup_date,
             do_create_associated_token_account,
-        );
-        let fee_payer_pubkey = args.fee_payer.pubkey();
-        let message = Message::new_with_blockhash(
-            &instructions,
-            Some(&fee_payer_pubkey),
-            &Hash::default(), // populated by a real blockhash for balance check and submission
-        );
+        )
+        .with_compute_unit_price(args.compute_unit_price.as_ref());
+        let message = if nonce_accounts.is_empty() {
+            Message::new_with_blockhash(
+                &instructions,
+                Some(&fee_payer_pubkey),
+                &Hash::default(), // populated by a real blockhash for balance check and submission
+            )
+        } else {
+            // Each batch sent uses every nonce account once, in order
+            Message::new_with_nonce(
+                instructions,
+                Some(&fee_payer_pubkey),
+                &nonce_accounts[i % nonce_accounts.len()],
+                &fee_payer_pubkey,
+            )
+        };
         messages.push(message);
         stake_extras.push((new_stake_account_keypair, lockup_date));
     }
     Ok(())
 }
 
+fn distribution_signers<'a>(
+    allocation: &TypedAllocation,
+    args: &'a DistributeTokensArgs,
+    new_stake_account_keypair: &'a Keypair,
+) -> Result<Vec<&'a dyn Signer>, Error> {
+    let mut signers = vec![&*args.fee_payer, &*args.sender_keypair];
+    if let Some(stake_args) = &args.stake_args {
+        signers.push(new_stake_account_keypair);
+        if let Some(sender_stake_args) = &stake_args.sender_stake_args {
+            signers.push(&*sender_stake_args.stake_authority);
+            signers.push(&*sender_stake_args.withdraw_authority);
+            signers.push(new_stake_account_keypair);
+            if allocation.lockup_date.is_some() {
+                if let Some(lockup_authority) = &sender_stake_args.lockup_authority {
+                    signers.push(&**lockup_authority);
+                } else {
+                    return Err(Error::MissingLockupAuthority);
+                }
+            }
+        }
+    }
+    Ok(unique_signers(signers))
+}
+
 fn send_messages(
     client: &RpcClient,
     db: &mut PickleDb,
@@ -406,7 +456,7 @@ fn send_messages(
     messages: Vec<Message>,
     stake_extras: StakeExtras,
 ) -> Result<(), Error> {
-    for ((allocation, message), (new_stake_account_keypair, lockup_date)) in
+    for ((allocation, mut message), (new_stake_account_keypair, lockup_date)) in
         allocations.iter().zip(messages).zip(stake_extras)
     {
         if exit.load(Ordering::SeqCst) {
@@ -414,28 +464,16 @@ fn send_messages(
             return Err(Error::ExitSignal);
         }
         let new_stake_account_address = new_stake_account_keypair.pubkey();
-
-        let mut signers = vec![&*args.fee_payer, &*args.sender_keypair];
-        if let Some(stake_args) = &args.stake_args {
-            signers.push(&new_stake_account_keypair);
-            if let Some(sender_stake_args) = &stake_args.sender_stake_args {
-                signers.push(&*sender_stake_args.stake_authority);
-                signers.push(&*sender_stake_args.withdraw_authority);
-                signers.push(&new_stake_account_keypair);
-                if allocation.lockup_date.is_some() {
-                    if let Some(lockup_authority) = &sender_stake_args.lockup_authority {
-                        signers.push(&**lockup_authority);
-                    } else {
-                        return Err(Error::MissingLockupAuthority);
-                    }
-                }
-            }
-        }
-        let signers = unique_signers(signers);
+        let signers = distribution_signers(allocation, args, &new_stake_account_keypair)?;
         let result: ClientResult<(Transaction, u64)> = {
             if args.dry_run {
                 Ok((Transaction::new_unsigned(message), std::u64::MAX))
             } else {
+                compute_budget::update_compute_unit_price(
+                    client,
+                    args.compute_unit_price.as_ref(),
+                    &mut message,
+                )?;
                 let (blockhash, last_valid_block_height) =
                     client.get_latest_blockhash_with_commitment(CommitmentConfig::default())?;
                 let transaction = Transaction::new(&signers, message, blockhash);
@@ -470,6 +508,145 @@ fn send_messages(
     Ok(())
 }
 
+// Sign the messages with the durable nonces of the pool and send them in batches that use each
+// nonce account once. A batch is written to the db before it is sent, and finalized before the
+// nonces are used again, so a distribution interrupted at any point resumes by sending the same
+// transactions rather than new ones.
+fn send_messages_with_nonces(
+    client: &RpcClient,
+    db: &mut PickleDb,
+    allocations: &[TypedAllocation],
+    args: &DistributeTokensArgs,
+    exit: Arc<AtomicBool>,
+    messages: Vec<Message>,
+    stake_extras: StakeExtras,
+    nonce_pool_size: usize,
+) -> Result<(), Error> {
+    let nonce_accounts = nonce::nonce_account_addresses(&args.fee_payer.pubkey(), nonce_pool_size);
+    let mut messages = messages.into_iter().zip(stake_extras);
+    for allocation_chunk in allocations.chunks(nonce_pool_size) {
+        if exit.load(Ordering::SeqCst) {
+            db.dump()?;
+            return Err(Error::ExitSignal);
+        }
+        let nonce_accounts = &nonce_accounts[..allocation_chunk.len()];
+        let nonce_blockhashes = if args.dry_run {
+            vec![None; nonce_accounts.len()]
+        } else {
+            nonce::get_nonce_blockhashes(client, nonce_accounts)?
+        };
+
+        let mut transactions = vec![];
+        for (
+            ((allocation, nonce_account), nonce_blockhash),
+            (mut message, (new_stake_account_keypair, lockup_date)),
+        ) in allocation_chunk
+            .iter()
+            .zip(nonce_accounts)
+            .zip(nonce_blockhashes)
+            .zip(messages.by_ref())
+        {
+            let signers = distribution_signers(allocation, args, &new_stake_account_keypair)?;
+            let transaction = if args.dry_run {
+                Transaction::new_unsigned(message)
+            } else {
+                let nonce_blockhash =
+                    nonce_blockhash.ok_or(Error::MissingNonceAccount(*nonce_account))?;
+                compute_budget::update_compute_unit_price(
+                    client,
+                    args.compute_unit_price.as_ref(),
+                    &mut message,
+                )?;
+                Transaction::new(&signers, message, nonce_blockhash)
+            };
+            let new_stake_account_address = new_stake_account_keypair.pubkey();
+            let new_stake_account_address_option =
+                args.stake_args.as_ref().map(|_| &new_stake_account_address);
+            db::set_transaction_info(
+                db,
+                &allocation.recipient,
+                allocation.amount,
+                &transaction,
+                new_stake_account_address_option,
+                false,
+                std::u64::MAX, // durable nonce transactions don't expire
+                lockup_date,
+            )?;
+            transactions.push(transaction);
+        }
+        db.dump()?;
+
+        if args.dry_run {
+            continue;
+        }
+        let config = RpcSendTransactionConfig {
+            skip_preflight: true,
+            ..RpcSendTransactionConfig::default()
+        };
+        for (allocation, transaction) in allocation_chunk.iter().zip(&transactions) {
+            if let Err(e) = client.send_transaction_with_config(transaction, config) {
+                // The transaction is sent again while finalizing
+                eprintln!("Error sending tokens to {}: {}", allocation.recipient, e);
+            }
+        }
+        finalize_transactions(client, db, false, exit.clone())?;
+    }
+    Ok(())
+}
+
+// Write the messages to the db with the current values of their nonces, for `process_sign_only` to
+// sign them offline. Each message advances its own nonce account, so they can all be signed before
+// any of them is sent.
+fn prepare_unsigned_transactions(
+    client: &RpcClient,
+    db: &mut PickleDb,
+    allocations: &[TypedAllocation],
+    args: &DistributeTokensArgs,
+    messages: Vec<Message>,
+    stake_extras: StakeExtras,
+) -> Result<(), Error> {
+    let nonce_accounts =
+        nonce::nonce_account_addresses(&args.fee_payer.pubkey(), allocations.len());
+    let nonce_blockhashes = nonce::get_nonce_blockhashes(client, &nonce_accounts)?;
+
+    let mut unsigned_transaction_infos = vec![];
+    for (
+        ((allocation, (nonce_account, nonce_blockhash)), mut message),
+        (new_stake_account_keypair, lockup_date),
+    ) in allocations
+        .iter()
+        .zip(nonce_accounts.iter().zip(nonce_blockhashes))
+        .zip(messages)
+        .zip(stake_extras)
+    {
+        // A dynamic compute unit price is set now, as it can't be looked up offline
+        compute_budget::update_compute_unit_price(
+            client,
+            args.compute_unit_price.as_ref(),
+            &mut message,
+        )?;
+        message.recent_blockhash =
+            nonce_blockhash.ok_or(Error::MissingNonceAccount(*nonce_account))?;
+        unsigned_transaction_infos.push(UnsignedTransactionInfo {
+            recipient: allocation.recipient,
+            amount: allocation.amount,
+            new_stake_account_address: args
+                .stake_args
+                .as_ref()
+                .map(|_| new_stake_account_keypair.pubkey()),
+            lockup_date,
+            message,
+        });
+    }
+    db::set_unsigned_transaction_infos(db, &unsigned_transaction_infos)?;
+    db.dump()?;
+    println!(
+        "Prepared {} transactions, sign them offline with --sign-only",
+        unsigned_transaction_infos.len()
+    );
+    Ok(())
+}
+
 fn distribute_allocations(
     client: &RpcClient,
     db: &mut PickleDb,
@@ -498,7 +675,39 @@ fn distribute_allocations(
         check_payer_balances(&messages, allocations, client, args)?;
     }
 
-    send_messages(client, db, allocations, args, exit, messages, stake_extras)?;
+    // The nonce accounts must exist for the messages advancing them to be simulated
+    let nonce_pool_size = nonce::nonce_pool_size(args, allocations.len());
+    if let Some(nonce_pool_size) = nonce_pool_size {
+        if !args.dry_run {
+            nonce::create_nonce_accounts(client, args, nonce_pool_size)?;
+        }
+    }
+
+    // The fees were checked with the maximum compute unit limit, which can only decrease here.
+    // The messages of a dry run may advance nonce accounts that don't exist yet, so they aren't
+    // simulated.
+    if args.compute_unit_price.is_some() && !(args.dry_run && nonce_pool_size.is_some()) {
+        compute_budget::update_compute_unit_limits(client, &mut messages)?;
+    }
+
+    match nonce_pool_size {
+        Some(_) if args.prepare_sign_only => {
+            prepare_unsigned_transactions(client, db, allocations, args, messages, stake_extras)?;
+        }
+        Some(nonce_pool_size) => {
+            send_messages_with_nonces(
+                client,
+                db,
+                allocations,
+                args,
+                exit,
+                messages,
+                stake_extras,
+                nonce_pool_size,
+            )?;
+        }
+        None => send_messages(client, db, allocations, args, exit, messages, stake_extras)?,
+    }
 
     db.dump()?;
     Ok(())
@@ -689,6 +898,102 @@ pub fn process_allocations(
     Ok(opt_confirmations)
 }
 
+/// Sign the transactions prepared with `prepare_sign_only` and write them to the db, without
+/// connecting to the cluster. Send them with `process_submit_transactions`.
+pub fn process_sign_only(args: &DistributeTokensArgs, exit: Arc<AtomicBool>) -> Result<(), Error> {
+    let with_lockup = args.stake_args.is_some();
+    let mut allocations: Vec<TypedAllocation> = read_allocations(
+        &args.input_csv,
+        args.transfer_amount,
+        with_lockup,
+        args.spl_token_args.is_some(),
+    )?;
+
+    let mut db = db::open_db(&args.transaction_db, false)?;
+    let transaction_infos = db::read_transaction_infos(&db);
+    apply_previous_transactions(&mut allocations, &transaction_infos);
+    if allocations.is_empty() {
+        eprintln!("No work to do");
+        return Ok(());
+    }
+
+    let unsigned_transaction_infos = db::read_unsigned_transaction_infos(&db);
+    let prepared_allocations = unsigned_transaction_infos
+        .iter()
+        .map(|info| TypedAllocation {
+            recipient: info.recipient,
+            amount: info.amount,
+            lockup_date: info.lockup_date,
+        })
+        .collect::<Vec<_>>();
+    if prepared_allocations != allocations {
+        return Err(Error::UnpreparedAllocations);
+    }
+
+    for (i, (allocation, unsigned_transaction_info)) in prepared_allocations
+        .iter()
+        .zip(&unsigned_transaction_infos)
+        .enumerate()
+    {
+        if exit.load(Ordering::SeqCst) {
+            db::set_unsigned_transaction_infos(&mut db, &unsigned_transaction_infos[i..])?;
+            db.dump()?;
+            return Err(Error::ExitSignal);
+        }
+        let new_stake_account_keypair = Keypair::new();
+        let mut message = unsigned_transaction_info.message.clone();
+        // The keypair of the prepared stake account wasn't kept, so create another one
+        if let Some(prepared_address) = unsigned_transaction_info.new_stake_account_address {
+            for account_key in &mut message.account_keys {
+                if *account_key == prepared_address {
+                    *account_key = new_stake_account_keypair.pubkey();
+                }
+            }
+        }
+        let signers = distribution_signers(allocation, args, &new_stake_account_keypair)?;
+        let nonce_blockhash = message.recent_blockhash;
+        let transaction = Transaction::new(&signers, message, nonce_blockhash);
+        let new_stake_account_address = new_stake_account_keypair.pubkey();
+        let new_stake_account_address_option = unsigned_transaction_info
+            .new_stake_account_address
+            .map(|_| &new_stake_account_address);
+        db::set_transaction_info(
+            &mut db,
+            &allocation.recipient,
+            allocation.amount,
+            &transaction,
+            new_stake_account_address_option,
+            false,
+            std::u64::MAX, // durable nonce transactions don't expire
+            allocation.lockup_date,
+        )?;
+    }
+
+    // The nonces are used up, so the same allocations can't be signed again
+    db::set_unsigned_transaction_infos(&mut db, &[])?;
+    db.dump()?;
+    println!(
+        "Signed {} transactions, send them with `solana-tokens submit-transactions`",
+        unsigned_transaction_infos.len()
+    );
+    Ok(())
+}
+
+/// Send the transactions signed with `sign_only` that are still pending, and wait for them to be
+/// finalized
+pub fn process_submit_transactions(
+    client: &RpcClient,
+    args: &SubmitTransactionsArgs,
+    exit: Arc<AtomicBool>,
+) -> Result<Option<usize>, Error> {
+    let mut db = db::open_db(&args.transaction_db, false)?;
+    let confirmations = finalize_transactions(client, &mut db, false, exit)?;
+    if let Some(output_path) = &args.output_path {
+        db::write_transaction_log(&db, &output_path)?;
+    }
+    Ok(confirmations)
+}
+
 fn finalize_transactions(
     client: &RpcClient,
     db: &mut PickleDb,
@@ -728,7 +1033,7 @@ fn update_finalized_transactions(
     exit: Arc<AtomicBool>,
 ) -> Result<Option<usize>, Error> {
     let transaction_infos = db::read_transaction_infos(db);
-    let unconfirmed_transactions: Vec<_> = transaction_infos
+    let mut unconfirmed_transactions: Vec<_> = transaction_infos
         .iter()
         .filter_map(|info| {
             if info.finalized_date.is_some() {
@@ -755,6 +1060,8 @@ fn update_finalized_transactions(
         );
     }
 
+    resolve_durable_nonce_transactions(client, &mut unconfirmed_transactions, &mut statuses)?;
+
     let mut confirmations = None;
     log_transaction_confirmations(
         client,
@@ -768,6 +1075,59 @@ fn update_finalized_transactions(
     Ok(confirmations)
 }
 
+// A durable nonce transaction without a status can still land while its nonce is unchanged, so send
+// it again. Once the nonce has advanced it can't land anymore, but its status may have been purged
+// since it did, so look it up in the transaction history and let it be handled as expired if it
+// isn't found.
+fn resolve_durable_nonce_transactions(
+    client: &RpcClient,
+    unconfirmed_transactions: &mut [(&Transaction, Slot)],
+    statuses: &mut [Option<TransactionStatus>],
+) -> Result<(), Error> {
+    let mut indexes = vec![];
+    let mut nonce_accounts = vec![];
+    for (i, ((transaction, _), status)) in unconfirmed_transactions
+        .iter()
+        .zip(statuses.iter())
+        .enumerate()
+    {
+        if status.is_none() {
+            if let Some(nonce_account) = nonce::durable_nonce_account(transaction) {
+                indexes.push(i);
+                nonce_accounts.push(nonce_account);
+            }
+        }
+    }
+    if indexes.is_empty() {
+        return Ok(());
+    }
+
+    let nonce_blockhashes = nonce::get_nonce_blockhashes(client, &nonce_accounts)?;
+    let config = RpcSendTransactionConfig {
+        skip_preflight: true,
+        ..RpcSendTransactionConfig::default()
+    };
+    for (i, nonce_blockhash) in indexes.into_iter().zip(nonce_blockhashes) {
+        let (transaction, last_valid_block_height) = &mut unconfirmed_transactions[i];
+        if nonce_blockhash == Some(transaction.message.recent_blockhash) {
+            if let Err(e) = client.send_transaction_with_config(*transaction, config) {
+                eprintln!(
+                    "Error resending transaction {}: {}",
+                    transaction.signatures[0], e
+                );
+            }
+        } else {
+            statuses[i] = client
+                .get_signature_statuses_with_history(&transaction.signatures[..1])?
+                .value
+                .pop()
+                .flatten();
+            *last_valid_block_height = 0;
+        }
+    }
+    Ok(())
+}
+
 fn log_transaction_confirmations(
     client: &RpcClient,
     db: &mut PickleDb,
@@ -824,7 +1184,11 @@ fn check_payer_balances(
     args: &DistributeTokensArgs,
 ) -> Result<(), Error> {
     let mut undistributed_tokens: u64 = allocations.iter().map(|x| x.amount).sum();
-    let fees = get_fee_estimate_for_messages(messages, client)?;
+    let mut fees = get_fee_estimate_for_messages(messages, client)?;
+    if let Some(nonce_pool_size) = nonce::nonce_pool_size(args, allocations.len()) {
+        fees +=
+            nonce::missing_nonce_accounts_rent(client, &args.fee_payer.pubkey(), nonce_pool_size)?;
+    }
 
     let (distribution_source, unlocked_sol_source) = if let Some(stake_args) = &args.stake_args {
         let total_unlocked_sol = allocations.len() as u64 * stake_args.unlocked_sol;
@@ -1022,6 +1386,10 @@ pub fn test_process_distribute_tokens_with_client(
         stake_args: None,
         spl_token_args: None,
         transfer_amount,
+        nonce_pool_size: None,
+        prepare_sign_only: false,
+        sign_only: false,
+        compute_unit_price: None,
     };
     let confirmations = process_allocations(client, &args, exit.clone()).unwrap();
     assert_eq!(confirmations, None);
@@ -1129,6 +1497,10 @@ pub fn test_process_create_stake_with_client(client: &RpcClient, sender_keypair:
         spl_token_args: None,
         sender_keypair: Box::new(sender_keypair),
         transfer_amount: None,
+        nonce_pool_size: None,
+        prepare_sign_only: false,
+        sign_only: false,
+        compute_unit_price: None,
     };
     let confirmations = process_allocations(client, &args, exit.clone()).unwrap();
     assert_eq!(confirmations, None);
@@ -1261,6 +1633,10 @@ pub fn test_process_distribute_stake_with_client(client: &RpcClient, sender_keyp
         spl_token_args: None,
         sender_keypair: Box::new(sender_keypair),
         transfer_amount: None,
+        nonce_pool_size: None,
+        prepare_sign_only: false,
+        sign_only: false,
+        compute_unit_price: None,
     };
     let confirmations = process_allocations(client, &args, exit.clone()).unwrap();
     assert_eq!(confirmations, None);
@@ -1307,6 +1683,7 @@ pub fn test_process_distribute_stake_with_client(client: &RpcClient, sender_keyp
 mod tests {
     use {
         super::*,
+        crate::args::ComputeUnitPrice,
         solana_sdk::{
             instruction::AccountMeta,
             signature::{read_keypair_file, write_keypair_file, Signer},
@@ -1810,6 +2187,10 @@ mod tests {
             spl_token_args: None,
             sender_keypair: Box::new(Keypair::new()),
             transfer_amount: None,
+            nonce_pool_size: None,
+            prepare_sign_only: false,
+            sign_only: false,
+            compute_unit_price: None,
         };
         let lockup_date = lockup_date_str.parse().unwrap();
         let instructions = distribution_instructions(
@@ -1859,6 +2240,10 @@ mod tests {
             stake_args,
             spl_token_args: None,
             transfer_amount: None,
+            nonce_pool_size: None,
+            prepare_sign_only: false,
+            sign_only: false,
+            compute_unit_price: None,
         };
         (allocations, args)
     }
@@ -1953,6 +2338,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_check_payer_balances_with_nonce_accounts() {
+        let alice = Keypair::new();
+        let test_validator = simple_test_validator(alice.pubkey());
+        let url = test_validator.rpc_url();
+
+        let client = RpcClient::new_with_commitment(url, CommitmentConfig::processed());
+        let sender_keypair_file = tmp_file_path("keypair_file", &alice.pubkey());
+        write_keypair_file(&alice, &sender_keypair_file).unwrap();
+
+        let fees = client
+            .get_fee_for_message(&one_signer_message(&client))
+            .unwrap();
+        let nonce_rent = client
+            .get_minimum_balance_for_rent_exemption(solana_sdk::nonce::State::size())
+            .unwrap();
+
+        // Fee payer funded for the fees and a single nonce account
+        let fee_payer = Keypair::new();
+        let fee_payer_keypair_file = tmp_file_path("keypair_file", &fee_payer.pubkey());
+        write_keypair_file(&fee_payer, &fee_payer_keypair_file).unwrap();
+        let transaction =
+            transfer(&client, fees + nonce_rent, &alice, &fee_payer.pubkey()).unwrap();
+        client
+            .send_and_confirm_transaction_with_spinner(&transaction)
+            .unwrap();
+
+        let (allocations, mut args) = initialize_check_payer_balances_inputs(
+            sol_to_lamports(1.0),
+            &sender_keypair_file,
+            &fee_payer_keypair_file,
+            None,
+        );
+        args.nonce_pool_size = Some(1);
+        check_payer_balances(&[one_signer_message(&client)], &allocations, &client, &args).unwrap();
+
+        args.nonce_pool_size = Some(2);
+        let err_result =
+            check_payer_balances(&[one_signer_message(&client)], &allocations, &client, &args)
+                .unwrap_err();
+        if let Error::InsufficientFunds(sources, amount) = err_result {
+            assert_eq!(sources, vec![FundingSource::FeePayer].into());
+            assert_eq!(amount, lamports_to_sol(fees + 2 * nonce_rent).to_string());
+        } else {
+            panic!("check_payer_balances should have errored");
+        }
+    }
+
     #[test]
     fn test_check_payer_balances_distribute_tokens_separate_payers() {
         solana_logger::setup();
@@ -2336,6 +2769,10 @@ mod tests {
             stake_args: None,
             spl_token_args: None,
             transfer_amount: None,
+            nonce_pool_size: None,
+            prepare_sign_only: false,
+            sign_only: false,
+            compute_unit_price: None,
         };
         let allocation = TypedAllocation {
             recipient,
@@ -2458,6 +2895,10 @@ mod tests {
             stake_args: None,
             spl_token_args: None,
             transfer_amount: None,
+            nonce_pool_size: None,
+            prepare_sign_only: false,
+            sign_only: false,
+            compute_unit_price: None,
         };
         let allocation = TypedAllocation {
             recipient,
@@ -2574,6 +3015,10 @@ mod tests {
             stake_args: None,
             spl_token_args: None,
             transfer_amount: None,
+            nonce_pool_size: None,
+            prepare_sign_only: false,
+            sign_only: false,
+            compute_unit_price: None,
         };
 
         let exit = Arc::new(AtomicBool::new(false));
@@ -2718,4 +3163,343 @@ mod tests {
         assert_eq!(transaction_info.len(), 1);
         assert_eq!(confs, None);
     }
+
+    #[test]
+    fn test_process_allocations_with_nonces() {
+        let sender_keypair = Keypair::new();
+        let test_validator = simple_test_validator_no_fees(sender_keypair.pubkey());
+        let url = test_validator.rpc_url();
+        let client = RpcClient::new_with_commitment(url, CommitmentConfig::processed());
+
+        let fee_payer = Keypair::new();
+        let transaction = transfer(
+            &client,
+            sol_to_lamports(1.0),
+            &sender_keypair,
+            &fee_payer.pubkey(),
+        )
+        .unwrap();
+        client
+            .send_and_confirm_transaction_with_spinner(&transaction)
+            .unwrap();
+
+        let recipients = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let allocations_file = NamedTempFile::new().unwrap();
+        let input_csv = allocations_file.path().to_str().unwrap().to_string();
+        let mut wtr = csv::WriterBuilder::new().from_writer(allocations_file);
+        wtr.write_record(["recipient", "amount"]).unwrap();
+        for recipient in &recipients {
+            wtr.write_record([recipient.to_string(), "1000".to_string()])
+                .unwrap();
+        }
+        wtr.flush().unwrap();
+
+        let dir = tempdir().unwrap();
+        let transaction_db = dir
+            .path()
+            .join("transactions.db")
+            .to_str()
+            .unwrap()
+            .to_string();
+        let nonce_accounts = nonce::nonce_account_addresses(&fee_payer.pubkey(), 1);
+        let args = DistributeTokensArgs {
+            sender_keypair: Box::new(sender_keypair),
+            fee_payer: Box::new(fee_payer),
+            dry_run: false,
+            input_csv,
+            transaction_db: transaction_db.clone(),
+            output_path: None,
+            stake_args: None,
+            spl_token_args: None,
+            transfer_amount: None,
+            nonce_pool_size: Some(1),
+            prepare_sign_only: false,
+            sign_only: false,
+            // Simulated to set the compute unit limit once the nonce account exists
+            compute_unit_price: Some(ComputeUnitPrice::Fixed(1)),
+        };
+        let exit = Arc::new(AtomicBool::default());
+        let confirmations = process_allocations(&client, &args, exit.clone()).unwrap();
+        assert_eq!(confirmations, None);
+
+        let nonce_account = client.get_account(&nonce_accounts[0]).unwrap();
+        let nonce_data = solana_rpc_client_nonce_utils::data_from_account(&nonce_account).unwrap();
+        assert_eq!(nonce_data.authority, args.fee_payer.pubkey());
+
+        // Both transfers were signed against the single nonce account, one batch at a time
+        let transaction_infos =
+            db::read_transaction_infos(&db::open_db(&transaction_db, true).unwrap());
+        assert_eq!(transaction_infos.len(), 2);
+        for transaction_info in &transaction_infos {
+            assert!(transaction_info.finalized_date.is_some());
+            assert_eq!(
+                nonce::durable_nonce_account(&transaction_info.transaction),
+                Some(nonce_accounts[0])
+            );
+        }
+        for recipient in &recipients {
+            assert_eq!(
+                client.get_balance(recipient).unwrap(),
+                sol_to_lamports(1000.0)
+            );
+        }
+
+        // Running it again reuses the nonce account and doesn't send anything
+        process_allocations(&client, &args, exit).unwrap();
+        let transaction_infos =
+            db::read_transaction_infos(&db::open_db(&transaction_db, true).unwrap());
+        assert_eq!(transaction_infos.len(), 2);
+        for recipient in &recipients {
+            assert_eq!(
+                client.get_balance(recipient).unwrap(),
+                sol_to_lamports(1000.0)
+            );
+        }
+    }
+
+    #[test]
+    fn test_process_allocations_prepare_and_sign_only() {
+        let sender_keypair = Keypair::new();
+        let test_validator = simple_test_validator_no_fees(sender_keypair.pubkey());
+        let url = test_validator.rpc_url();
+        let client = RpcClient::new_with_commitment(url, CommitmentConfig::processed());
+
+        let fee_payer = Keypair::new();
+        let transaction = transfer(
+            &client,
+            sol_to_lamports(1.0),
+            &sender_keypair,
+            &fee_payer.pubkey(),
+        )
+        .unwrap();
+        client
+            .send_and_confirm_transaction_with_spinner(&transaction)
+            .unwrap();
+
+        let recipients = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let allocations_file = NamedTempFile::new().unwrap();
+        let input_csv = allocations_file.path().to_str().unwrap().to_string();
+        let mut wtr = csv::WriterBuilder::new().from_writer(allocations_file);
+        wtr.write_record(["recipient", "amount"]).unwrap();
+        for recipient in &recipients {
+            wtr.write_record([recipient.to_string(), "1000".to_string()])
+                .unwrap();
+        }
+        wtr.flush().unwrap();
+
+        let dir = tempdir().unwrap();
+        let transaction_db = dir
+            .path()
+            .join("transactions.db")
+            .to_str()
+            .unwrap()
+            .to_string();
+        let nonce_accounts = nonce::nonce_account_addresses(&fee_payer.pubkey(), 2);
+        let mut args = DistributeTokensArgs {
+            sender_keypair: Box::new(sender_keypair),
+            fee_payer: Box::new(fee_payer),
+            dry_run: false,
+            input_csv,
+            transaction_db: transaction_db.clone(),
+            output_path: None,
+            stake_args: None,
+            spl_token_args: None,
+            transfer_amount: None,
+            nonce_pool_size: None,
+            prepare_sign_only: false,
+            sign_only: true,
+            compute_unit_price: None,
+        };
+        let submit_args = SubmitTransactionsArgs {
+            transaction_db: transaction_db.clone(),
+            output_path: None,
+        };
+        let exit = Arc::new(AtomicBool::default());
+
+        // Nothing can be signed before it's prepared
+        assert!(matches!(
+            process_sign_only(&args, exit.clone()),
+            Err(Error::UnpreparedAllocations)
+        ));
+
+        // Preparing creates a nonce account per allocation and records the unsigned transactions
+        args.sign_only = false;
+        args.prepare_sign_only = true;
+        args.compute_unit_price = Some(ComputeUnitPrice::Fixed(1));
+        assert_eq!(
+            process_allocations(&client, &args, exit.clone()).unwrap(),
+            None
+        );
+        for nonce_account in &nonce_accounts {
+            assert!(client.get_account(nonce_account).is_ok());
+        }
+        let db = db::open_db(&transaction_db, true).unwrap();
+        assert!(db::read_transaction_infos(&db).is_empty());
+        assert_eq!(db::read_unsigned_transaction_infos(&db).len(), 2);
+
+        // Every allocation is signed without the cluster, each against its own nonce
+        args.prepare_sign_only = false;
+        args.sign_only = true;
+        args.compute_unit_price = None;
+        process_sign_only(&args, exit.clone()).unwrap();
+        let db = db::open_db(&transaction_db, true).unwrap();
+        assert!(db::read_unsigned_transaction_infos(&db).is_empty());
+        let transaction_infos = db::read_transaction_infos(&db);
+        assert_eq!(transaction_infos.len(), 2);
+        let mut signed_nonce_accounts = transaction_infos
+            .iter()
+            .map(|info| {
+                assert!(info.finalized_date.is_none());
+                nonce::durable_nonce_account(&info.transaction).unwrap()
+            })
+            .collect::<Vec<_>>();
+        signed_nonce_accounts.sort();
+        let mut expected_nonce_accounts = nonce_accounts.clone();
+        expected_nonce_accounts.sort();
+        assert_eq!(signed_nonce_accounts, expected_nonce_accounts);
+        for recipient in &recipients {
+            assert_eq!(client.get_balance(recipient).unwrap(), 0);
+        }
+
+        // Signing again has nothing left to do
+        process_sign_only(&args, exit.clone()).unwrap();
+        assert_eq!(
+            db::read_transaction_infos(&db::open_db(&transaction_db, true).unwrap()).len(),
+            2
+        );
+
+        assert_eq!(
+            process_submit_transactions(&client, &submit_args, exit).unwrap(),
+            None
+        );
+        let transaction_infos =
+            db::read_transaction_infos(&db::open_db(&transaction_db, true).unwrap());
+        assert_eq!(transaction_infos.len(), 2);
+        for transaction_info in &transaction_infos {
+            assert!(transaction_info.finalized_date.is_some());
+        }
+        for recipient in &recipients {
+            assert_eq!(
+                client.get_balance(recipient).unwrap(),
+                sol_to_lamports(1000.0)
+            );
+        }
+    }
+
+    #[test]
+    fn test_finalize_durable_nonce_transactions() {
+        let sender_keypair = Keypair::new();
+        let test_validator = simple_test_validator_no_fees(sender_keypair.pubkey());
+        let url = test_validator.rpc_url();
+        let client = RpcClient::new_with_commitment(url, CommitmentConfig::processed());
+
+        let dir = tempdir().unwrap();
+        let db_file = dir
+            .path()
+            .join("durable_nonce_transactions.db")
+            .to_str()
+            .unwrap()
+            .to_string();
+        let mut db = db::open_db(&db_file, false).unwrap();
+
+        let fee_payer = Keypair::new();
+        let transaction = transfer(
+            &client,
+            sol_to_lamports(1.0),
+            &sender_keypair,
+            &fee_payer.pubkey(),
+        )
+        .unwrap();
+        client
+            .send_and_confirm_transaction_with_spinner(&transaction)
+            .unwrap();
+
+        let sender_pubkey = sender_keypair.pubkey();
+        let args = DistributeTokensArgs {
+            sender_keypair: Box::new(sender_keypair),
+            fee_payer: Box::new(fee_payer),
+            dry_run: false,
+            input_csv: "".to_string(),
+            transaction_db: "".to_string(),
+            output_path: None,
+            stake_args: None,
+            spl_token_args: None,
+            transfer_amount: None,
+            nonce_pool_size: Some(1),
+            prepare_sign_only: false,
+            sign_only: false,
+            compute_unit_price: None,
+        };
+        nonce::create_nonce_accounts(&client, &args, 1).unwrap();
+        let nonce_account = nonce::nonce_account_addresses(&args.fee_payer.pubkey(), 1)[0];
+        let nonce_blockhash =
+            nonce::get_nonce_blockhashes(&client, &[nonce_account]).unwrap()[0].unwrap();
+
+        let signed_transaction = |recipient: &Pubkey| {
+            let message = Message::new_with_nonce(
+                vec![system_instruction::transfer(
+                    &sender_pubkey,
+                    recipient,
+                    sol_to_lamports(1.0),
+                )],
+                Some(&args.fee_payer.pubkey()),
+                &nonce_account,
+                &args.fee_payer.pubkey(),
+            );
+            Transaction::new(
+                &[&*args.fee_payer, &*args.sender_keypair],
+                message,
+                nonce_blockhash,
+            )
+        };
+
+        // A transaction recorded but never sent, as after a crash, is sent while finalizing
+        let recipient = Pubkey::new_unique();
+        let transaction = signed_transaction(&recipient);
+        db::set_transaction_info(
+            &mut db,
+            &recipient,
+            sol_to_lamports(1.0),
+            &transaction,
+            None,
+            false,
+            std::u64::MAX,
+            None,
+        )
+        .unwrap();
+        let exit = Arc::new(AtomicBool::default());
+        assert_eq!(
+            finalize_transactions(&client, &mut db, false, exit.clone()).unwrap(),
+            None
+        );
+        assert_eq!(
+            client.get_balance(&recipient).unwrap(),
+            sol_to_lamports(1.0)
+        );
+        let transaction_info = db
+            .get::<TransactionInfo>(&transaction.signatures[0].to_string())
+            .unwrap();
+        assert!(transaction_info.finalized_date.is_some());
+
+        // A transaction signed against a nonce that has since advanced can never land, so it's
+        // no longer waited for
+        let recipient = Pubkey::new_unique();
+        let transaction = signed_transaction(&recipient);
+        db::set_transaction_info(
+            &mut db,
+            &recipient,
+            sol_to_lamports(1.0),
+            &transaction,
+            None,
+            false,
+            std::u64::MAX,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            finalize_transactions(&client, &mut db, false, exit).unwrap(),
+            None
+        );
+        assert_eq!(client.get_balance(&recipient).unwrap(), 0);
+    }
 }
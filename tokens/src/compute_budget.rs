@@ -0,0 +1,262 @@
+use {
+    crate::{args::ComputeUnitPrice, commands::Error},
+    solana_rpc_client::rpc_client::RpcClient,
+    solana_rpc_client_api::{
+        client_error::Result as ClientResult, config::RpcSimulateTransactionConfig,
+    },
+    solana_sdk::{
+        compute_budget::{self, ComputeBudgetInstruction},
+        instruction::Instruction,
+        message::Message,
+        pubkey::Pubkey,
+        transaction::Transaction,
+    },
+    std::collections::{hash_map::Entry, HashMap},
+};
+
+/// The largest compute unit limit a transaction can request, which messages are built with until
+/// their limit is estimated
+const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+pub(crate) trait WithComputeUnitPrice {
+    fn with_compute_unit_price(self, compute_unit_price: Option<&ComputeUnitPrice>) -> Self;
+}
+
+impl WithComputeUnitPrice for Vec<Instruction> {
+    fn with_compute_unit_price(mut self, compute_unit_price: Option<&ComputeUnitPrice>) -> Self {
+        if let Some(compute_unit_price) = compute_unit_price {
+            // A dynamic price is only set when the message is signed, and can't exceed its
+            // maximum, so start from the maximum for the fees to be checked at it
+            let initial_compute_unit_price = match compute_unit_price {
+                ComputeUnitPrice::Fixed(compute_unit_price) => *compute_unit_price,
+                ComputeUnitPrice::Dynamic {
+                    max_compute_unit_price,
+                } => *max_compute_unit_price,
+            };
+            self.push(ComputeBudgetInstruction::set_compute_unit_limit(
+                MAX_COMPUTE_UNIT_LIMIT,
+            ));
+            self.push(ComputeBudgetInstruction::set_compute_unit_price(
+                initial_compute_unit_price,
+            ));
+        }
+        self
+    }
+}
+
+// Replace the data of the compute budget instruction of `message` of the same kind as
+// `instruction`
+fn update_compute_budget_instruction(message: &mut Message, instruction: Instruction) {
+    let Some(program_id_index) = message
+        .account_keys
+        .iter()
+        .position(compute_budget::check_id)
+    else {
+        return;
+    };
+    if let Some(compiled_instruction) = message.instructions.iter_mut().find(|compiled| {
+        usize::from(compiled.program_id_index) == program_id_index
+            && compiled.data.first() == instruction.data.first()
+    }) {
+        compiled_instruction.data = instruction.data;
+    }
+}
+
+fn simulate_compute_unit_limit(client: &RpcClient, message: &Message) -> Result<u32, Error> {
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        ..RpcSimulateTransactionConfig::default()
+    };
+    let result = client
+        .simulate_transaction_with_config(&Transaction::new_unsigned(message.clone()), config)?
+        .value;
+    if let Some(err) = result.err {
+        return Err(Error::SimulationError(err));
+    }
+    // Leave some headroom, as the accounts may change before the transaction lands
+    Ok(result
+        .units_consumed
+        .and_then(|units_consumed| u32::try_from(units_consumed + units_consumed / 10).ok())
+        .map_or(MAX_COMPUTE_UNIT_LIMIT, |compute_unit_limit| {
+            compute_unit_limit.min(MAX_COMPUTE_UNIT_LIMIT)
+        }))
+}
+
+/// Set the compute unit limit of the messages to what they consume in a simulation.
+///
+/// Messages calling the same programs consume about as many compute units, so only the first of
+/// them is simulated.
+pub(crate) fn update_compute_unit_limits(
+    client: &RpcClient,
+    messages: &mut [Message],
+) -> Result<(), Error> {
+    let mut compute_unit_limits = HashMap::new();
+    for message in messages {
+        let program_ids = message
+            .instructions
+            .iter()
+            .map(|instruction| *instruction.program_id(&message.account_keys))
+            .collect::<Vec<Pubkey>>();
+        let compute_unit_limit = match compute_unit_limits.entry(program_ids) {
+            Entry::Occupied(entry) => *entry.get(),
+            Entry::Vacant(entry) => *entry.insert(simulate_compute_unit_limit(client, message)?),
+        };
+        update_compute_budget_instruction(
+            message,
+            ComputeBudgetInstruction::set_compute_unit_limit(compute_unit_limit),
+        );
+    }
+    Ok(())
+}
+
+/// Set the compute unit price of a message about to be signed, when it's dynamic, to the median
+/// of the prioritization fees recently paid to write the accounts it writes, up to the maximum
+pub(crate) fn update_compute_unit_price(
+    client: &RpcClient,
+    compute_unit_price: Option<&ComputeUnitPrice>,
+    message: &mut Message,
+) -> ClientResult<()> {
+    let Some(ComputeUnitPrice::Dynamic {
+        max_compute_unit_price,
+    }) = compute_unit_price
+    else {
+        return Ok(());
+    };
+    let writable_accounts = message
+        .account_keys
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| message.is_writable(*i))
+        .map(|(_, pubkey)| *pubkey)
+        .collect::<Vec<_>>();
+    let mut prioritization_fees = client
+        .get_recent_prioritization_fees(&writable_accounts)?
+        .into_iter()
+        .map(|fee| fee.prioritization_fee)
+        .collect::<Vec<_>>();
+    prioritization_fees.sort_unstable();
+    let compute_unit_price = prioritization_fees
+        .get(prioritization_fees.len() / 2)
+        .copied()
+        .unwrap_or_default();
+    let compute_unit_price = compute_unit_price.min(*max_compute_unit_price);
+    update_compute_budget_instruction(
+        message,
+        ComputeBudgetInstruction::set_compute_unit_price(compute_unit_price),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        serde_json::json,
+        solana_rpc_client_api::{
+            request::RpcRequest,
+            response::{Response, RpcResponseContext, RpcSimulateTransactionResult},
+        },
+        solana_sdk::system_instruction,
+    };
+
+    fn transfer_message(compute_unit_price: Option<&ComputeUnitPrice>) -> Message {
+        let from = Pubkey::new_unique();
+        let instructions = vec![system_instruction::transfer(
+            &from,
+            &Pubkey::new_unique(),
+            42,
+        )]
+        .with_compute_unit_price(compute_unit_price);
+        Message::new(&instructions, Some(&from))
+    }
+
+    fn compute_budget_instructions(message: &Message) -> Vec<Vec<u8>> {
+        message
+            .instructions
+            .iter()
+            .filter(|instruction| {
+                compute_budget::check_id(instruction.program_id(&message.account_keys))
+            })
+            .map(|instruction| instruction.data.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_update_compute_unit_limits() {
+        let mut mocks = HashMap::new();
+        mocks.insert(
+            RpcRequest::SimulateTransaction,
+            json!(Response {
+                context: RpcResponseContext {
+                    slot: 1,
+                    api_version: None
+                },
+                value: RpcSimulateTransactionResult {
+                    err: None,
+                    logs: None,
+                    accounts: None,
+                    units_consumed: Some(1_000),
+                    return_data: None,
+                },
+            }),
+        );
+        let client = RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks);
+
+        // The mock is only returned once, so both messages use the limit simulated for the first
+        let compute_unit_price = ComputeUnitPrice::Fixed(42);
+        let mut messages = vec![
+            transfer_message(Some(&compute_unit_price)),
+            transfer_message(Some(&compute_unit_price)),
+        ];
+        update_compute_unit_limits(&client, &mut messages).unwrap();
+        for message in &messages {
+            assert_eq!(
+                compute_budget_instructions(message),
+                vec![
+                    ComputeBudgetInstruction::set_compute_unit_limit(1_100).data,
+                    ComputeBudgetInstruction::set_compute_unit_price(42).data,
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_update_compute_unit_price() {
+        let client = RpcClient::new_mock("succeeds".to_string());
+        let mut message = transfer_message(Some(&ComputeUnitPrice::Fixed(42)));
+        let fixed_message = message.clone();
+        update_compute_unit_price(&client, Some(&ComputeUnitPrice::Fixed(42)), &mut message)
+            .unwrap();
+        assert_eq!(message, fixed_message);
+
+        // The mock returns a recent prioritization fee of 10_000
+        let compute_unit_price = ComputeUnitPrice::Dynamic {
+            max_compute_unit_price: 20_000,
+        };
+        let mut message = transfer_message(Some(&compute_unit_price));
+        assert_eq!(
+            compute_budget_instructions(&message)[1],
+            ComputeBudgetInstruction::set_compute_unit_price(20_000).data
+        );
+        update_compute_unit_price(&client, Some(&compute_unit_price), &mut message).unwrap();
+        assert_eq!(
+            compute_budget_instructions(&message)[1],
+            ComputeBudgetInstruction::set_compute_unit_price(10_000).data
+        );
+
+        let compute_unit_price = ComputeUnitPrice::Dynamic {
+            max_compute_unit_price: 5_000,
+        };
+        let mut message = transfer_message(Some(&compute_unit_price));
+        assert_eq!(
+            compute_budget_instructions(&message)[1],
+            ComputeBudgetInstruction::set_compute_unit_price(5_000).data
+        );
+        update_compute_unit_price(&client, Some(&compute_unit_price), &mut message).unwrap();
+        assert_eq!(
+            compute_budget_instructions(&message)[1],
+            ComputeBudgetInstruction::set_compute_unit_price(5_000).data
+        );
+    }
+}
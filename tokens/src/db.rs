@@ -2,7 +2,10 @@ use {
     chrono::prelude::*,
     pickledb::{error::Error, PickleDb, PickleDbDumpPolicy},
     serde::{Deserialize, Serialize},
-    solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature, transaction::Transaction},
+    solana_sdk::{
+        clock::Slot, message::Message, pubkey::Pubkey, signature::Signature,
+        transaction::Transaction,
+    },
     solana_transaction_status::TransactionStatus,
     std::{cmp::Ordering, fs, io, path::Path},
 };
@@ -18,6 +21,22 @@ pub struct TransactionInfo {
     pub lockup_date: Option<DateTime<Utc>>,
 }
 
+/// A transaction prepared online by `--prepare-sign-only`, for `--sign-only` to sign offline
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UnsignedTransactionInfo {
+    pub recipient: Pubkey,
+    pub amount: u64,
+    /// The stake account created by the message, replaced by a new one when it's signed
+    pub new_stake_account_address: Option<Pubkey>,
+    pub lockup_date: Option<DateTime<Utc>>,
+    /// The message advances a nonce account, and its recent blockhash is the current nonce
+    pub message: Message,
+}
+
+/// The list of the transactions left to sign with `--sign-only`, kept apart from the transaction
+/// infos
+const UNSIGNED_TRANSACTIONS: &str = "unsigned-transactions";
+
 #[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
 struct SignedTransactionInfo {
     recipient: String,
@@ -103,6 +122,25 @@ pub fn read_transaction_infos(db: &PickleDb) -> Vec<TransactionInfo> {
         .collect()
 }
 
+pub fn read_unsigned_transaction_infos(db: &PickleDb) -> Vec<UnsignedTransactionInfo> {
+    if !db.lexists(UNSIGNED_TRANSACTIONS) {
+        return vec![];
+    }
+    db.liter(UNSIGNED_TRANSACTIONS)
+        .map(|item| item.get_item::<UnsignedTransactionInfo>().unwrap())
+        .collect()
+}
+
+/// Replace the transactions left to sign with `--sign-only`
+pub fn set_unsigned_transaction_infos(
+    db: &mut PickleDb,
+    unsigned_transaction_infos: &[UnsignedTransactionInfo],
+) -> Result<(), Error> {
+    db.lcreate(UNSIGNED_TRANSACTIONS)?
+        .lextend(unsigned_transaction_infos);
+    Ok(())
+}
+
 pub fn set_transaction_info(
     db: &mut PickleDb,
     recipient: &Pubkey,
@@ -265,6 +303,31 @@ mod tests {
         assert_eq!(signed_infos, vec![signed_info]);
     }
 
+    #[test]
+    fn test_unsigned_transaction_infos() {
+        let mut db =
+            PickleDb::new_yaml(NamedTempFile::new().unwrap(), PickleDbDumpPolicy::NeverDump);
+        assert!(read_unsigned_transaction_infos(&db).is_empty());
+
+        let unsigned_transaction_info = UnsignedTransactionInfo {
+            recipient: Pubkey::new_unique(),
+            amount: 42,
+            new_stake_account_address: None,
+            lockup_date: None,
+            message: Message::default(),
+        };
+        set_unsigned_transaction_infos(&mut db, &[unsigned_transaction_info.clone()]).unwrap();
+        assert_eq!(
+            read_unsigned_transaction_infos(&db),
+            vec![unsigned_transaction_info]
+        );
+        // They aren't read as transaction infos
+        assert!(read_transaction_infos(&db).is_empty());
+
+        set_unsigned_transaction_infos(&mut db, &[]).unwrap();
+        assert!(read_unsigned_transaction_infos(&db).is_empty());
+    }
+
     #[test]
     fn test_update_finalized_transaction_not_landed() {
         // Keep waiting for a transaction that hasn't landed yet.
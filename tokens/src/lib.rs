@@ -2,7 +2,9 @@
 pub mod arg_parser;
 pub mod args;
 pub mod commands;
+mod compute_budget;
 mod db;
+pub mod nonce;
 pub mod spl_token;
 pub mod stake;
 pub mod token_display;
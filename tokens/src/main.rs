@@ -42,9 +42,14 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     match command_args.command {
         Command::DistributeTokens(mut args) => {
-            spl_token::update_token_args(&client, &mut args.spl_token_args)?;
-            stake::update_stake_args(&client, &mut args.stake_args)?;
-            commands::process_allocations(&client, &args, exit)?;
+            if args.sign_only {
+                // Signs the transactions prepared online, without connecting to the cluster
+                commands::process_sign_only(&args, exit)?;
+            } else {
+                spl_token::update_token_args(&client, &mut args.spl_token_args)?;
+                stake::update_stake_args(&client, &mut args.stake_args)?;
+                commands::process_allocations(&client, &args, exit)?;
+            }
         }
         Command::Balances(mut args) => {
             spl_token::update_decimals(&client, &mut args.spl_token_args)?;
@@ -53,6 +58,9 @@ fn main() -> Result<(), Box<dyn Error>> {
         Command::TransactionLog(args) => {
             commands::process_transaction_log(&args)?;
         }
+        Command::SubmitTransactions(args) => {
+            commands::process_submit_transactions(&client, &args, exit)?;
+        }
     }
     Ok(())
 }
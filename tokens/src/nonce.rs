@@ -0,0 +1,165 @@
+use {
+    crate::{args::DistributeTokensArgs, commands::Error},
+    solana_rpc_client::rpc_client::RpcClient,
+    solana_rpc_client_api::request::MAX_MULTIPLE_ACCOUNTS,
+    solana_rpc_client_nonce_utils::{data_from_account, Error as NonceError},
+    solana_sdk::{
+        hash::Hash,
+        message::Message,
+        nonce::State,
+        pubkey::Pubkey,
+        signature::Signer,
+        system_instruction, system_program,
+        transaction::{self, Transaction},
+    },
+};
+
+fn nonce_account_seed(index: usize) -> String {
+    format!("tokens-nonce-{index}")
+}
+
+/// Addresses of the nonce account pool, derived from the fee payer so that a resumed
+/// distribution uses the same pool
+pub fn nonce_account_addresses(fee_payer: &Pubkey, pool_size: usize) -> Vec<Pubkey> {
+    (0..pool_size)
+        .map(|index| {
+            Pubkey::create_with_seed(fee_payer, &nonce_account_seed(index), &system_program::id())
+                .expect("nonce account seed is valid")
+        })
+        .collect()
+}
+
+/// Return the number of nonce accounts `args` signs `allocation_count` allocations with, if any.
+///
+/// Preparing to sign offline takes a nonce account per allocation, since every transaction is signed
+/// before any of them is sent.
+pub fn nonce_pool_size(args: &DistributeTokensArgs, allocation_count: usize) -> Option<usize> {
+    if args.prepare_sign_only {
+        Some(allocation_count)
+    } else {
+        args.nonce_pool_size
+    }
+}
+
+/// Create the nonce accounts of the pool that don't exist yet, with the fee payer as their
+/// authority
+pub fn create_nonce_accounts(
+    client: &RpcClient,
+    args: &DistributeTokensArgs,
+    pool_size: usize,
+) -> Result<(), Error> {
+    let fee_payer_pubkey = args.fee_payer.pubkey();
+    let addresses = nonce_account_addresses(&fee_payer_pubkey, pool_size);
+    let mut accounts = vec![];
+    for address_chunk in addresses.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        accounts.extend(client.get_multiple_accounts(address_chunk)?);
+    }
+
+    let rent = client.get_minimum_balance_for_rent_exemption(State::size())?;
+    for (index, (address, account)) in addresses.iter().zip(accounts).enumerate() {
+        if let Some(account) = account {
+            let data = data_from_account(&account)?;
+            if data.authority != fee_payer_pubkey {
+                return Err(NonceError::InvalidAuthority {
+                    provided: fee_payer_pubkey,
+                    expected: data.authority,
+                }
+                .into());
+            }
+            continue;
+        }
+
+        let instructions = system_instruction::create_nonce_account_with_seed(
+            &fee_payer_pubkey,
+            address,
+            &fee_payer_pubkey,
+            &nonce_account_seed(index),
+            &fee_payer_pubkey,
+            rent,
+        );
+        let message = Message::new(&instructions, Some(&fee_payer_pubkey));
+        let transaction =
+            Transaction::new(&[&*args.fee_payer], message, client.get_latest_blockhash()?);
+        client.send_and_confirm_transaction_with_spinner(&transaction)?;
+        println!("Created nonce account {address}");
+    }
+    Ok(())
+}
+
+/// Return the rent the fee payer pays to create the nonce accounts of the pool that don't exist yet
+pub fn missing_nonce_accounts_rent(
+    client: &RpcClient,
+    fee_payer: &Pubkey,
+    pool_size: usize,
+) -> Result<u64, Error> {
+    let addresses = nonce_account_addresses(fee_payer, pool_size);
+    let mut missing_accounts = 0;
+    for address_chunk in addresses.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        missing_accounts += client
+            .get_multiple_accounts(address_chunk)?
+            .iter()
+            .filter(|account| account.is_none())
+            .count() as u64;
+    }
+    if missing_accounts == 0 {
+        return Ok(0);
+    }
+    let rent = client.get_minimum_balance_for_rent_exemption(State::size())?;
+    Ok(rent * missing_accounts)
+}
+
+/// Return the current blockhash of each nonce account, or None if it isn't an initialized nonce
+/// account
+pub fn get_nonce_blockhashes(
+    client: &RpcClient,
+    nonce_accounts: &[Pubkey],
+) -> Result<Vec<Option<Hash>>, Error> {
+    let mut blockhashes = vec![];
+    for nonce_account_chunk in nonce_accounts.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        blockhashes.extend(
+            client
+                .get_multiple_accounts(nonce_account_chunk)?
+                .into_iter()
+                .map(|account| {
+                    let data = data_from_account(&account?).ok()?;
+                    Some(data.blockhash())
+                }),
+        );
+    }
+    Ok(blockhashes)
+}
+
+/// Return the nonce account the transaction was signed against, if it uses a durable nonce
+pub fn durable_nonce_account(transaction: &Transaction) -> Option<Pubkey> {
+    let instruction = transaction::uses_durable_nonce(transaction)?;
+    transaction::get_nonce_pubkey_from_instruction(instruction, transaction).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_durable_nonce_account() {
+        let fee_payer = Pubkey::new_unique();
+        let nonce_accounts = nonce_account_addresses(&fee_payer, 2);
+        assert_eq!(nonce_accounts.len(), 2);
+        assert_ne!(nonce_accounts[0], nonce_accounts[1]);
+        assert_eq!(nonce_accounts, nonce_account_addresses(&fee_payer, 2));
+
+        let instruction = system_instruction::transfer(&fee_payer, &Pubkey::new_unique(), 42);
+        let message = Message::new_with_nonce(
+            vec![instruction.clone()],
+            Some(&fee_payer),
+            &nonce_accounts[1],
+            &fee_payer,
+        );
+        let transaction = Transaction::new_unsigned(message);
+        assert_eq!(durable_nonce_account(&transaction), Some(nonce_accounts[1]));
+
+        let message =
+            Message::new_with_blockhash(&[instruction], Some(&fee_payer), &Hash::new_unique());
+        let transaction = Transaction::new_unsigned(message);
+        assert_eq!(durable_nonce_account(&transaction), None);
+    }
+}
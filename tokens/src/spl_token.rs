@@ -2,6 +2,7 @@ use {
     crate::{
         args::{DistributeTokensArgs, SplTokenArgs},
         commands::{get_fee_estimate_for_messages, Error, FundingSource, TypedAllocation},
+        nonce,
     },
     console::style,
     solana_account_decoder::parse_token::{real_number_string, real_number_string_trimmed},
@@ -85,7 +86,11 @@ pub(crate) fn check_spl_token_balances(
         .as_ref()
         .expect("spl_token_args must be some");
     let allocation_amount: u64 = allocations.iter().map(|x| x.amount).sum();
-    let fees = get_fee_estimate_for_messages(messages, client)?;
+    let mut fees = get_fee_estimate_for_messages(messages, client)?;
+    if let Some(nonce_pool_size) = nonce::nonce_pool_size(args, allocations.len()) {
+        fees +=
+            nonce::missing_nonce_accounts_rent(client, &args.fee_payer.pubkey(), nonce_pool_size)?;
+    }
 
     let token_account_rent_exempt_balance =
         client.get_minimum_balance_for_rent_exemption(SplTokenAccount::LEN)?;
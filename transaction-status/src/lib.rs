@@ -1004,6 +1004,19 @@ pub struct ConfirmedTransactionWithStatusMeta {
     pub block_time: Option<UnixTimestamp>,
 }
 
+/// The balances of an account before and after one of its transactions
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountBalanceChange {
+    pub signature: Signature,
+    pub slot: Slot,
+    pub block_time: Option<UnixTimestamp>,
+    pub err: Option<TransactionError>,
+    pub pre_balance: u64,
+    pub post_balance: u64,
+    pub pre_token_balance: Option<TransactionTokenBalance>,
+    pub post_token_balance: Option<TransactionTokenBalance>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct VersionedConfirmedTransactionWithStatusMeta {
     pub slot: Slot,
@@ -1031,6 +1044,36 @@ impl ConfirmedTransactionWithStatusMeta {
     pub fn get_transaction(&self) -> VersionedTransaction {
         self.tx_with_meta.get_transaction()
     }
+
+    /// Returns the balances of `address` before and after the transaction, or None if the
+    /// transaction is missing its metadata or does not load `address`
+    pub fn account_balance_change(&self, address: &Pubkey) -> Option<AccountBalanceChange> {
+        let TransactionWithStatusMeta::Complete(tx_with_meta) = &self.tx_with_meta else {
+            return None;
+        };
+        let index = tx_with_meta
+            .account_keys()
+            .iter()
+            .position(|key| key == address)?;
+        let meta = &tx_with_meta.meta;
+        let token_balance = |token_balances: &Option<Vec<TransactionTokenBalance>>| {
+            token_balances
+                .as_ref()?
+                .iter()
+                .find(|token_balance| usize::from(token_balance.account_index) == index)
+                .cloned()
+        };
+        Some(AccountBalanceChange {
+            signature: tx_with_meta.transaction.signatures[0],
+            slot: self.slot,
+            block_time: self.block_time,
+            err: meta.status.clone().err(),
+            pre_balance: *meta.pre_balances.get(index)?,
+            post_balance: *meta.post_balances.get(index)?,
+            pre_token_balance: token_balance(&meta.pre_token_balances),
+            post_token_balance: token_balance(&meta.post_token_balances),
+        })
+    }
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -1562,4 +1605,82 @@ mod test {
             expected_json_output_value
         );
     }
+
+    #[test]
+    fn test_account_balance_change() {
+        let payer = Pubkey::new_unique();
+        let token_account = Pubkey::new_unique();
+        let loaded_account = Pubkey::new_unique();
+        let signature = Signature::new_unique();
+        let token_balance = |amount: &str| TransactionTokenBalance {
+            account_index: 1,
+            mint: Pubkey::new_unique().to_string(),
+            ui_token_amount: UiTokenAmount {
+                ui_amount: None,
+                decimals: 0,
+                amount: amount.to_string(),
+                ui_amount_string: amount.to_string(),
+            },
+            owner: payer.to_string(),
+            program_id: Pubkey::new_unique().to_string(),
+        };
+        let pre_token_balance = token_balance("10");
+        let post_token_balance = token_balance("7");
+        let transaction = ConfirmedTransactionWithStatusMeta {
+            slot: 42,
+            tx_with_meta: TransactionWithStatusMeta::Complete(VersionedTransactionWithStatusMeta {
+                transaction: VersionedTransaction {
+                    signatures: vec![signature],
+                    message: VersionedMessage::V0(v0::Message {
+                        account_keys: vec![payer, token_account],
+                        ..v0::Message::default()
+                    }),
+                },
+                meta: TransactionStatusMeta {
+                    status: Err(TransactionError::AccountInUse),
+                    pre_balances: vec![100, 20, 30],
+                    post_balances: vec![95, 20, 31],
+                    pre_token_balances: Some(vec![pre_token_balance.clone()]),
+                    post_token_balances: Some(vec![post_token_balance.clone()]),
+                    loaded_addresses: LoadedAddresses {
+                        writable: vec![loaded_account],
+                        readonly: vec![],
+                    },
+                    ..TransactionStatusMeta::default()
+                },
+            }),
+            block_time: Some(1_234),
+        };
+
+        let change = |pre_balance, post_balance| AccountBalanceChange {
+            signature,
+            slot: 42,
+            block_time: Some(1_234),
+            err: Some(TransactionError::AccountInUse),
+            pre_balance,
+            post_balance,
+            pre_token_balance: None,
+            post_token_balance: None,
+        };
+        assert_eq!(
+            transaction.account_balance_change(&payer),
+            Some(change(100, 95))
+        );
+        assert_eq!(
+            transaction.account_balance_change(&token_account),
+            Some(AccountBalanceChange {
+                pre_token_balance: Some(pre_token_balance),
+                post_token_balance: Some(post_token_balance),
+                ..change(20, 20)
+            })
+        );
+        assert_eq!(
+            transaction.account_balance_change(&loaded_account),
+            Some(change(30, 31))
+        );
+        assert_eq!(
+            transaction.account_balance_change(&Pubkey::new_unique()),
+            None
+        );
+    }
 }
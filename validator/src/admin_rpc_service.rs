@@ -4,7 +4,8 @@ use {
     jsonrpc_core_client::{transports::ipc, RpcError},
     jsonrpc_derive::rpc,
     jsonrpc_ipc_server::{
-        tokio::sync::oneshot::channel as oneshot_channel, RequestContext, ServerBuilder,
+        tokio::sync::oneshot::{channel as oneshot_channel, Sender as OneShotSender},
+        RequestContext, ServerBuilder,
     },
     jsonrpc_server_utils::tokio,
     log::*,
@@ -15,11 +16,14 @@ use {
         consensus::{tower_storage::TowerStorage, Tower},
         validator::ValidatorStartProgress,
     },
-    solana_geyser_plugin_manager::GeyserPluginManagerRequest,
+    solana_geyser_plugin_manager::{
+        loaded_geyser_plugin::GeyserPluginInfo, GeyserPluginManagerRequest,
+    },
     solana_gossip::contact_info::{ContactInfo, Protocol, SOCKET_ADDR_UNSPECIFIED},
     solana_rpc::rpc::verify_pubkey,
     solana_rpc_client_api::{config::RpcAccountIndex, custom_error::RpcCustomError},
     solana_sdk::{
+        clock::{Slot, UnixTimestamp},
         exit::Exit,
         pubkey::Pubkey,
         signature::{read_keypair_file, Keypair, Signer},
@@ -47,10 +51,27 @@ pub struct AdminRpcRequestMetadata {
     pub staked_nodes_overrides: Arc<RwLock<HashMap<Pubkey, u64>>>,
     pub post_init: Arc<RwLock<Option<AdminRpcRequestMetadataPostInit>>>,
     pub rpc_to_plugin_manager_sender: Option<Sender<GeyserPluginManagerRequest>>,
+    pub clock_request_sender: Option<Sender<TestValidatorClockRequest>>,
 }
 
 impl Metadata for AdminRpcRequestMetadata {}
 
+/// Requests to move the clock of `solana-test-validator`, which are handled by the owner of the
+/// `TestValidator` as warping restarts it
+pub enum TestValidatorClockRequest {
+    WarpSlots {
+        slots: u64,
+        response_sender: OneShotSender<Result<Slot>>,
+    },
+    WarpToNextEpoch {
+        response_sender: OneShotSender<Result<Slot>>,
+    },
+    SetClockUnixTimestamp {
+        unix_timestamp: UnixTimestamp,
+        response_sender: OneShotSender<Result<()>>,
+    },
+}
+
 impl AdminRpcRequestMetadata {
     fn with_post_init<F, R>(&self, func: F) -> Result<R>
     where
@@ -163,6 +184,12 @@ pub trait AdminRpc {
     #[rpc(meta, name = "listPlugins")]
     fn list_plugins(&self, meta: Self::Metadata) -> BoxFuture<Result<Vec<String>>>;
 
+    #[rpc(meta, name = "listPluginsWithInfo")]
+    fn list_plugins_with_info(
+        &self,
+        meta: Self::Metadata,
+    ) -> BoxFuture<Result<Vec<GeyserPluginInfo>>>;
+
     #[rpc(meta, name = "rpcAddress")]
     fn rpc_addr(&self, meta: Self::Metadata) -> Result<Option<SocketAddr>>;
 
@@ -233,6 +260,19 @@ pub trait AdminRpc {
         meta: Self::Metadata,
         public_tpu_forwards_addr: SocketAddr,
     ) -> Result<()>;
+
+    #[rpc(meta, name = "warpSlots")]
+    fn warp_slots(&self, meta: Self::Metadata, slots: u64) -> BoxFuture<Result<Slot>>;
+
+    #[rpc(meta, name = "warpToNextEpoch")]
+    fn warp_to_next_epoch(&self, meta: Self::Metadata) -> BoxFuture<Result<Slot>>;
+
+    #[rpc(meta, name = "setClockUnixTimestamp")]
+    fn set_clock_unix_timestamp(
+        &self,
+        meta: Self::Metadata,
+        unix_timestamp: UnixTimestamp,
+    ) -> BoxFuture<Result<()>>;
 }
 
 pub struct AdminRpcImpl;
@@ -379,6 +419,34 @@ impl AdminRpc for AdminRpcImpl {
         })
     }
 
+    fn list_plugins_with_info(
+        &self,
+        meta: Self::Metadata,
+    ) -> BoxFuture<Result<Vec<GeyserPluginInfo>>> {
+        Box::pin(async move {
+            // Construct channel for plugin to respond to this particular rpc request instance
+            let (response_sender, response_receiver) = oneshot_channel();
+
+            // Send request to plugin manager
+            if let Some(ref rpc_to_manager_sender) = meta.rpc_to_plugin_manager_sender {
+                rpc_to_manager_sender
+                    .send(GeyserPluginManagerRequest::ListPluginsWithInfo { response_sender })
+                    .expect("GeyerPluginService should never drop request receiver");
+            } else {
+                return Err(jsonrpc_core::Error {
+                    code: ErrorCode::InvalidRequest,
+                    message: "No geyser plugin service".to_string(),
+                    data: None,
+                });
+            }
+
+            // Await response from plugin manager
+            response_receiver
+                .await
+                .expect("GeyerPluginService's oneshot sender shouldn't drop early")
+        })
+    }
+
     fn rpc_addr(&self, meta: Self::Metadata) -> Result<Option<SocketAddr>> {
         debug!("rpc_addr admin rpc request received");
         Ok(meta.rpc_addr)
@@ -643,9 +711,67 @@ impl AdminRpc for AdminRpcImpl {
             Ok(())
         })
     }
+
+    fn warp_slots(&self, meta: Self::Metadata, slots: u64) -> BoxFuture<Result<Slot>> {
+        debug!("warp_slots admin rpc request received: {slots}");
+        AdminRpcImpl::send_clock_request(meta, |response_sender| {
+            TestValidatorClockRequest::WarpSlots {
+                slots,
+                response_sender,
+            }
+        })
+    }
+
+    fn warp_to_next_epoch(&self, meta: Self::Metadata) -> BoxFuture<Result<Slot>> {
+        debug!("warp_to_next_epoch admin rpc request received");
+        AdminRpcImpl::send_clock_request(meta, |response_sender| {
+            TestValidatorClockRequest::WarpToNextEpoch { response_sender }
+        })
+    }
+
+    fn set_clock_unix_timestamp(
+        &self,
+        meta: Self::Metadata,
+        unix_timestamp: UnixTimestamp,
+    ) -> BoxFuture<Result<()>> {
+        debug!("set_clock_unix_timestamp admin rpc request received: {unix_timestamp}");
+        AdminRpcImpl::send_clock_request(meta, |response_sender| {
+            TestValidatorClockRequest::SetClockUnixTimestamp {
+                unix_timestamp,
+                response_sender,
+            }
+        })
+    }
 }
 
 impl AdminRpcImpl {
+    fn send_clock_request<T: Send + 'static>(
+        meta: AdminRpcRequestMetadata,
+        request: impl FnOnce(OneShotSender<Result<T>>) -> TestValidatorClockRequest,
+    ) -> BoxFuture<Result<T>> {
+        Box::pin(async move {
+            // Construct channel for the test validator to respond to this particular rpc request
+            // instance
+            let (response_sender, response_receiver) = oneshot_channel();
+
+            let Some(ref clock_request_sender) = meta.clock_request_sender else {
+                return Err(jsonrpc_core::Error {
+                    code: ErrorCode::InvalidRequest,
+                    message: "Clock control is only supported by solana-test-validator".to_string(),
+                    data: None,
+                });
+            };
+            clock_request_sender
+                .send(request(response_sender))
+                .map_err(|_| jsonrpc_core::error::Error::invalid_params("Validator is exiting"))?;
+
+            // Await response from the test validator
+            response_receiver
+                .await
+                .map_err(|_| jsonrpc_core::error::Error::invalid_params("Validator is exiting"))?
+        })
+    }
+
     fn add_authorized_voter_keypair(
         meta: AdminRpcRequestMetadata,
         authorized_voter: Keypair,
@@ -891,6 +1017,7 @@ mod tests {
                 }))),
                 staked_nodes_overrides: Arc::new(RwLock::new(HashMap::new())),
                 rpc_to_plugin_manager_sender: None,
+                clock_request_sender: None,
             };
             let mut io = MetaIoHandler::default();
             io.extend_with(AdminRpcImpl.to_delegate());
@@ -931,6 +1058,7 @@ mod tests {
                         AccountIndex::SplTokenMint,
                         AccountIndex::SplTokenOwner,
                     ]),
+                    memcmp_indexes: HashSet::new(),
                 }
             } else {
                 AccountSecondaryIndexes::default()
@@ -1,9 +1,9 @@
 use {
-    clap::{crate_name, value_t, value_t_or_exit, values_t_or_exit},
-    crossbeam_channel::unbounded,
+    clap::{crate_name, value_t, value_t_or_exit, values_t, values_t_or_exit},
+    crossbeam_channel::{select, unbounded, Receiver},
     itertools::Itertools,
     log::*,
-    solana_accounts_db::accounts_index::{AccountIndex, AccountSecondaryIndexes},
+    solana_accounts_db::accounts_index::{AccountIndex, AccountSecondaryIndexes, MemcmpIndex},
     solana_clap_utils::{
         input_parsers::{pubkey_of, pubkeys_of, value_of},
         input_validators::normalize_to_url_if_moniker,
@@ -29,8 +29,10 @@ use {
     solana_streamer::socket::SocketAddrSpace,
     solana_test_validator::*,
     solana_validator::{
-        admin_rpc_service, cli, dashboard::Dashboard, ledger_lockfile, lock_ledger,
-        println_name_value, redirect_stderr_to_file,
+        admin_rpc_service::{self, TestValidatorClockRequest},
+        cli,
+        dashboard::Dashboard,
+        ledger_lockfile, lock_ledger, println_name_value, redirect_stderr_to_file,
     },
     std::{
         collections::HashSet,
@@ -39,6 +41,7 @@ use {
         path::{Path, PathBuf},
         process::exit,
         sync::{Arc, RwLock},
+        thread,
         time::{Duration, SystemTime, UNIX_EPOCH},
     },
 };
@@ -77,9 +80,15 @@ fn main() {
         })
         .collect();
 
+    let memcmp_indexes = values_t!(matches, "account_index_memcmp", MemcmpIndex)
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+
     let account_indexes = AccountSecondaryIndexes {
         keys: None,
         indexes,
+        memcmp_indexes,
     };
 
     if !ledger_path.exists() {
@@ -156,6 +165,8 @@ fn main() {
     let rpc_port = value_t_or_exit!(matches, "rpc_port", u16);
     let enable_vote_subscription = matches.is_present("rpc_pubsub_enable_vote_subscription");
     let enable_block_subscription = matches.is_present("rpc_pubsub_enable_block_subscription");
+    let enable_transaction_subscription =
+        matches.is_present("rpc_pubsub_enable_transaction_subscription");
     let faucet_port = value_t_or_exit!(matches, "faucet_port", u16);
     let ticks_per_slot = value_t!(matches, "ticks_per_slot", u64).ok();
     let slots_per_epoch = value_t!(matches, "slots_per_epoch", Slot).ok();
@@ -282,6 +293,17 @@ fn main() {
             .map(|v| v.into_iter().collect())
             .unwrap_or_default();
 
+    let program_accounts_to_clone: HashSet<_> = pubkeys_of(&matches, "clone_program_accounts")
+        .map(|v| v.into_iter().collect())
+        .unwrap_or_default();
+
+    let clone_snapshot_archive = matches
+        .value_of("clone_snapshot_archive")
+        .map(PathBuf::from);
+    let clone_incremental_snapshot_archive = matches
+        .value_of("clone_incremental_snapshot_archive")
+        .map(PathBuf::from);
+
     let warp_slot = if matches.is_present("warp_slot") {
         Some(match matches.value_of("warp_slot") {
             Some(_) => value_t_or_exit!(matches, "warp_slot", Slot),
@@ -357,6 +379,7 @@ fn main() {
         for (name, long) in &[
             ("bpf_program", "--bpf-program"),
             ("clone_account", "--clone"),
+            ("clone_snapshot_archive", "--clone-snapshot-archive"),
             ("account", "--account"),
             ("mint_address", "--mint"),
             ("ticks_per_slot", "--ticks-per-slot"),
@@ -393,6 +416,7 @@ fn main() {
         } else {
             (None, None)
         };
+    let (clock_request_sender, clock_request_receiver) = unbounded();
     admin_rpc_service::run(
         &ledger_path,
         admin_rpc_service::AdminRpcRequestMetadata {
@@ -405,6 +429,7 @@ fn main() {
             post_init: admin_service_post_init,
             tower_storage: tower_storage.clone(),
             rpc_to_plugin_manager_sender,
+            clock_request_sender: Some(clock_request_sender),
         },
     );
     let dashboard = if output == Output::Dashboard {
@@ -430,6 +455,7 @@ fn main() {
                 String
             ),
             timeout: None,
+            local_storage_path: None,
         })
     } else {
         None
@@ -445,6 +471,7 @@ fn main() {
         .pubsub_config(PubSubConfig {
             enable_vote_subscription,
             enable_block_subscription,
+            enable_transaction_subscription,
             ..PubSubConfig::default()
         })
         .rpc_port(rpc_port)
@@ -470,41 +497,60 @@ fn main() {
         ..JsonRpcConfig::default_for_test()
     });
 
-    if !accounts_to_clone.is_empty() {
-        if let Err(e) = genesis.clone_accounts(
-            accounts_to_clone,
-            cluster_rpc_client
-                .as_ref()
-                .expect("bug: --url argument missing?"),
-            false,
-        ) {
-            println!("Error: clone_accounts failed: {e}");
-            exit(1);
+    if let Some(clone_snapshot_archive) = clone_snapshot_archive {
+        // Unpacking the snapshot archives is expensive, so skip it if the accounts are ignored
+        if !TestValidatorGenesis::ledger_exists(&ledger_path) {
+            if let Err(e) = genesis.clone_accounts_from_snapshot_archives(
+                &clone_snapshot_archive,
+                clone_incremental_snapshot_archive.as_deref(),
+                &SnapshotAccountsSelection {
+                    addresses: accounts_to_clone,
+                    maybe_addresses: accounts_to_maybe_clone,
+                    owners: program_accounts_to_clone,
+                    upgradeable_programs: upgradeable_programs_to_clone,
+                },
+            ) {
+                println!("Error: clone_accounts_from_snapshot_archives failed: {e}");
+                exit(1);
+            }
+        }
+    } else {
+        if !accounts_to_clone.is_empty() {
+            if let Err(e) = genesis.clone_accounts(
+                accounts_to_clone,
+                cluster_rpc_client
+                    .as_ref()
+                    .expect("bug: --url argument missing?"),
+                false,
+            ) {
+                println!("Error: clone_accounts failed: {e}");
+                exit(1);
+            }
         }
-    }
 
-    if !accounts_to_maybe_clone.is_empty() {
-        if let Err(e) = genesis.clone_accounts(
-            accounts_to_maybe_clone,
-            cluster_rpc_client
-                .as_ref()
-                .expect("bug: --url argument missing?"),
-            true,
-        ) {
-            println!("Error: clone_accounts failed: {e}");
-            exit(1);
+        if !accounts_to_maybe_clone.is_empty() {
+            if let Err(e) = genesis.clone_accounts(
+                accounts_to_maybe_clone,
+                cluster_rpc_client
+                    .as_ref()
+                    .expect("bug: --url argument missing?"),
+                true,
+            ) {
+                println!("Error: clone_accounts failed: {e}");
+                exit(1);
+            }
         }
-    }
 
-    if !upgradeable_programs_to_clone.is_empty() {
-        if let Err(e) = genesis.clone_upgradeable_programs(
-            upgradeable_programs_to_clone,
-            cluster_rpc_client
-                .as_ref()
-                .expect("bug: --url argument missing?"),
-        ) {
-            println!("Error: clone_upgradeable_programs failed: {e}");
-            exit(1);
+        if !upgradeable_programs_to_clone.is_empty() {
+            if let Err(e) = genesis.clone_upgradeable_programs(
+                upgradeable_programs_to_clone,
+                cluster_rpc_client
+                    .as_ref()
+                    .expect("bug: --url argument missing?"),
+            ) {
+                println!("Error: clone_upgradeable_programs failed: {e}");
+                exit(1);
+            }
         }
     }
 
@@ -555,14 +601,32 @@ fn main() {
         genesis.compute_unit_limit(compute_unit_limit);
     }
 
+    let (exit_sender, exit_receiver) = unbounded();
+    genesis
+        .validator_exit
+        .write()
+        .unwrap()
+        .register_exit(Box::new(move || {
+            let _ = exit_sender.send(());
+        }));
+
     match genesis.start_with_mint_address_and_geyser_plugin_rpc(
         mint_address,
         socket_addr_space,
         rpc_to_plugin_manager_receiver,
     ) {
-        Ok(test_validator) => {
-            if let Some(dashboard) = dashboard {
-                dashboard.run(Duration::from_millis(250));
+        Ok(mut test_validator) => {
+            let dashboard_thread = dashboard.map(|dashboard| {
+                thread::Builder::new()
+                    .name("solDashboard".into())
+                    .spawn(move || dashboard.run(Duration::from_millis(250)))
+                    .unwrap()
+            });
+            // Warping restarts the validator, so the clock requests are processed by the owner
+            // of `test_validator` until it exits
+            process_clock_requests(&mut test_validator, &clock_request_receiver, &exit_receiver);
+            if let Some(dashboard_thread) = dashboard_thread {
+                dashboard_thread.join().unwrap();
             }
             test_validator.join();
         }
@@ -574,6 +638,47 @@ fn main() {
     }
 }
 
+fn process_clock_requests(
+    test_validator: &mut TestValidator,
+    clock_request_receiver: &Receiver<TestValidatorClockRequest>,
+    exit_receiver: &Receiver<()>,
+) {
+    let map_err = |err: Box<dyn std::error::Error>| {
+        jsonrpc_core::error::Error::invalid_params(err.to_string())
+    };
+    loop {
+        let request = select! {
+            recv(exit_receiver) -> _ => break,
+            recv(clock_request_receiver) -> request => match request {
+                Ok(request) => request,
+                Err(_) => break,
+            },
+        };
+        // The requester may have gone away, so failures to respond are ignored
+        match request {
+            TestValidatorClockRequest::WarpSlots {
+                slots,
+                response_sender,
+            } => {
+                let _ = response_sender.send(test_validator.warp_slots(slots).map_err(map_err));
+            }
+            TestValidatorClockRequest::WarpToNextEpoch { response_sender } => {
+                let _ = response_sender.send(test_validator.warp_to_next_epoch().map_err(map_err));
+            }
+            TestValidatorClockRequest::SetClockUnixTimestamp {
+                unix_timestamp,
+                response_sender,
+            } => {
+                let _ = response_sender.send(
+                    test_validator
+                        .set_clock_unix_timestamp(unix_timestamp)
+                        .map_err(map_err),
+                );
+            }
+        }
+    }
+}
+
 fn remove_directory_contents(ledger_path: &Path) -> Result<(), io::Error> {
     for entry in fs::read_dir(ledger_path)? {
         let entry = entry?;
@@ -7,6 +7,7 @@ use {
         accounts_db::{
             DEFAULT_ACCOUNTS_SHRINK_OPTIMIZE_TOTAL_SPACE, DEFAULT_ACCOUNTS_SHRINK_RATIO,
         },
+        accounts_index::MemcmpIndex,
         hardened_unpack::MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
     },
     solana_clap_utils::{
@@ -186,6 +187,17 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .validator(port_validator)
                 .help("Enable JSON RPC on this port, and the next port for the RPC websocket"),
         )
+        .arg(
+            Arg::with_name("rpc_grpc_port")
+                .long("rpc-grpc-port")
+                .value_name("PORT")
+                .takes_value(true)
+                .validator(port_validator)
+                .requires("rpc_port")
+                .requires("full_rpc_api")
+                .help("Enable the gRPC endpoint for the account, block and transaction \
+                       RPC methods, and the account and slot subscriptions, on this port"),
+        )
         .arg(
             Arg::with_name("full_rpc_api")
                 .long("full-rpc-api")
@@ -878,6 +890,14 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .default_value(&default_args.rpc_bigtable_app_profile_id)
                 .help("Bigtable application profile id to use in requests")
         )
+        .arg(
+            Arg::with_name("rpc_bigtable_local_storage")
+                .long("rpc-bigtable-local-storage")
+                .takes_value(true)
+                .value_name("DIR")
+                .help("Store the BigTable ledger data in this local RocksDB directory \
+                       instead of a BigTable instance")
+        )
         .arg(
             Arg::with_name("rpc_pubsub_worker_threads")
                 .long("rpc-pubsub-worker-threads")
@@ -900,6 +920,13 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .takes_value(false)
                 .help("Enable the unstable RPC PubSub `voteSubscribe` subscription"),
         )
+        .arg(
+            Arg::with_name("rpc_pubsub_enable_transaction_subscription")
+                .long("rpc-pubsub-enable-transaction-subscription")
+                .requires("enable_rpc_transaction_history")
+                .takes_value(false)
+                .help("Enable the unstable RPC PubSub `transactionSubscribe` subscription"),
+        )
         .arg(
             Arg::with_name("rpc_pubsub_max_connections")
                 .long("rpc-pubsub-max-connections")
@@ -971,6 +998,37 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .help("The maximum total size of notifications that RPC PubSub will store \
                        across all connections."),
         )
+        .arg(
+            Arg::with_name("rpc_pubsub_replay_capacity_items")
+                .long("rpc-pubsub-replay-capacity-items")
+                .takes_value(true)
+                .value_name("NUMBER")
+                .validator(is_parsable::<usize>)
+                .default_value(&default_args.rpc_pubsub_replay_capacity_items)
+                .help("The maximum number of account, program, logs and slot notifications \
+                       that RPC PubSub will keep for resuming subscriptions with `fromSlot`. \
+                       0 will disable resuming subscriptions"),
+        )
+        .arg(
+            Arg::with_name("rpc_pubsub_replay_capacity_bytes")
+                .long("rpc-pubsub-replay-capacity-bytes")
+                .takes_value(true)
+                .value_name("BYTES")
+                .validator(is_parsable::<usize>)
+                .default_value(&default_args.rpc_pubsub_replay_capacity_bytes)
+                .help("The maximum total size of notifications that RPC PubSub will keep \
+                       for resuming subscriptions"),
+        )
+        .arg(
+            Arg::with_name("rpc_pubsub_replay_linger_secs")
+                .long("rpc-pubsub-replay-linger-secs")
+                .takes_value(true)
+                .value_name("SECONDS")
+                .validator(is_parsable::<u64>)
+                .default_value(&default_args.rpc_pubsub_replay_linger_secs)
+                .help("How long RPC PubSub keeps the subscriptions of a closed connection, \
+                       so that they can be resumed"),
+        )
         .arg(
             Arg::with_name("rpc_pubsub_notification_threads")
                 .long("rpc-pubsub-notification-threads")
@@ -1169,6 +1227,17 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .value_name("INDEX")
                 .help("Enable an accounts index, indexed by the selected account field"),
         )
+        .arg(
+            Arg::with_name("account_index_memcmp")
+                .long("account-index-memcmp")
+                .takes_value(true)
+                .multiple(true)
+                .validator(|value| value.parse::<MemcmpIndex>().map(|_| ()))
+                .value_name("PROGRAM_ID:OFFSET:LENGTH")
+                .help("Enable an accounts index of the accounts owned by PROGRAM_ID, indexed by \
+                       the LENGTH bytes at OFFSET in their data, up to 32 bytes. Used by the \
+                       getProgramAccounts memcmp filters covering these bytes"),
+        )
         .arg(
             Arg::with_name("account_index_exclude_key")
                 .long(EXCLUDE_KEY)
@@ -1213,6 +1282,13 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .help("Create ancient storages in one shot instead of appending.")
                 .hidden(hidden_unless_forced()),
             )
+        .arg(
+            Arg::with_name("accounts_db_ancient_cold_storage")
+                .long("accounts-db-ancient-cold-storage")
+                .help("Rewrite ancient slots into compressed cold storages instead of appending them to ancient append vecs.")
+                .conflicts_with("accounts_db_create_ancient_storage_packed")
+                .hidden(hidden_unless_forced()),
+            )
         .arg(
             Arg::with_name("accounts_db_ancient_append_vecs")
                 .long("accounts-db-ancient-append-vecs")
@@ -1916,6 +1992,9 @@ pub struct DefaultArgs {
     pub rpc_pubsub_max_active_subscriptions: String,
     pub rpc_pubsub_queue_capacity_items: String,
     pub rpc_pubsub_queue_capacity_bytes: String,
+    pub rpc_pubsub_replay_capacity_items: String,
+    pub rpc_pubsub_replay_capacity_bytes: String,
+    pub rpc_pubsub_replay_linger_secs: String,
     pub rpc_send_transaction_retry_ms: String,
     pub rpc_send_transaction_batch_ms: String,
     pub rpc_send_transaction_leader_forward_count: String,
@@ -1989,6 +2068,16 @@ impl DefaultArgs {
             rpc_pubsub_queue_capacity_bytes: PubSubConfig::default()
                 .queue_capacity_bytes
                 .to_string(),
+            rpc_pubsub_replay_capacity_items: PubSubConfig::default()
+                .replay_capacity_items
+                .to_string(),
+            rpc_pubsub_replay_capacity_bytes: PubSubConfig::default()
+                .replay_capacity_bytes
+                .to_string(),
+            rpc_pubsub_replay_linger_secs: PubSubConfig::default()
+                .replay_linger
+                .as_secs()
+                .to_string(),
             send_transaction_service_config: send_transaction_service::Config::default(),
             rpc_send_transaction_retry_ms: default_send_transaction_service_config
                 .retry_rate_ms
@@ -2168,6 +2257,17 @@ pub fn test_app<'a>(version: &'a str, default_args: &'a DefaultTestArgs) -> App<
                 .value_name("INDEX")
                 .help("Enable an accounts index, indexed by the selected account field"),
         )
+        .arg(
+            Arg::with_name("account_index_memcmp")
+                .long("account-index-memcmp")
+                .takes_value(true)
+                .multiple(true)
+                .validator(|value| value.parse::<MemcmpIndex>().map(|_| ()))
+                .value_name("PROGRAM_ID:OFFSET:LENGTH")
+                .help("Enable an accounts index of the accounts owned by PROGRAM_ID, indexed by \
+                       the LENGTH bytes at OFFSET in their data, up to 32 bytes. Used by the \
+                       getProgramAccounts memcmp filters covering these bytes"),
+        )
         .arg(
             Arg::with_name("faucet_port")
                 .long("faucet-port")
@@ -2224,6 +2324,12 @@ pub fn test_app<'a>(version: &'a str, default_args: &'a DefaultTestArgs) -> App<
                 .takes_value(false)
                 .help("Enable the unstable RPC PubSub `blockSubscribe` subscription"),
         )
+        .arg(
+            Arg::with_name("rpc_pubsub_enable_transaction_subscription")
+                .long("rpc-pubsub-enable-transaction-subscription")
+                .takes_value(false)
+                .help("Enable the unstable RPC PubSub `transactionSubscribe` subscription"),
+        )
         .arg(
             Arg::with_name("bpf_program")
                 .long("bpf-program")
@@ -2377,10 +2483,10 @@ pub fn test_app<'a>(version: &'a str, default_args: &'a DefaultTestArgs) -> App<
                 .takes_value(true)
                 .validator(is_pubkey_or_keypair)
                 .multiple(true)
-                .requires("json_rpc_url")
+                .requires("clone_source")
                 .help(
-                    "Copy an account from the cluster referenced by the --url argument the \
-                     genesis configuration. \
+                    "Copy an account from the cluster referenced by the --url argument, or \
+                     from --clone-snapshot-archive, the genesis configuration. \
                      If the ledger already exists then this parameter is silently ignored",
                 ),
         )
@@ -2391,10 +2497,10 @@ pub fn test_app<'a>(version: &'a str, default_args: &'a DefaultTestArgs) -> App<
                 .takes_value(true)
                 .validator(is_pubkey_or_keypair)
                 .multiple(true)
-                .requires("json_rpc_url")
+                .requires("clone_source")
                 .help(
-                    "Copy an account from the cluster referenced by the --url argument, \
-                     skipping it if it doesn't exist. \
+                    "Copy an account from the cluster referenced by the --url argument, or \
+                     from --clone-snapshot-archive, skipping it if it doesn't exist. \
                      If the ledger already exists then this parameter is silently ignored",
                 ),
         )
@@ -2405,13 +2511,55 @@ pub fn test_app<'a>(version: &'a str, default_args: &'a DefaultTestArgs) -> App<
                 .takes_value(true)
                 .validator(is_pubkey_or_keypair)
                 .multiple(true)
-                .requires("json_rpc_url")
+                .requires("clone_source")
                 .help(
                     "Copy an upgradeable program and its executable data from the cluster \
-                     referenced by the --url argument the genesis configuration. \
+                     referenced by the --url argument, or from --clone-snapshot-archive, the \
+                     genesis configuration. \
                      If the ledger already exists then this parameter is silently ignored",
                 ),
         )
+        .arg(
+            Arg::with_name("clone_program_accounts")
+                .long("clone-program-accounts")
+                .value_name("PROGRAM_ID")
+                .takes_value(true)
+                .validator(is_pubkey_or_keypair)
+                .multiple(true)
+                .requires("clone_snapshot_archive")
+                .help(
+                    "Copy all the accounts owned by a program from --clone-snapshot-archive \
+                     to the genesis configuration. \
+                     If the ledger already exists then this parameter is silently ignored",
+                ),
+        )
+        .arg(
+            Arg::with_name("clone_snapshot_archive")
+                .long("clone-snapshot-archive")
+                .value_name("FULL_SNAPSHOT_ARCHIVE")
+                .takes_value(true)
+                .help(
+                    "Copy the --clone, --maybe-clone, --clone-upgradeable-program and \
+                     --clone-program-accounts accounts from this local full snapshot archive \
+                     instead of the cluster referenced by the --url argument",
+                ),
+        )
+        .arg(
+            Arg::with_name("clone_incremental_snapshot_archive")
+                .long("clone-incremental-snapshot-archive")
+                .value_name("INCREMENTAL_SNAPSHOT_ARCHIVE")
+                .takes_value(true)
+                .requires("clone_snapshot_archive")
+                .help(
+                    "Apply this local incremental snapshot archive on top of \
+                     --clone-snapshot-archive",
+                ),
+        )
+        .group(
+            ArgGroup::with_name("clone_source")
+                .args(&["json_rpc_url", "clone_snapshot_archive"])
+                .multiple(true),
+        )
         .arg(
             Arg::with_name("warp_slot")
                 .required(false)
@@ -11,7 +11,7 @@ use {
         accounts_db::{AccountShrinkThreshold, AccountsDb, AccountsDbConfig, CreateAncientStorage},
         accounts_index::{
             AccountIndex, AccountSecondaryIndexes, AccountSecondaryIndexesIncludeExclude,
-            AccountsIndexConfig, IndexLimitMb,
+            AccountsIndexConfig, IndexLimitMb, MemcmpIndex,
         },
         partitioned_rewards::TestPartitionedEpochRewards,
     },
@@ -553,7 +553,7 @@ pub fn main() {
                 ("list", _) => {
                     let admin_client = admin_rpc_service::connect(&ledger_path);
                     let plugins = admin_rpc_service::runtime()
-                        .block_on(async move { admin_client.await?.list_plugins().await })
+                        .block_on(async move { admin_client.await?.list_plugins_with_info().await })
                         .unwrap_or_else(|err| {
                             println!("Failed to list plugins: {err}");
                             exit(1);
@@ -561,7 +561,28 @@ pub fn main() {
                     if !plugins.is_empty() {
                         println!("Currently the following plugins are loaded:");
                         for (plugin, i) in plugins.into_iter().zip(1..) {
-                            println!("  {i}) {plugin}");
+                            println!("  {i}) {}", plugin.name);
+                            println!(
+                                "     notifications: {}, average latency: {}us, \
+                                 max latency: {}us, dropped: {}",
+                                plugin.notifications,
+                                plugin.average_latency_us,
+                                plugin.max_latency_us,
+                                plugin.dropped_notifications,
+                            );
+                            if let Some(queue) = plugin.notification_queue {
+                                println!(
+                                    "     queue: {}/{}, overflow policy: {:?}{}",
+                                    queue.depth,
+                                    queue.capacity,
+                                    queue.overflow_policy,
+                                    if queue.disconnected {
+                                        ", disconnected"
+                                    } else {
+                                        ""
+                                    },
+                                );
+                            }
                         }
                     } else {
                         println!("There are currently no plugins loaded");
@@ -1196,6 +1217,7 @@ pub fn main() {
             .is_present("accounts_db_create_ancient_storage_packed")
             .then_some(CreateAncientStorage::Pack)
             .unwrap_or_default(),
+        ancient_cold_storage: matches.is_present("accounts_db_ancient_cold_storage"),
         test_partitioned_epoch_rewards,
         test_skip_rewrites_but_include_in_bank_hash: matches
             .is_present("accounts_db_test_skip_rewrites"),
@@ -1230,6 +1252,7 @@ pub fn main() {
             timeout: value_t!(matches, "rpc_bigtable_timeout", u64)
                 .ok()
                 .map(Duration::from_secs),
+            local_storage_path: value_t!(matches, "rpc_bigtable_local_storage", PathBuf).ok(),
         })
     } else {
         None
@@ -1313,9 +1336,14 @@ pub fn main() {
                 // https://github.com/solana-labs/solana/issues/12250
             )
         }),
+        rpc_grpc_addr: value_t!(matches, "rpc_grpc_port", u16)
+            .ok()
+            .map(|rpc_grpc_port| SocketAddr::new(rpc_bind_address, rpc_grpc_port)),
         pubsub_config: PubSubConfig {
             enable_block_subscription: matches.is_present("rpc_pubsub_enable_block_subscription"),
             enable_vote_subscription: matches.is_present("rpc_pubsub_enable_vote_subscription"),
+            enable_transaction_subscription: matches
+                .is_present("rpc_pubsub_enable_transaction_subscription"),
             max_active_subscriptions: value_t_or_exit!(
                 matches,
                 "rpc_pubsub_max_active_subscriptions",
@@ -1337,6 +1365,21 @@ pub fn main() {
             } else {
                 Some(0)
             },
+            replay_capacity_items: value_t_or_exit!(
+                matches,
+                "rpc_pubsub_replay_capacity_items",
+                usize
+            ),
+            replay_capacity_bytes: value_t_or_exit!(
+                matches,
+                "rpc_pubsub_replay_capacity_bytes",
+                usize
+            ),
+            replay_linger: Duration::from_secs(value_t_or_exit!(
+                matches,
+                "rpc_pubsub_replay_linger_secs",
+                u64
+            )),
         },
         voting_disabled: matches.is_present("no_voting") || restricted_repair_only_mode,
         wait_for_supermajority: value_t!(matches, "wait_for_supermajority", Slot).ok(),
@@ -1702,6 +1745,7 @@ pub fn main() {
             tower_storage: validator_config.tower_storage.clone(),
             staked_nodes_overrides,
             rpc_to_plugin_manager_sender,
+            clock_request_sender: None,
         },
     );
 
@@ -1937,8 +1981,14 @@ fn process_account_indexes(matches: &ArgMatches) -> AccountSecondaryIndexes {
         None
     };
 
+    let memcmp_indexes = values_t!(matches, "account_index_memcmp", MemcmpIndex)
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+
     AccountSecondaryIndexes {
         keys,
         indexes: account_indexes,
+        memcmp_indexes,
     }
 }
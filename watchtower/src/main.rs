@@ -2,6 +2,7 @@
 #![allow(clippy::arithmetic_side_effects)]
 
 use {
+    crate::rules::{RuleMonitor, RulesConfig},
     clap::{crate_description, crate_name, value_t, value_t_or_exit, App, Arg},
     log::*,
     solana_clap_utils::{
@@ -22,11 +23,15 @@ use {
     std::{
         collections::HashMap,
         error,
+        path::Path,
+        process::exit,
         thread::sleep,
         time::{Duration, Instant},
     },
 };
 
+mod rules;
+
 struct Config {
     address_labels: HashMap<String, String>,
     ignore_http_bad_gateway: bool,
@@ -39,6 +44,8 @@ struct Config {
     unhealthy_threshold: usize,
     validator_identity_pubkeys: Vec<Pubkey>,
     name_suffix: String,
+    notifier_config: Option<String>,
+    rules_config: RulesConfig,
 }
 
 fn get_config() -> Config {
@@ -65,7 +72,12 @@ fn get_config() -> Config {
         and a sending number owned by that account,
         define environment variable before running `solana-watchtower`:
 
-        export TWILIO_CONFIG='ACCOUNT=<account>,TOKEN=<securityToken>,TO=<receivingNumber>,FROM=<sendingNumber>'")
+        export TWILIO_CONFIG='ACCOUNT=<account>,TOKEN=<securityToken>,TO=<receivingNumber>,FROM=<sendingNumber>'
+
+        Alternatively, all the notification channels, including generic JSON webhooks and email via SMTP,
+        can be set from a config file with `--notifier-config` or:
+
+        export NOTIFIER_CONFIG=<path>")
         .arg({
             let arg = Arg::with_name("config_file")
                 .short("C")
@@ -168,6 +180,22 @@ fn get_config() -> Config {
                 .default_value("")
                 .help("Add this string into all notification messages after \"solana-watchtower\"")
         )
+        .arg(
+            Arg::with_name("notifier_config")
+                .long("notifier-config")
+                .value_name("PATH")
+                .takes_value(true)
+                .help("Send notifications to the channels of this notifier config file \
+                    instead of the ones set by environment variables")
+        )
+        .arg(
+            Arg::with_name("rules_config")
+                .long("rules-config")
+                .value_name("PATH")
+                .takes_value(true)
+                .help("Alert on the skip rate, vote credit deficit, root distance and version \
+                    drift of the validator identities, with the thresholds of this rules file")
+        )
         .get_matches();
 
     let config = if let Some(config_file) = matches.value_of("config_file") {
@@ -198,6 +226,16 @@ fn get_config() -> Config {
     let ignore_http_bad_gateway = matches.is_present("ignore_http_bad_gateway");
 
     let name_suffix = value_t_or_exit!(matches, "name_suffix", String);
+    let notifier_config = matches.value_of("notifier_config").map(str::to_string);
+    let rules_config = matches
+        .value_of("rules_config")
+        .map(|rules_config| {
+            RulesConfig::load(Path::new(rules_config)).unwrap_or_else(|err| {
+                eprintln!("{err}");
+                exit(1);
+            })
+        })
+        .unwrap_or_default();
 
     let config = Config {
         address_labels: config.address_labels,
@@ -211,6 +249,8 @@ fn get_config() -> Config {
         unhealthy_threshold,
         validator_identity_pubkeys,
         name_suffix,
+        notifier_config,
+        rules_config,
     };
 
     info!("RPC URL: {}", config.json_rpc_url);
@@ -252,13 +292,17 @@ fn main() -> Result<(), Box<dyn error::Error>> {
     let config = get_config();
 
     let rpc_client = RpcClient::new_with_timeout(config.json_rpc_url.clone(), config.rpc_timeout);
-    let notifier = Notifier::default();
+    let notifier = match &config.notifier_config {
+        Some(notifier_config) => Notifier::from_config_file(Path::new(notifier_config))?,
+        None => Notifier::default(),
+    };
     let mut last_transaction_count = 0;
     let mut last_recent_blockhash = Hash::default();
     let mut last_notification_msg = "".into();
     let mut num_consecutive_failures = 0;
     let mut last_success = Instant::now();
     let mut incident = Hash::new_unique();
+    let mut rule_monitor = RuleMonitor::new(config.rules_config.clone());
 
     loop {
         let failure = match get_cluster_info(&config, &rpc_client) {
@@ -357,6 +401,37 @@ fn main() -> Result<(), Box<dyn error::Error>> {
                     failures.push(("delinquent", validator_errors.join(",")));
                 }
 
+                for rule_notification in rule_monitor.check(
+                    &rpc_client,
+                    &vote_accounts,
+                    &config.validator_identity_pubkeys,
+                ) {
+                    let formatted_validator_identity = format_labeled_address(
+                        &rule_notification.validator.to_string(),
+                        &config.address_labels,
+                    );
+                    let status = match rule_notification.notification_type {
+                        NotificationType::Trigger { .. } => "Error",
+                        NotificationType::Resolve { .. } => "Resolved",
+                    };
+                    let notification_msg = format!(
+                        "solana-watchtower{}: {}: {}: {} {}",
+                        config.name_suffix,
+                        status,
+                        rule_notification.rule,
+                        formatted_validator_identity,
+                        rule_notification.description
+                    );
+                    warn!("{}", notification_msg);
+                    datapoint_info!(
+                        "watchtower-rule",
+                        ("rule", rule_notification.rule, String),
+                        ("validator", rule_notification.validator.to_string(), String),
+                        ("status", status, String)
+                    );
+                    notifier.send(&notification_msg, &rule_notification.notification_type);
+                }
+
                 for failure in failures.iter() {
                     error!("{} sanity failure: {}", failure.0, failure.1);
                 }
@@ -0,0 +1,513 @@
+//! Configurable rules monitoring the performance of the validators, each with
+//! its own trigger and resolve thresholds.  A rule triggers an incident when
+//! its value rises above the trigger threshold, and resolves it once the value
+//! falls back to the resolve threshold, so that a value hovering around a
+//! single threshold does not flood the notification channels.
+//!
+//! An example rules file, in YAML:
+//! ```yaml
+//! skip_rate:            # % of the last `leader_slots` leader slots skipped
+//!   leader_slots: 100
+//!   trigger: 20
+//!   resolve: 10
+//! vote_credit_deficit:  # % of vote credits below the cluster median this epoch
+//!   trigger: 10
+//!   resolve: 5
+//! root_distance:        # slots between the validator's root and the cluster's
+//!   trigger: 128
+//!   resolve: 32
+//! version_drift:        # % of the active stake running a newer version
+//!   trigger: 50
+//!   resolve: 33
+//! ```
+
+use {
+    log::*,
+    serde_derive::Deserialize,
+    solana_notifier::NotificationType,
+    solana_rpc_client::rpc_client::RpcClient,
+    solana_rpc_client_api::{
+        config::{
+            RpcBlockProductionConfig, RpcBlockProductionConfigRange, RpcLeaderScheduleConfig,
+        },
+        response::{RpcVoteAccountInfo, RpcVoteAccountStatus},
+    },
+    solana_sdk::{clock::Epoch, epoch_info::EpochInfo, hash::Hash, pubkey::Pubkey},
+    std::{collections::HashMap, fs::File, path::Path},
+};
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Thresholds {
+    pub trigger: f64,
+    pub resolve: f64,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SkipRateRule {
+    /// The number of most recent leader slots of the current epoch
+    pub leader_slots: usize,
+    pub trigger: f64,
+    pub resolve: f64,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct RulesConfig {
+    pub skip_rate: Option<SkipRateRule>,
+    pub vote_credit_deficit: Option<Thresholds>,
+    pub root_distance: Option<Thresholds>,
+    pub version_drift: Option<Thresholds>,
+}
+
+impl RulesConfig {
+    pub fn load(rules_file: &Path) -> Result<Self, String> {
+        let file = File::open(rules_file)
+            .map_err(|err| format!("Unable to open {}: {err}", rules_file.display()))?;
+        let config: Self = serde_yaml::from_reader(file)
+            .map_err(|err| format!("Unable to parse {}: {err}", rules_file.display()))?;
+
+        let skip_rate_thresholds = config.skip_rate.map(|rule| Thresholds {
+            trigger: rule.trigger,
+            resolve: rule.resolve,
+        });
+        for (rule, thresholds) in [
+            (Rule::SkipRate, skip_rate_thresholds),
+            (Rule::VoteCreditDeficit, config.vote_credit_deficit),
+            (Rule::RootDistance, config.root_distance),
+            (Rule::VersionDrift, config.version_drift),
+        ] {
+            if let Some(thresholds) = thresholds {
+                if thresholds.resolve > thresholds.trigger {
+                    return Err(format!(
+                        "The resolve threshold of {} is above its trigger threshold",
+                        rule.name()
+                    ));
+                }
+            }
+        }
+        if matches!(config.skip_rate, Some(rule) if rule.leader_slots == 0) {
+            return Err("The number of leader slots of skip-rate must be positive".to_string());
+        }
+        Ok(config)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Rule {
+    SkipRate,
+    VoteCreditDeficit,
+    RootDistance,
+    VersionDrift,
+}
+
+impl Rule {
+    fn name(&self) -> &'static str {
+        match self {
+            Rule::SkipRate => "skip-rate",
+            Rule::VoteCreditDeficit => "vote-credit-deficit",
+            Rule::RootDistance => "root-distance",
+            Rule::VersionDrift => "version-drift",
+        }
+    }
+}
+
+/// The incident of a rule for a validator
+#[derive(Debug, Default)]
+struct Alert {
+    /// The open incident, if the rule has triggered
+    incident: Option<Hash>,
+}
+
+impl Alert {
+    /// Returns the notification to send after observing `value`, if any
+    fn update(&mut self, value: f64, thresholds: &Thresholds) -> Option<NotificationType> {
+        match self.incident {
+            None if value > thresholds.trigger => {
+                let incident = Hash::new_unique();
+                self.incident = Some(incident);
+                Some(NotificationType::Trigger { incident })
+            }
+            Some(incident) if value <= thresholds.resolve => {
+                self.incident = None;
+                Some(NotificationType::Resolve { incident })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A notification of a rule triggering or resolving for a validator
+pub struct RuleNotification {
+    pub rule: &'static str,
+    pub validator: Pubkey,
+    pub description: String,
+    pub notification_type: NotificationType,
+}
+
+pub struct RuleMonitor {
+    config: RulesConfig,
+    alerts: HashMap<(Rule, Pubkey), Alert>,
+}
+
+impl RuleMonitor {
+    pub fn new(config: RulesConfig) -> Self {
+        Self {
+            config,
+            alerts: HashMap::default(),
+        }
+    }
+
+    /// Evaluates the rules for each of the validators.  A rule whose data
+    /// cannot be fetched is skipped, leaving its incidents unchanged.
+    pub fn check(
+        &mut self,
+        rpc_client: &RpcClient,
+        vote_accounts: &RpcVoteAccountStatus,
+        validators: &[Pubkey],
+    ) -> Vec<RuleNotification> {
+        if self.config == RulesConfig::default() || validators.is_empty() {
+            return vec![];
+        }
+        let epoch_info = match rpc_client.get_epoch_info() {
+            Ok(epoch_info) => epoch_info,
+            Err(err) => {
+                warn!("Unable to evaluate the rules: {err}");
+                return vec![];
+            }
+        };
+
+        let versions = if self.config.version_drift.is_some() {
+            match rpc_client.get_cluster_nodes() {
+                Ok(nodes) => nodes
+                    .into_iter()
+                    .filter_map(|node| {
+                        let version = semver::Version::parse(node.version.as_ref()?).ok()?;
+                        Some((node.pubkey, version))
+                    })
+                    .collect(),
+                Err(err) => {
+                    warn!("Unable to evaluate {}: {err}", Rule::VersionDrift.name());
+                    HashMap::default()
+                }
+            }
+        } else {
+            HashMap::default()
+        };
+
+        let mut notifications = vec![];
+        for validator in validators {
+            let mut values = vec![];
+            if let Some(rule) = self.config.skip_rate {
+                match skip_rate(rpc_client, &epoch_info, validator, rule.leader_slots) {
+                    Ok(Some((rate, leader_slots))) => values.push((
+                        Rule::SkipRate,
+                        Thresholds {
+                            trigger: rule.trigger,
+                            resolve: rule.resolve,
+                        },
+                        rate,
+                        format!("skipped {rate:.2}% of its last {leader_slots} leader slots"),
+                    )),
+                    Ok(None) => {}
+                    Err(err) => warn!("Unable to evaluate {}: {err}", Rule::SkipRate.name()),
+                }
+            }
+            let vote_account = find_vote_account(vote_accounts, validator);
+            if let (Some(thresholds), Some(vote_account)) =
+                (self.config.vote_credit_deficit, vote_account)
+            {
+                if let Some(deficit) =
+                    vote_credit_deficit(vote_accounts, vote_account, epoch_info.epoch)
+                {
+                    values.push((
+                        Rule::VoteCreditDeficit,
+                        thresholds,
+                        deficit,
+                        format!("earned {deficit:.2}% fewer vote credits than the cluster median"),
+                    ));
+                }
+            }
+            if let (Some(thresholds), Some(vote_account)) =
+                (self.config.root_distance, vote_account)
+            {
+                let distance = epoch_info
+                    .absolute_slot
+                    .saturating_sub(vote_account.root_slot);
+                values.push((
+                    Rule::RootDistance,
+                    thresholds,
+                    distance as f64,
+                    format!("root is {distance} slots behind the cluster"),
+                ));
+            }
+            if let Some(thresholds) = self.config.version_drift {
+                if let Some(version) = versions.get(&validator.to_string()) {
+                    let drift = newer_version_stake_percent(vote_accounts, &versions, version);
+                    values.push((
+                        Rule::VersionDrift,
+                        thresholds,
+                        drift,
+                        format!(
+                            "runs {version}, older than the version of {drift:.2}% of the stake"
+                        ),
+                    ));
+                }
+            }
+
+            for (rule, thresholds, value, description) in values {
+                debug!("{} {validator}: {value}", rule.name());
+                let alert = self.alerts.entry((rule, *validator)).or_default();
+                if let Some(notification_type) = alert.update(value, &thresholds) {
+                    notifications.push(RuleNotification {
+                        rule: rule.name(),
+                        validator: *validator,
+                        description,
+                        notification_type,
+                    });
+                }
+            }
+        }
+        notifications
+    }
+}
+
+fn find_vote_account<'a>(
+    vote_accounts: &'a RpcVoteAccountStatus,
+    validator: &Pubkey,
+) -> Option<&'a RpcVoteAccountInfo> {
+    let validator = validator.to_string();
+    vote_accounts
+        .current
+        .iter()
+        .chain(vote_accounts.delinquent.iter())
+        .find(|vote_account| vote_account.node_pubkey == validator)
+}
+
+/// Returns the skip rate of the validator over its last `leader_slots` leader
+/// slots of the current epoch, and the number of those leader slots
+fn skip_rate(
+    rpc_client: &RpcClient,
+    epoch_info: &EpochInfo,
+    validator: &Pubkey,
+    leader_slots: usize,
+) -> Result<Option<(f64, usize)>, String> {
+    let identity = validator.to_string();
+    let leader_schedule = rpc_client
+        .get_leader_schedule_with_config(
+            Some(epoch_info.absolute_slot),
+            RpcLeaderScheduleConfig {
+                identity: Some(identity.clone()),
+                commitment: None,
+            },
+        )
+        .map_err(|err| err.to_string())?
+        .unwrap_or_default();
+    let Some(first_slot) = leader_schedule.get(&identity).and_then(|slot_indices| {
+        last_leader_slots_start(slot_indices, epoch_info.slot_index, leader_slots)
+    }) else {
+        return Ok(None);
+    };
+
+    let epoch_first_slot = epoch_info.absolute_slot - epoch_info.slot_index;
+    let block_production = rpc_client
+        .get_block_production_with_config(RpcBlockProductionConfig {
+            identity: Some(identity.clone()),
+            range: Some(RpcBlockProductionConfigRange {
+                first_slot: epoch_first_slot + first_slot,
+                last_slot: Some(epoch_info.absolute_slot),
+            }),
+            commitment: None,
+        })
+        .map_err(|err| err.to_string())?
+        .value;
+    Ok(block_production
+        .by_identity
+        .get(&identity)
+        .and_then(|(leader_slots, blocks_produced)| {
+            skip_rate_percent(*leader_slots, *blocks_produced)
+                .map(|skip_rate| (skip_rate, *leader_slots))
+        }))
+}
+
+/// Returns the epoch slot index of the first of the last `count` leader slots
+/// up to `slot_index`
+fn last_leader_slots_start(slot_indices: &[usize], slot_index: u64, count: usize) -> Option<u64> {
+    let past_slot_indices: Vec<_> = slot_indices
+        .iter()
+        .map(|slot_index| *slot_index as u64)
+        .filter(|leader_slot_index| *leader_slot_index <= slot_index)
+        .collect();
+    past_slot_indices
+        .get(past_slot_indices.len().saturating_sub(count))
+        .copied()
+}
+
+fn skip_rate_percent(leader_slots: usize, blocks_produced: usize) -> Option<f64> {
+    (leader_slots > 0)
+        .then(|| leader_slots.saturating_sub(blocks_produced) as f64 * 100. / leader_slots as f64)
+}
+
+fn epoch_credits(vote_account: &RpcVoteAccountInfo, epoch: Epoch) -> u64 {
+    vote_account
+        .epoch_credits
+        .iter()
+        .find(|(credits_epoch, ..)| *credits_epoch == epoch)
+        .map(|(_, credits, prev_credits)| credits.saturating_sub(*prev_credits))
+        .unwrap_or_default()
+}
+
+/// Returns how many percents fewer vote credits than the median of the
+/// current validators the vote account earned in the epoch
+fn vote_credit_deficit(
+    vote_accounts: &RpcVoteAccountStatus,
+    vote_account: &RpcVoteAccountInfo,
+    epoch: Epoch,
+) -> Option<f64> {
+    let mut credits: Vec<_> = vote_accounts
+        .current
+        .iter()
+        .map(|vote_account| epoch_credits(vote_account, epoch))
+        .collect();
+    if credits.is_empty() {
+        return None;
+    }
+    credits.sort_unstable();
+    let median = credits[credits.len() / 2];
+    (median > 0).then(|| {
+        median.saturating_sub(epoch_credits(vote_account, epoch)) as f64 * 100. / median as f64
+    })
+}
+
+/// Returns the percentage of the active stake whose validators run a version
+/// newer than `version`
+fn newer_version_stake_percent(
+    vote_accounts: &RpcVoteAccountStatus,
+    versions: &HashMap<String, semver::Version>,
+    version: &semver::Version,
+) -> f64 {
+    let (total_stake, newer_stake) = vote_accounts.current.iter().fold(
+        (0u64, 0u64),
+        |(total_stake, newer_stake), vote_account| {
+            let is_newer = versions
+                .get(&vote_account.node_pubkey)
+                .map_or(false, |node_version| node_version > version);
+            (
+                total_stake + vote_account.activated_stake,
+                newer_stake
+                    + if is_newer {
+                        vote_account.activated_stake
+                    } else {
+                        0
+                    },
+            )
+        },
+    );
+    if total_stake == 0 {
+        return 0.;
+    }
+    newer_stake as f64 * 100. / total_stake as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_vote_account(node_pubkey: &Pubkey, stake: u64, credits: u64) -> RpcVoteAccountInfo {
+        RpcVoteAccountInfo {
+            vote_pubkey: Pubkey::new_unique().to_string(),
+            node_pubkey: node_pubkey.to_string(),
+            activated_stake: stake,
+            commission: 0,
+            epoch_vote_account: true,
+            epoch_credits: vec![(1, 1_000, 1_000 - credits)],
+            last_vote: 0,
+            root_slot: 0,
+        }
+    }
+
+    #[test]
+    fn test_alert_hysteresis() {
+        let thresholds = Thresholds {
+            trigger: 20.,
+            resolve: 10.,
+        };
+        let mut alert = Alert::default();
+        assert!(alert.update(20., &thresholds).is_none());
+        let Some(NotificationType::Trigger { incident }) = alert.update(25., &thresholds) else {
+            panic!("expected a trigger");
+        };
+        assert!(alert.update(30., &thresholds).is_none());
+        assert!(alert.update(15., &thresholds).is_none());
+        assert!(alert.update(21., &thresholds).is_none());
+        assert!(matches!(
+            alert.update(10., &thresholds),
+            Some(NotificationType::Resolve { incident: resolved }) if resolved == incident
+        ));
+        assert!(alert.update(15., &thresholds).is_none());
+        assert!(matches!(
+            alert.update(21., &thresholds),
+            Some(NotificationType::Trigger { incident: triggered }) if triggered != incident
+        ));
+    }
+
+    #[test]
+    fn test_last_leader_slots_start() {
+        let slot_indices = [4, 5, 6, 7, 20, 21, 22, 23];
+        assert_eq!(last_leader_slots_start(&slot_indices, 3, 4), None);
+        assert_eq!(last_leader_slots_start(&slot_indices, 10, 2), Some(6));
+        assert_eq!(last_leader_slots_start(&slot_indices, 10, 8), Some(4));
+        assert_eq!(last_leader_slots_start(&slot_indices, 30, 6), Some(6));
+        assert_eq!(skip_rate_percent(0, 0), None);
+        assert_eq!(skip_rate_percent(8, 6), Some(25.));
+    }
+
+    #[test]
+    fn test_vote_credit_deficit_and_version_drift() {
+        let validators: Vec<_> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let vote_accounts = RpcVoteAccountStatus {
+            current: vec![
+                new_vote_account(&validators[0], 10, 400),
+                new_vote_account(&validators[1], 20, 500),
+                new_vote_account(&validators[2], 30, 600),
+            ],
+            delinquent: vec![new_vote_account(&validators[3], 40, 0)],
+        };
+        assert_eq!(
+            vote_credit_deficit(&vote_accounts, &vote_accounts.current[0], 1),
+            Some(20.)
+        );
+        assert_eq!(
+            vote_credit_deficit(&vote_accounts, &vote_accounts.current[2], 1),
+            Some(0.)
+        );
+        assert_eq!(
+            vote_credit_deficit(&vote_accounts, &vote_accounts.delinquent[0], 2),
+            None
+        );
+
+        let versions: HashMap<_, _> = [("1.17.5", 0), ("1.18.0", 1), ("1.18.1", 2)]
+            .into_iter()
+            .map(|(version, i)| {
+                (
+                    validators[i].to_string(),
+                    semver::Version::parse(version).unwrap(),
+                )
+            })
+            .collect();
+        let version = |i: usize| &versions[&validators[i].to_string()];
+        assert_eq!(
+            newer_version_stake_percent(&vote_accounts, &versions, version(0)),
+            (20. + 30.) * 100. / 60.
+        );
+        assert_eq!(
+            newer_version_stake_percent(&vote_accounts, &versions, version(1)),
+            50.
+        );
+        assert_eq!(
+            newer_version_stake_percent(&vote_accounts, &versions, version(2)),
+            0.
+        );
+    }
+}